@@ -0,0 +1,9 @@
+-- Per-user read markers for tickets (unread = never read, or updated since last read)
+CREATE TABLE IF NOT EXISTS ticket_reads (
+    recording_id UUID NOT NULL REFERENCES recordings(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    read_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (recording_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_ticket_reads_user ON ticket_reads(user_id);
@@ -0,0 +1,4 @@
+-- Gemini token usage per analysis job (for cost reporting)
+ALTER TABLE analysis_jobs ADD COLUMN IF NOT EXISTS prompt_tokens INTEGER;
+ALTER TABLE analysis_jobs ADD COLUMN IF NOT EXISTS candidates_tokens INTEGER;
+ALTER TABLE analysis_jobs ADD COLUMN IF NOT EXISTS total_tokens INTEGER;
@@ -0,0 +1,6 @@
+-- Liveness timestamp for running analysis jobs; stale ones are reclaimed by the worker sweep
+ALTER TABLE analysis_jobs ADD COLUMN IF NOT EXISTS heartbeat_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_analysis_jobs_processing_heartbeat
+    ON analysis_jobs (heartbeat_at)
+    WHERE status = 'processing';
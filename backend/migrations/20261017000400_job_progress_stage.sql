@@ -0,0 +1,2 @@
+-- Current processing stage of a running analysis job (downloading, uploading_to_gemini, analyzing, saving)
+ALTER TABLE analysis_jobs ADD COLUMN IF NOT EXISTS progress_stage VARCHAR(32);
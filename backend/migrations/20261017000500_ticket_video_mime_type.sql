@@ -0,0 +1,2 @@
+-- MIME type of the uploaded video, used as Content-Type when streaming it back
+ALTER TABLE recordings ADD COLUMN IF NOT EXISTS video_mime_type VARCHAR(100);
@@ -0,0 +1,9 @@
+-- Free-form labels the team attaches to tickets (stored normalized: trimmed, lowercase)
+CREATE TABLE IF NOT EXISTS ticket_labels (
+    ticket_id UUID NOT NULL REFERENCES recordings(id) ON DELETE CASCADE,
+    label VARCHAR(50) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (ticket_id, label)
+);
+
+CREATE INDEX IF NOT EXISTS idx_ticket_labels_label ON ticket_labels(label);
@@ -0,0 +1,2 @@
+-- Browser console output and failed network requests captured by the widget with a submission
+ALTER TABLE recordings ADD COLUMN IF NOT EXISTS client_diagnostics JSONB NOT NULL DEFAULT '{}';
@@ -0,0 +1,4 @@
+-- Author of automatic chat messages (status changes etc.); has no credentials, so cannot sign in
+INSERT INTO users (id, name, role, onboarding_completed, quota_limit, quota_used)
+VALUES ('00000000-0000-0000-0000-000000000001', 'Ortrace', 'internal', TRUE, 0, 0)
+ON CONFLICT (id) DO NOTHING;
@@ -0,0 +1,11 @@
+-- Per-user chat read markers: everything up to and including last_read_message_id has been read
+CREATE TABLE IF NOT EXISTS chat_reads (
+    recording_id UUID NOT NULL REFERENCES recordings(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    last_read_message_id UUID REFERENCES chat_messages(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (recording_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_chat_messages_recording_created
+    ON chat_messages(recording_id, created_at, id);
@@ -0,0 +1,2 @@
+-- Per-issue triage state, independent of the ticket's status
+ALTER TABLE issues ADD COLUMN IF NOT EXISTS issue_status VARCHAR(20) NOT NULL DEFAULT 'open';
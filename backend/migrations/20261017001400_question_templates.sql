@@ -0,0 +1,6 @@
+-- Per-owner default analysis questions, used for new projects created without their own
+CREATE TABLE IF NOT EXISTS question_templates (
+    owner_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    analysis_questions JSONB NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
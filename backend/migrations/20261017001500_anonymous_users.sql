@@ -0,0 +1,10 @@
+-- Customers created for widget submissions. They have no credentials and are only ever
+-- matched by email against other anonymous users, never against registered accounts.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS is_anonymous BOOLEAN NOT NULL DEFAULT FALSE;
+
+UPDATE users SET is_anonymous = TRUE
+WHERE role = 'customer' AND password_hash IS NULL AND google_id IS NULL;
+
+-- An anonymous record may share its email with a registered account
+ALTER TABLE users DROP CONSTRAINT IF EXISTS users_email_key;
+CREATE INDEX IF NOT EXISTS idx_users_email_anonymous ON users(email) WHERE is_anonymous;
@@ -0,0 +1,3 @@
+-- MIME type of the media a job analyzes; image/* jobs analyze a screenshot instead of a
+-- video. NULL for jobs queued before this column existed, which are all videos.
+ALTER TABLE analysis_jobs ADD COLUMN IF NOT EXISTS mime_type VARCHAR(100);
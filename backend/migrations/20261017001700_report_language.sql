@@ -0,0 +1,2 @@
+-- Language the analysis was written in (the project's report_language at the time)
+ALTER TABLE reports ADD COLUMN IF NOT EXISTS language VARCHAR(50) NOT NULL DEFAULT 'English';
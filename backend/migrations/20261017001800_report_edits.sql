@@ -0,0 +1,3 @@
+-- Who last corrected the report by hand, and when. raw_analysis keeps the original output.
+ALTER TABLE reports ADD COLUMN IF NOT EXISTS edited_by UUID REFERENCES users(id) ON DELETE SET NULL;
+ALTER TABLE reports ADD COLUMN IF NOT EXISTS edited_at TIMESTAMPTZ;
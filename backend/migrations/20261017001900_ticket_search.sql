@@ -0,0 +1,42 @@
+-- Full-text search over a ticket's description (weight A) and its report overview
+-- (weight B). Kept current by triggers on both tables.
+ALTER TABLE recordings ADD COLUMN IF NOT EXISTS search_vector tsvector;
+
+CREATE OR REPLACE FUNCTION recording_search_vector(description TEXT, recording UUID)
+RETURNS tsvector AS $$
+    SELECT setweight(to_tsvector('english', COALESCE(description, '')), 'A')
+        || setweight(to_tsvector('english', COALESCE(
+            (SELECT string_agg(overview, ' ') FROM reports WHERE recording_id = recording), ''
+        )), 'B')
+$$ LANGUAGE sql STABLE;
+
+CREATE OR REPLACE FUNCTION recordings_search_vector_update() RETURNS trigger AS $$
+BEGIN
+    NEW.search_vector := recording_search_vector(NEW.task_description, NEW.id);
+    RETURN NEW;
+END
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS recordings_search_vector ON recordings;
+CREATE TRIGGER recordings_search_vector
+    BEFORE INSERT OR UPDATE OF task_description ON recordings
+    FOR EACH ROW EXECUTE FUNCTION recordings_search_vector_update();
+
+CREATE OR REPLACE FUNCTION reports_search_vector_update() RETURNS trigger AS $$
+DECLARE
+    changed UUID := CASE WHEN TG_OP = 'DELETE' THEN OLD.recording_id ELSE NEW.recording_id END;
+BEGIN
+    UPDATE recordings SET search_vector = recording_search_vector(task_description, id)
+    WHERE id = changed;
+    RETURN NULL;
+END
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS reports_search_vector ON reports;
+CREATE TRIGGER reports_search_vector
+    AFTER INSERT OR UPDATE OF overview OR DELETE ON reports
+    FOR EACH ROW EXECUTE FUNCTION reports_search_vector_update();
+
+UPDATE recordings SET search_vector = recording_search_vector(task_description, id);
+
+CREATE INDEX IF NOT EXISTS idx_recordings_search_vector ON recordings USING GIN (search_vector);
@@ -0,0 +1,11 @@
+-- Duplicate tickets folded into another one. The source ticket keeps its row (closed as
+-- not-relevant); its chat messages now live on the target.
+CREATE TABLE IF NOT EXISTS ticket_merges (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    source_ticket_id UUID NOT NULL REFERENCES recordings(id) ON DELETE CASCADE,
+    target_ticket_id UUID NOT NULL REFERENCES recordings(id) ON DELETE CASCADE,
+    merged_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    merged_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_ticket_merges_target ON ticket_merges(target_ticket_id);
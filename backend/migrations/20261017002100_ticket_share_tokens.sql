@@ -0,0 +1,11 @@
+-- Read-only links to one ticket's report and video; only a SHA-256 of each token is stored
+CREATE TABLE IF NOT EXISTS ticket_share_tokens (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    recording_id UUID NOT NULL REFERENCES recordings(id) ON DELETE CASCADE,
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    revoked_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_ticket_share_tokens_recording ON ticket_share_tokens(recording_id);
@@ -0,0 +1,11 @@
+-- Idempotency-Key values of widget submissions, so a retried submit returns the ticket it
+-- already created. Rows older than the TTL in TicketService are reused or pruned.
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    key VARCHAR(255) NOT NULL,
+    ticket_id UUID NOT NULL REFERENCES recordings(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (project_id, key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_created ON idempotency_keys(created_at);
@@ -0,0 +1,4 @@
+-- The submitter's overall mood in the session (frustrated/neutral/satisfied) and how
+-- frustrated they were, 0-100; NULL for reports analyzed before these were asked for
+ALTER TABLE reports ADD COLUMN IF NOT EXISTS sentiment VARCHAR(20);
+ALTER TABLE reports ADD COLUMN IF NOT EXISTS frustration_score INTEGER;
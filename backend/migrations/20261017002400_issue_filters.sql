@@ -0,0 +1,5 @@
+-- Project-wide issue listing walks issues -> reports -> recordings by project
+CREATE INDEX IF NOT EXISTS idx_issues_report ON issues(report_id);
+CREATE INDEX IF NOT EXISTS idx_reports_recording ON reports(recording_id);
+CREATE INDEX IF NOT EXISTS idx_recordings_project ON recordings(project_id);
+CREATE INDEX IF NOT EXISTS idx_issues_severity ON issues(severity);
@@ -0,0 +1,23 @@
+-- One anonymous user per email, so concurrent widget submissions can upsert it.
+-- Earlier races may have left duplicates: fold them into the oldest record first.
+CREATE TEMP TABLE anonymous_duplicates AS
+SELECT id, keep_id
+FROM (
+    SELECT id, FIRST_VALUE(id) OVER (PARTITION BY email ORDER BY created_at, id) AS keep_id
+    FROM users
+    WHERE is_anonymous AND email IS NOT NULL
+) ranked
+WHERE id <> keep_id;
+
+UPDATE recordings r SET customer_id = d.keep_id
+FROM anonymous_duplicates d WHERE r.customer_id = d.id;
+
+UPDATE chat_messages m SET sender_id = d.keep_id
+FROM anonymous_duplicates d WHERE m.sender_id = d.id;
+
+DELETE FROM users WHERE id IN (SELECT id FROM anonymous_duplicates);
+
+DROP TABLE anonymous_duplicates;
+
+DROP INDEX IF EXISTS idx_users_email_anonymous;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_users_email_anonymous ON users(email) WHERE is_anonymous;
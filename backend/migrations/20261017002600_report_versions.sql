@@ -0,0 +1,14 @@
+-- Reanalysis keeps earlier reports. The live report stays the only `reports` row of its
+-- ticket; each one it replaces is archived here together with its issues.
+ALTER TABLE reports ADD COLUMN IF NOT EXISTS report_version INTEGER NOT NULL DEFAULT 1;
+
+CREATE TABLE IF NOT EXISTS report_versions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    recording_id UUID NOT NULL REFERENCES recordings(id) ON DELETE CASCADE,
+    report_version INTEGER NOT NULL,
+    -- The `reports` row and its `issues` rows as they were when replaced
+    report JSONB NOT NULL,
+    issues JSONB NOT NULL DEFAULT '[]',
+    archived_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (recording_id, report_version)
+);
@@ -0,0 +1,8 @@
+-- Merging no longer moves a duplicate's chat. The source keeps its thread, and the target
+-- gets copies marked with the ticket they came from; only the team sees those, since the
+-- two tickets' customers are usually different people. No foreign key: deleting the
+-- source must not make the copies visible.
+ALTER TABLE chat_messages ADD COLUMN IF NOT EXISTS merged_from UUID;
+
+-- A ticket can only be merged once
+CREATE UNIQUE INDEX IF NOT EXISTS idx_ticket_merges_source ON ticket_merges(source_ticket_id);
@@ -0,0 +1,18 @@
+-- `updated_at` is the version editors send back as `expected_updated_at`, so every change
+-- to a ticket must move it, including ones whose query doesn't set it. An explicit value
+-- in the UPDATE is kept, and refreshing the derived search vector alone is not a change.
+CREATE OR REPLACE FUNCTION recordings_touch_updated_at() RETURNS trigger AS $$
+BEGIN
+    IF NEW.updated_at IS NOT DISTINCT FROM OLD.updated_at
+        AND to_jsonb(NEW) - 'search_vector' IS DISTINCT FROM to_jsonb(OLD) - 'search_vector'
+    THEN
+        NEW.updated_at := NOW();
+    END IF;
+    RETURN NEW;
+END
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS recordings_updated_at ON recordings;
+CREATE TRIGGER recordings_updated_at
+    BEFORE UPDATE ON recordings
+    FOR EACH ROW EXECUTE FUNCTION recordings_touch_updated_at();
@@ -0,0 +1,7 @@
+-- Projects a user may work in: the ones they own plus the ones they are a member of.
+-- Every access check filters on this instead of repeating the union.
+CREATE OR REPLACE FUNCTION accessible_project_ids(member UUID)
+RETURNS SETOF UUID AS $$
+    SELECT id FROM projects WHERE owner_id = member
+    UNION SELECT project_id FROM project_members WHERE user_id = member
+$$ LANGUAGE sql STABLE;
@@ -0,0 +1,469 @@
+//! Admin controller - deployment diagnostics for internal users
+
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+    Extension,
+};
+use uuid::Uuid;
+
+use crate::dto::{
+    ApiResponse, DeadLetterJobResponse, JobListItem, JobListQueryParams, OAuthConfigResponse,
+    PaginatedResponse, RetryJobResponse, SubmitterExportQuery, SubmitterExportResponse,
+    UpdateQuotaRequest, UserQuotaResponse, ValidatedJson,
+};
+use crate::error::{AppError, Result};
+use crate::models::{JobStatus, User};
+use crate::services::JobListQuery;
+use crate::state::ReadyAppState;
+
+/// GET /api/v1/admin/oauth/config - Computed Google OAuth settings (helps debug redirect_uri_mismatch)
+pub async fn get_oauth_config(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+) -> Result<Json<ApiResponse<OAuthConfigResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    Ok(Json(ApiResponse::success(
+        OAuthConfigResponse::from_config(&state.config),
+    )))
+}
+
+/// GET /api/v1/admin/jobs - Analysis jobs on your tickets, newest first
+///
+/// Query params: status, recording_id, created_after, created_before, page, per_page.
+pub async fn list_jobs(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Query(query): Query<JobListQueryParams>,
+) -> Result<Json<ApiResponse<PaginatedResponse<JobListItem>>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+    query.validate().map_err(AppError::validation)?;
+
+    let (jobs, total) = state
+        .queue
+        .list_jobs(
+            user.id,
+            &JobListQuery {
+                status: query.status,
+                recording_id: query.recording_id,
+                created_after: query.created_after,
+                created_before: query.created_before,
+                page: query.page,
+                per_page: query.per_page,
+            },
+        )
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to list jobs: {}", e)))?;
+    let items = jobs.into_iter().map(JobListItem::from).collect();
+
+    Ok(Json(ApiResponse::success(PaginatedResponse::new(
+        items,
+        total,
+        query.page,
+        query.per_page,
+    ))))
+}
+
+/// GET /api/v1/admin/jobs/dead-letter - Analysis jobs that exhausted their retries
+pub async fn list_dead_letter_jobs(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+) -> Result<Json<ApiResponse<Vec<DeadLetterJobResponse>>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    let jobs = state
+        .queue
+        .list_dead_letter(user.id)
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to list dead-letter jobs: {}", e)))?;
+    let items = jobs.into_iter().map(DeadLetterJobResponse::from).collect();
+
+    Ok(Json(ApiResponse::success(items)))
+}
+
+/// POST /api/v1/admin/jobs/:id/retry - Put a failed analysis job back on the queue
+pub async fn retry_job(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<RetryJobResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    let job = state
+        .queue
+        .get_job(job_id)
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to load job: {}", e)))?
+        .ok_or_else(|| AppError::not_found("Job not found"))?;
+    let recording_id = job
+        .recording_id
+        .ok_or_else(|| AppError::not_found("Job not found"))?;
+    state.tickets.get_for_owner(recording_id, user.id).await?;
+
+    if job.status != JobStatus::Failed {
+        return Err(AppError::conflict(format!(
+            "Only failed jobs can be retried; this job is {}",
+            job.status
+        )));
+    }
+
+    let retried = state
+        .queue
+        .retry_job(job_id)
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to retry job: {}", e)))?
+        .ok_or_else(|| AppError::conflict("Job is no longer failed"))?;
+    tracing::info!(%job_id, %recording_id, by = %user.id, "Job manually retried");
+
+    Ok(Json(ApiResponse::success(RetryJobResponse::from(retried))))
+}
+
+/// PUT /api/v1/admin/users/:id/quota - Set how many analyses a user may run
+///
+/// Only for the accounts in ADMIN_USER_IDS; an internal role alone is self-service.
+pub async fn update_user_quota(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(user_id): Path<Uuid>,
+    ValidatedJson(req): ValidatedJson<UpdateQuotaRequest>,
+) -> Result<Json<ApiResponse<UserQuotaResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !state.config.admin_user_ids.contains(&user.id) {
+        return Err(AppError::forbidden());
+    }
+
+    let updated = state
+        .auth
+        .set_quota_limit(&user_id, req.quota_limit)
+        .await?;
+    tracing::info!(%user_id, quota_limit = req.quota_limit, by = %user.id, "User quota updated");
+    Ok(Json(ApiResponse::success(UserQuotaResponse::from(updated))))
+}
+
+/// GET /api/v1/admin/submitters/export?email= - Every ticket a submitter sent to your
+/// projects, fully exported (data-subject access requests)
+pub async fn export_submitter_data(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Query(query): Query<SubmitterExportQuery>,
+) -> Result<Json<ApiResponse<SubmitterExportResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+    let email = query.email.trim();
+    if email.is_empty() {
+        return Err(AppError::validation("email is required"));
+    }
+
+    let mut tickets = Vec::new();
+    for ticket in state.tickets.list_for_submitter(user.id, email).await? {
+        tickets.push(super::ticket::ticket_export(&state, &user, ticket).await?);
+    }
+    tracing::info!(by = %user.id, tickets = tickets.len(), "Submitter data exported");
+    Ok(Json(ApiResponse::success(SubmitterExportResponse {
+        email: email.to_string(),
+        exported_at: chrono::Utc::now(),
+        tickets,
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::models::CreateJobRequest;
+    use crate::test_support::{seed_project, seed_ticket, seed_user, test_app};
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use sqlx::PgPool;
+    use tower::ServiceExt;
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn failed_job_can_be_retried_and_dequeued(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "internal").await;
+        let project_id = seed_project(&pool, owner.id).await;
+        let ticket_id = seed_ticket(&pool, project_id, owner.id, "failed").await;
+
+        let config = Config::test_default();
+        let (app, state) = test_app(pool.clone(), &config).await;
+        let job_id = state
+            .queue
+            .enqueue(CreateJobRequest {
+                video_storage_path: "videos/x.webm".to_string(),
+                video_size_bytes: 10,
+                mime_type: None,
+                prompt: None,
+                user_id: Some(owner.id),
+                recording_id: Some(ticket_id),
+            })
+            .await
+            .unwrap();
+        let token = state.auth.generate_tokens(&owner).unwrap().0;
+        let retry = || {
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/admin/jobs/{}/retry", job_id))
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        // Pending jobs cannot be retried
+        let response = app.clone().oneshot(retry()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        state.queue.dequeue().await.unwrap().unwrap();
+        state
+            .queue
+            .fail_job(job_id, "Gemini timed out".to_string())
+            .await
+            .unwrap();
+
+        let response = app.oneshot(retry()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["data"]["status"], "pending");
+        assert_eq!(json["data"]["retry_count"], 1);
+
+        let status: String =
+            sqlx::query_scalar("SELECT status::text FROM recordings WHERE id = $1")
+                .bind(ticket_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(status, "processing");
+
+        let job = state.queue.dequeue().await.unwrap().unwrap();
+        assert_eq!(job.id, job_id);
+        assert!(job.error_message.is_none());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn submitter_export_covers_own_projects_only(pool: PgPool) {
+        let mut users = Vec::new();
+        for (email, role) in [
+            ("owner@example.com", "internal"),
+            ("other@example.com", "internal"),
+            ("jane@example.com", "customer"),
+        ] {
+            users.push(seed_user(&pool, email, role).await);
+        }
+        let (owner, other, jane) = (&users[0], &users[1], &users[2]);
+        let mut project_ids = Vec::new();
+        for owner_id in [owner.id, other.id] {
+            let project_id = seed_project(&pool, owner_id).await;
+            project_ids.push(project_id);
+        }
+        // Jane's account ticket and a widget ticket under her email in the owner's project,
+        // plus one in someone else's project
+        let mut ticket_ids = Vec::new();
+        for (project_id, customer_id, submitter_email) in [
+            (project_ids[0], jane.id, None),
+            (project_ids[0], owner.id, Some("Jane@Example.com")),
+            (project_ids[1], jane.id, None),
+        ] {
+            let ticket_id = sqlx::query_scalar::<_, Uuid>(
+                "INSERT INTO recordings (project_id, customer_id, status, submitter_email) VALUES ($1, $2, 'analyzed', $3) RETURNING id",
+            )
+            .bind(project_id)
+            .bind(customer_id)
+            .bind(submitter_email)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            ticket_ids.push(ticket_id);
+        }
+
+        let config = Config::test_default();
+        let (app, state) = test_app(pool, &config).await;
+        let token = state.auth.generate_tokens(owner).unwrap().0;
+        let request = |email: &str| {
+            Request::builder()
+                .uri(format!("/api/v1/admin/submitters/export?email={}", email))
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(request("%20")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let response = app.oneshot(request("jane@example.com")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let export = &serde_json::from_slice::<serde_json::Value>(&body).unwrap()["data"];
+        let exported: Vec<&str> = export["tickets"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["ticket"]["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            exported,
+            vec![ticket_ids[0].to_string(), ticket_ids[1].to_string()]
+        );
+        assert!(export["tickets"][0]["chat"].as_array().unwrap().is_empty());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn job_list_filters_by_status_and_paginates(pool: PgPool) {
+        let mut owners = Vec::new();
+        for email in ["owner@example.com", "other@example.com"] {
+            owners.push(seed_user(&pool, email, "internal").await);
+        }
+        let mut ticket_ids = Vec::new();
+        for owner in &owners {
+            let project_id = seed_project(&pool, owner.id).await;
+            let ticket_id = seed_ticket(&pool, project_id, owner.id, "processing").await;
+            ticket_ids.push(ticket_id);
+        }
+        // Three failed and one completed job on the owner's ticket, one failed job elsewhere
+        let mut job_ids = Vec::new();
+        for (minutes_ago, ticket_id, status) in [
+            (40, ticket_ids[0], "failed"),
+            (30, ticket_ids[0], "completed"),
+            (20, ticket_ids[0], "failed"),
+            (10, ticket_ids[0], "failed"),
+            (5, ticket_ids[1], "failed"),
+        ] {
+            let job_id = sqlx::query_scalar::<_, Uuid>(
+                r#"
+                INSERT INTO analysis_jobs (recording_id, status, video_storage_path, video_size_bytes, error_message, created_at)
+                VALUES ($1, $2, 'videos/x.webm', 10, $3, NOW() - make_interval(mins => $4))
+                RETURNING id
+                "#,
+            )
+            .bind(ticket_id)
+            .bind(status)
+            .bind((status == "failed").then(|| "x".repeat(500)))
+            .bind(minutes_ago)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            job_ids.push(job_id);
+        }
+
+        let config = Config::test_default();
+        let (app, state) = test_app(pool, &config).await;
+        let token = state.auth.generate_tokens(&owners[0]).unwrap().0;
+        let list = |query: &str| {
+            let app = app.clone();
+            let request = Request::builder()
+                .uri(format!("/api/v1/admin/jobs?{}", query))
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap();
+            async move {
+                let response = app.oneshot(request).await.unwrap();
+                let status = response.status();
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&body).unwrap()["data"].clone(),
+                )
+            }
+        };
+        let ids = |page: &serde_json::Value| -> Vec<String> {
+            page["items"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|job| job["id"].as_str().unwrap().to_string())
+                .collect()
+        };
+
+        let (status, page) = list("status=failed&per_page=2").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(page["total"], 3);
+        assert_eq!(page["total_pages"], 2);
+        assert_eq!(
+            ids(&page),
+            vec![job_ids[3].to_string(), job_ids[2].to_string()]
+        );
+        let error = page["items"][0]["error_message"].as_str().unwrap();
+        assert!(error.len() < 500 && error.ends_with("..."));
+
+        let (_, page) = list("status=failed&per_page=2&page=2").await;
+        assert_eq!(ids(&page), vec![job_ids[0].to_string()]);
+
+        let (_, page) = list("status=completed").await;
+        assert_eq!(ids(&page), vec![job_ids[1].to_string()]);
+        assert!(page["items"][0]["error_message"].is_null());
+
+        let (_, page) = list(&format!("recording_id={}", ticket_ids[1])).await;
+        assert_eq!(page["total"], 0);
+
+        let (status, _) = list("per_page=500").await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn only_configured_admins_change_quotas(pool: PgPool) {
+        let admin = seed_user(&pool, "admin@example.com", "internal").await;
+        let other = seed_user(&pool, "other@example.com", "internal").await;
+        let customer = seed_user(&pool, "jane@example.com", "customer").await;
+
+        let config = Config {
+            admin_user_ids: vec![admin.id],
+            ..Config::test_default()
+        };
+        let (app, state) = test_app(pool, &config).await;
+        let set_quota = |caller: &User, target: Uuid, limit: i32| {
+            let token = state.auth.generate_tokens(caller).unwrap().0;
+            let request = Request::builder()
+                .method("PUT")
+                .uri(format!("/api/v1/admin/users/{}/quota", target))
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    serde_json::json!({ "quota_limit": limit }).to_string(),
+                ))
+                .unwrap();
+            app.clone().oneshot(request)
+        };
+
+        // Internal accounts cannot change someone else's limit, or raise their own
+        for target in [customer.id, other.id] {
+            let response = set_quota(&other, target, 1000).await.unwrap();
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        }
+
+        let response = set_quota(&admin, customer.id, 5).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = set_quota(&admin, Uuid::new_v4(), 5).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let limits: Vec<i32> =
+            sqlx::query_scalar("SELECT quota_limit FROM users WHERE id = ANY($1) ORDER BY email")
+                .bind(vec![admin.id, other.id, customer.id])
+                .fetch_all(&state.db)
+                .await
+                .unwrap();
+        assert_eq!(limits, vec![100, 5, 100]);
+    }
+}
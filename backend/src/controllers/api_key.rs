@@ -0,0 +1,80 @@
+//! API key controller - project-scoped submission keys
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    Extension,
+};
+use uuid::Uuid;
+
+use crate::dto::{ApiKeyResponse, ApiResponse, CreateApiKeyRequest, CreatedApiKeyResponse};
+use crate::error::{AppError, Result};
+use crate::models::User;
+use crate::state::ReadyAppState;
+
+/// GET /api/v1/projects/:id/api-keys - List a project's API keys
+pub async fn list_api_keys(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<ApiKeyResponse>>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    state.projects.get_managed(id, user.id).await?;
+    let keys = state.api_keys.list(id).await?;
+
+    Ok(Json(ApiResponse::success(
+        keys.into_iter().map(ApiKeyResponse::from).collect(),
+    )))
+}
+
+/// POST /api/v1/projects/:id/api-keys - Generate an API key
+pub async fn create_api_key(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+    req: Option<Json<CreateApiKeyRequest>>,
+) -> Result<(StatusCode, Json<ApiResponse<CreatedApiKeyResponse>>)> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+    req.validate().map_err(AppError::validation)?;
+    state.projects.get_managed(id, user.id).await?;
+
+    let (api_key, key) = state
+        .api_keys
+        .create(id, req.label.as_deref().unwrap_or_default())
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ApiResponse::success(CreatedApiKeyResponse {
+            key,
+            api_key: ApiKeyResponse::from(api_key),
+        })),
+    ))
+}
+
+/// DELETE /api/v1/projects/:id/api-keys/:key_id - Revoke an API key
+pub async fn revoke_api_key(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path((id, key_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ApiResponse<ApiKeyResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    state.projects.get_managed(id, user.id).await?;
+    let api_key = state.api_keys.revoke(key_id, id).await?;
+
+    Ok(Json(ApiResponse::success(ApiKeyResponse::from(api_key))))
+}
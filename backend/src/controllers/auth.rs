@@ -5,7 +5,7 @@
 
 use axum::{
     extract::{Query, State},
-    http::StatusCode,
+    http::{header, HeaderMap, StatusCode},
     response::{IntoResponse, Redirect, Response},
     Extension, Json,
 };
@@ -14,8 +14,9 @@ use rand::Rng;
 use serde::Deserialize;
 
 use crate::dto::{
-    ApiResponse, AuthResponse, CompleteOnboardingRequest, GoogleTokenRequest, LoginRequest,
-    RefreshTokenRequest, RegisterRequest, UserResponse,
+    ApiResponse, AuthResponse, CompleteOnboardingRequest, DeleteAccountRequest, GoogleTokenRequest,
+    IntrospectTokenRequest, LoginRequest, MessageResponse, RefreshTokenRequest, RegisterRequest,
+    TokenValidationResponse, UserResponse, ValidatedJson,
 };
 use crate::error::{AppError, Result};
 use crate::models::{User, UserRole};
@@ -24,7 +25,7 @@ use crate::state::ReadyAppState;
 /// POST /api/v1/auth/register - Register with email/password
 pub async fn register(
     State(ready): State<ReadyAppState>,
-    Json(req): Json<RegisterRequest>,
+    ValidatedJson(req): ValidatedJson<RegisterRequest>,
 ) -> Result<(StatusCode, Json<ApiResponse<AuthResponse>>)> {
     let state = ready.get_or_unavailable().await?;
     let role = req.role.unwrap_or(UserRole::Internal);
@@ -40,7 +41,7 @@ pub async fn register(
 /// POST /api/v1/auth/login - Login with email/password
 pub async fn login(
     State(ready): State<ReadyAppState>,
-    Json(req): Json<LoginRequest>,
+    ValidatedJson(req): ValidatedJson<LoginRequest>,
 ) -> Result<Json<ApiResponse<AuthResponse>>> {
     let state = ready.get_or_unavailable().await?;
     let response = state.auth.login(&req.email, &req.password).await?;
@@ -100,10 +101,7 @@ pub async fn google_start(
             "Google OAuth is not configured. Set GOOGLE_CLIENT_ID and GOOGLE_CLIENT_SECRET.",
         ));
     }
-    let backend_redirect_uri = format!(
-        "{}/api/v1/auth/google/callback",
-        state.config.api_url.trim_end_matches('/')
-    );
+    let backend_redirect_uri = state.config.google_redirect_uri();
     tracing::info!(
         "Google OAuth redirect_uri sent to Google: {}",
         backend_redirect_uri
@@ -210,10 +208,7 @@ pub async fn google_callback(
         let redirect = format!("{}/auth?error=server_config", frontend_url);
         return Redirect::temporary(redirect.as_str()).into_response();
     }
-    let redirect_uri = format!(
-        "{}/api/v1/auth/google/callback",
-        state.config.api_url.trim_end_matches('/')
-    );
+    let redirect_uri = state.config.google_redirect_uri();
     // Exchange code for tokens
     let token_url = "https://oauth2.googleapis.com/token";
     let body = format!(
@@ -324,6 +319,25 @@ pub async fn refresh_token(
     Ok(Json(ApiResponse::success(response)))
 }
 
+/// POST /api/v1/auth/introspect - Check whether an access token is valid
+///
+/// Request body: `{ "token": "..." }`. An expired, malformed or orphaned token is simply
+/// `valid: false`; the reason is not disclosed.
+pub async fn introspect_token(
+    State(ready): State<ReadyAppState>,
+    Json(req): Json<IntrospectTokenRequest>,
+) -> Result<Json<ApiResponse<TokenValidationResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    let user = match state.auth.validate_access_token(&req.token) {
+        Ok(claims) => state.auth.find_user_by_id(&claims.sub).await?,
+        Err(_) => None,
+    };
+    Ok(Json(ApiResponse::success(TokenValidationResponse {
+        valid: user.is_some(),
+        user: user.map(UserResponse::from),
+    })))
+}
+
 /// GET /api/v1/auth/me - Get current user info
 pub async fn get_current_user(
     Extension(user): Extension<User>,
@@ -331,11 +345,44 @@ pub async fn get_current_user(
     Ok(Json(ApiResponse::success(UserResponse::from(user))))
 }
 
+/// DELETE /api/v1/auth/me - Delete the current user's account and everything they own
+///
+/// Request body: `{ "password": "..." }`, or `{}` with an access token from the last few
+/// minutes (for Google accounts, sign in again first).
+pub async fn delete_account(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    headers: HeaderMap,
+    Json(req): Json<DeleteAccountRequest>,
+) -> Result<Json<ApiResponse<MessageResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    let access_token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .unwrap_or_default();
+    state
+        .auth
+        .verify_reauthentication(&user, req.password.as_deref(), access_token)?;
+
+    let files = state.auth.delete_account(&user.id).await?;
+    // The rows are gone; a file left behind is only logged
+    for path in files {
+        if let Err(e) = state.storage.delete(&path).await {
+            tracing::warn!(path = %path, error = %e, "Failed to delete file of deleted account");
+        }
+    }
+
+    Ok(Json(ApiResponse::success(MessageResponse::new(
+        "Account deleted",
+    ))))
+}
+
 /// POST /api/v1/auth/onboarding - Complete customer onboarding
 pub async fn complete_onboarding(
     State(ready): State<ReadyAppState>,
     Extension(user): Extension<User>,
-    Json(req): Json<CompleteOnboardingRequest>,
+    ValidatedJson(req): ValidatedJson<CompleteOnboardingRequest>,
 ) -> Result<Json<ApiResponse<UserResponse>>> {
     let state = ready.get_or_unavailable().await?;
     if user.onboarding_completed {
@@ -424,3 +471,205 @@ async fn verify_google_token(id_token: &str, client_id: &str) -> Result<GoogleTo
 
     Ok(token_info)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::models::UserClaims;
+    use crate::test_support::{seed_project, seed_user, test_app};
+    use axum::body::Body;
+    use axum::http::Request;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use sqlx::PgPool;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn delete_account_removes_owned_data_and_files(pool: PgPool) {
+        let config = Config::test_default();
+        let (app, state) = test_app(pool.clone(), &config).await;
+        let password_hash = state.auth.hash_password("correct horse").unwrap();
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            INSERT INTO users (email, role, onboarding_completed, quota_limit, quota_used, password_hash)
+            VALUES ('leaving@example.com', 'internal', TRUE, 100, 0, $1)
+            RETURNING *
+            "#,
+        )
+        .bind(&password_hash)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let other_id = seed_user(&pool, "staying@example.com", "internal").await.id;
+        let mut project_ids = Vec::new();
+        for owner_id in [user.id, other_id] {
+            let project_id = seed_project(&pool, owner_id).await;
+            project_ids.push(project_id);
+        }
+        // A ticket in their own project, one they submitted elsewhere, and one they only chatted on
+        let mut ticket_ids = Vec::new();
+        for (project_id, customer_id, video) in [
+            (project_ids[0], other_id, Some("videos/own-project.webm")),
+            (project_ids[1], user.id, Some("videos/submitted.webm")),
+            (project_ids[1], other_id, None),
+        ] {
+            let ticket_id = sqlx::query_scalar::<_, Uuid>(
+                "INSERT INTO recordings (project_id, customer_id, status, video_storage_path) VALUES ($1, $2, 'analyzed', $3) RETURNING id",
+            )
+            .bind(project_id)
+            .bind(customer_id)
+            .bind(video)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            ticket_ids.push(ticket_id);
+        }
+        sqlx::query("INSERT INTO chat_messages (recording_id, sender_id, message) VALUES ($1, $2, 'Looking into it'), ($1, $3, 'Thanks')")
+            .bind(ticket_ids[2])
+            .bind(user.id)
+            .bind(other_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        for path in ["videos/own-project.webm", "videos/submitted.webm"] {
+            state.storage.upload(path, b"video").await.unwrap();
+        }
+
+        let fresh_token = state.auth.generate_tokens(&user).unwrap().0;
+        let now = chrono::Utc::now().timestamp();
+        let stale_token = encode(
+            &Header::default(),
+            &UserClaims {
+                sub: user.id,
+                email: "leaving@example.com".to_string(),
+                role: user.role,
+                exp: now + 600,
+                iat: now - 600,
+            },
+            &EncodingKey::from_secret(config.jwt_secret.as_bytes()),
+        )
+        .unwrap();
+        let storage = state.storage.clone();
+        let request = |token: &str, body: serde_json::Value| {
+            Request::builder()
+                .method("DELETE")
+                .uri("/api/v1/auth/me")
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        // Re-authentication is required: a wrong password or an old token is refused
+        let response = app
+            .clone()
+            .oneshot(request(
+                &fresh_token,
+                serde_json::json!({ "password": "wrong" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = app
+            .clone()
+            .oneshot(request(&stale_token, serde_json::json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app
+            .oneshot(request(
+                &stale_token,
+                serde_json::json!({ "password": "correct horse" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let users: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM users WHERE id = ANY($1)")
+            .bind([user.id, other_id])
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(users, vec![other_id]);
+        let projects: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM projects")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(projects, vec![project_ids[1]]);
+        let tickets: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM recordings")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(tickets, vec![ticket_ids[2]]);
+        let messages: Vec<String> = sqlx::query_scalar("SELECT message FROM chat_messages")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(messages, vec!["Thanks"]);
+        for path in ["videos/own-project.webm", "videos/submitted.webm"] {
+            assert!(!storage.exists(path).await.unwrap(), "{path} still stored");
+        }
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn introspect_reports_only_whether_a_token_is_valid(pool: PgPool) {
+        let config = Config::test_default();
+        let (app, state) = test_app(pool.clone(), &config).await;
+        let user = seed_user(&pool, "gateway@example.com", "customer").await;
+        let (access_token, refresh_token, _) = state.auth.generate_tokens(&user).unwrap();
+        let now = chrono::Utc::now().timestamp();
+        let expired_token = encode(
+            &Header::default(),
+            &UserClaims {
+                sub: user.id,
+                email: "gateway@example.com".to_string(),
+                role: user.role,
+                exp: now - 3600,
+                iat: now - 7200,
+            },
+            &EncodingKey::from_secret(config.jwt_secret.as_bytes()),
+        )
+        .unwrap();
+        let introspect = |token: &str| {
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/auth/introspect")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    serde_json::json!({ "token": token }).to_string(),
+                ))
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(introspect(&access_token))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["data"]["valid"], true);
+        assert_eq!(body["data"]["user"]["id"], user.id.to_string());
+        assert_eq!(body["data"]["user"]["email"], "gateway@example.com");
+
+        for token in [expired_token.as_str(), refresh_token.as_str(), "not-a-jwt"] {
+            let response = app.clone().oneshot(introspect(token)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(
+                body["data"],
+                serde_json::json!({ "valid": false, "user": null })
+            );
+        }
+    }
+}
@@ -1,27 +1,50 @@
 //! Chat controller
 
+use std::sync::Arc;
+
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
-    response::Json,
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, Query, State,
+    },
+    http::{header, HeaderMap, StatusCode},
+    response::{Json, Response},
     Extension,
 };
+use tokio::sync::broadcast::{self, error::RecvError};
 use uuid::Uuid;
+use validator::Validate;
 
 use crate::dto::{
-    ApiResponse, ChatMessageResponse, EditMessageRequest, MessageResponse, SendMessageRequest,
+    ApiResponse, ChatMessageResponse, ChatMessagesQuery, ChatMessagesResponse, ChatSocketFrame,
+    ChatSocketQuery, EditMessageRequest, MarkChatReadRequest, MessageResponse, SendMessageRequest,
+    ValidatedJson,
 };
-use crate::error::Result;
+use crate::error::{AppError, Result};
+use crate::middleware::authenticate_token;
 use crate::models::User;
-use crate::state::ReadyAppState;
+use crate::services::{ChatEvent, MessageCursor};
+use crate::state::{AppState, ReadyAppState};
 
 /// GET /api/v1/recordings/:id/messages - Get chat messages for a recording
+///
+/// `?limit=&before=` switch to newest-first pages for long threads.
 pub async fn get_messages(
     State(ready): State<ReadyAppState>,
     Extension(user): Extension<User>,
     Path(recording_id): Path<Uuid>,
-) -> Result<Json<ApiResponse<Vec<ChatMessageResponse>>>> {
+    Query(query): Query<ChatMessagesQuery>,
+) -> Result<Json<ApiResponse<ChatMessagesResponse>>> {
     let state = ready.get_or_unavailable().await?;
+    query.validate().map_err(AppError::validation)?;
+    let before = query
+        .before
+        .as_deref()
+        .map(|token| {
+            MessageCursor::decode(token).ok_or_else(|| AppError::validation("Invalid cursor"))
+        })
+        .transpose()?;
+
     // Verify access
     state
         .chat
@@ -29,7 +52,24 @@ pub async fn get_messages(
         .await?;
 
     // Get messages
-    let messages = state.chat.get_messages(recording_id, user.id).await?;
+    let messages = if query.is_paged() {
+        let limit = query.limit.unwrap_or(ChatMessagesQuery::DEFAULT_LIMIT);
+        let (messages, next_cursor) = state
+            .chat
+            .get_messages_paged(recording_id, user.id, user.role, before, limit)
+            .await?;
+        ChatMessagesResponse::Page {
+            messages,
+            next_cursor,
+        }
+    } else {
+        ChatMessagesResponse::All(
+            state
+                .chat
+                .get_messages(recording_id, user.id, user.role)
+                .await?,
+        )
+    };
 
     Ok(Json(ApiResponse::success(messages)))
 }
@@ -39,7 +79,7 @@ pub async fn send_message(
     State(ready): State<ReadyAppState>,
     Extension(user): Extension<User>,
     Path(recording_id): Path<Uuid>,
-    Json(req): Json<SendMessageRequest>,
+    ValidatedJson(req): ValidatedJson<SendMessageRequest>,
 ) -> Result<(StatusCode, Json<ApiResponse<ChatMessageResponse>>)> {
     let state = ready.get_or_unavailable().await?;
     // Verify access
@@ -57,12 +97,37 @@ pub async fn send_message(
     Ok((StatusCode::CREATED, Json(ApiResponse::success(message))))
 }
 
+/// POST /api/v1/tickets/:id/messages/read - Mark messages read up to one (default: newest)
+pub async fn mark_messages_read(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(recording_id): Path<Uuid>,
+    req: Option<Json<MarkChatReadRequest>>,
+) -> Result<Json<ApiResponse<MessageResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    // Verify access
+    state
+        .chat
+        .verify_access(recording_id, user.id, user.role)
+        .await?;
+
+    let Json(req) = req.unwrap_or_default();
+    state
+        .chat
+        .mark_read(recording_id, user.id, req.message_id)
+        .await?;
+
+    Ok(Json(ApiResponse::success(MessageResponse::new(
+        "Messages marked as read",
+    ))))
+}
+
 /// PUT /api/v1/recordings/:recording_id/messages/:message_id - Edit a message
 pub async fn edit_message(
     State(ready): State<ReadyAppState>,
     Extension(user): Extension<User>,
     Path((recording_id, message_id)): Path<(Uuid, Uuid)>,
-    Json(req): Json<EditMessageRequest>,
+    ValidatedJson(req): ValidatedJson<EditMessageRequest>,
 ) -> Result<Json<ApiResponse<MessageResponse>>> {
     let state = ready.get_or_unavailable().await?;
     // Verify access to recording
@@ -102,3 +167,164 @@ pub async fn delete_message(
         "Message deleted",
     ))))
 }
+
+/// GET /api/v1/tickets/:id/ws - Live chat for a ticket over WebSocket
+///
+/// Authenticates with the Authorization header or, for browsers, `?token=`. The server
+/// pushes every new message as `{"type":"message","message":{...}}`; clients send
+/// `{"message":"..."}` text frames to post.
+pub async fn chat_ws(
+    State(ready): State<ReadyAppState>,
+    Path(recording_id): Path<Uuid>,
+    Query(query): Query<ChatSocketQuery>,
+    headers: HeaderMap,
+    ws: WebSocketUpgrade,
+) -> Result<Response> {
+    let state = ready.get_or_unavailable().await?;
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::to_string)
+        .or(query.token)
+        .ok_or_else(AppError::unauthorized)?;
+    let user = authenticate_token(&state, &token).await?;
+
+    // Verify access
+    state
+        .chat
+        .verify_access(recording_id, user.id, user.role)
+        .await?;
+
+    // Subscribe before answering so nothing sent after the handshake is missed
+    let events = state.chat.subscribe(recording_id);
+    Ok(ws.on_upgrade(move |socket| chat_socket(socket, events, state, user, recording_id)))
+}
+
+async fn chat_socket(
+    mut socket: WebSocket,
+    mut events: broadcast::Receiver<ChatEvent>,
+    state: Arc<AppState>,
+    user: User,
+    recording_id: Uuid,
+) {
+    loop {
+        let frame = tokio::select! {
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Text(text))) => {
+                    match post_socket_message(&state, &user, recording_id, &text).await {
+                        // Our own message comes back through the broadcast
+                        Ok(()) => continue,
+                        Err(error) => ChatSocketFrame::Error { error },
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // Pings are answered by axum; binary frames are ignored
+                Some(Ok(_)) => continue,
+            },
+            event = events.recv() => match event {
+                Ok(event) => ChatSocketFrame::Message {
+                    message: event.message_for(user.id),
+                },
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(%recording_id, skipped, "Chat socket lagged behind");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            },
+        };
+
+        let Ok(text) = serde_json::to_string(&frame) else {
+            continue;
+        };
+        if socket.send(Message::Text(text)).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Post a message received on the socket; `Err` is shown to the sender
+async fn post_socket_message(
+    state: &AppState,
+    user: &User,
+    recording_id: Uuid,
+    text: &str,
+) -> std::result::Result<(), String> {
+    let req: SendMessageRequest =
+        serde_json::from_str(text).map_err(|e| format!("Invalid message: {}", e))?;
+    req.validate().map_err(|e| e.to_string())?;
+    state
+        .chat
+        .send_message(recording_id, user.id, user.role, req)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::test_support::{seed_project, seed_ticket, seed_user, test_app};
+    use futures::{SinkExt, StreamExt};
+    use sqlx::PgPool;
+    use std::net::SocketAddr;
+    use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn messages_reach_other_sockets_on_the_ticket(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "internal").await;
+        let customer = seed_user(&pool, "customer@example.com", "customer").await;
+        let project_id = seed_project(&pool, owner.id).await;
+        let ticket_id = seed_ticket(&pool, project_id, customer.id, "completed").await;
+
+        let config = Config::test_default();
+        let (app, state) = test_app(pool, &config).await;
+        let (owner_token, _, _) = state.auth.generate_tokens(&owner).unwrap();
+        let (customer_token, _, _) = state.auth.generate_tokens(&customer).unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await
+            .unwrap();
+        });
+
+        let url = |token: &str| {
+            format!(
+                "ws://{}/api/v1/tickets/{}/ws?token={}",
+                addr, ticket_id, token
+            )
+        };
+        let (mut owner_ws, _) = tokio_tungstenite::connect_async(url(&owner_token))
+            .await
+            .unwrap();
+        let (mut customer_ws, _) = tokio_tungstenite::connect_async(url(&customer_token))
+            .await
+            .unwrap();
+
+        customer_ws
+            .send(WsMessage::Text(r#"{"message":"It crashed again"}"#.into()))
+            .await
+            .unwrap();
+
+        let frame = tokio::time::timeout(std::time::Duration::from_secs(5), owner_ws.next())
+            .await
+            .expect("no message within 5s")
+            .unwrap()
+            .unwrap();
+        let frame: serde_json::Value = serde_json::from_str(frame.to_text().unwrap()).unwrap();
+        assert_eq!(frame["type"], "message");
+        assert_eq!(frame["message"]["message"], "It crashed again");
+        assert_eq!(frame["message"]["recording_id"], ticket_id.to_string());
+        assert_eq!(frame["message"]["is_own"], false);
+
+        // Strangers cannot open the socket
+        let stranger = url("not-a-token");
+        assert!(tokio_tungstenite::connect_async(stranger).await.is_err());
+    }
+}
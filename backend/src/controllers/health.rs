@@ -1,29 +1,118 @@
 //! Health check controller
 
+use std::time::Duration;
+
 use axum::{extract::State, http::StatusCode, response::Json};
 use serde::Serialize;
 
 use crate::state::ReadyAppState;
 
+/// How long readiness waits on the storage backend before calling it unavailable
+const STORAGE_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
 #[derive(Serialize)]
 pub struct HealthResponse {
     pub status: &'static str,
     pub service: &'static str,
     pub version: &'static str,
+    /// Storage sub-check (`ok` / `unavailable`); only reported by readiness once the
+    /// database answers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage: Option<&'static str>,
 }
 
-/// GET /health - Health check endpoint (returns 503 until DB and services are ready)
-pub async fn health(State(ready): State<ReadyAppState>) -> (StatusCode, Json<HealthResponse>) {
-    let (status, status_str) = match ready.get().await {
-        Some(_) => (StatusCode::OK, "ok"),
-        None => (StatusCode::SERVICE_UNAVAILABLE, "starting"),
-    };
+fn health_response(
+    status: StatusCode,
+    status_str: &'static str,
+) -> (StatusCode, Json<HealthResponse>) {
     (
         status,
         Json(HealthResponse {
             status: status_str,
             service: "ortrace-api",
             version: env!("CARGO_PKG_VERSION"),
+            storage: None,
         }),
     )
 }
+
+/// GET /health/live - Liveness check: 200 whenever the process is serving requests,
+/// including while startup (DB connect + migrations) is still running
+pub async fn health_live() -> (StatusCode, Json<HealthResponse>) {
+    health_response(StatusCode::OK, "alive")
+}
+
+/// GET /health, GET /health/ready - Readiness check (returns 503 until DB and services are
+/// ready, or while the database or storage does not answer)
+pub async fn health(State(ready): State<ReadyAppState>) -> (StatusCode, Json<HealthResponse>) {
+    let Some(state) = ready.get().await else {
+        return health_response(StatusCode::SERVICE_UNAVAILABLE, "starting");
+    };
+    if let Err(e) = sqlx::query("SELECT 1").execute(&state.db).await {
+        tracing::warn!(error = %e, "Readiness check could not reach the database");
+        return health_response(StatusCode::SERVICE_UNAVAILABLE, "unavailable");
+    }
+    let storage_ok = match tokio::time::timeout(STORAGE_PROBE_TIMEOUT, state.storage.probe()).await
+    {
+        Ok(Ok(())) => true,
+        Ok(Err(e)) => {
+            tracing::warn!(error = %e, "Readiness check could not reach storage");
+            false
+        }
+        Err(_) => {
+            tracing::warn!("Readiness check timed out waiting for storage");
+            false
+        }
+    };
+    let (status, Json(mut body)) = if storage_ok {
+        health_response(StatusCode::OK, "ok")
+    } else {
+        health_response(StatusCode::SERVICE_UNAVAILABLE, "unavailable")
+    };
+    body.storage = Some(if storage_ok { "ok" } else { "unavailable" });
+    (status, Json(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::state::AppState;
+    use sqlx::PgPool;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn live_is_ok_before_startup_finishes() {
+        let (status, Json(body)) = health_live().await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.status, "alive");
+    }
+
+    #[tokio::test]
+    async fn ready_is_unavailable_before_state_is_set() {
+        let (status, Json(body)) = health(State(ReadyAppState::new())).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body.status, "starting");
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn ready_tracks_the_database(pool: PgPool) {
+        let ready = ReadyAppState::new();
+        let state = AppState::new(Config::test_default(), pool.clone())
+            .await
+            .unwrap();
+        ready.set(Arc::new(state)).await;
+
+        let (status, Json(body)) = health(State(ready.clone())).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.storage, Some("ok"));
+
+        pool.close().await;
+        let (status, Json(body)) = health(State(ready)).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body.status, "unavailable");
+        let (status, _) = health_live().await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
@@ -0,0 +1,51 @@
+//! API metadata controller
+
+use axum::response::Json;
+
+use crate::dto::{ApiResponse, ErrorCodeInfo};
+use crate::error::ErrorCode;
+
+/// GET /api/v1/meta/error-codes - Every `code` an error response can carry, with its HTTP status
+pub async fn list_error_codes() -> Json<ApiResponse<Vec<ErrorCodeInfo>>> {
+    let codes = ErrorCode::ALL
+        .into_iter()
+        .map(ErrorCodeInfo::from)
+        .collect();
+    Json(ApiResponse::success(codes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::router::create_router;
+    use crate::state::ReadyAppState;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn error_codes_are_listed_without_login() {
+        let app = create_router(ReadyAppState::new(), &Config::test_default());
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/v1/meta/error-codes")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let codes = body["data"].as_array().unwrap();
+        assert_eq!(codes.len(), ErrorCode::ALL.len());
+        assert!(codes
+            .iter()
+            .any(|c| c["code"] == "STALE_UPDATE" && c["status"] == 409));
+    }
+}
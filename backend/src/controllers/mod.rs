@@ -1,15 +1,25 @@
 //! API controllers
 
+pub mod admin;
+pub mod api_key;
 pub mod auth;
 pub mod chat;
 pub mod health;
+pub mod meta;
 pub mod project;
+pub mod session;
 pub mod ticket;
+pub mod webhook;
 pub mod widget;
 
+pub use admin::*;
+pub use api_key::*;
 pub use auth::*;
 pub use chat::*;
 pub use health::*;
+pub use meta::*;
 pub use project::*;
+pub use session::*;
 pub use ticket::*;
+pub use webhook::*;
 pub use widget::*;
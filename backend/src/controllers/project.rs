@@ -1,32 +1,53 @@
 //! Project controller
 
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
-    response::Json,
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
     Extension,
 };
 use uuid::Uuid;
 
 use crate::dto::{
-    ApiResponse, CreateProjectRequest, MessageResponse, ProjectListItem, ProjectResponse,
-    UpdateProjectRequest,
+    AddProjectMemberRequest, ApiResponse, CreateProjectRequest, MessageResponse, PaginatedResponse,
+    ProjectAnalyticsQuery, ProjectAnalyticsResponse, ProjectIssuesQuery, ProjectListItem,
+    ProjectListQuery, ProjectResponse, ProjectUnreadResponse, QuestionTemplateRequest,
+    QuestionTemplateResponse, ReorderQuestionsRequest, TopIssuesQuery, TransferProjectRequest,
+    UpdateProjectRequest, ValidatedJson,
 };
 use crate::error::{AppError, Result};
-use crate::models::User;
+use crate::models::{AnalysisQuestions, Project, ProjectMember, User};
+use crate::services::{ProjectIssue, TopIssue};
 use crate::state::ReadyAppState;
 
 /// POST /api/v1/projects - Create a new project
 pub async fn create_project(
     State(ready): State<ReadyAppState>,
     Extension(user): Extension<User>,
-    Json(req): Json<CreateProjectRequest>,
+    ValidatedJson(req): ValidatedJson<CreateProjectRequest>,
 ) -> Result<(StatusCode, Json<ApiResponse<ProjectResponse>>)> {
     let state = ready.get_or_unavailable().await?;
     if !user.is_internal() {
         return Err(AppError::forbidden());
     }
 
+    if let Some(ref mapping) = req.outcome_mapping {
+        mapping.validate().map_err(AppError::validation)?;
+    }
+    if let Some(ref url) = req.slack_webhook_url {
+        Project::validate_slack_webhook_url(url).map_err(AppError::validation)?;
+    }
+    if let Some(ref url) = req.discord_webhook_url {
+        Project::validate_discord_webhook_url(url).map_err(AppError::validation)?;
+    }
+    if let Some(ref language) = req.report_language {
+        Project::validate_report_language(language).map_err(AppError::validation)?;
+    }
+    if let Some(ref jira) = req.jira {
+        jira.validate().map_err(AppError::validation)?;
+    }
+
     let project = state
         .projects
         .create(
@@ -36,6 +57,14 @@ pub async fn create_project(
             req.require_auth.unwrap_or(false),
             req.is_active.unwrap_or(true),
             req.analysis_questions.clone(),
+            req.outcome_mapping.clone(),
+            req.slack_webhook_url.as_deref(),
+            req.discord_webhook_url.as_deref(),
+            req.report_language.as_deref(),
+            req.jira.clone(),
+            req.gemini_params,
+            req.include_prior_sessions.unwrap_or(false),
+            req.max_duration_seconds,
         )
         .await?;
     let response = ProjectResponse::from_project(project, 0);
@@ -43,38 +72,79 @@ pub async fn create_project(
     Ok((StatusCode::CREATED, Json(ApiResponse::success(response))))
 }
 
-/// GET /api/v1/projects - List projects for current user
+/// GET /api/v1/projects - List projects for current user.
+/// Query params: search, is_active, page, per_page.
 pub async fn list_projects(
     State(ready): State<ReadyAppState>,
     Extension(user): Extension<User>,
-) -> Result<Json<ApiResponse<Vec<ProjectListItem>>>> {
+    Query(query): Query<ProjectListQuery>,
+) -> Result<Json<ApiResponse<PaginatedResponse<ProjectListItem>>>> {
     let state = ready.get_or_unavailable().await?;
     if !user.is_internal() {
         return Err(AppError::forbidden());
     }
+    query.validate().map_err(AppError::validation)?;
 
-    let projects = state.projects.list(user.id).await?;
-    let items: Vec<ProjectListItem> = futures::future::join_all(projects.into_iter().map(|p| {
-        let state = state.clone();
-        async move {
-            let ticket_count = state.projects.count_tickets(p.id).await.unwrap_or(0);
-            let require_auth = p.require_auth();
-            let analysis_questions = p.analysis_questions();
-            ProjectListItem {
-                id: p.id,
-                name: p.name,
-                domain: p.domain,
-                is_active: p.is_active,
-                require_auth,
-                analysis_questions,
-                created_at: p.created_at,
-                ticket_count,
-            }
-        }
-    }))
-    .await;
+    let (projects, total) = state
+        .projects
+        .list(
+            user.id,
+            query.search(),
+            query.is_active,
+            query.page,
+            query.per_page,
+        )
+        .await?;
+    let items = projects
+        .into_iter()
+        .map(|p| ProjectListItem::from_project(p.project, p.ticket_count))
+        .collect();
+
+    Ok(Json(ApiResponse::success(PaginatedResponse::new(
+        items,
+        total,
+        query.page,
+        query.per_page,
+    ))))
+}
+
+/// GET /api/v1/me/question-template - Analysis questions new projects start with
+pub async fn get_question_template(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+) -> Result<Json<ApiResponse<QuestionTemplateResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    let template = state.projects.question_template(user.id).await?;
+    let response = QuestionTemplateResponse {
+        is_default: template.is_none(),
+        analysis_questions: template.unwrap_or_default(),
+    };
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// PUT /api/v1/me/question-template - Set the analysis questions new projects start with
+pub async fn set_question_template(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Json(req): Json<QuestionTemplateRequest>,
+) -> Result<Json<ApiResponse<QuestionTemplateResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
 
-    Ok(Json(ApiResponse::success(items)))
+    let analysis_questions = state
+        .projects
+        .set_question_template(user.id, req.analysis_questions)
+        .await?;
+    Ok(Json(ApiResponse::success(QuestionTemplateResponse {
+        analysis_questions,
+        is_default: false,
+    })))
 }
 
 /// GET /api/v1/projects/:id - Get project by ID
@@ -88,7 +158,7 @@ pub async fn get_project(
         return Err(AppError::forbidden());
     }
 
-    let project = state.projects.get_owned(id, user.id).await?;
+    let project = state.projects.get_accessible(id, user.id).await?;
     let ticket_count = state.projects.count_tickets(id).await.unwrap_or(0);
     let response = ProjectResponse::from_project(project, ticket_count);
 
@@ -100,7 +170,7 @@ pub async fn update_project(
     State(ready): State<ReadyAppState>,
     Extension(user): Extension<User>,
     Path(id): Path<Uuid>,
-    Json(req): Json<UpdateProjectRequest>,
+    ValidatedJson(req): ValidatedJson<UpdateProjectRequest>,
 ) -> Result<Json<ApiResponse<ProjectResponse>>> {
     let state = ready.get_or_unavailable().await?;
     if !user.is_internal() {
@@ -114,6 +184,11 @@ pub async fn update_project(
         is_active = ?req.is_active,
         require_auth = ?req.require_auth,
         has_analysis_questions = req.analysis_questions.is_some(),
+        has_outcome_mapping = req.outcome_mapping.is_some(),
+        has_slack_webhook_url = req.slack_webhook_url.is_some(),
+        has_discord_webhook_url = req.discord_webhook_url.is_some(),
+        report_language = ?req.report_language,
+        has_jira = req.jira.is_some(),
         "PUT /projects/:id - update request received"
     );
     if let Some(ref aq) = req.analysis_questions {
@@ -124,6 +199,21 @@ pub async fn update_project(
             "analysis_questions payload"
         );
     }
+    if let Some(ref mapping) = req.outcome_mapping {
+        mapping.validate().map_err(AppError::validation)?;
+    }
+    if let Some(ref url) = req.slack_webhook_url {
+        Project::validate_slack_webhook_url(url).map_err(AppError::validation)?;
+    }
+    if let Some(ref url) = req.discord_webhook_url {
+        Project::validate_discord_webhook_url(url).map_err(AppError::validation)?;
+    }
+    if let Some(ref language) = req.report_language {
+        Project::validate_report_language(language).map_err(AppError::validation)?;
+    }
+    if let Some(ref jira) = req.jira {
+        jira.validate().map_err(AppError::validation)?;
+    }
 
     let project = state
         .projects
@@ -135,6 +225,14 @@ pub async fn update_project(
             req.is_active,
             req.require_auth,
             req.analysis_questions.clone(),
+            req.outcome_mapping.clone(),
+            req.slack_webhook_url.as_deref(),
+            req.discord_webhook_url.as_deref(),
+            req.report_language.as_deref(),
+            req.jira.clone(),
+            req.gemini_params,
+            req.include_prior_sessions,
+            req.max_duration_seconds,
         )
         .await?;
     let ticket_count = state.projects.count_tickets(id).await.unwrap_or(0);
@@ -143,6 +241,224 @@ pub async fn update_project(
     Ok(Json(ApiResponse::success(response)))
 }
 
+/// GET /api/v1/projects/:id/unread - Count of tickets the current user hasn't read
+pub async fn get_project_unread(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<ProjectUnreadResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    state.projects.get_accessible(id, user.id).await?;
+    let unread_count = state.tickets.count_unread(id, user.id).await?;
+
+    Ok(Json(ApiResponse::success(ProjectUnreadResponse {
+        project_id: id,
+        unread_count,
+    })))
+}
+
+/// GET /api/v1/projects/:id/analytics?from=&to=&bucket=day|week - Project analytics
+/// (token usage totals plus ticket trends; the window defaults to the last 30 days)
+pub async fn get_project_analytics(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ProjectAnalyticsQuery>,
+) -> Result<Json<ApiResponse<ProjectAnalyticsResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    let (from, to) = query
+        .range(chrono::Utc::now())
+        .map_err(AppError::validation)?;
+    let trends = state
+        .projects
+        .get_trends(id, user.id, from, to, query.bucket)
+        .await?;
+    let usage = state.projects.get_analytics(id).await?;
+
+    Ok(Json(ApiResponse::success(ProjectAnalyticsResponse {
+        usage,
+        trends,
+    })))
+}
+
+/// GET /api/v1/projects/:id/issues/top?limit= - Issues recurring across the project's tickets
+pub async fn get_project_top_issues(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<TopIssuesQuery>,
+) -> Result<Json<ApiResponse<Vec<TopIssue>>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    let limit = query.limit().map_err(AppError::validation)?;
+    state.projects.get_accessible(id, user.id).await?;
+    let issues = state.tickets.top_issues(id, limit).await?;
+
+    Ok(Json(ApiResponse::success(issues)))
+}
+
+/// GET /api/v1/projects/:id/issues?severity=&tag=&page=&per_page= - Issues across the
+/// project's tickets, newest first, each with the ticket it came from
+pub async fn list_project_issues(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ProjectIssuesQuery>,
+) -> Result<Json<ApiResponse<PaginatedResponse<ProjectIssue>>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+    query.validate().map_err(AppError::validation)?;
+
+    state.projects.get_accessible(id, user.id).await?;
+    let (issues, total) = state
+        .tickets
+        .list_project_issues(id, query.severity, query.tag(), query.page, query.per_page)
+        .await?;
+
+    Ok(Json(ApiResponse::success(PaginatedResponse::new(
+        issues,
+        total,
+        query.page,
+        query.per_page,
+    ))))
+}
+
+/// GET /api/v1/projects/:id/tickets/export.csv - Download the project's tickets as CSV
+pub async fn export_project_tickets_csv(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Response> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    state.projects.get_accessible(id, user.id).await?;
+    let disposition = format!("attachment; filename=\"tickets-{}.csv\"", id);
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (header::CONTENT_DISPOSITION, disposition.as_str()),
+        ],
+        Body::from_stream(state.tickets.export_csv(id)),
+    )
+        .into_response())
+}
+
+/// POST /api/v1/projects/:id/transfer - Hand the project to another internal user
+pub async fn transfer_project(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+    ValidatedJson(req): ValidatedJson<TransferProjectRequest>,
+) -> Result<Json<ApiResponse<ProjectResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    let project = state
+        .projects
+        .transfer(id, user.id, &req.new_owner_email)
+        .await?;
+    let ticket_count = state.projects.count_tickets(id).await.unwrap_or(0);
+    Ok(Json(ApiResponse::success(ProjectResponse::from_project(
+        project,
+        ticket_count,
+    ))))
+}
+
+/// PUT /api/v1/projects/:id/questions/reorder - Set the order analysis questions are asked in
+pub async fn reorder_project_questions(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+    ValidatedJson(req): ValidatedJson<ReorderQuestionsRequest>,
+) -> Result<Json<ApiResponse<AnalysisQuestions>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    let questions = state
+        .projects
+        .reorder_questions(id, user.id, &req.ids)
+        .await?;
+    Ok(Json(ApiResponse::success(questions)))
+}
+
+/// GET /api/v1/projects/:id/members - Owner and members of a project
+pub async fn list_project_members(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<ProjectMember>>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    state.projects.get_accessible(id, user.id).await?;
+    let members = state.projects.list_members(id).await?;
+    Ok(Json(ApiResponse::success(members)))
+}
+
+/// POST /api/v1/projects/:id/members - Share the project with an internal user
+pub async fn add_project_member(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+    ValidatedJson(req): ValidatedJson<AddProjectMemberRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<ProjectMember>>)> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    state.projects.get_managed(id, user.id).await?;
+    let member = state.projects.add_member(id, &req.email, req.role).await?;
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(member))))
+}
+
+/// DELETE /api/v1/projects/:id/members/:user_id - Stop sharing the project with a user
+pub async fn remove_project_member(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path((id, member_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ApiResponse<MessageResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    // Members may leave on their own; removing others takes manage rights
+    if member_id == user.id {
+        state.projects.get_accessible(id, user.id).await?;
+    } else {
+        state.projects.get_managed(id, user.id).await?;
+    }
+    state.projects.remove_member(id, member_id).await?;
+    Ok(Json(ApiResponse::success(MessageResponse::new(
+        "Member removed",
+    ))))
+}
+
 /// DELETE /api/v1/projects/:id - Delete a project
 pub async fn delete_project(
     State(ready): State<ReadyAppState>,
@@ -159,3 +475,499 @@ pub async fn delete_project(
         "Project deleted",
     ))))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::test_support::{seed_project, seed_ticket, seed_user, test_app};
+    use axum::http::Request;
+    use sqlx::PgPool;
+    use tower::ServiceExt;
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn transfer_moves_project_and_tickets_to_new_owner(pool: PgPool) {
+        let old_owner = seed_user(&pool, "old@example.com", "internal").await;
+        let new_owner = seed_user(&pool, "new@example.com", "internal").await;
+        let customer = seed_user(&pool, "customer@example.com", "customer").await;
+        let project_id = seed_project(&pool, old_owner.id).await;
+        let ticket_id = seed_ticket(&pool, project_id, customer.id, "analyzed").await;
+
+        let config = Config::test_default();
+        let (app, state) = test_app(pool, &config).await;
+        let old_token = state.auth.generate_tokens(&old_owner).unwrap().0;
+        let new_token = state.auth.generate_tokens(&new_owner).unwrap().0;
+        let send = |method: &str, uri: String, token: &str, body: Option<serde_json::Value>| {
+            let request = Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .header(header::CONTENT_TYPE, "application/json");
+            let body = body.map_or_else(Body::empty, |b| Body::from(b.to_string()));
+            app.clone().oneshot(request.body(body).unwrap())
+        };
+        let transfer = |token: &str, email: &str| {
+            send(
+                "POST",
+                format!("/api/v1/projects/{}/transfer", project_id),
+                token,
+                Some(serde_json::json!({ "new_owner_email": email })),
+            )
+        };
+
+        // Customers can't be made owners, and only the owner can transfer
+        let response = transfer(&old_token, "customer@example.com").await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let response = transfer(&new_token, "new@example.com").await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = transfer(&old_token, "New@Example.com").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        for (token, project_status, ticket_status) in [
+            (&old_token, StatusCode::NOT_FOUND, StatusCode::NOT_FOUND),
+            (&new_token, StatusCode::OK, StatusCode::OK),
+        ] {
+            let response = send(
+                "GET",
+                format!("/api/v1/projects/{}", project_id),
+                token,
+                None,
+            )
+            .await
+            .unwrap();
+            assert_eq!(response.status(), project_status);
+            let response = send("GET", format!("/api/v1/tickets/{}", ticket_id), token, None)
+                .await
+                .unwrap();
+            assert_eq!(response.status(), ticket_status);
+        }
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn members_work_tickets_but_non_members_cannot(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "internal").await;
+        let member = seed_user(&pool, "member@example.com", "internal").await;
+        let outsider = seed_user(&pool, "outsider@example.com", "internal").await;
+        let customer = seed_user(&pool, "customer@example.com", "customer").await;
+        let project_id = seed_project(&pool, owner.id).await;
+        let ticket_id = seed_ticket(&pool, project_id, customer.id, "analyzed").await;
+
+        let config = Config::test_default();
+        let (app, state) = test_app(pool, &config).await;
+        let owner_token = state.auth.generate_tokens(&owner).unwrap().0;
+        let member_token = state.auth.generate_tokens(&member).unwrap().0;
+        let outsider_token = state.auth.generate_tokens(&outsider).unwrap().0;
+        let send = |method: &str, uri: String, token: &str, body: Option<serde_json::Value>| {
+            let request = Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .header(header::CONTENT_TYPE, "application/json");
+            let body = body.map_or_else(Body::empty, |b| Body::from(b.to_string()));
+            app.clone().oneshot(request.body(body).unwrap())
+        };
+        let json = |response: Response| async move {
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+        };
+
+        let response = send(
+            "POST",
+            format!("/api/v1/projects/{}/members", project_id),
+            &owner_token,
+            Some(serde_json::json!({ "email": "member@example.com" })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let response = send(
+            "GET",
+            format!("/api/v1/projects/{}/members", project_id),
+            &member_token,
+            None,
+        )
+        .await
+        .unwrap();
+        let members = json(response).await;
+        assert_eq!(members["data"][0]["user_id"], owner.id.to_string());
+        assert_eq!(members["data"][0]["role"], "owner");
+        assert_eq!(members["data"][1]["user_id"], member.id.to_string());
+        assert_eq!(members["data"][1]["role"], "member");
+
+        // Plain members can't manage the project
+        let response = send(
+            "POST",
+            format!("/api/v1/projects/{}/members", project_id),
+            &member_token,
+            Some(serde_json::json!({ "email": "outsider@example.com" })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let update = serde_json::json!({ "ticket_status": "in_progress" });
+        for (token, visible, update_status) in [
+            (&member_token, 1, StatusCode::OK),
+            (&outsider_token, 0, StatusCode::NOT_FOUND),
+        ] {
+            let response = send("GET", "/api/v1/tickets".to_string(), token, None)
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let tickets = json(response).await;
+            assert_eq!(tickets["data"]["total"], visible);
+
+            let response = send(
+                "PUT",
+                format!("/api/v1/tickets/{}", ticket_id),
+                token,
+                Some(update.clone()),
+            )
+            .await
+            .unwrap();
+            assert_eq!(response.status(), update_status);
+        }
+
+        // Once removed, the member loses access
+        let response = send(
+            "DELETE",
+            format!("/api/v1/projects/{}/members/{}", project_id, member.id),
+            &owner_token,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = send(
+            "GET",
+            format!("/api/v1/tickets/{}", ticket_id),
+            &member_token,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn read_all_clears_unread_until_a_ticket_changes(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "internal").await;
+        let other = seed_user(&pool, "other@example.com", "internal").await;
+        let customer = seed_user(&pool, "customer@example.com", "customer").await;
+        let project_id = seed_project(&pool, owner.id).await;
+        let other_project_id = seed_project(&pool, other.id).await;
+        let mut ticket_ids = Vec::new();
+        for _ in 0..2 {
+            let ticket_id = seed_ticket(&pool, project_id, customer.id, "analyzed").await;
+            ticket_ids.push(ticket_id);
+        }
+
+        let config = Config::test_default();
+        let (app, state) = test_app(pool, &config).await;
+        let token = state.auth.generate_tokens(&owner).unwrap().0;
+        let send = |method: &str, uri: String, body: Option<serde_json::Value>| {
+            let request = Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .header(header::CONTENT_TYPE, "application/json");
+            let body = body.map_or_else(Body::empty, |b| Body::from(b.to_string()));
+            app.clone().oneshot(request.body(body).unwrap())
+        };
+        let unread = |project_id: Uuid| async move {
+            let response = send(
+                "GET",
+                format!("/api/v1/projects/{}/unread", project_id),
+                None,
+            )
+            .await
+            .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()["data"]["unread_count"]
+                .as_i64()
+                .unwrap()
+        };
+
+        assert_eq!(unread(project_id).await, 2);
+
+        let response = send(
+            "POST",
+            format!("/api/v1/tickets/read-all?project_id={}", project_id),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(unread(project_id).await, 0);
+
+        let response = send(
+            "PUT",
+            format!("/api/v1/tickets/{}", ticket_ids[0]),
+            Some(serde_json::json!({ "priority": "urgent" })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(unread(project_id).await, 1);
+
+        let response = send(
+            "GET",
+            format!("/api/v1/projects/{}/unread", other_project_id),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response = send(
+            "POST",
+            format!("/api/v1/tickets/read-all?project_id={}", other_project_id),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn reordered_questions_persist(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "internal").await;
+        let project_id = seed_project(&pool, owner.id).await;
+
+        let config = Config::test_default();
+        let (app, state) = test_app(pool.clone(), &config).await;
+        let token = state.auth.generate_tokens(&owner).unwrap().0;
+        let reorder = |ids: serde_json::Value| {
+            let request = Request::builder()
+                .method("PUT")
+                .uri(format!("/api/v1/projects/{}/questions/reorder", project_id))
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(serde_json::json!({ "ids": ids }).to_string()))
+                .unwrap();
+            app.clone().oneshot(request)
+        };
+
+        let response = reorder(serde_json::json!(["bug-missing"])).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = reorder(serde_json::json!(["bug-workarounds", "bug-user-error"]))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let project = sqlx::query_as::<_, Project>("SELECT * FROM projects WHERE id = $1")
+            .bind(project_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let questions = project.analysis_questions();
+        let ids: Vec<&str> = questions.bug.iter().map(|q| q.id.as_str()).collect();
+        assert_eq!(ids, ["bug-workarounds", "bug-user-error", "bug-blocked"]);
+        assert_eq!(
+            questions.enabled_for_type(crate::models::FeedbackType::Bug)[0],
+            "Did the user try alternative paths or workarounds?"
+        );
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn new_projects_inherit_the_owners_question_template(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "internal").await;
+        let other = seed_user(&pool, "other@example.com", "internal").await;
+
+        let config = Config::test_default();
+        let (app, state) = test_app(pool, &config).await;
+        let owner_token = state.auth.generate_tokens(&owner).unwrap().0;
+        let other_token = state.auth.generate_tokens(&other).unwrap().0;
+        let send = |method: &str, uri: &str, token: &str, body: Option<serde_json::Value>| {
+            let request = Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .header(header::CONTENT_TYPE, "application/json");
+            let body = body.map_or_else(Body::empty, |b| Body::from(b.to_string()));
+            app.clone().oneshot(request.body(body).unwrap())
+        };
+        let json = |response: Response| async move {
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+        };
+        let create = |token: &str, name: &str| {
+            send(
+                "POST",
+                "/api/v1/projects",
+                token,
+                Some(serde_json::json!({ "name": name, "domain": "example.com" })),
+            )
+        };
+
+        let response = send("GET", "/api/v1/me/question-template", &owner_token, None)
+            .await
+            .unwrap();
+        assert_eq!(json(response).await["data"]["is_default"], true);
+
+        let template = serde_json::json!({
+            "analysis_questions": {
+                "bug": [{ "id": "org-bug", "text": "Which release?", "enabled": true, "is_custom": true }],
+                "feedback": [],
+                "idea": []
+            }
+        });
+        let response = send(
+            "PUT",
+            "/api/v1/me/question-template",
+            &owner_token,
+            Some(template),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = create(&owner_token, "Templated").await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let questions = &json(response).await["data"]["analysis_questions"];
+        assert_eq!(questions["bug"][0]["id"], "org-bug");
+        assert_eq!(questions["bug"].as_array().unwrap().len(), 1);
+        assert_eq!(questions["idea"].as_array().unwrap().len(), 0);
+
+        // Other owners still start from the built-in questions
+        let response = create(&other_token, "Default").await.unwrap();
+        let questions = &json(response).await["data"]["analysis_questions"];
+        assert_eq!(questions["bug"][0]["id"], "bug-blocked");
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn project_issues_filter_by_severity_and_tag(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "internal").await;
+        let outsider = seed_user(&pool, "outsider@example.com", "internal").await;
+        let customer = seed_user(&pool, "customer@example.com", "customer").await;
+        let mut project_ids = Vec::new();
+        for (owner_id, name) in [(owner.id, "App"), (outsider.id, "Other")] {
+            let project_id = sqlx::query_scalar::<_, Uuid>(
+                "INSERT INTO projects (owner_id, name, domain, settings) VALUES ($1, $2, $2 || '.example.com', '{}') RETURNING id",
+            )
+            .bind(owner_id)
+            .bind(name)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            project_ids.push(project_id);
+        }
+        let (project_id, other_project_id) = (project_ids[0], project_ids[1]);
+        let mut ticket_ids = Vec::new();
+        for (project_id, title, severity, tags) in [
+            (
+                project_id,
+                "Pay button dead",
+                "critical",
+                r#"["Frontend", "checkout"]"#,
+            ),
+            (project_id, "Slow search", "low", r#"["backend"]"#),
+            (project_id, "Orders lost", "critical", r#"["backend"]"#),
+            (
+                other_project_id,
+                "Other project crash",
+                "critical",
+                r#"["frontend"]"#,
+            ),
+        ] {
+            let ticket_id = seed_ticket(&pool, project_id, customer.id, "analyzed").await;
+            let report_id = sqlx::query_scalar::<_, Uuid>(
+                "INSERT INTO reports (recording_id) VALUES ($1) RETURNING id",
+            )
+            .bind(ticket_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            sqlx::query(
+                "INSERT INTO issues (report_id, title, severity, tags) VALUES ($1, $2, $3, $4::jsonb)",
+            )
+            .bind(report_id)
+            .bind(title)
+            .bind(severity)
+            .bind(tags)
+            .execute(&pool)
+            .await
+            .unwrap();
+            ticket_ids.push(ticket_id);
+        }
+
+        let config = Config::test_default();
+        let (app, state) = test_app(pool.clone(), &config).await;
+        let owner_token = state.auth.generate_tokens(&owner).unwrap().0;
+        let customer_token = state.auth.generate_tokens(&customer).unwrap().0;
+        let list = |query: &str, token: &str| {
+            let request = Request::builder()
+                .uri(format!("/api/v1/projects/{}/issues{}", project_id, query))
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap();
+            app.clone().oneshot(request)
+        };
+        let titles = |response: Response| async move {
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let body = serde_json::from_slice::<serde_json::Value>(&body).unwrap();
+            let mut titles: Vec<String> = body["data"]["items"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|issue| issue["title"].as_str().unwrap().to_string())
+                .collect();
+            titles.sort();
+            (titles, body["data"]["total"].as_i64().unwrap())
+        };
+
+        let (all, total) = titles(list("", &owner_token).await.unwrap()).await;
+        assert_eq!(total, 3);
+        assert_eq!(all, ["Orders lost", "Pay button dead", "Slow search"]);
+
+        let (critical, _) = titles(list("?severity=critical", &owner_token).await.unwrap()).await;
+        assert_eq!(critical, ["Orders lost", "Pay button dead"]);
+        let (frontend, _) = titles(list("?tag=frontend", &owner_token).await.unwrap()).await;
+        assert_eq!(frontend, ["Pay button dead"]);
+        let (both, _) = titles(
+            list("?severity=critical&tag=backend", &owner_token)
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(both, ["Orders lost"]);
+
+        // Pages carry the ticket reference
+        let response = list("?severity=low&per_page=1", &owner_token)
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = serde_json::from_slice::<serde_json::Value>(&body).unwrap();
+        assert_eq!(
+            body["data"]["items"][0]["ticket_id"],
+            ticket_ids[1].to_string()
+        );
+        assert_eq!(body["data"]["items"][0]["severity"], "low");
+        assert_eq!(body["data"]["total_pages"], 1);
+
+        let response = list("?per_page=0", &owner_token).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let response = list("", &customer_token).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+}
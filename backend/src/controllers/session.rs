@@ -0,0 +1,378 @@
+//! Session controller - legacy share-link feedback sessions
+//! Owners manage their sessions; any signed-in user holding a share link can submit to one.
+
+use axum::{
+    extract::{multipart::Multipart, Path, State},
+    http::StatusCode,
+    response::Json,
+    Extension,
+};
+use uuid::Uuid;
+
+use crate::controllers::widget::read_video_upload;
+use crate::dto::{
+    ApiResponse, CreateSessionRequest, MessageResponse, SessionResponse, SessionSubmitRequest,
+    SharedSessionResponse, UpdateSessionRequest, ValidatedJson, WidgetSubmitResponse,
+};
+use crate::error::{AppError, Result};
+use crate::models::{FeedbackType, User};
+use crate::state::ReadyAppState;
+
+/// GET /api/v1/sessions - List your sessions
+pub async fn list_sessions(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+) -> Result<Json<ApiResponse<Vec<SessionResponse>>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    let sessions = state.sessions.list(user.id).await?;
+    Ok(Json(ApiResponse::success(
+        sessions.into_iter().map(SessionResponse::from).collect(),
+    )))
+}
+
+/// POST /api/v1/sessions - Create a session and its share token
+pub async fn create_session(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    ValidatedJson(req): ValidatedJson<CreateSessionRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<SessionResponse>>)> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    let session = state
+        .sessions
+        .create(
+            user.id,
+            &req.name,
+            req.session_type.unwrap_or(FeedbackType::Feedback),
+            req.questions.as_deref().unwrap_or_default(),
+        )
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ApiResponse::success(SessionResponse::from(session))),
+    ))
+}
+
+/// GET /api/v1/sessions/:id - Get one of your sessions
+pub async fn get_session(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<SessionResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    let session = state.sessions.get_owned(id, user.id).await?;
+    Ok(Json(ApiResponse::success(SessionResponse::from(session))))
+}
+
+/// PUT /api/v1/sessions/:id - Rename a session or replace its questions
+pub async fn update_session(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+    ValidatedJson(req): ValidatedJson<UpdateSessionRequest>,
+) -> Result<Json<ApiResponse<SessionResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    let session = state
+        .sessions
+        .update(id, user.id, req.name.as_deref(), req.questions.as_deref())
+        .await?;
+    Ok(Json(ApiResponse::success(SessionResponse::from(session))))
+}
+
+/// DELETE /api/v1/sessions/:id - Delete a session with its tickets
+pub async fn delete_session(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<MessageResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    state.sessions.delete(id, user.id).await?;
+    Ok(Json(ApiResponse::success(MessageResponse::new(
+        "Session deleted",
+    ))))
+}
+
+/// GET /api/v1/sessions/join/:token - Session behind a share link
+pub async fn get_shared_session(
+    State(ready): State<ReadyAppState>,
+    Path(token): Path<String>,
+) -> Result<Json<ApiResponse<SharedSessionResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    let session = state.sessions.get_by_share_token(&token).await?;
+    Ok(Json(ApiResponse::success(SharedSessionResponse::from(
+        session,
+    ))))
+}
+
+/// POST /api/v1/sessions/join/:token/tickets - Submit a ticket to a session as the
+/// signed-in user
+pub async fn submit_session_ticket(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(token): Path<String>,
+    ValidatedJson(req): ValidatedJson<SessionSubmitRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<WidgetSubmitResponse>>)> {
+    let state = ready.get_or_unavailable().await?;
+    let session = state.sessions.get_by_share_token(&token).await?;
+
+    let ticket = state
+        .tickets
+        .create_from_session(
+            &session,
+            &user,
+            req.description.as_deref(),
+            req.page_url.as_deref(),
+        )
+        .await?;
+
+    let response = WidgetSubmitResponse {
+        ticket_id: ticket.id,
+        message: "Feedback submitted successfully".to_string(),
+    };
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(response))))
+}
+
+/// POST /api/v1/sessions/join/:token/tickets/:id/upload - Upload the video of a ticket you
+/// submitted to the session
+pub async fn upload_session_video(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path((token, ticket_id)): Path<(String, Uuid)>,
+    mut multipart: Multipart,
+) -> Result<Json<ApiResponse<WidgetSubmitResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    let session = state.sessions.get_by_share_token(&token).await?;
+    let video = read_video_upload(&mut multipart, state.config.max_upload_mb).await?;
+
+    let ticket = state
+        .tickets
+        .get_by_id(ticket_id)
+        .await?
+        .filter(|t| t.session_id == Some(session.id) && t.customer_id == user.id)
+        .ok_or_else(|| AppError::not_found("Ticket not found"))?;
+
+    state
+        .tickets
+        .upload_video(
+            ticket.id,
+            user.id,
+            video.data,
+            video.duration_seconds,
+            video.content_type.as_deref(),
+            video.file_name.as_deref(),
+        )
+        .await?;
+
+    let response = WidgetSubmitResponse {
+        ticket_id,
+        message: "Video uploaded and processing started".to_string(),
+    };
+    Ok(Json(ApiResponse::success(response)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::test_support::{seed_user, test_app};
+    use axum::body::Body;
+    use axum::http::{header, Request};
+    use axum::Router;
+    use sqlx::PgPool;
+    use tower::ServiceExt;
+
+    async fn send(
+        app: &Router,
+        method: &str,
+        uri: &str,
+        token: &str,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::AUTHORIZATION, format!("Bearer {}", token));
+        if body.is_some() {
+            request = request.header(header::CONTENT_TYPE, "application/json");
+        }
+        let body = body.map_or_else(Body::empty, |b| Body::from(b.to_string()));
+        let response = app
+            .clone()
+            .oneshot(request.body(body).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn owners_create_and_manage_their_sessions(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "internal").await;
+        let other = seed_user(&pool, "other@example.com", "internal").await;
+        let customer = seed_user(&pool, "customer@example.com", "customer").await;
+
+        let config = Config::test_default();
+        let (app, state) = test_app(pool, &config).await;
+        let owner_token = state.auth.generate_tokens(&owner).unwrap().0;
+        let other_token = state.auth.generate_tokens(&other).unwrap().0;
+        let customer_token = state.auth.generate_tokens(&customer).unwrap().0;
+        let create = serde_json::json!({
+            "name": "  Checkout study  ",
+            "questions": ["Could you pay?", "  "],
+        });
+
+        let (status, _) = send(
+            &app,
+            "POST",
+            "/api/v1/sessions",
+            &customer_token,
+            Some(create.clone()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, body) =
+            send(&app, "POST", "/api/v1/sessions", &owner_token, Some(create)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let session = &body["data"];
+        assert_eq!(session["name"], "Checkout study");
+        assert_eq!(session["type"], "feedback");
+        assert_eq!(session["questions"], serde_json::json!(["Could you pay?"]));
+        assert!(!session["share_token"].as_str().unwrap().is_empty());
+        let uri = format!("/api/v1/sessions/{}", session["id"].as_str().unwrap());
+
+        let (status, body) = send(&app, "GET", "/api/v1/sessions", &owner_token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"].as_array().unwrap().len(), 1);
+        let (_, body) = send(&app, "GET", "/api/v1/sessions", &other_token, None).await;
+        assert!(body["data"].as_array().unwrap().is_empty());
+        let (status, _) = send(&app, "GET", &uri, &other_token, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let rename = serde_json::json!({ "name": "Checkout v2" });
+        let (status, _) = send(&app, "PUT", &uri, &other_token, Some(rename.clone())).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, body) = send(&app, "PUT", &uri, &owner_token, Some(rename)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["name"], "Checkout v2");
+        assert_eq!(
+            body["data"]["questions"],
+            serde_json::json!(["Could you pay?"])
+        );
+
+        let (status, _) = send(&app, "DELETE", &uri, &owner_token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = send(&app, "GET", &uri, &owner_token, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn session_tickets_are_visible_to_the_session_owner(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "internal").await;
+        let other = seed_user(&pool, "other@example.com", "internal").await;
+        let customer = seed_user(&pool, "customer@example.com", "customer").await;
+
+        let config = Config::test_default();
+        let (app, state) = test_app(pool.clone(), &config).await;
+        let owner_token = state.auth.generate_tokens(&owner).unwrap().0;
+        let other_token = state.auth.generate_tokens(&other).unwrap().0;
+        let customer_token = state.auth.generate_tokens(&customer).unwrap().0;
+
+        let create = serde_json::json!({ "name": "Bug bash", "type": "bug" });
+        let (_, body) = send(&app, "POST", "/api/v1/sessions", &owner_token, Some(create)).await;
+        let session_id = body["data"]["id"].as_str().unwrap().to_string();
+        let token = body["data"]["share_token"].as_str().unwrap().to_string();
+
+        let (status, body) = send(
+            &app,
+            "GET",
+            &format!("/api/v1/sessions/join/{}", token),
+            &customer_token,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["name"], "Bug bash");
+        assert!(body["data"].get("share_token").is_none());
+
+        let (status, _) = send(
+            &app,
+            "POST",
+            "/api/v1/sessions/join/not-a-token/tickets",
+            &customer_token,
+            Some(serde_json::json!({})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, body) = send(
+            &app,
+            "POST",
+            &format!("/api/v1/sessions/join/{}/tickets", token),
+            &customer_token,
+            Some(serde_json::json!({ "description": "Search returns nothing" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let ticket_id = body["data"]["ticket_id"].as_str().unwrap().to_string();
+        let ticket_uri = format!("/api/v1/tickets/{}", ticket_id);
+
+        let (status, body) = send(&app, "GET", &ticket_uri, &owner_token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["session_id"], session_id);
+        assert_eq!(body["data"]["project_name"], "Bug bash");
+        assert_eq!(body["data"]["feedback_type"], "bug");
+        assert_eq!(body["data"]["submitter_email"], "customer@example.com");
+        let (_, body) = send(&app, "GET", "/api/v1/tickets", &owner_token, None).await;
+        assert_eq!(body["data"]["items"][0]["id"], ticket_id);
+
+        let (status, _) = send(&app, "GET", &ticket_uri, &other_token, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (_, body) = send(&app, "GET", "/api/v1/tickets", &other_token, None).await;
+        assert!(body["data"]["items"].as_array().unwrap().is_empty());
+
+        let (status, _) = send(
+            &app,
+            "POST",
+            &format!("{}/close", ticket_uri),
+            &owner_token,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (session_status, closed_reason) = sqlx::query_as::<_, (String, Option<String>)>(
+            "SELECT session_status, closed_reason FROM recordings WHERE id = $1",
+        )
+        .bind(Uuid::parse_str(&ticket_id).unwrap())
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(session_status, "closed");
+        assert_eq!(closed_reason.as_deref(), Some("resolved"));
+    }
+}
@@ -1,24 +1,32 @@
 //! Ticket controller
 
 use axum::{
+    body::Body,
     extract::{Path, Query, State},
-    http::{header, StatusCode},
+    http::{header, HeaderMap, StatusCode},
     response::{IntoResponse, Json, Response},
     Extension,
 };
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
 use crate::dto::{
-    ApiResponse, MessageResponse, PaginatedResponse, TicketDetailResponse, TicketListItem,
-    TicketListQueryParams, UpdateTicketRequest,
+    AddTicketLabelRequest, ApiResponse, CreateTicketRequest, CreatedTicketShareResponse,
+    IssueExportResponse, IssueResponse, MarkAllReadQuery, MarkAllReadResponse, MergeTicketRequest,
+    MessageResponse, PaginatedResponse, ReportDiff, ReportHistoryResponse, ReportVersionResponse,
+    TicketDetailResponse, TicketExportResponse, TicketLabelsResponse, TicketListItem,
+    TicketListQueryParams, TicketShareResponse, UpdateIssueRequest, UpdateReportRequest,
+    UpdateTicketRequest, ValidatedJson,
 };
 use crate::error::{AppError, Result};
-use crate::models::User;
-use crate::services::TicketListQuery;
+use crate::models::{FeedbackTicket, ProcessingStatus, User};
+use crate::services::{
+    render_report_pdf, SortDirection, TicketCursor, TicketListQuery, TicketSortKey,
+};
 use crate::state::ReadyAppState;
 
 /// GET /api/v1/tickets - List tickets for internal user.
-/// Query params: project_id (optional, restricts to that project), feedback_type, ticket_status, priority, search, page, per_page.
+/// Query params: project_id (optional, restricts to that project), feedback_type, ticket_status, priority, search, assignee_id, created_after, created_before, label, sort_by, sort_dir, cursor, page, per_page.
 pub async fn list_tickets(
     State(ready): State<ReadyAppState>,
     Extension(user): Extension<User>,
@@ -28,28 +36,101 @@ pub async fn list_tickets(
     if !user.is_internal() {
         return Err(AppError::forbidden());
     }
+    let service_query = list_query(&query)?;
 
-    let service_query = TicketListQuery {
-        project_id: query.project_id,
-        feedback_type: query.feedback_type,
-        ticket_status: query.ticket_status,
-        priority: query.priority,
-        search: query.search.clone(),
-        page: query.page,
-        per_page: query.per_page,
-    };
+    let (tickets, total) = state
+        .tickets
+        .list_for_owner(user.id, service_query.clone())
+        .await?;
+    let next_cursor = service_query.next_cursor(&tickets);
+
+    let items: Vec<TicketListItem> = tickets
+        .into_iter()
+        .map(TicketListItem::from_details)
+        .collect();
 
-    let (tickets, total) = state.tickets.list_for_owner(user.id, service_query).await?;
+    let response = PaginatedResponse::new(items, total, query.page, query.per_page)
+        .with_next_cursor(next_cursor);
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// GET /api/v1/my/tickets - List tickets the current user submitted.
+/// Query params: project_id (optional), feedback_type, ticket_status, priority, search, assignee_id, created_after, created_before, label, sort_by, sort_dir, cursor, page, per_page.
+pub async fn list_my_tickets(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Query(query): Query<TicketListQueryParams>,
+) -> Result<Json<ApiResponse<PaginatedResponse<TicketListItem>>>> {
+    let state = ready.get_or_unavailable().await?;
+    let service_query = list_query(&query)?;
+
+    let (tickets, total) = state
+        .tickets
+        .list_for_customer(user.id, service_query.clone())
+        .await?;
+    let next_cursor = service_query.next_cursor(&tickets);
 
     let items: Vec<TicketListItem> = tickets
         .into_iter()
         .map(TicketListItem::from_details)
         .collect();
 
-    let response = PaginatedResponse::new(items, total, query.page, query.per_page);
+    let response = PaginatedResponse::new(items, total, query.page, query.per_page)
+        .with_next_cursor(next_cursor);
     Ok(Json(ApiResponse::success(response)))
 }
 
+/// Validate list query params and convert them for the ticket service
+fn list_query(query: &TicketListQueryParams) -> Result<TicketListQuery> {
+    query.validate().map_err(AppError::validation)?;
+
+    let cursor = query
+        .cursor
+        .as_deref()
+        .map(|token| {
+            TicketCursor::decode(token).ok_or_else(|| AppError::validation("Invalid cursor"))
+        })
+        .transpose()?;
+    let search = query
+        .search
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from);
+    let service_query = TicketListQuery {
+        project_id: query.project_id,
+        feedback_type: query.feedback_type,
+        ticket_status: query.ticket_status,
+        priority: query.priority,
+        search: search.clone(),
+        assignee_id: query.assignee_id,
+        created_after: query.created_after,
+        created_before: query.created_before,
+        sentiment: query.sentiment,
+        label: query
+            .label
+            .as_deref()
+            .map(FeedbackTicket::normalize_label)
+            .transpose()
+            .map_err(AppError::validation)?,
+        // Search results come best match first unless another order is asked for
+        sort_by: match query.sort_by.as_deref() {
+            None if search.is_some() => TicketSortKey::Relevance,
+            sort_by => TicketSortKey::from_param(sort_by),
+        },
+        sort_dir: SortDirection::from_param(query.sort_dir.as_deref()),
+        cursor,
+        page: query.page,
+        per_page: query.per_page,
+    };
+    if service_query.cursor.is_some() && !service_query.supports_cursor() {
+        return Err(AppError::validation(
+            "cursor pagination only supports the default created_at desc sort",
+        ));
+    }
+    Ok(service_query)
+}
+
 /// GET /api/v1/tickets/:id - Get ticket details
 pub async fn get_ticket(
     State(ready): State<ReadyAppState>,
@@ -57,25 +138,61 @@ pub async fn get_ticket(
     Path(id): Path<Uuid>,
 ) -> Result<Json<ApiResponse<TicketDetailResponse>>> {
     let state = ready.get_or_unavailable().await?;
-    let ticket = state
-        .tickets
-        .get_by_id(id)
-        .await?
-        .ok_or_else(|| AppError::not_found("Ticket not found"))?;
+    let ticket = load_accessible_ticket(&state, &user, id).await?;
 
-    // Check access: either owner of project or customer who submitted
-    if !user.is_internal() && ticket.customer_id != user.id {
+    if user.is_internal() {
+        if let Err(e) = state.tickets.mark_read(ticket.id, user.id).await {
+            tracing::warn!(ticket_id = %ticket.id, error = %e, "Failed to record ticket read");
+        }
+    }
+
+    let response = ticket_detail(&state, &user, ticket).await?;
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// POST /api/v1/tickets - Log a ticket by hand in one of your projects (internal users only)
+pub async fn create_ticket(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    ValidatedJson(req): ValidatedJson<CreateTicketRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<TicketDetailResponse>>)> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
         return Err(AppError::forbidden());
     }
 
+    let project = state
+        .projects
+        .get_accessible(req.project_id, user.id)
+        .await?;
+    let ticket = state
+        .tickets
+        .create_manual(
+            project.id,
+            user.id,
+            req.feedback_type,
+            req.description.trim(),
+            req.submitter_email.as_deref(),
+            req.submitter_name.as_deref(),
+            req.page_url.as_deref(),
+        )
+        .await?;
+
+    let response = ticket_detail(&state, &user, ticket).await?;
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(response))))
+}
+
+/// Ticket detail as seen by `user`
+async fn ticket_detail(
+    state: &crate::state::AppState,
+    user: &User,
+    ticket: FeedbackTicket,
+) -> Result<TicketDetailResponse> {
     let video_url = state.tickets.get_video_url(&ticket).await?;
+    let screenshot_url = state.tickets.get_screenshot_url(&ticket).await?;
 
-    // Get project name if available
-    let project_name = if let Some(project_id) = ticket.project_id {
-        state.projects.get_by_id(project_id).await?.map(|p| p.name)
-    } else {
-        None
-    };
+    // Project name, or the session name for legacy session-based tickets
+    let project_name = state.tickets.get_context_name(&ticket).await?;
 
     // Get assignee name if available
     let assignee_name = if let Some(assignee_id) = ticket.assignee_id {
@@ -90,13 +207,36 @@ pub async fn get_ticket(
 
     let ai_confidence: Option<i32> =
         sqlx::query_scalar("SELECT confidence FROM reports WHERE recording_id = $1")
-            .bind(id)
+            .bind(ticket.id)
             .fetch_optional(&state.db)
             .await?;
 
-    let response = TicketDetailResponse {
+    let labels = state.tickets.get_labels(ticket.id).await?;
+    let unread_count = state
+        .chat
+        .unread_count(ticket.id, user.id, user.role)
+        .await?;
+
+    let progress_stage = if ticket.status == ProcessingStatus::Processing {
+        state.tickets.get_progress_stage(ticket.id).await?
+    } else {
+        None
+    };
+
+    let job = state
+        .queue
+        .get_job_by_recording(ticket.id)
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to load analysis job: {}", e)))?;
+    let job_status = job.as_ref().map(|j| j.status);
+    let job_error = job
+        .and_then(|j| j.error_message)
+        .filter(|_| user.is_internal());
+
+    Ok(TicketDetailResponse {
         id: ticket.id,
         project_id: ticket.project_id,
+        session_id: ticket.session_id,
         project_name,
         feedback_type: ticket.feedback_type,
         ticket_status: ticket.ticket_status,
@@ -109,19 +249,60 @@ pub async fn get_ticket(
         category: ticket.category,
         page_url: ticket.page_url,
         browser_info: ticket.browser_info.0,
+        prior_experience: ticket.prior_experience,
         video_url,
+        screenshot_url,
         duration_seconds: ticket.duration_seconds,
         status: ticket.status,
+        progress_stage,
+        job_status,
+        job_error,
         ai_confidence,
         due_date: ticket.due_date,
+        labels,
+        unread_count,
         created_at: ticket.created_at,
         updated_at: ticket.updated_at,
-    };
+    })
+}
 
-    Ok(Json(ApiResponse::success(response)))
+/// GET /api/v1/tickets/:id/export - Everything stored about a ticket as one JSON document
+pub async fn export_ticket(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<TicketExportResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    let ticket = state.tickets.get_for_owner(id, user.id).await?;
+    let export = ticket_export(&state, &user, ticket).await?;
+    Ok(Json(ApiResponse::success(export)))
 }
 
-/// PUT /api/v1/tickets/:id - Update a ticket (status, priority, assignee)
+/// Ticket detail (with browser info), report with issues, and chat transcript
+pub(crate) async fn ticket_export(
+    state: &crate::state::AppState,
+    user: &User,
+    ticket: FeedbackTicket,
+) -> Result<TicketExportResponse> {
+    let report = find_report(state, &ticket).await?;
+    let chat = state
+        .chat
+        .get_messages(ticket.id, user.id, user.role)
+        .await?;
+    let ticket = ticket_detail(state, user, ticket).await?;
+    Ok(TicketExportResponse {
+        exported_at: chrono::Utc::now(),
+        ticket,
+        report,
+        chat,
+    })
+}
+
+/// PUT /api/v1/tickets/:id - Update a ticket (status, priority, assignee, due date)
 pub async fn update_ticket(
     State(ready): State<ReadyAppState>,
     Extension(user): Extension<User>,
@@ -133,6 +314,12 @@ pub async fn update_ticket(
         return Err(AppError::forbidden());
     }
 
+    if let Some(expected_updated_at) = req.expected_updated_at {
+        state
+            .tickets
+            .claim_unchanged(id, user.id, expected_updated_at)
+            .await?;
+    }
     if let Some(status) = req.ticket_status {
         state.tickets.update_status(id, user.id, status).await?;
     }
@@ -145,12 +332,75 @@ pub async fn update_ticket(
             .update_assignee(id, user.id, req.assignee_id)
             .await?;
     }
+    if let Some(due_date) = req.due_date {
+        state.tickets.update_due_date(id, user.id, due_date).await?;
+    }
 
     Ok(Json(ApiResponse::success(MessageResponse::new(
         "Ticket updated",
     ))))
 }
 
+/// POST /api/v1/tickets/read-all?project_id= - Mark all of a project's tickets as read
+pub async fn mark_all_tickets_read(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Query(query): Query<MarkAllReadQuery>,
+) -> Result<Json<ApiResponse<MarkAllReadResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    // Verify access
+    state
+        .projects
+        .get_accessible(query.project_id, user.id)
+        .await?;
+
+    let marked_count = state
+        .tickets
+        .mark_all_read(query.project_id, user.id)
+        .await?;
+    Ok(Json(ApiResponse::success(MarkAllReadResponse {
+        project_id: query.project_id,
+        marked_count,
+    })))
+}
+
+/// POST /api/v1/tickets/:id/labels - Add a label to a ticket
+pub async fn add_ticket_label(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<AddTicketLabelRequest>,
+) -> Result<Json<ApiResponse<TicketLabelsResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    let label = FeedbackTicket::normalize_label(&req.label).map_err(AppError::validation)?;
+    let labels = state.tickets.add_label(id, user.id, &label).await?;
+    Ok(Json(ApiResponse::success(TicketLabelsResponse { labels })))
+}
+
+/// DELETE /api/v1/tickets/:id/labels/:label - Remove a label from a ticket
+pub async fn remove_ticket_label(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path((id, label)): Path<(Uuid, String)>,
+) -> Result<Json<ApiResponse<TicketLabelsResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    let label = FeedbackTicket::normalize_label(&label).map_err(AppError::validation)?;
+    let labels = state.tickets.remove_label(id, user.id, &label).await?;
+    Ok(Json(ApiResponse::success(TicketLabelsResponse { labels })))
+}
+
 /// POST /api/v1/tickets/:id/close - Close a ticket
 pub async fn close_ticket(
     State(ready): State<ReadyAppState>,
@@ -168,6 +418,23 @@ pub async fn close_ticket(
     ))))
 }
 
+/// POST /api/v1/tickets/:id/reanalyze - Re-run analysis on the stored video
+pub async fn reanalyze_ticket(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<MessageResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    state.tickets.reanalyze(id, user.id).await?;
+    Ok(Json(ApiResponse::success(MessageResponse::new(
+        "Reanalysis queued",
+    ))))
+}
+
 /// POST /api/v1/tickets/:id/reopen - Reopen a ticket
 pub async fn reopen_ticket(
     State(ready): State<ReadyAppState>,
@@ -185,6 +452,97 @@ pub async fn reopen_ticket(
     ))))
 }
 
+/// POST /api/v1/my/tickets/:id/reopen - Reopen a resolved ticket the current user submitted
+pub async fn reopen_my_ticket(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<MessageResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+
+    state.tickets.reopen_for_customer(id, user.id).await?;
+    Ok(Json(ApiResponse::success(MessageResponse::new(
+        "Ticket reopened",
+    ))))
+}
+
+/// POST /api/v1/tickets/:id/merge - Merge a duplicate ticket into another one
+pub async fn merge_ticket(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<MergeTicketRequest>,
+) -> Result<Json<ApiResponse<MessageResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    state.tickets.merge(id, req.into_ticket_id, user.id).await?;
+    Ok(Json(ApiResponse::success(MessageResponse::new(
+        "Ticket merged",
+    ))))
+}
+
+/// POST /api/v1/tickets/:id/share - Mint a read-only share link for the report and video
+pub async fn share_ticket(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ApiResponse<CreatedTicketShareResponse>>)> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    let (share, token) = state.tickets.create_share_token(id, user.id).await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(ApiResponse::success(CreatedTicketShareResponse::new(
+            share, token,
+        ))),
+    ))
+}
+
+/// DELETE /api/v1/tickets/:id/share/:share_id - Revoke a share link
+pub async fn revoke_ticket_share(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path((id, share_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ApiResponse<TicketShareResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    let share = state
+        .tickets
+        .revoke_share_token(id, share_id, user.id)
+        .await?;
+    Ok(Json(ApiResponse::success(share.into())))
+}
+
+/// GET /api/v1/shared/:token/report - Report of a shared ticket (no login)
+pub async fn get_shared_report(
+    State(ready): State<ReadyAppState>,
+    Path(token): Path<String>,
+) -> Result<Json<ApiResponse<crate::dto::ReportResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    let ticket = state.tickets.get_by_share_token(&token).await?;
+    let response = load_report(&state, &ticket).await?;
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// GET /api/v1/shared/:token/video - Video of a shared ticket (no login)
+pub async fn get_shared_video(
+    State(ready): State<ReadyAppState>,
+    Path(token): Path<String>,
+) -> Result<Response> {
+    let state = ready.get_or_unavailable().await?;
+    let ticket = state.tickets.get_by_share_token(&token).await?;
+    stream_video(&state, ticket).await
+}
+
 /// DELETE /api/v1/tickets/:id - Delete a ticket
 pub async fn delete_ticket(
     State(ready): State<ReadyAppState>,
@@ -209,33 +567,60 @@ pub async fn get_video(
     Path(id): Path<Uuid>,
 ) -> Result<Response> {
     let state = ready.get_or_unavailable().await?;
-    let ticket = state
-        .tickets
-        .get_by_id(id)
-        .await?
-        .ok_or_else(|| AppError::not_found("Ticket not found"))?;
-
-    if !user.is_internal() && ticket.customer_id != user.id {
-        return Err(AppError::forbidden());
-    }
+    let ticket = load_accessible_ticket(&state, &user, id).await?;
+    stream_video(&state, ticket).await
+}
 
+/// Stream a ticket's stored video inline
+async fn stream_video(state: &crate::state::AppState, ticket: FeedbackTicket) -> Result<Response> {
+    let content_type = ticket.video_content_type().to_string();
     let path = ticket
         .video_storage_path
         .ok_or_else(|| AppError::not_found("Video not found"))?;
 
-    let data = state
+    let stream = state
         .storage
-        .download(&path)
+        .download_stream(&path)
         .await
         .map_err(|e| AppError::internal(format!("Failed to download video: {}", e)))?;
 
     Ok((
         StatusCode::OK,
         [
-            (header::CONTENT_TYPE, "video/webm"),
+            (header::CONTENT_TYPE, content_type.as_str()),
+            (header::CONTENT_DISPOSITION, "inline"),
+        ],
+        Body::from_stream(stream),
+    )
+        .into_response())
+}
+
+/// GET /api/v1/tickets/:id/screenshot - Stream the screenshot attached from the widget
+pub async fn get_screenshot(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Response> {
+    let state = ready.get_or_unavailable().await?;
+    let ticket = load_accessible_ticket(&state, &user, id).await?;
+
+    let path = ticket
+        .screenshot_url
+        .ok_or_else(|| AppError::not_found("Screenshot not found"))?;
+
+    let stream = state
+        .storage
+        .download_stream(&path)
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to download screenshot: {}", e)))?;
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "image/png"),
             (header::CONTENT_DISPOSITION, "inline"),
         ],
-        data,
+        Body::from_stream(stream),
     )
         .into_response())
 }
@@ -245,26 +630,191 @@ pub async fn get_report(
     State(ready): State<ReadyAppState>,
     Extension(user): Extension<User>,
     Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Response> {
+    let state = ready.get_or_unavailable().await?;
+    let ticket = load_accessible_ticket(&state, &user, id).await?;
+    let (response, etag) = find_versioned_report(&state, &ticket)
+        .await?
+        .ok_or_else(|| {
+            AppError::not_found("Report not found - analysis may still be processing")
+        })?;
+
+    let etag_header = [(header::ETAG, etag.as_str())];
+    if etag_matches(&headers, &etag) {
+        return Ok((StatusCode::NOT_MODIFIED, etag_header).into_response());
+    }
+    Ok((etag_header, Json(ApiResponse::success(response))).into_response())
+}
+
+/// PUT /api/v1/tickets/:id/report - Correct the report's overview, outcome, confidence
+/// or suggested actions; the original AI output stays in `raw_analysis`
+pub async fn update_report(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+    ValidatedJson(req): ValidatedJson<UpdateReportRequest>,
 ) -> Result<Json<ApiResponse<crate::dto::ReportResponse>>> {
     let state = ready.get_or_unavailable().await?;
-    let ticket = state
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+    if req.is_empty() {
+        return Err(AppError::bad_request("No report fields to update"));
+    }
+
+    let ticket = load_accessible_ticket(&state, &user, id).await?;
+    state
         .tickets
-        .get_by_id(id)
-        .await?
-        .ok_or_else(|| AppError::not_found("Ticket not found"))?;
+        .update_report(ticket.id, user.id, &req)
+        .await?;
+    let response = load_report(&state, &ticket).await?;
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// GET /api/v1/tickets/:id/report.pdf - Download the analysis report as a PDF
+pub async fn get_report_pdf(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Response> {
+    let state = ready.get_or_unavailable().await?;
+    let ticket = load_accessible_ticket(&state, &user, id).await?;
+    let report = load_report(&state, &ticket).await?;
+
+    let pdf = render_report_pdf(ticket.id, &report)?;
+    let disposition = format!("attachment; filename=\"ortrace-report-{}.pdf\"", ticket.id);
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/pdf"),
+            (header::CONTENT_DISPOSITION, disposition.as_str()),
+        ],
+        pdf,
+    )
+        .into_response())
+}
 
-    if !user.is_internal() && ticket.customer_id != user.id {
+/// GET /api/v1/tickets/:id/report/raw - The model's unparsed analysis output, for
+/// debugging sparse or failed parses (internal users only)
+pub async fn get_raw_report(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Response> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
         return Err(AppError::forbidden());
     }
+    let ticket = load_accessible_ticket(&state, &user, id).await?;
+
+    let raw_analysis = sqlx::query_scalar::<_, Option<String>>(
+        "SELECT raw_analysis FROM reports WHERE recording_id = $1",
+    )
+    .bind(ticket.id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| AppError::not_found("Report not found - analysis may still be processing"))?
+    .ok_or_else(|| AppError::not_found("Report has no raw analysis"))?;
+
+    Ok((
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+        raw_analysis,
+    )
+        .into_response())
+}
+
+/// GET /api/v1/tickets/:id/report/history - Every report version of the ticket, newest
+/// first, with what the latest reanalysis changed
+pub async fn get_report_history(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<ReportHistoryResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    let ticket = load_accessible_ticket(&state, &user, id).await?;
+
+    let mut versions = Vec::new();
+    let current =
+        sqlx::query_as::<_, crate::models::Report>("SELECT * FROM reports WHERE recording_id = $1")
+            .bind(ticket.id)
+            .fetch_optional(&state.db)
+            .await?;
+    if let Some(report) = current {
+        let issues = sqlx::query_as::<_, crate::models::Issue>(
+            "SELECT * FROM issues WHERE report_id = $1 ORDER BY severity, created_at",
+        )
+        .bind(report.id)
+        .fetch_all(&state.db)
+        .await?;
+        versions.push(version_response(report, issues, &ticket, true));
+    }
+    for archived in state.tickets.report_versions(ticket.id).await? {
+        versions.push(version_response(
+            archived.report.0,
+            archived.issues.0,
+            &ticket,
+            false,
+        ));
+    }
+
+    let diff = match versions.as_slice() {
+        [newer, older, ..] => Some(ReportDiff::between(older, newer)),
+        _ => None,
+    };
+    Ok(Json(ApiResponse::success(ReportHistoryResponse {
+        versions,
+        diff,
+    })))
+}
+
+fn version_response(
+    report: crate::models::Report,
+    issues: Vec<crate::models::Issue>,
+    ticket: &FeedbackTicket,
+    current: bool,
+) -> ReportVersionResponse {
+    ReportVersionResponse {
+        report_version: report.report_version,
+        current,
+        created_at: report.created_at,
+        report: build_report_response(report, issues, ticket),
+    }
+}
+
+async fn load_report(
+    state: &crate::state::AppState,
+    ticket: &FeedbackTicket,
+) -> Result<crate::dto::ReportResponse> {
+    find_report(state, ticket)
+        .await?
+        .ok_or_else(|| AppError::not_found("Report not found - analysis may still be processing"))
+}
 
-    let report =
+async fn find_report(
+    state: &crate::state::AppState,
+    ticket: &FeedbackTicket,
+) -> Result<Option<crate::dto::ReportResponse>> {
+    Ok(find_versioned_report(state, ticket)
+        .await?
+        .map(|(report, _)| report))
+}
+
+/// The ticket's report with its ETag
+async fn find_versioned_report(
+    state: &crate::state::AppState,
+    ticket: &FeedbackTicket,
+) -> Result<Option<(crate::dto::ReportResponse, String)>> {
+    let Some(report) =
         sqlx::query_as::<_, crate::models::Report>("SELECT * FROM reports WHERE recording_id = $1")
-            .bind(id)
+            .bind(ticket.id)
             .fetch_optional(&state.db)
             .await?
-            .ok_or_else(|| {
-                AppError::not_found("Report not found - analysis may still be processing")
-            })?;
+    else {
+        return Ok(None);
+    };
 
     let issues = sqlx::query_as::<_, crate::models::Issue>(
         "SELECT * FROM issues WHERE report_id = $1 ORDER BY severity, created_at",
@@ -273,8 +823,90 @@ pub async fn get_report(
     .fetch_all(&state.db)
     .await?;
 
-    let response = build_report_response(report, issues, &ticket);
-    Ok(Json(ApiResponse::success(response)))
+    let etag = report_etag(&report, &issues);
+    Ok(Some((build_report_response(report, issues, ticket), etag)))
+}
+
+/// Strong ETag for a report: changes whenever the report or any of its issues is updated,
+/// or an issue is added or removed
+fn report_etag(report: &crate::models::Report, issues: &[crate::models::Issue]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(report.id.as_bytes());
+    hasher.update(report.updated_at.timestamp_micros().to_be_bytes());
+    for issue in issues {
+        hasher.update(issue.id.as_bytes());
+        hasher.update(issue.updated_at.timestamp_micros().to_be_bytes());
+    }
+    format!("\"{}\"", hex::encode(&hasher.finalize()[..16]))
+}
+
+/// Whether `If-None-Match` lists `etag` (or `*`). Weak validators compare equal to strong
+/// ones for GET, as RFC 9110 specifies.
+fn etag_matches(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|candidate| candidate.trim())
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+}
+
+/// POST /api/v1/tickets/:id/issues/:issue_id/export/jira - Create a Jira issue from a report issue
+pub async fn export_issue_to_jira(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path((id, issue_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ApiResponse<IssueExportResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    let ticket = state.tickets.get_for_owner(id, user.id).await?;
+    let project_id = ticket
+        .project_id
+        .ok_or_else(|| AppError::bad_request("Ticket does not belong to a project"))?;
+    let project = state.projects.get_accessible(project_id, user.id).await?;
+    let jira = project
+        .jira_settings()
+        .ok_or_else(|| AppError::bad_request("Jira is not configured for this project"))?;
+
+    let issue = state.tickets.get_issue(id, issue_id).await?;
+    if let Some(url) = issue.external_ticket_url {
+        return Err(AppError::conflict(format!(
+            "Issue already exported to {}",
+            url
+        )));
+    }
+
+    let url = state.jira.create_issue(&jira, &issue, id).await?;
+    let issue = state.tickets.set_issue_external_url(issue.id, &url).await?;
+
+    Ok(Json(ApiResponse::success(IssueExportResponse {
+        issue_id: issue.id,
+        external_ticket_url: url,
+    })))
+}
+
+/// PUT /api/v1/tickets/:id/issues/:issue_id - Mark a report issue open, resolved or dismissed
+pub async fn update_issue(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path((id, issue_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<UpdateIssueRequest>,
+) -> Result<Json<ApiResponse<IssueResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    let issue = state
+        .tickets
+        .update_issue_status(id, issue_id, user.id, req.issue_status)
+        .await?;
+
+    Ok(Json(ApiResponse::success(issue_response(issue))))
 }
 
 /// GET /api/v1/tickets/overview - Get overview stats
@@ -291,6 +923,46 @@ pub async fn get_overview(
     Ok(Json(ApiResponse::success(stats)))
 }
 
+/// Load a ticket the user may view: internal users must own its project (or, for legacy
+/// tickets, its session); customers must be the submitter.
+async fn load_accessible_ticket(
+    state: &crate::state::AppState,
+    user: &User,
+    id: Uuid,
+) -> Result<crate::models::FeedbackTicket> {
+    if user.is_internal() {
+        return state.tickets.get_for_owner(id, user.id).await;
+    }
+
+    let ticket = state
+        .tickets
+        .get_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Ticket not found"))?;
+    if ticket.customer_id != user.id {
+        return Err(AppError::forbidden());
+    }
+    Ok(ticket)
+}
+
+fn issue_response(i: crate::models::Issue) -> IssueResponse {
+    IssueResponse {
+        id: i.id,
+        title: i.title,
+        severity: i.severity,
+        tags: crate::models::report::string_array_from_value(&i.tags.0),
+        observed_behavior: i.observed_behavior,
+        expected_behavior: i.expected_behavior,
+        evidence: crate::models::report::evidence_from_value(&i.evidence.0),
+        screenshots: crate::models::report::string_array_from_value(&i.screenshots.0),
+        impact: crate::models::report::string_array_from_value(&i.impact.0),
+        reproduction_steps: crate::models::report::string_array_from_value(&i.reproduction_steps.0),
+        confidence: i.confidence,
+        external_ticket_url: i.external_ticket_url,
+        issue_status: i.issue_status,
+    }
+}
+
 fn build_report_response(
     report: crate::models::Report,
     issues: Vec<crate::models::Issue>,
@@ -300,6 +972,7 @@ fn build_report_response(
     use crate::models::ReportOutcome;
 
     let outcome = report.outcome.unwrap_or(ReportOutcome::Partial);
+    let issues: Vec<IssueResponse> = issues.into_iter().map(issue_response).collect();
 
     ReportResponse {
         id: report.id,
@@ -309,31 +982,16 @@ fn build_report_response(
             confidence: report.confidence.unwrap_or(0),
             overview: report.overview.unwrap_or_default(),
         },
+        sentiment: report.sentiment,
+        frustration_score: report.frustration_score,
         metrics: ReportMetrics {
             task_completion_rate: report.task_completion_rate.unwrap_or(0),
             total_hesitation_time: report.total_hesitation_time.unwrap_or(0),
             retries_count: report.retries_count.unwrap_or(0),
             abandonment_point: report.abandonment_point,
         },
-        issues: issues
-            .into_iter()
-            .map(|i| IssueResponse {
-                id: i.id,
-                title: i.title,
-                severity: i.severity,
-                tags: crate::models::report::string_array_from_value(&i.tags.0),
-                observed_behavior: i.observed_behavior,
-                expected_behavior: i.expected_behavior,
-                evidence: crate::models::report::evidence_from_value(&i.evidence.0),
-                screenshots: crate::models::report::string_array_from_value(&i.screenshots.0),
-                impact: crate::models::report::string_array_from_value(&i.impact.0),
-                reproduction_steps: crate::models::report::string_array_from_value(
-                    &i.reproduction_steps.0,
-                ),
-                confidence: i.confidence,
-                external_ticket_url: i.external_ticket_url,
-            })
-            .collect(),
+        severity_breakdown: SeverityBreakdown::from_issues(&issues),
+        issues,
         question_analysis: crate::models::report::question_analysis_from_value(
             &report.question_analysis.0,
         ),
@@ -341,5 +999,891 @@ fn build_report_response(
         possible_solutions: crate::models::report::string_array_from_value(
             &report.possible_solutions.0,
         ),
+        language: report.language,
+        edited: report.edited_at.is_some(),
+        edited_at: report.edited_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::test_support::{seed_project, seed_ticket, seed_user, test_app};
+    use axum::http::Request;
+    use sqlx::PgPool;
+    use tower::ServiceExt;
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn report_pdf_is_served_to_ticket_participants(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "internal").await;
+        let customer = seed_user(&pool, "customer@example.com", "customer").await;
+        let stranger = seed_user(&pool, "stranger@example.com", "customer").await;
+        let project_id = seed_project(&pool, owner.id).await;
+        let ticket_id = seed_ticket(&pool, project_id, customer.id, "analyzed").await;
+        let report_id = sqlx::query_scalar::<_, Uuid>(
+            "INSERT INTO reports (recording_id, outcome, confidence, overview) VALUES ($1, 'failed', 85, 'Checkout failed.') RETURNING id",
+        )
+        .bind(ticket_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO issues (report_id, title, severity) VALUES ($1, 'Pay button does nothing', 'critical')")
+            .bind(report_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let config = Config::test_default();
+        let (app, state) = test_app(pool, &config).await;
+        let token = |user: &User| state.auth.generate_tokens(user).unwrap().0;
+        let tokens = [token(&owner), token(&customer), token(&stranger)];
+        let request = |token: &str| {
+            Request::builder()
+                .uri(format!("/api/v1/tickets/{}/report.pdf", ticket_id))
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        for token in &tokens[..2] {
+            let response = app.clone().oneshot(request(token)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers()[header::CONTENT_TYPE], "application/pdf");
+            assert!(response.headers()[header::CONTENT_DISPOSITION]
+                .to_str()
+                .unwrap()
+                .contains(".pdf"));
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            assert!(body.starts_with(b"%PDF"));
+        }
+
+        let response = app.oneshot(request(&tokens[2])).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn etag_matches_any_listed_validator() {
+        let headers = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::IF_NONE_MATCH, value.parse().unwrap());
+            headers
+        };
+        assert!(etag_matches(&headers("\"abc\""), "\"abc\""));
+        assert!(etag_matches(&headers("\"x\", W/\"abc\""), "\"abc\""));
+        assert!(etag_matches(&headers("*"), "\"abc\""));
+        assert!(!etag_matches(&headers("\"abcd\""), "\"abc\""));
+        assert!(!etag_matches(&HeaderMap::new(), "\"abc\""));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn unchanged_report_is_not_modified(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "internal").await;
+        let project_id = seed_project(&pool, owner.id).await;
+        let ticket_id = seed_ticket(&pool, project_id, owner.id, "analyzed").await;
+        sqlx::query("INSERT INTO reports (recording_id, outcome, confidence, overview) VALUES ($1, 'failed', 85, 'Checkout failed.')")
+            .bind(ticket_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let config = Config::test_default();
+        let (app, state) = test_app(pool, &config).await;
+        let token = state.auth.generate_tokens(&owner).unwrap().0;
+        let get_report = |if_none_match: Option<&str>| {
+            let mut request = Request::builder()
+                .uri(format!("/api/v1/tickets/{}/report", ticket_id))
+                .header(header::AUTHORIZATION, format!("Bearer {}", token));
+            if let Some(etag) = if_none_match {
+                request = request.header(header::IF_NONE_MATCH, etag);
+            }
+            app.clone().oneshot(request.body(Body::empty()).unwrap())
+        };
+
+        let response = get_report(None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers()[header::ETAG]
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        let response = get_report(Some(&etag)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], etag.as_str());
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(body.is_empty());
+
+        let edit = Request::builder()
+            .method("PUT")
+            .uri(format!("/api/v1/tickets/{}/report", ticket_id))
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(r#"{"confidence": 60}"#))
+            .unwrap();
+        assert_eq!(
+            app.clone().oneshot(edit).await.unwrap().status(),
+            StatusCode::OK
+        );
+
+        let response = get_report(Some(&etag)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(response.headers()[header::ETAG], etag.as_str());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn report_history_lists_versions_and_the_latest_diff(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "internal").await;
+        let project_id = seed_project(&pool, owner.id).await;
+        let ticket_id = sqlx::query_scalar::<_, Uuid>(
+            "INSERT INTO recordings (project_id, customer_id, status, video_storage_path) VALUES ($1, $2, 'analyzed', 'videos/t.webm') RETURNING id",
+        )
+        .bind(project_id)
+        .bind(owner.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let add_report =
+            |version: i32,
+             overview: &'static str,
+             issues: &'static [(&'static str, &'static str)]| {
+                let pool = pool.clone();
+                async move {
+                    let report_id = sqlx::query_scalar::<_, Uuid>(
+                    "INSERT INTO reports (recording_id, outcome, confidence, overview, report_version) VALUES ($1, 'failed', 80, $2, $3) RETURNING id",
+                )
+                .bind(ticket_id)
+                .bind(overview)
+                .bind(version)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+                    for (title, severity) in issues {
+                        sqlx::query(
+                            "INSERT INTO issues (report_id, title, severity) VALUES ($1, $2, $3)",
+                        )
+                        .bind(report_id)
+                        .bind(title)
+                        .bind(severity)
+                        .execute(&pool)
+                        .await
+                        .unwrap();
+                    }
+                }
+            };
+
+        let config = Config::test_default();
+        let (app, state) = test_app(pool.clone(), &config).await;
+        let token = state.auth.generate_tokens(&owner).unwrap().0;
+        add_report(
+            1,
+            "Checkout failed.",
+            &[("Pay button spins", "high"), ("Footer typo", "low")],
+        )
+        .await;
+        state.tickets.reanalyze(ticket_id, owner.id).await.unwrap();
+        add_report(
+            2,
+            "Checkout failed at payment.",
+            &[("Pay button spins", "critical")],
+        )
+        .await;
+
+        let request = Request::builder()
+            .uri(format!("/api/v1/tickets/{}/report/history", ticket_id))
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let versions = body["data"]["versions"].as_array().unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0]["report_version"], 2);
+        assert_eq!(versions[0]["current"], true);
+        assert_eq!(
+            versions[0]["executive_summary"]["overview"],
+            "Checkout failed at payment."
+        );
+        assert_eq!(versions[1]["report_version"], 1);
+        assert_eq!(versions[1]["current"], false);
+        assert_eq!(versions[1]["issues"].as_array().unwrap().len(), 2);
+
+        let diff = &body["data"]["diff"];
+        assert_eq!(diff["from_version"], 1);
+        assert_eq!(diff["to_version"], 2);
+        assert_eq!(diff["overview_changed"], true);
+        assert_eq!(diff["outcome_changed"], false);
+        assert_eq!(diff["added_issues"], serde_json::json!([]));
+        assert_eq!(
+            diff["removed_issues"],
+            serde_json::json!([{ "title": "Footer typo", "severity": "low" }])
+        );
+        assert_eq!(
+            diff["severity_changes"],
+            serde_json::json!([{ "title": "Pay button spins", "from": "high", "to": "critical" }])
+        );
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn job_error_is_only_shown_to_internal_users(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "internal").await;
+        let customer = seed_user(&pool, "customer@example.com", "customer").await;
+        let project_id = seed_project(&pool, owner.id).await;
+        let ticket_id = seed_ticket(&pool, project_id, customer.id, "failed").await;
+        sqlx::query(
+            r#"
+            INSERT INTO analysis_jobs (user_id, recording_id, status, video_storage_path, video_size_bytes, error_message)
+            VALUES ($1, $2, 'dead_letter', 'videos/x.webm', 10, 'Video too large for analysis')
+            "#,
+        )
+        .bind(customer.id)
+        .bind(ticket_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let config = Config::test_default();
+        let (app, state) = test_app(pool, &config).await;
+        let owner_token = state.auth.generate_tokens(&owner).unwrap().0;
+        let customer_token = state.auth.generate_tokens(&customer).unwrap().0;
+        let detail = |token: String| {
+            let app = app.clone();
+            async move {
+                let response = app
+                    .oneshot(
+                        Request::builder()
+                            .uri(format!("/api/v1/tickets/{}", ticket_id))
+                            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                            .body(Body::empty())
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                assert_eq!(response.status(), StatusCode::OK);
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap()["data"].clone()
+            }
+        };
+
+        let internal = detail(owner_token).await;
+        assert_eq!(internal["status"], "failed");
+        assert_eq!(internal["job_status"], "dead_letter");
+        assert_eq!(internal["job_error"], "Video too large for analysis");
+
+        let external = detail(customer_token).await;
+        assert_eq!(external["job_status"], "dead_letter");
+        assert!(external["job_error"].is_null());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn internal_reviewer_can_correct_a_report(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "internal").await;
+        let customer = seed_user(&pool, "customer@example.com", "customer").await;
+        let project_id = seed_project(&pool, owner.id).await;
+        let ticket_id = seed_ticket(&pool, project_id, customer.id, "analyzed").await;
+        sqlx::query(
+            r#"
+            INSERT INTO reports (recording_id, outcome, confidence, overview, raw_analysis)
+            VALUES ($1, 'failed', 40, 'Checkout failed.', '{"overview": "Checkout failed."}')
+            "#,
+        )
+        .bind(ticket_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let config = Config::test_default();
+        let (app, state) = test_app(pool.clone(), &config).await;
+        let owner_token = state.auth.generate_tokens(&owner).unwrap().0;
+        let customer_token = state.auth.generate_tokens(&customer).unwrap().0;
+        let put = |token: &str, body: serde_json::Value| {
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/api/v1/tickets/{}/report", ticket_id))
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+        let correction = serde_json::json!({
+            "overview": "Checkout worked after a retry.",
+            "outcome": "partial",
+            "confidence": 90,
+            "suggested_actions": ["Show payment errors inline", " "]
+        });
+
+        let response = app
+            .clone()
+            .oneshot(put(&customer_token, correction.clone()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = app
+            .clone()
+            .oneshot(put(&owner_token, serde_json::json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let response = app
+            .clone()
+            .oneshot(put(&owner_token, serde_json::json!({ "confidence": 150 })))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let response = app.oneshot(put(&owner_token, correction)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let report = &serde_json::from_slice::<serde_json::Value>(&body).unwrap()["data"];
+        assert_eq!(report["edited"], true);
+        assert_eq!(
+            report["executive_summary"]["overview"],
+            "Checkout worked after a retry."
+        );
+        assert_eq!(report["executive_summary"]["outcome"], "partial");
+        assert_eq!(report["executive_summary"]["confidence"], 90);
+        assert_eq!(
+            report["suggested_actions"],
+            serde_json::json!(["Show payment errors inline"])
+        );
+
+        let (raw_analysis, edited_by): (String, Option<Uuid>) =
+            sqlx::query_as("SELECT raw_analysis, edited_by FROM reports WHERE recording_id = $1")
+                .bind(ticket_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(raw_analysis, r#"{"overview": "Checkout failed."}"#);
+        assert_eq!(edited_by, Some(owner.id));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn share_links_expose_one_ticket_until_revoked(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "internal").await;
+        let customer = seed_user(&pool, "customer@example.com", "customer").await;
+        let project_id = seed_project(&pool, owner.id).await;
+        let mut ticket_ids = Vec::new();
+        for (video, overview) in [
+            ("videos/shared.webm", "Checkout failed."),
+            ("videos/private.webm", "Login failed."),
+        ] {
+            let ticket_id = sqlx::query_scalar::<_, Uuid>(
+                r#"
+                INSERT INTO recordings (project_id, customer_id, status, video_storage_path)
+                VALUES ($1, $2, 'analyzed', $3)
+                RETURNING id
+                "#,
+            )
+            .bind(project_id)
+            .bind(customer.id)
+            .bind(video)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            sqlx::query("INSERT INTO reports (recording_id, outcome, confidence, overview) VALUES ($1, 'failed', 85, $2)")
+                .bind(ticket_id)
+                .bind(overview)
+                .execute(&pool)
+                .await
+                .unwrap();
+            ticket_ids.push(ticket_id);
+        }
+        let (shared_id, private_id) = (ticket_ids[0], ticket_ids[1]);
+
+        let config = Config::test_default();
+        let (app, state) = test_app(pool, &config).await;
+        state
+            .storage
+            .upload("videos/shared.webm", b"shared video")
+            .await
+            .unwrap();
+        let owner_token = state.auth.generate_tokens(&owner).unwrap().0;
+        let customer_token = state.auth.generate_tokens(&customer).unwrap().0;
+        let authed = |method: &str, uri: String, token: &str| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+        let public = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let json = |response: Response| async move {
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()["data"].clone()
+        };
+
+        let share_uri = format!("/api/v1/tickets/{}/share", shared_id);
+        let response = app
+            .clone()
+            .oneshot(authed("POST", share_uri.clone(), &customer_token))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = app
+            .clone()
+            .oneshot(authed("POST", share_uri, &owner_token))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let share = json(response).await;
+        let report_url = share["report_url"].as_str().unwrap().to_string();
+        let video_url = share["video_url"].as_str().unwrap().to_string();
+        assert_eq!(share["ticket_id"], shared_id.to_string());
+
+        // The token opens only the ticket it was minted for
+        let response = app.clone().oneshot(public(&report_url)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let report = json(response).await;
+        assert_eq!(report["recording_id"], shared_id.to_string());
+        assert_eq!(report["executive_summary"]["overview"], "Checkout failed.");
+        let response = app.clone().oneshot(public(&video_url)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"shared video");
+        let response = app
+            .clone()
+            .oneshot(public("/api/v1/shared/not-a-token/report"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // Revoking goes through the ticket the link belongs to
+        let share_id = share["id"].as_str().unwrap();
+        let response = app
+            .clone()
+            .oneshot(authed(
+                "DELETE",
+                format!("/api/v1/tickets/{}/share/{}", private_id, share_id),
+                &owner_token,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response = app
+            .clone()
+            .oneshot(authed(
+                "DELETE",
+                format!("/api/v1/tickets/{}/share/{}", shared_id, share_id),
+                &owner_token,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(json(response).await["is_active"], false);
+
+        for uri in [&report_url, &video_url] {
+            let response = app.clone().oneshot(public(uri)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        }
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn export_bundles_ticket_report_and_chat(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "internal").await;
+        let customer = seed_user(&pool, "customer@example.com", "customer").await;
+        let project_id = seed_project(&pool, owner.id).await;
+        let ticket_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO recordings (project_id, customer_id, status, submitter_email, browser_info)
+            VALUES ($1, $2, 'analyzed', 'customer@example.com', '{"userAgent": "Firefox"}')
+            RETURNING id
+            "#,
+        )
+        .bind(project_id)
+        .bind(customer.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let report_id = sqlx::query_scalar::<_, Uuid>(
+            "INSERT INTO reports (recording_id, outcome, confidence, overview) VALUES ($1, 'failed', 85, 'Checkout failed.') RETURNING id",
+        )
+        .bind(ticket_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO issues (report_id, title, severity) VALUES ($1, 'Pay button does nothing', 'critical')")
+            .bind(report_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO chat_messages (recording_id, sender_id, message) VALUES ($1, $2, 'It broke again')")
+            .bind(ticket_id)
+            .bind(customer.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let config = Config::test_default();
+        let (app, state) = test_app(pool, &config).await;
+        let owner_token = state.auth.generate_tokens(&owner).unwrap().0;
+        let customer_token = state.auth.generate_tokens(&customer).unwrap().0;
+        let request = |token: &str| {
+            Request::builder()
+                .uri(format!("/api/v1/tickets/{}/export", ticket_id))
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(request(&customer_token)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = app.oneshot(request(&owner_token)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let export = &serde_json::from_slice::<serde_json::Value>(&body).unwrap()["data"];
+        assert!(export["exported_at"].is_string());
+        assert_eq!(export["ticket"]["id"], ticket_id.to_string());
+        assert_eq!(export["ticket"]["submitter_email"], "customer@example.com");
+        assert_eq!(export["ticket"]["browser_info"]["userAgent"], "Firefox");
+        assert_eq!(
+            export["report"]["executive_summary"]["overview"],
+            "Checkout failed."
+        );
+        assert_eq!(
+            export["report"]["issues"][0]["title"],
+            "Pay button does nothing"
+        );
+        assert_eq!(export["chat"][0]["message"], "It broke again");
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn internal_users_create_tickets_only_in_their_projects(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "internal").await;
+        let other = seed_user(&pool, "other@example.com", "internal").await;
+        let customer = seed_user(&pool, "customer@example.com", "customer").await;
+        let mut project_ids = Vec::new();
+        for user in [&owner, &other] {
+            let project_id = seed_project(&pool, user.id).await;
+            project_ids.push(project_id);
+        }
+
+        let config = Config::test_default();
+        let (app, state) = test_app(pool, &config).await;
+        let owner_token = state.auth.generate_tokens(&owner).unwrap().0;
+        let customer_token = state.auth.generate_tokens(&customer).unwrap().0;
+        let request = |token: &str, project_id: Uuid| {
+            let body = serde_json::json!({
+                "project_id": project_id,
+                "feedback_type": "bug",
+                "description": "  Customer called: export button is greyed out  ",
+                "submitter_email": "caller@example.com",
+                "submitter_name": "Caller",
+            });
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/tickets")
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(request(&customer_token, project_ids[0]))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = app
+            .clone()
+            .oneshot(request(&owner_token, project_ids[1]))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = app
+            .oneshot(request(&owner_token, project_ids[0]))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let ticket = &serde_json::from_slice::<serde_json::Value>(&body).unwrap()["data"];
+        assert_eq!(ticket["project_id"], project_ids[0].to_string());
+        assert_eq!(ticket["feedback_type"], "bug");
+        assert_eq!(
+            ticket["task_description"],
+            "Customer called: export button is greyed out"
+        );
+        assert_eq!(ticket["submitter_email"], "caller@example.com");
+        assert!(ticket["video_url"].is_null());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn legacy_session_tickets_resolve_through_their_session(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "internal").await;
+        let other = seed_user(&pool, "other@example.com", "internal").await;
+        let customer = seed_user(&pool, "customer@example.com", "customer").await;
+        let session_id = sqlx::query_scalar::<_, Uuid>(
+            "INSERT INTO sessions (owner_id, name, share_token) VALUES ($1, 'Spring beta', 'legacy-share') RETURNING id",
+        )
+        .bind(owner.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let ticket_id = sqlx::query_scalar::<_, Uuid>(
+            "INSERT INTO recordings (session_id, customer_id, status) VALUES ($1, $2, 'pending') RETURNING id",
+        )
+        .bind(session_id)
+        .bind(customer.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        let config = Config::test_default();
+        let (app, state) = test_app(pool, &config).await;
+        let request = |user: &User| {
+            let token = state.auth.generate_tokens(user).unwrap().0;
+            Request::builder()
+                .uri(format!("/api/v1/tickets/{}", ticket_id))
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(request(&other)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = app.oneshot(request(&owner)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let ticket = &serde_json::from_slice::<serde_json::Value>(&body).unwrap()["data"];
+        assert_eq!(ticket["id"], ticket_id.to_string());
+        assert_eq!(ticket["project_name"], "Spring beta");
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn raw_analysis_is_served_as_text_to_internal_users(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "internal").await;
+        let customer = seed_user(&pool, "customer@example.com", "customer").await;
+        let project_id = seed_project(&pool, owner.id).await;
+        let mut ticket_ids = Vec::new();
+        for _ in 0..2 {
+            let ticket_id = seed_ticket(&pool, project_id, customer.id, "analyzed").await;
+            ticket_ids.push(ticket_id);
+        }
+        let raw = "```json\n{\"overview\": \"Checkout failed.\"\n```";
+        sqlx::query(
+            "INSERT INTO reports (recording_id, outcome, confidence, overview, raw_analysis) VALUES ($1, 'failed', 40, 'Checkout failed.', $2)",
+        )
+        .bind(ticket_ids[0])
+        .bind(raw)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let config = Config::test_default();
+        let (app, state) = test_app(pool, &config).await;
+        let owner_token = state.auth.generate_tokens(&owner).unwrap().0;
+        let customer_token = state.auth.generate_tokens(&customer).unwrap().0;
+        let request = |token: &str, ticket_id: Uuid| {
+            Request::builder()
+                .uri(format!("/api/v1/tickets/{}/report/raw", ticket_id))
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(request(&customer_token, ticket_ids[0]))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = app
+            .clone()
+            .oneshot(request(&owner_token, ticket_ids[1]))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = app
+            .oneshot(request(&owner_token, ticket_ids[0]))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/plain; charset=utf-8"
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body, raw.as_bytes());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn stale_ticket_updates_are_refused(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "internal").await;
+        let project_id = seed_project(&pool, owner.id).await;
+        let ticket_id = seed_ticket(&pool, project_id, owner.id, "analyzed").await;
+        let loaded_at: chrono::DateTime<chrono::Utc> =
+            sqlx::query_scalar("SELECT updated_at FROM recordings WHERE id = $1")
+                .bind(ticket_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+
+        let config = Config::test_default();
+        let (app, state) = test_app(pool.clone(), &config).await;
+        let owner_token = state.auth.generate_tokens(&owner).unwrap().0;
+        let put = |body: serde_json::Value| {
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/api/v1/tickets/{}", ticket_id))
+                .header(header::AUTHORIZATION, format!("Bearer {}", owner_token))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(put(serde_json::json!({
+                "ticket_status": "in_progress",
+                "priority": "high",
+                "expected_updated_at": loaded_at,
+            })))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // A second editor still holding the old version
+        let response = app
+            .oneshot(put(serde_json::json!({
+                "ticket_status": "resolved",
+                "expected_updated_at": loaded_at,
+            })))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let error = serde_json::from_slice::<serde_json::Value>(&body).unwrap();
+        assert_eq!(error["code"], "STALE_UPDATE");
+
+        let (ticket_status, updated_at): (String, chrono::DateTime<chrono::Utc>) =
+            sqlx::query_as("SELECT ticket_status, updated_at FROM recordings WHERE id = $1")
+                .bind(ticket_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(ticket_status, "in_progress");
+        assert!(updated_at > loaded_at);
+        assert_eq!(
+            serde_json::from_value::<chrono::DateTime<chrono::Utc>>(
+                error["current_updated_at"].clone()
+            )
+            .unwrap(),
+            updated_at
+        );
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn closing_a_ticket_makes_held_versions_stale(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "internal").await;
+        let project_id = seed_project(&pool, owner.id).await;
+        let ticket_id = seed_ticket(&pool, project_id, owner.id, "analyzed").await;
+        let loaded_at: chrono::DateTime<chrono::Utc> =
+            sqlx::query_scalar("SELECT updated_at FROM recordings WHERE id = $1")
+                .bind(ticket_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+
+        let config = Config::test_default();
+        let (app, state) = test_app(pool.clone(), &config).await;
+        let owner_token = state.auth.generate_tokens(&owner).unwrap().0;
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!("/api/v1/tickets/{}/close", ticket_id))
+                    .header(header::AUTHORIZATION, format!("Bearer {}", owner_token))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // An editor who loaded the ticket before it was closed cannot reopen it by accident
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("PUT")
+                    .uri(format!("/api/v1/tickets/{}", ticket_id))
+                    .header(header::AUTHORIZATION, format!("Bearer {}", owner_token))
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(
+                        serde_json::json!({
+                            "ticket_status": "in_progress",
+                            "expected_updated_at": loaded_at,
+                        })
+                        .to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        // Updates that don't set it still move the version
+        let closed_at: chrono::DateTime<chrono::Utc> =
+            sqlx::query_scalar("SELECT updated_at FROM recordings WHERE id = $1")
+                .bind(ticket_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        sqlx::query("UPDATE recordings SET priority = 'high' WHERE id = $1")
+            .bind(ticket_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let updated_at: chrono::DateTime<chrono::Utc> =
+            sqlx::query_scalar("SELECT updated_at FROM recordings WHERE id = $1")
+                .bind(ticket_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(updated_at > closed_at);
     }
 }
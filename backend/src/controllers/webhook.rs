@@ -0,0 +1,130 @@
+//! Webhook controller - project-scoped webhook management
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    Extension,
+};
+use uuid::Uuid;
+
+use crate::dto::{
+    ApiResponse, CreateWebhookRequest, MessageResponse, UpdateWebhookRequest, WebhookResponse,
+    WebhookTestResponse,
+};
+use crate::error::{AppError, Result};
+use crate::models::User;
+use crate::state::ReadyAppState;
+
+/// GET /api/v1/projects/:id/webhooks - List a project's webhooks
+pub async fn list_webhooks(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<WebhookResponse>>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    state.projects.get_managed(id, user.id).await?;
+    let webhooks = state.webhooks.list(id).await?;
+
+    Ok(Json(ApiResponse::success(
+        webhooks.into_iter().map(WebhookResponse::from).collect(),
+    )))
+}
+
+/// POST /api/v1/projects/:id/webhooks - Create a webhook
+pub async fn create_webhook(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<CreateWebhookRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<WebhookResponse>>)> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    req.validate().map_err(AppError::validation)?;
+    state.projects.get_managed(id, user.id).await?;
+
+    let webhook = state
+        .webhooks
+        .create(id, &req.url, &req.events, req.is_active.unwrap_or(true))
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ApiResponse::success(WebhookResponse::from(webhook))),
+    ))
+}
+
+/// PUT /api/v1/projects/:id/webhooks/:webhook_id - Update a webhook
+pub async fn update_webhook(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path((id, webhook_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<UpdateWebhookRequest>,
+) -> Result<Json<ApiResponse<WebhookResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    req.validate().map_err(AppError::validation)?;
+    state.projects.get_managed(id, user.id).await?;
+
+    let webhook = state
+        .webhooks
+        .update(
+            webhook_id,
+            id,
+            req.url.as_deref(),
+            req.events.as_deref(),
+            req.is_active,
+        )
+        .await?;
+
+    Ok(Json(ApiResponse::success(WebhookResponse::from(webhook))))
+}
+
+/// DELETE /api/v1/projects/:id/webhooks/:webhook_id - Delete a webhook
+pub async fn delete_webhook(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path((id, webhook_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ApiResponse<MessageResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    state.projects.get_managed(id, user.id).await?;
+    state.webhooks.delete(webhook_id, id).await?;
+
+    Ok(Json(ApiResponse::success(MessageResponse::new(
+        "Webhook deleted",
+    ))))
+}
+
+/// POST /api/v1/projects/:id/webhooks/:webhook_id/test - Send a test `ping` and report the
+/// receiver's response
+pub async fn test_webhook(
+    State(ready): State<ReadyAppState>,
+    Extension(user): Extension<User>,
+    Path((id, webhook_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ApiResponse<WebhookTestResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    if !user.is_internal() {
+        return Err(AppError::forbidden());
+    }
+
+    state.projects.get_managed(id, user.id).await?;
+    let delivery = state.webhooks.send_test(webhook_id, id).await?;
+
+    Ok(Json(ApiResponse::success(WebhookTestResponse::from(
+        delivery,
+    ))))
+}
@@ -1,18 +1,21 @@
 //! Widget controller - public API for end-user widget submissions
-//! Identified by project_id in the URL path, no authentication required.
+//! Identified by project_id in the URL path. Submissions carry an `X-Ortrace-Key` project
+//! API key when the project requires auth or has keys configured.
 
 use axum::{
     extract::{multipart::Multipart, Path, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::Json,
 };
 use uuid::Uuid;
 
 use crate::dto::{
-    ApiResponse, WidgetConfigQuery, WidgetConfigResponse, WidgetSubmitRequest, WidgetSubmitResponse,
+    ApiResponse, ConfirmUploadRequest, DirectUploadResponse, ValidatedJson, WidgetConfigQuery,
+    WidgetConfigResponse, WidgetSubmitRequest, WidgetSubmitResponse,
 };
 use crate::error::{AppError, Result};
-use crate::models::Project;
+use crate::models::{ClientDiagnostics, Project};
+use crate::services::{is_video_container, WidgetTicketStatus, DIRECT_UPLOAD_URL_EXPIRY_SECS};
 use crate::state::ReadyAppState;
 
 /// Look up an active project by ID or return 404
@@ -24,6 +27,42 @@ async fn resolve_project(state: &crate::state::AppState, project_id: Uuid) -> Re
         .ok_or_else(|| AppError::not_found("Project not found or inactive"))
 }
 
+/// Header carrying a project API key on widget/server submissions
+const API_KEY_HEADER: &str = "X-Ortrace-Key";
+
+/// Header a client sets to the same value when retrying one submission
+const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+/// Longest accepted `Idempotency-Key` (matches the `idempotency_keys.key` column)
+const MAX_IDEMPOTENCY_KEY_CHARS: usize = 255;
+
+/// Project API key presented with a submission, if any
+fn submission_key(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(API_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+}
+
+/// `Idempotency-Key` sent with a submission, if any
+fn idempotency_key(headers: &HeaderMap) -> Result<Option<&str>> {
+    let Some(key) = headers
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+    else {
+        return Ok(None);
+    };
+    if key.chars().count() > MAX_IDEMPOTENCY_KEY_CHARS {
+        return Err(AppError::bad_request(format!(
+            "Idempotency-Key must be at most {} characters",
+            MAX_IDEMPOTENCY_KEY_CHARS
+        )));
+    }
+    Ok(Some(key))
+}
+
 /// GET /api/v1/widget/:project_id/config - Get widget configuration by project ID
 pub async fn get_widget_config(
     State(ready): State<ReadyAppState>,
@@ -70,30 +109,59 @@ pub async fn get_widget_config_by_domain(
 pub async fn submit_feedback(
     State(ready): State<ReadyAppState>,
     Path(project_id): Path<Uuid>,
-    Json(req): Json<WidgetSubmitRequest>,
+    headers: HeaderMap,
+    ValidatedJson(req): ValidatedJson<WidgetSubmitRequest>,
 ) -> Result<(StatusCode, Json<ApiResponse<WidgetSubmitResponse>>)> {
     let state = ready.get_or_unavailable().await?;
     let project = resolve_project(&state, project_id).await?;
+    state
+        .api_keys
+        .authorize_submission(&project, submission_key(&headers))
+        .await?;
 
-    // Create or find an anonymous customer user for this submission
-    let customer_id = get_or_create_anonymous_user(&state, req.submitter_email.as_deref()).await?;
+    // A retry of a submission that already went through gets the same ticket back. This
+    // lookup only spares the common sequential retry an anonymous user; concurrent retries
+    // are settled when the ticket is created.
+    let idempotency_key = idempotency_key(&headers)?;
+    let existing = match idempotency_key {
+        Some(key) => {
+            state
+                .tickets
+                .find_by_idempotency_key(project.id, key)
+                .await?
+        }
+        None => None,
+    };
 
-    let ticket = state
-        .tickets
-        .create_from_widget(
-            project.id,
-            customer_id,
-            req.feedback_type,
-            Some(&req.description),
-            req.submitter_email.as_deref(),
-            req.submitter_name.as_deref(),
-            req.page_url.as_deref(),
-            req.browser_info,
-        )
-        .await?;
+    let ticket_id = match existing {
+        Some(ticket_id) => ticket_id,
+        None => {
+            // Create or find an anonymous customer user for this submission
+            let customer_id =
+                get_or_create_anonymous_user(&state, req.submitter_email.as_deref()).await?;
+
+            let ticket = state
+                .tickets
+                .create_from_widget(
+                    project.id,
+                    customer_id,
+                    req.feedback_type,
+                    Some(&req.description),
+                    req.submitter_email.as_deref(),
+                    req.submitter_name.as_deref(),
+                    req.page_url.as_deref(),
+                    req.browser_info,
+                    req.prior_experience.as_deref(),
+                    ClientDiagnostics::capped(req.console_logs, req.network_errors),
+                    idempotency_key,
+                )
+                .await?;
+            ticket.id
+        }
+    };
 
     let response = WidgetSubmitResponse {
-        ticket_id: ticket.id,
+        ticket_id,
         message: "Feedback submitted successfully".to_string(),
     };
 
@@ -104,18 +172,70 @@ pub async fn submit_feedback(
 pub async fn upload_widget_video(
     State(ready): State<ReadyAppState>,
     Path((project_id, ticket_id)): Path<(Uuid, Uuid)>,
+    headers: HeaderMap,
     mut multipart: Multipart,
 ) -> Result<Json<ApiResponse<WidgetSubmitResponse>>> {
     let state = ready.get_or_unavailable().await?;
-    // Verify the project is active
-    let _project = resolve_project(&state, project_id).await?;
+    // Verify the project is active and the submission is allowed
+    let project = resolve_project(&state, project_id).await?;
+    state
+        .api_keys
+        .authorize_submission(&project, submission_key(&headers))
+        .await?;
+
+    // The key only vouches for this project, so the ticket must belong to it
+    let ticket = state
+        .tickets
+        .get_by_id(ticket_id)
+        .await?
+        .filter(|ticket| ticket.project_id == Some(project_id))
+        .ok_or_else(|| AppError::not_found("Ticket not found"))?;
+
+    let video = read_video_upload(&mut multipart, state.config.max_upload_mb).await?;
+
+    let _updated = state
+        .tickets
+        .upload_video(
+            ticket_id,
+            ticket.customer_id,
+            video.data,
+            video.duration_seconds,
+            video.content_type.as_deref(),
+            video.file_name.as_deref(),
+        )
+        .await?;
+
+    let response = WidgetSubmitResponse {
+        ticket_id,
+        message: "Video uploaded and processing started".to_string(),
+    };
 
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// Video sent as the `video` field of a multipart upload, with its optional `duration`
+pub(crate) struct VideoUpload {
+    pub data: Vec<u8>,
+    pub content_type: Option<String>,
+    pub file_name: Option<String>,
+    pub duration_seconds: i32,
+}
+
+/// Read an upload form, rejecting a missing, non-video or oversized file
+pub(crate) async fn read_video_upload(
+    multipart: &mut Multipart,
+    max_upload_mb: usize,
+) -> Result<VideoUpload> {
     let mut video_data: Option<Vec<u8>> = None;
+    let mut content_type: Option<String> = None;
+    let mut file_name: Option<String> = None;
     let mut duration_seconds: i32 = 0;
 
     while let Ok(Some(field)) = multipart.next_field().await {
         match field.name().unwrap_or("") {
             "video" => {
+                content_type = field.content_type().map(str::to_string);
+                file_name = field.file_name().map(str::to_string);
                 let bytes = field
                     .bytes()
                     .await
@@ -132,53 +252,174 @@ pub async fn upload_widget_video(
     }
 
     let video = video_data.ok_or_else(|| AppError::bad_request("Missing video file"))?;
+    // Sniff the bytes rather than trusting the field's Content-Type, which browsers guess
+    // from the file extension
+    if !is_video_container(&video) {
+        return Err(AppError::bad_request("Unsupported video format"));
+    }
 
-    const MAX_SIZE_MB: f64 = 50.0;
-    let size_mb = video.len() as f64 / (1024.0 * 1024.0);
-    if size_mb > MAX_SIZE_MB {
+    if video.len() > max_upload_mb * 1024 * 1024 {
         return Err(AppError::bad_request(format!(
             "Video too large ({:.1}MB). Max: {}MB",
-            size_mb, MAX_SIZE_MB
+            video.len() as f64 / (1024.0 * 1024.0),
+            max_upload_mb
         )));
     }
 
-    // Get ticket to find its customer_id
+    Ok(VideoUpload {
+        data: video,
+        content_type,
+        file_name,
+        duration_seconds,
+    })
+}
+
+/// POST /api/v1/widget/:project_id/tickets/:id/upload-url - Signed URL for uploading the
+/// ticket's video straight to storage
+pub async fn widget_upload_url(
+    State(ready): State<ReadyAppState>,
+    Path((project_id, ticket_id)): Path<(Uuid, Uuid)>,
+    headers: HeaderMap,
+) -> Result<Json<ApiResponse<DirectUploadResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    let project = resolve_project(&state, project_id).await?;
+    state
+        .api_keys
+        .authorize_submission(&project, submission_key(&headers))
+        .await?;
+
     let ticket = state
         .tickets
         .get_by_id(ticket_id)
         .await?
+        .filter(|ticket| ticket.project_id == Some(project_id))
         .ok_or_else(|| AppError::not_found("Ticket not found"))?;
 
-    let _updated = state
+    let upload = state
+        .tickets
+        .direct_upload_url(ticket_id, ticket.customer_id, state.config.max_upload_mb)
+        .await?;
+
+    Ok(Json(ApiResponse::success(DirectUploadResponse {
+        ticket_id,
+        upload_url: upload.url,
+        upload_headers: upload.headers.into_iter().collect(),
+        expires_in_secs: DIRECT_UPLOAD_URL_EXPIRY_SECS,
+    })))
+}
+
+/// POST /api/v1/widget/:project_id/tickets/:id/confirm-upload - Start analysis of a video
+/// the widget uploaded straight to storage
+pub async fn confirm_widget_upload(
+    State(ready): State<ReadyAppState>,
+    Path((project_id, ticket_id)): Path<(Uuid, Uuid)>,
+    headers: HeaderMap,
+    ValidatedJson(req): ValidatedJson<ConfirmUploadRequest>,
+) -> Result<Json<ApiResponse<WidgetSubmitResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    let project = resolve_project(&state, project_id).await?;
+    state
+        .api_keys
+        .authorize_submission(&project, submission_key(&headers))
+        .await?;
+
+    let ticket = state
+        .tickets
+        .get_by_id(ticket_id)
+        .await?
+        .filter(|ticket| ticket.project_id == Some(project_id))
+        .ok_or_else(|| AppError::not_found("Ticket not found"))?;
+
+    state
         .tickets
-        .upload_video(ticket_id, ticket.customer_id, video, duration_seconds)
+        .confirm_upload(
+            ticket_id,
+            ticket.customer_id,
+            req.duration.unwrap_or(0),
+            req.content_type.as_deref(),
+            state.config.max_upload_mb,
+        )
         .await?;
 
     let response = WidgetSubmitResponse {
         ticket_id,
-        message: "Video uploaded and processing started".to_string(),
+        message: "Video received and processing started".to_string(),
     };
 
     Ok(Json(ApiResponse::success(response)))
 }
 
-/// Get or create an anonymous user for widget submissions
+/// POST /api/v1/widget/:project_id/tickets/:id/screenshot - Attach a PNG screenshot to a widget ticket
+pub async fn upload_widget_screenshot(
+    State(ready): State<ReadyAppState>,
+    Path((project_id, ticket_id)): Path<(Uuid, Uuid)>,
+    headers: HeaderMap,
+    mut multipart: Multipart,
+) -> Result<Json<ApiResponse<WidgetSubmitResponse>>> {
+    let state = ready.get_or_unavailable().await?;
+    let project = resolve_project(&state, project_id).await?;
+    state
+        .api_keys
+        .authorize_submission(&project, submission_key(&headers))
+        .await?;
+
+    let mut screenshot: Option<Vec<u8>> = None;
+    while let Ok(Some(field)) = multipart.next_field().await {
+        if field.name() == Some("screenshot") {
+            let bytes = field
+                .bytes()
+                .await
+                .map_err(|e| AppError::bad_request(format!("Error reading screenshot: {}", e)))?;
+            screenshot = Some(bytes.to_vec());
+        }
+    }
+    let screenshot = screenshot.ok_or_else(|| AppError::bad_request("Missing screenshot file"))?;
+
+    state
+        .tickets
+        .upload_screenshot(ticket_id, project.id, screenshot)
+        .await?;
+
+    let response = WidgetSubmitResponse {
+        ticket_id,
+        message: "Screenshot uploaded".to_string(),
+    };
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// GET /api/v1/widget/:project_id/tickets/:id/status - Analysis status for the submitter
+/// (the widget polls this after uploading; rate limited with the other widget calls)
+pub async fn get_widget_ticket_status(
+    State(ready): State<ReadyAppState>,
+    Path((project_id, ticket_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ApiResponse<WidgetTicketStatus>>> {
+    let state = ready.get_or_unavailable().await?;
+    let project = resolve_project(&state, project_id).await?;
+
+    let status = state
+        .tickets
+        .get_widget_status(ticket_id, project.id)
+        .await?;
+
+    Ok(Json(ApiResponse::success(status)))
+}
+
+/// Get or create an anonymous user for widget submissions. Only earlier anonymous
+/// records are reused: an email typed into the widget proves nothing about who owns it,
+/// so submissions are never attached to a registered account. The upsert leans on the
+/// unique index over anonymous emails, so concurrent submissions share one record.
 async fn get_or_create_anonymous_user(
     state: &crate::state::AppState,
     email: Option<&str>,
 ) -> Result<Uuid> {
-    if let Some(email) = email {
-        // Check if user exists
-        if let Some(user) = state.auth.find_user_by_email(email).await? {
-            return Ok(user.id);
-        }
-    }
-
-    // Create a new anonymous customer
+    // Without an email nothing conflicts and every submission gets its own record
     let id: Uuid = sqlx::query_scalar(
         r#"
-        INSERT INTO users (email, role, onboarding_completed)
-        VALUES ($1, 'customer', true)
+        INSERT INTO users (email, role, onboarding_completed, is_anonymous)
+        VALUES ($1, 'customer', true, true)
+        ON CONFLICT (email) WHERE is_anonymous
+        DO UPDATE SET email = EXCLUDED.email
         RETURNING id
         "#,
     )
@@ -188,3 +429,664 @@ async fn get_or_create_anonymous_user(
 
     Ok(id)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::models::UserRole;
+    use crate::state::AppState;
+    use crate::test_support::{seed_project, seed_project_with, seed_ticket, seed_user, test_app};
+    use axum::body::Body;
+    use axum::http::{header, Request};
+    use sqlx::PgPool;
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn widget_email_never_attaches_to_a_registered_account(pool: PgPool) {
+        let state = AppState::new(Config::test_default(), pool.clone())
+            .await
+            .unwrap();
+        let registered = state
+            .auth
+            .register(
+                "jane@example.com",
+                "correct horse battery",
+                None,
+                UserRole::Customer,
+            )
+            .await
+            .unwrap()
+            .user
+            .id;
+
+        let first = get_or_create_anonymous_user(&state, Some("jane@example.com"))
+            .await
+            .unwrap();
+        assert_ne!(first, registered);
+        let is_anonymous: bool = sqlx::query_scalar("SELECT is_anonymous FROM users WHERE id = $1")
+            .bind(first)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(is_anonymous);
+
+        // Later submissions with the same email share the anonymous record
+        let second = get_or_create_anonymous_user(&state, Some("jane@example.com"))
+            .await
+            .unwrap();
+        assert_eq!(second, first);
+        let without_email = get_or_create_anonymous_user(&state, None).await.unwrap();
+        assert_ne!(without_email, first);
+
+        // The registered account is still the one found for sign-in
+        let user = state
+            .auth
+            .find_user_by_email("jane@example.com")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(user.id, registered);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn anonymous_submitter_email_can_still_register(pool: PgPool) {
+        let state = AppState::new(Config::test_default(), pool).await.unwrap();
+        let anonymous = get_or_create_anonymous_user(&state, Some("sam@example.com"))
+            .await
+            .unwrap();
+
+        let registered = state
+            .auth
+            .register(
+                "sam@example.com",
+                "correct horse battery",
+                None,
+                UserRole::Customer,
+            )
+            .await
+            .unwrap()
+            .user
+            .id;
+        assert_ne!(registered, anonymous);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn concurrent_submissions_share_one_anonymous_user(pool: PgPool) {
+        let state = Arc::new(
+            AppState::new(Config::test_default(), pool.clone())
+                .await
+                .unwrap(),
+        );
+        let tasks: Vec<_> = (0..8)
+            .map(|_| {
+                let state = state.clone();
+                tokio::spawn(async move {
+                    get_or_create_anonymous_user(&state, Some("rush@example.com"))
+                        .await
+                        .unwrap()
+                })
+            })
+            .collect();
+        let mut ids = Vec::new();
+        for task in tasks {
+            ids.push(task.await.unwrap());
+        }
+        assert!(ids.iter().all(|id| *id == ids[0]), "{ids:?}");
+        let again = get_or_create_anonymous_user(&state, Some("rush@example.com"))
+            .await
+            .unwrap();
+        assert_eq!(again, ids[0]);
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users WHERE email = $1")
+            .bind("rush@example.com")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+
+        // Submissions without an email never share a record
+        let first = get_or_create_anonymous_user(&state, None).await.unwrap();
+        let second = get_or_create_anonymous_user(&state, None).await.unwrap();
+        assert_ne!(first, second);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn prior_experience_round_trips_to_ticket_detail(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "internal").await;
+        let project_id = seed_project(&pool, owner.id).await;
+
+        let config = Config::test_default();
+        let (app, state) = test_app(pool, &config).await;
+        let token = state.auth.generate_tokens(&owner).unwrap().0;
+        let json = |response: axum::response::Response| async move {
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+        };
+
+        let submit = Request::builder()
+            .method("POST")
+            .uri(format!("/api/v1/widget/{}/submit", project_id))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::json!({
+                    "feedback_type": "bug",
+                    "description": "Checkout button does nothing",
+                    "prior_experience": " first visit "
+                })
+                .to_string(),
+            ))
+            .unwrap();
+        let response = app.clone().oneshot(submit).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let ticket_id = json(response).await["data"]["ticket_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let detail = Request::builder()
+            .uri(format!("/api/v1/tickets/{}", ticket_id))
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(detail).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            json(response).await["data"]["prior_experience"],
+            "first visit"
+        );
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn idempotency_key_returns_the_original_ticket(pool: PgPool) {
+        let owner_id = seed_user(&pool, "owner@example.com", "internal").await.id;
+        let project_id = seed_project(&pool, owner_id).await;
+
+        let config = Config::test_default();
+        let (app, _) = test_app(pool.clone(), &config).await;
+        let submit = |key: Option<&str>| {
+            let mut request = Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/widget/{}/submit", project_id))
+                .header(header::CONTENT_TYPE, "application/json");
+            if let Some(key) = key {
+                request = request.header(IDEMPOTENCY_KEY_HEADER, key);
+            }
+            let app = app.clone();
+            let request = request
+                .body(Body::from(
+                    serde_json::json!({ "feedback_type": "bug", "description": "Cart is empty" })
+                        .to_string(),
+                ))
+                .unwrap();
+            async move {
+                let response = app.oneshot(request).await.unwrap();
+                assert_eq!(response.status(), StatusCode::CREATED);
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                let body = serde_json::from_slice::<serde_json::Value>(&body).unwrap();
+                body["data"]["ticket_id"].as_str().unwrap().to_string()
+            }
+        };
+        let ticket_count = || async {
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM recordings")
+                .fetch_one(&pool)
+                .await
+                .unwrap()
+        };
+
+        let first = submit(Some("retry-1")).await;
+        assert_eq!(submit(Some("retry-1")).await, first);
+        assert_eq!(ticket_count().await, 1);
+
+        assert_ne!(submit(Some("retry-2")).await, first);
+        assert_ne!(submit(None).await, first);
+        assert_eq!(ticket_count().await, 3);
+
+        // Once the key expires, the same value starts a new ticket
+        sqlx::query("UPDATE idempotency_keys SET created_at = NOW() - INTERVAL '25 hours'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let after_expiry = submit(Some("retry-1")).await;
+        assert_ne!(after_expiry, first);
+        assert_eq!(submit(Some("retry-1")).await, after_expiry);
+        assert_eq!(ticket_count().await, 4);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn concurrent_retries_with_one_key_share_a_ticket(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "internal").await;
+        let project_id = seed_project(&pool, owner.id).await;
+
+        let config = Config::test_default();
+        let (app, _) = test_app(pool.clone(), &config).await;
+        let tasks: Vec<_> = (0..8)
+            .map(|_| {
+                let request = Request::builder()
+                    .method("POST")
+                    .uri(format!("/api/v1/widget/{}/submit", project_id))
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .header(IDEMPOTENCY_KEY_HEADER, "double-click")
+                    .body(Body::from(
+                        serde_json::json!({ "feedback_type": "bug", "description": "Cart is empty" })
+                            .to_string(),
+                    ))
+                    .unwrap();
+                let app = app.clone();
+                tokio::spawn(async move {
+                    let response = app.oneshot(request).await.unwrap();
+                    assert_eq!(response.status(), StatusCode::CREATED);
+                    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                        .await
+                        .unwrap();
+                    let body = serde_json::from_slice::<serde_json::Value>(&body).unwrap();
+                    body["data"]["ticket_id"].as_str().unwrap().to_string()
+                })
+            })
+            .collect();
+        let mut ids = Vec::new();
+        for task in tasks {
+            ids.push(task.await.unwrap());
+        }
+        assert!(ids.iter().all(|id| *id == ids[0]), "{ids:?}");
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM recordings")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn video_upload_accepts_webm_and_rejects_other_files(pool: PgPool) {
+        let owner_id = seed_user(&pool, "owner@example.com", "internal").await.id;
+        let project_id = seed_project(&pool, owner_id).await;
+
+        let config = Config::test_default();
+        let (app, _) = test_app(pool.clone(), &config).await;
+        let json = |response: axum::response::Response| async move {
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+        };
+
+        let submit = Request::builder()
+            .method("POST")
+            .uri(format!("/api/v1/widget/{}/submit", project_id))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::json!({ "feedback_type": "bug", "description": "Video freezes" })
+                    .to_string(),
+            ))
+            .unwrap();
+        let response = app.clone().oneshot(submit).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let ticket_id = json(response).await["data"]["ticket_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let upload = |file_name: &str, content_type: &str, data: &[u8]| {
+            let mut body = format!(
+                "--X\r\nContent-Disposition: form-data; name=\"video\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+                file_name, content_type
+            )
+            .into_bytes();
+            body.extend_from_slice(data);
+            body.extend_from_slice(b"\r\n--X--\r\n");
+            Request::builder()
+                .method("POST")
+                .uri(format!(
+                    "/api/v1/widget/{}/tickets/{}/upload",
+                    project_id, ticket_id
+                ))
+                .header(header::CONTENT_TYPE, "multipart/form-data; boundary=X")
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        // A text file is refused even when the browser labels it as a video
+        let response = app
+            .clone()
+            .oneshot(upload("notes.webm", "video/webm", b"steps to reproduce"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(json(response).await["error"], "Unsupported video format");
+        let stored_path: Option<String> =
+            sqlx::query_scalar("SELECT video_storage_path FROM recordings WHERE id = $1")
+                .bind(Uuid::parse_str(&ticket_id).unwrap())
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(stored_path, None);
+
+        let webm = [0x1A, 0x45, 0xDF, 0xA3, 0x84, 0x42, 0x82, 0x81, 0x77];
+        let response = app
+            .oneshot(upload("recording.webm", "video/webm", &webm))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn video_upload_respects_the_project_duration_limit(pool: PgPool) {
+        let owner_id = seed_user(&pool, "owner@example.com", "internal").await.id;
+        let project_id = seed_project_with(
+            &pool,
+            owner_id,
+            serde_json::json!({"max_duration_seconds": 60}),
+        )
+        .await;
+        let ticket_id =
+            |pool: PgPool| async move { seed_ticket(&pool, project_id, owner_id, "pending").await };
+        let (short, long) = (ticket_id(pool.clone()).await, ticket_id(pool.clone()).await);
+
+        let config = Config::test_default();
+        let (app, _) = test_app(pool.clone(), &config).await;
+        // No duration in the header, so the client-reported one is used
+        let webm = [0x1A, 0x45, 0xDF, 0xA3, 0x84, 0x42, 0x82, 0x81, 0x77];
+        let upload = |ticket_id: Uuid, duration: i32| {
+            let mut body = format!(
+                "--X\r\nContent-Disposition: form-data; name=\"duration\"\r\n\r\n{}\r\n--X\r\nContent-Disposition: form-data; name=\"video\"; filename=\"recording.webm\"\r\nContent-Type: video/webm\r\n\r\n",
+                duration
+            )
+            .into_bytes();
+            body.extend_from_slice(&webm);
+            body.extend_from_slice(b"\r\n--X--\r\n");
+            app.clone().oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!(
+                        "/api/v1/widget/{}/tickets/{}/upload",
+                        project_id, ticket_id
+                    ))
+                    .header(header::CONTENT_TYPE, "multipart/form-data; boundary=X")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+        };
+
+        let response = upload(short, 45).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = upload(long, 600).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"], "Video too long (600s). Max: 60s");
+
+        // Nothing was stored, queued or charged for the rejected video
+        let rows: Vec<(Uuid, Option<String>, Option<Uuid>)> = sqlx::query_as(
+            "SELECT id, video_storage_path, analysis_job_id FROM recordings WHERE id = ANY($1) ORDER BY id = $2 DESC",
+        )
+        .bind([short, long])
+        .bind(short)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert!(rows[0].1.is_some() && rows[0].2.is_some());
+        assert_eq!((rows[1].1.as_deref(), rows[1].2), (None, None));
+        let quota_used: i32 = sqlx::query_scalar("SELECT quota_used FROM users WHERE id = $1")
+            .bind(owner_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(quota_used, 1);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn widget_uploads_are_charged_to_the_project_owner(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "customer").await;
+        sqlx::query("UPDATE users SET quota_limit = 2 WHERE id = $1")
+            .bind(owner.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let project_id = seed_project(&pool, owner.id).await;
+
+        let config = Config::test_default();
+        let (app, _) = test_app(pool.clone(), &config).await;
+        let webm = [0x1A, 0x45, 0xDF, 0xA3, 0x84, 0x42, 0x82, 0x81, 0x77];
+        // A fresh submitter email for every ticket, so each gets its own anonymous account
+        let submit_and_upload = |n: usize| {
+            let app = app.clone();
+            async move {
+                let submit = Request::builder()
+                    .method("POST")
+                    .uri(format!("/api/v1/widget/{}/submit", project_id))
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(
+                        serde_json::json!({
+                            "feedback_type": "bug",
+                            "description": "Cart is empty",
+                            "submitter_email": format!("visitor{}@example.com", n)
+                        })
+                        .to_string(),
+                    ))
+                    .unwrap();
+                let response = app.clone().oneshot(submit).await.unwrap();
+                assert_eq!(response.status(), StatusCode::CREATED);
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                let ticket_id = serde_json::from_slice::<serde_json::Value>(&body).unwrap()["data"]
+                    ["ticket_id"]
+                    .as_str()
+                    .unwrap()
+                    .to_string();
+
+                let mut body = b"--X\r\nContent-Disposition: form-data; name=\"duration\"\r\n\r\n5\r\n--X\r\nContent-Disposition: form-data; name=\"video\"; filename=\"recording.webm\"\r\nContent-Type: video/webm\r\n\r\n".to_vec();
+                body.extend_from_slice(&webm);
+                body.extend_from_slice(b"\r\n--X--\r\n");
+                let upload = Request::builder()
+                    .method("POST")
+                    .uri(format!(
+                        "/api/v1/widget/{}/tickets/{}/upload",
+                        project_id, ticket_id
+                    ))
+                    .header(header::CONTENT_TYPE, "multipart/form-data; boundary=X")
+                    .body(Body::from(body))
+                    .unwrap();
+                app.oneshot(upload).await.unwrap().status()
+            }
+        };
+
+        assert_eq!(submit_and_upload(1).await, StatusCode::OK);
+        assert_eq!(submit_and_upload(2).await, StatusCode::OK);
+        assert_eq!(submit_and_upload(3).await, StatusCode::PAYMENT_REQUIRED);
+
+        let used: Vec<(String, i32)> = sqlx::query_as(
+            "SELECT email, quota_used FROM users WHERE email LIKE '%@example.com' ORDER BY email",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            used,
+            vec![
+                ("owner@example.com".to_string(), 2),
+                ("visitor1@example.com".to_string(), 0),
+                ("visitor2@example.com".to_string(), 0),
+                ("visitor3@example.com".to_string(), 0),
+            ]
+        );
+        let queued: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM analysis_jobs")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(queued, 2);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn video_upload_only_reaches_tickets_of_the_authorized_project(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "internal").await;
+        let open_project = seed_project(&pool, owner.id).await;
+        let locked_project =
+            seed_project_with(&pool, owner.id, serde_json::json!({"require_auth": true})).await;
+        let locked_ticket = seed_ticket(&pool, locked_project, owner.id, "pending").await;
+
+        let config = Config::test_default();
+        let (app, _) = test_app(pool.clone(), &config).await;
+        let webm = [0x1A, 0x45, 0xDF, 0xA3, 0x84, 0x42, 0x82, 0x81, 0x77];
+        let mut body = b"--X\r\nContent-Disposition: form-data; name=\"video\"; filename=\"recording.webm\"\r\nContent-Type: video/webm\r\n\r\n".to_vec();
+        body.extend_from_slice(&webm);
+        body.extend_from_slice(b"\r\n--X--\r\n");
+        let upload = |project_id: Uuid| {
+            app.clone().oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!(
+                        "/api/v1/widget/{}/tickets/{}/upload",
+                        project_id, locked_ticket
+                    ))
+                    .header(header::CONTENT_TYPE, "multipart/form-data; boundary=X")
+                    .body(Body::from(body.clone()))
+                    .unwrap(),
+            )
+        };
+
+        // The open project's access does not extend to the key-protected project's ticket
+        let response = upload(open_project).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response = upload(locked_project).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let (video_path, job_id): (Option<String>, Option<Uuid>) = sqlx::query_as(
+            "SELECT video_storage_path, analysis_job_id FROM recordings WHERE id = $1",
+        )
+        .bind(locked_ticket)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!((video_path, job_id), (None, None));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn confirm_upload_queues_analysis_of_the_stored_video(pool: PgPool) {
+        let owner = seed_user(&pool, "owner@example.com", "internal").await;
+        let project_id = seed_project(&pool, owner.id).await;
+        let ticket_id =
+            |pool: PgPool| async move { seed_ticket(&pool, project_id, owner.id, "pending").await };
+        let (uploaded, missing, not_video, too_large) = (
+            ticket_id(pool.clone()).await,
+            ticket_id(pool.clone()).await,
+            ticket_id(pool.clone()).await,
+            ticket_id(pool.clone()).await,
+        );
+
+        let mut config = Config::test_default();
+        config.max_upload_mb = 1;
+        let (app, state) = test_app(pool.clone(), &config).await;
+        let mut webm = vec![0u8; 1234];
+        webm[..4].copy_from_slice(&[0x1A, 0x45, 0xDF, 0xA3]);
+        let mut large = vec![0u8; 2 * 1024 * 1024];
+        large[..4].copy_from_slice(&[0x1A, 0x45, 0xDF, 0xA3]);
+        for (ticket_id, data) in [
+            (uploaded, webm.as_slice()),
+            (not_video, b"hello, not a video".as_slice()),
+            (too_large, large.as_slice()),
+        ] {
+            state
+                .storage
+                .upload(
+                    &format!("recordings/{}/{}.webm", project_id, ticket_id),
+                    data,
+                )
+                .await
+                .unwrap();
+        }
+        let post = |path: &str, ticket_id: Uuid| {
+            app.clone().oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!(
+                        "/api/v1/widget/{}/tickets/{}/{}",
+                        project_id, ticket_id, path
+                    ))
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(
+                        r#"{"duration": 95, "content_type": "video/webm"}"#,
+                    ))
+                    .unwrap(),
+            )
+        };
+        let job_id = |ticket_id: Uuid| {
+            let pool = pool.clone();
+            async move {
+                sqlx::query_scalar::<_, Option<Uuid>>(
+                    "SELECT analysis_job_id FROM recordings WHERE id = $1",
+                )
+                .bind(ticket_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap()
+            }
+        };
+        let quota_used = || async {
+            sqlx::query_scalar::<_, i32>("SELECT quota_used FROM users WHERE id = $1")
+                .bind(owner.id)
+                .fetch_one(&pool)
+                .await
+                .unwrap()
+        };
+
+        // Local storage cannot hand out upload URLs; the objects above stand in for PUTs
+        let response = post("upload-url", uploaded).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = post("confirm-upload", uploaded).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let (status, size, duration, job_id_after): (
+            String,
+            Option<i64>,
+            Option<i32>,
+            Option<Uuid>,
+        ) = sqlx::query_as(
+            "SELECT status, video_size_bytes, duration_seconds, analysis_job_id FROM recordings WHERE id = $1",
+        )
+        .bind(uploaded)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(status, "processing");
+        assert_eq!(size, Some(1234));
+        assert_eq!(duration, Some(95));
+        assert!(job_id_after.is_some());
+        assert_eq!(quota_used().await, 1);
+
+        // Confirming twice would queue (and charge) a second analysis
+        let response = post("confirm-upload", uploaded).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let response = post("upload-url", uploaded).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        // Missing, non-video and oversized objects are refused before the quota is charged,
+        // and refused objects are removed
+        for ticket_id in [missing, not_video, too_large] {
+            let response = post("confirm-upload", ticket_id).await.unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+            assert_eq!(job_id(ticket_id).await, None);
+            let path = format!("recordings/{}/{}.webm", project_id, ticket_id);
+            assert_eq!(state.storage.object_size(&path).await.unwrap(), None);
+        }
+        assert_eq!(quota_used().await, 1);
+    }
+}
@@ -0,0 +1,249 @@
+//! Admin DTOs - diagnostics for self-hosted deployments
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::config::Config;
+use crate::dto::TicketExportResponse;
+use crate::models::{AnalysisJob, JobStatus, User};
+
+// ============================================================================
+// Request DTOs
+// ============================================================================
+
+/// Set a user's analysis quota
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateQuotaRequest {
+    #[validate(range(min = 0, message = "quota_limit must not be negative"))]
+    pub quota_limit: i32,
+}
+
+/// Filters and page for the admin job list
+#[derive(Debug, Deserialize)]
+pub struct JobListQueryParams {
+    pub status: Option<JobStatus>,
+    pub recording_id: Option<Uuid>,
+    /// Only jobs created at or after this time
+    pub created_after: Option<DateTime<Utc>>,
+    /// Only jobs created before this time
+    pub created_before: Option<DateTime<Utc>>,
+    #[serde(default = "default_page")]
+    pub page: i32,
+    #[serde(default = "default_per_page")]
+    pub per_page: i32,
+}
+
+/// Largest `per_page` the job list accepts
+const MAX_JOBS_PER_PAGE: i32 = 100;
+
+impl JobListQueryParams {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.page < 1 {
+            return Err("page must be at least 1".to_string());
+        }
+        if !(1..=MAX_JOBS_PER_PAGE).contains(&self.per_page) {
+            return Err(format!(
+                "per_page must be between 1 and {}",
+                MAX_JOBS_PER_PAGE
+            ));
+        }
+        if let (Some(after), Some(before)) = (self.created_after, self.created_before) {
+            if after >= before {
+                return Err("created_after must be earlier than created_before".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+fn default_page() -> i32 {
+    1
+}
+
+fn default_per_page() -> i32 {
+    20
+}
+
+/// Export everything submitted under one email address
+#[derive(Debug, Deserialize)]
+pub struct SubmitterExportQuery {
+    pub email: String,
+}
+
+// ============================================================================
+// Response DTOs
+// ============================================================================
+
+/// Google OAuth configuration as the backend sees it, for comparing against the Google Console
+#[derive(Debug, Serialize)]
+pub struct OAuthConfigResponse {
+    /// Exact redirect_uri sent to Google; must be an authorized redirect URI in the console
+    pub redirect_uri: String,
+    /// Client id with the middle masked; `None` when GOOGLE_CLIENT_ID is unset
+    pub client_id: Option<String>,
+    pub client_secret_set: bool,
+}
+
+impl OAuthConfigResponse {
+    pub fn from_config(config: &Config) -> Self {
+        let client_id =
+            (!config.google_client_id.is_empty()).then(|| mask_client_id(&config.google_client_id));
+        Self {
+            redirect_uri: config.google_redirect_uri(),
+            client_id,
+            client_secret_set: !config.google_client_secret.is_empty(),
+        }
+    }
+}
+
+/// Longest job error returned in the job list
+const MAX_JOB_ERROR_CHARS: usize = 200;
+
+/// Analysis job in the admin job list (error truncated; analysis output and prompt omitted)
+#[derive(Debug, Serialize)]
+pub struct JobListItem {
+    pub id: Uuid,
+    pub recording_id: Option<Uuid>,
+    pub status: JobStatus,
+    pub retry_count: i32,
+    pub error_message: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl From<AnalysisJob> for JobListItem {
+    fn from(job: AnalysisJob) -> Self {
+        Self {
+            id: job.id,
+            recording_id: job.recording_id,
+            status: job.status,
+            retry_count: job.retry_count,
+            error_message: job.error_message.map(|e| truncate_error(&e)),
+            created_at: job.created_at,
+            started_at: job.started_at,
+            completed_at: job.completed_at,
+        }
+    }
+}
+
+fn truncate_error(error: &str) -> String {
+    if error.chars().count() <= MAX_JOB_ERROR_CHARS {
+        return error.to_string();
+    }
+    let kept: String = error.chars().take(MAX_JOB_ERROR_CHARS).collect();
+    format!("{}...", kept.trim_end())
+}
+
+/// Dead-lettered analysis job (analysis output and prompt omitted)
+#[derive(Debug, Serialize)]
+pub struct DeadLetterJobResponse {
+    pub id: Uuid,
+    pub recording_id: Option<Uuid>,
+    pub video_storage_path: String,
+    pub error_message: Option<String>,
+    pub retry_count: i32,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl From<AnalysisJob> for DeadLetterJobResponse {
+    fn from(job: AnalysisJob) -> Self {
+        Self {
+            id: job.id,
+            recording_id: job.recording_id,
+            video_storage_path: job.video_storage_path,
+            error_message: job.error_message,
+            retry_count: job.retry_count,
+            created_at: job.created_at,
+            completed_at: job.completed_at,
+        }
+    }
+}
+
+/// A job after a manual retry
+#[derive(Debug, Serialize)]
+pub struct RetryJobResponse {
+    pub id: Uuid,
+    pub recording_id: Option<Uuid>,
+    pub status: JobStatus,
+    pub retry_count: i32,
+}
+
+impl From<AnalysisJob> for RetryJobResponse {
+    fn from(job: AnalysisJob) -> Self {
+        Self {
+            id: job.id,
+            recording_id: job.recording_id,
+            status: job.status,
+            retry_count: job.retry_count,
+        }
+    }
+}
+
+/// A user's analysis quota and how much of it is used
+#[derive(Debug, Serialize)]
+pub struct UserQuotaResponse {
+    pub user_id: Uuid,
+    pub quota_limit: i32,
+    pub quota_used: i32,
+    /// Internal users are never refused for quota
+    pub exempt: bool,
+}
+
+impl From<User> for UserQuotaResponse {
+    fn from(user: User) -> Self {
+        Self {
+            user_id: user.id,
+            quota_limit: user.quota_limit,
+            quota_used: user.quota_used,
+            exempt: user.is_internal(),
+        }
+    }
+}
+
+/// All tickets a submitter sent to the caller's projects, each fully exported
+#[derive(Debug, Serialize)]
+pub struct SubmitterExportResponse {
+    pub email: String,
+    pub exported_at: DateTime<Utc>,
+    pub tickets: Vec<TicketExportResponse>,
+}
+
+/// Keep enough of the id to recognise it (numeric prefix and domain suffix), hide the rest
+fn mask_client_id(id: &str) -> String {
+    let chars: Vec<char> = id.chars().collect();
+    if chars.len() <= 12 {
+        return "*".repeat(chars.len());
+    }
+    let head: String = chars[..6].iter().collect();
+    let tail: String = chars[chars.len() - 6..].iter().collect();
+    format!("{}...{}", head, tail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mask_client_id_keeps_prefix_and_suffix() {
+        let masked = mask_client_id("123456789012-abcdefghijk.apps.googleusercontent.com");
+        assert_eq!(masked, "123456...nt.com");
+    }
+
+    #[test]
+    fn mask_client_id_hides_short_values_entirely() {
+        assert_eq!(mask_client_id("short-id"), "********");
+    }
+
+    #[test]
+    fn truncate_error_keeps_short_errors_and_cuts_long_ones() {
+        assert_eq!(truncate_error("Gemini timed out"), "Gemini timed out");
+        let long = "é".repeat(MAX_JOB_ERROR_CHARS + 1);
+        let truncated = truncate_error(&long);
+        assert_eq!(truncated.chars().count(), MAX_JOB_ERROR_CHARS + 3);
+        assert!(truncated.ends_with("..."));
+    }
+}
@@ -0,0 +1,74 @@
+//! Project API key DTOs
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::ProjectApiKey;
+
+/// Longest label an API key can have
+const MAX_LABEL_CHARS: usize = 100;
+
+// ============================================================================
+// Request DTOs
+// ============================================================================
+
+/// Create API key request
+#[derive(Debug, Default, Deserialize)]
+pub struct CreateApiKeyRequest {
+    /// Free-form note, e.g. where the key is deployed
+    pub label: Option<String>,
+}
+
+impl CreateApiKeyRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(ref label) = self.label {
+            if label.trim().chars().count() > MAX_LABEL_CHARS {
+                return Err(format!(
+                    "Label must be at most {} characters",
+                    MAX_LABEL_CHARS
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Response DTOs
+// ============================================================================
+
+/// API key response (never includes the key)
+#[derive(Debug, Serialize)]
+pub struct ApiKeyResponse {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub label: String,
+    pub key_prefix: String,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl From<ProjectApiKey> for ApiKeyResponse {
+    fn from(api_key: ProjectApiKey) -> Self {
+        let is_active = api_key.is_active();
+        Self {
+            id: api_key.id,
+            project_id: api_key.project_id,
+            label: api_key.label,
+            key_prefix: api_key.key_prefix,
+            is_active,
+            created_at: api_key.created_at,
+            revoked_at: api_key.revoked_at,
+        }
+    }
+}
+
+/// Newly created API key; the only response that carries the plaintext key
+#[derive(Debug, Serialize)]
+pub struct CreatedApiKeyResponse {
+    pub key: String,
+    #[serde(flatten)]
+    pub api_key: ApiKeyResponse,
+}
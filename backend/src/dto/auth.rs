@@ -49,6 +49,12 @@ pub struct RefreshTokenRequest {
     pub refresh_token: String,
 }
 
+/// Access token introspection request
+#[derive(Debug, Deserialize)]
+pub struct IntrospectTokenRequest {
+    pub token: String,
+}
+
 /// Customer onboarding completion request
 #[derive(Debug, Deserialize, Validate)]
 pub struct CompleteOnboardingRequest {
@@ -57,6 +63,12 @@ pub struct CompleteOnboardingRequest {
     pub company_name: Option<String>,
 }
 
+/// Account deletion request; without a password the access token must be recent
+#[derive(Debug, Default, Deserialize)]
+pub struct DeleteAccountRequest {
+    pub password: Option<String>,
+}
+
 // ============================================================================
 // Response DTOs
 // ============================================================================
@@ -121,8 +133,7 @@ pub struct GoogleAuthUrlResponse {
     pub url: String,
 }
 
-/// Token validation response
-#[allow(dead_code)] // Reserved for future token validation endpoint
+/// Token introspection response; `user` is only present for a valid token
 #[derive(Debug, Serialize)]
 pub struct TokenValidationResponse {
     pub valid: bool,
@@ -148,6 +159,7 @@ mod tests {
             refresh_token_hash: None,
             quota_limit: 10,
             quota_used: 3,
+            is_anonymous: false,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
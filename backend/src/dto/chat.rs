@@ -20,6 +20,41 @@ pub struct SendMessageRequest {
     pub message: String,
 }
 
+/// Chat message list query parameters. Without either, the whole thread is returned
+/// oldest first; with either, one page is returned newest first.
+#[derive(Debug, Deserialize)]
+pub struct ChatMessagesQuery {
+    /// `next_cursor` from the previous page; only older messages are returned
+    pub before: Option<String>,
+    /// Page size (default 50, at most 100)
+    pub limit: Option<i64>,
+}
+
+impl ChatMessagesQuery {
+    pub const DEFAULT_LIMIT: i64 = 50;
+    pub const MAX_LIMIT: i64 = 100;
+
+    pub fn is_paged(&self) -> bool {
+        self.before.is_some() || self.limit.is_some()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(limit) = self.limit {
+            if !(1..=Self::MAX_LIMIT).contains(&limit) {
+                return Err(format!("limit must be between 1 and {}", Self::MAX_LIMIT));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Mark chat messages as read
+#[derive(Debug, Default, Deserialize)]
+pub struct MarkChatReadRequest {
+    /// Last message read; the newest message when absent
+    pub message_id: Option<Uuid>,
+}
+
 /// Edit a chat message
 #[derive(Debug, Deserialize, Validate)]
 pub struct EditMessageRequest {
@@ -47,4 +82,37 @@ pub struct ChatMessageResponse {
     pub sent_at: DateTime<Utc>,
     pub edited_at: Option<DateTime<Utc>>,
     pub is_own: bool, // Whether this message was sent by the current user
+    /// Whether another participant has read up to this message
+    pub seen_by_other: bool,
+}
+
+/// Chat messages, as a whole thread or one page of it
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum ChatMessagesResponse {
+    /// The whole thread, oldest first
+    All(Vec<ChatMessageResponse>),
+    /// One page, newest first
+    Page {
+        messages: Vec<ChatMessageResponse>,
+        /// Pass as `before` to fetch older messages; absent on the oldest page
+        next_cursor: Option<String>,
+    },
+}
+
+/// Frame pushed to clients on the ticket chat WebSocket
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChatSocketFrame {
+    /// A message sent to the ticket, by anyone including this client
+    Message { message: ChatMessageResponse },
+    /// A frame from this client could not be handled; the socket stays open
+    Error { error: String },
+}
+
+/// Query parameters for the ticket chat WebSocket
+#[derive(Debug, Deserialize)]
+pub struct ChatSocketQuery {
+    /// Access token, for browsers that cannot set headers on the upgrade request
+    pub token: Option<String>,
 }
@@ -26,6 +26,9 @@ pub struct PaginatedResponse<T: Serialize> {
     pub page: i32,
     pub per_page: i32,
     pub total_pages: i32,
+    /// Token for the next page in keyset pagination, when there may be more results
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 impl<T: Serialize> PaginatedResponse<T> {
@@ -37,8 +40,14 @@ impl<T: Serialize> PaginatedResponse<T> {
             page,
             per_page,
             total_pages,
+            next_cursor: None,
         }
     }
+
+    pub fn with_next_cursor(mut self, next_cursor: Option<String>) -> Self {
+        self.next_cursor = next_cursor;
+        self
+    }
 }
 
 /// Simple message response
@@ -0,0 +1,23 @@
+//! API metadata DTOs
+
+use serde::Serialize;
+
+use crate::error::ErrorCode;
+
+/// One entry of the error code catalog
+#[derive(Debug, Serialize)]
+pub struct ErrorCodeInfo {
+    pub code: ErrorCode,
+    pub status: u16,
+    pub description: &'static str,
+}
+
+impl From<ErrorCode> for ErrorCodeInfo {
+    fn from(code: ErrorCode) -> Self {
+        Self {
+            code,
+            status: code.status().as_u16(),
+            description: code.description(),
+        }
+    }
+}
@@ -1,15 +1,27 @@
 //! Data Transfer Objects for API requests and responses
 
+pub mod admin;
+pub mod api_key;
 pub mod auth;
 pub mod chat;
 pub mod common;
+pub mod meta;
 pub mod project;
+pub mod session;
 pub mod ticket;
+pub mod validated_json;
+pub mod webhook;
 pub mod widget;
 
+pub use admin::*;
+pub use api_key::*;
 pub use auth::*;
 pub use chat::*;
 pub use common::*;
+pub use meta::*;
 pub use project::*;
+pub use session::*;
 pub use ticket::*;
+pub use validated_json::*;
+pub use webhook::*;
 pub use widget::*;
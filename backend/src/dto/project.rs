@@ -5,7 +5,11 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::Validate;
 
-use crate::models::{AnalysisQuestions, Project};
+use crate::models::{
+    AnalysisQuestions, GeminiParams, IssueSeverity, JiraSettings, OutcomeMapping, Project,
+    ProjectRole,
+};
+use crate::services::{ProjectAnalytics, ProjectTrends};
 
 // ============================================================================
 // Request DTOs
@@ -31,6 +35,23 @@ pub struct CreateProjectRequest {
     pub require_auth: Option<bool>,
     pub is_active: Option<bool>,
     pub analysis_questions: Option<AnalysisQuestions>,
+    /// Priority/status adjustments applied when analysis reports a given outcome.
+    pub outcome_mapping: Option<OutcomeMapping>,
+    /// Slack incoming-webhook URL notified when tickets are created or resolved.
+    pub slack_webhook_url: Option<String>,
+    /// Discord webhook URL notified when tickets are created or resolved.
+    pub discord_webhook_url: Option<String>,
+    /// Language analysis reports are written in (defaults to English).
+    pub report_language: Option<String>,
+    /// Jira credentials for exporting report issues.
+    pub jira: Option<JiraSettings>,
+    /// Gemini temperature / output length overrides; out-of-range values are clamped.
+    pub gemini_params: Option<GeminiParams>,
+    /// Whether analysis prompts list the submitter's earlier resolved tickets.
+    pub include_prior_sessions: Option<bool>,
+    /// Longest video accepted for analysis, in seconds; unset or 0 means no limit.
+    #[validate(range(min = 0, message = "max_duration_seconds must not be negative"))]
+    pub max_duration_seconds: Option<i32>,
 }
 
 /// Update project request
@@ -48,6 +69,206 @@ pub struct UpdateProjectRequest {
     /// Whether users must be authenticated in the customer's app before submitting feedback.
     pub require_auth: Option<bool>,
     pub analysis_questions: Option<AnalysisQuestions>,
+    pub outcome_mapping: Option<OutcomeMapping>,
+    /// Empty string removes the Slack integration.
+    pub slack_webhook_url: Option<String>,
+    /// Empty string removes the Discord integration.
+    pub discord_webhook_url: Option<String>,
+    /// Empty string goes back to English.
+    pub report_language: Option<String>,
+    /// Replaces the stored Jira credentials (the API token must be resent).
+    pub jira: Option<JiraSettings>,
+    /// Replaces the stored Gemini overrides; `{}` goes back to the defaults.
+    pub gemini_params: Option<GeminiParams>,
+    pub include_prior_sessions: Option<bool>,
+    /// 0 removes the limit.
+    #[validate(range(min = 0, message = "max_duration_seconds must not be negative"))]
+    pub max_duration_seconds: Option<i32>,
+}
+
+/// Transfer project ownership request
+#[derive(Debug, Deserialize, Validate)]
+pub struct TransferProjectRequest {
+    /// Email of the internal user who becomes the owner
+    #[validate(email(message = "Invalid email address"))]
+    pub new_owner_email: String,
+}
+
+/// Reorder analysis questions request
+#[derive(Debug, Deserialize, Validate)]
+pub struct ReorderQuestionsRequest {
+    /// Question ids in the order they should be asked; unlisted questions follow them
+    #[validate(length(min = 1, message = "ids must not be empty"))]
+    pub ids: Vec<String>,
+}
+
+/// Set the current user's default analysis questions for new projects
+#[derive(Debug, Deserialize)]
+pub struct QuestionTemplateRequest {
+    pub analysis_questions: AnalysisQuestions,
+}
+
+/// Share a project with an internal user
+#[derive(Debug, Deserialize, Validate)]
+pub struct AddProjectMemberRequest {
+    #[validate(email(message = "Invalid email address"))]
+    pub email: String,
+    /// Defaults to `member`
+    #[serde(default)]
+    pub role: ProjectRole,
+}
+
+/// Width of each point in an analytics trend series
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnalyticsBucket {
+    #[default]
+    Day,
+    Week,
+}
+
+impl AnalyticsBucket {
+    /// `date_trunc` field name (also a valid interval unit)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnalyticsBucket::Day => "day",
+            AnalyticsBucket::Week => "week",
+        }
+    }
+}
+
+/// Project list query parameters
+#[derive(Debug, Deserialize)]
+pub struct ProjectListQuery {
+    /// Case-insensitive match on name or domain
+    pub search: Option<String>,
+    pub is_active: Option<bool>,
+    #[serde(default = "default_page")]
+    pub page: i32,
+    #[serde(default = "default_per_page")]
+    pub per_page: i32,
+}
+
+/// Largest `per_page` the project list accepts
+const MAX_PROJECTS_PER_PAGE: i32 = 100;
+
+impl ProjectListQuery {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.page < 1 {
+            return Err("page must be at least 1".to_string());
+        }
+        if !(1..=MAX_PROJECTS_PER_PAGE).contains(&self.per_page) {
+            return Err(format!(
+                "per_page must be between 1 and {}",
+                MAX_PROJECTS_PER_PAGE
+            ));
+        }
+        Ok(())
+    }
+
+    /// Trimmed search term, if any
+    pub fn search(&self) -> Option<&str> {
+        self.search
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+    }
+}
+
+fn default_page() -> i32 {
+    1
+}
+
+fn default_per_page() -> i32 {
+    20
+}
+
+/// Project analytics query parameters
+#[derive(Debug, Deserialize)]
+pub struct ProjectAnalyticsQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub bucket: AnalyticsBucket,
+}
+
+/// Window used when `from` is omitted
+const DEFAULT_ANALYTICS_DAYS: i64 = 30;
+/// Longest window a trend query may cover
+const MAX_ANALYTICS_DAYS: i64 = 366;
+
+impl ProjectAnalyticsQuery {
+    /// Resolve the `[from, to)` window: `to` defaults to now, `from` to 30 days before `to`
+    pub fn range(&self, now: DateTime<Utc>) -> Result<(DateTime<Utc>, DateTime<Utc>), String> {
+        let to = self.to.unwrap_or(now);
+        let from = self
+            .from
+            .unwrap_or(to - chrono::Duration::days(DEFAULT_ANALYTICS_DAYS));
+        if from >= to {
+            return Err("from must be earlier than to".to_string());
+        }
+        if to - from > chrono::Duration::days(MAX_ANALYTICS_DAYS) {
+            return Err(format!(
+                "Analytics range must be at most {} days",
+                MAX_ANALYTICS_DAYS
+            ));
+        }
+        Ok((from, to))
+    }
+}
+
+/// Top issues query parameters
+#[derive(Debug, Deserialize)]
+pub struct TopIssuesQuery {
+    /// Number of issue groups (default 10, at most 50)
+    pub limit: Option<i64>,
+}
+
+impl TopIssuesQuery {
+    const DEFAULT_LIMIT: i64 = 10;
+    const MAX_LIMIT: i64 = 50;
+
+    pub fn limit(&self) -> Result<i64, String> {
+        let limit = self.limit.unwrap_or(Self::DEFAULT_LIMIT);
+        if !(1..=Self::MAX_LIMIT).contains(&limit) {
+            return Err(format!("limit must be between 1 and {}", Self::MAX_LIMIT));
+        }
+        Ok(limit)
+    }
+}
+
+/// Filters and page for the project issue list
+#[derive(Debug, Deserialize)]
+pub struct ProjectIssuesQuery {
+    pub severity: Option<IssueSeverity>,
+    /// Issues carrying this tag (case-insensitive)
+    pub tag: Option<String>,
+    #[serde(default = "default_page")]
+    pub page: i32,
+    #[serde(default = "default_per_page")]
+    pub per_page: i32,
+}
+
+impl ProjectIssuesQuery {
+    const MAX_PER_PAGE: i32 = 100;
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.page < 1 {
+            return Err("page must be at least 1".to_string());
+        }
+        if !(1..=Self::MAX_PER_PAGE).contains(&self.per_page) {
+            return Err(format!(
+                "per_page must be between 1 and {}",
+                Self::MAX_PER_PAGE
+            ));
+        }
+        Ok(())
+    }
+
+    /// The tag filter, trimmed; `None` when blank
+    pub fn tag(&self) -> Option<&str> {
+        self.tag.as_deref().map(str::trim).filter(|t| !t.is_empty())
+    }
 }
 
 // ============================================================================
@@ -63,6 +284,14 @@ pub struct ProjectResponse {
     pub is_active: bool,
     pub require_auth: bool,
     pub analysis_questions: AnalysisQuestions,
+    pub outcome_mapping: OutcomeMapping,
+    pub slack_webhook_url: Option<String>,
+    pub discord_webhook_url: Option<String>,
+    pub report_language: String,
+    pub jira: Option<JiraConfigResponse>,
+    pub gemini_params: GeminiParams,
+    pub include_prior_sessions: bool,
+    pub max_duration_seconds: Option<i32>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub ticket_count: i64,
@@ -72,6 +301,14 @@ impl ProjectResponse {
     pub fn from_project(project: Project, ticket_count: i64) -> Self {
         let require_auth = project.require_auth();
         let analysis_questions = project.analysis_questions();
+        let outcome_mapping = project.outcome_mapping();
+        let slack_webhook_url = project.slack_webhook_url();
+        let discord_webhook_url = project.discord_webhook_url();
+        let report_language = project.report_language();
+        let jira = project.jira_settings().map(JiraConfigResponse::from);
+        let gemini_params = project.gemini_params();
+        let include_prior_sessions = project.include_prior_sessions();
+        let max_duration_seconds = project.max_duration_seconds();
         Self {
             id: project.id,
             name: project.name,
@@ -79,6 +316,14 @@ impl ProjectResponse {
             is_active: project.is_active,
             require_auth,
             analysis_questions,
+            outcome_mapping,
+            slack_webhook_url,
+            discord_webhook_url,
+            report_language,
+            jira,
+            gemini_params,
+            include_prior_sessions,
+            max_duration_seconds,
             created_at: project.created_at,
             updated_at: project.updated_at,
             ticket_count,
@@ -86,6 +331,35 @@ impl ProjectResponse {
     }
 }
 
+/// Analysis questions new projects start with when created without their own
+#[derive(Debug, Serialize)]
+pub struct QuestionTemplateResponse {
+    pub analysis_questions: AnalysisQuestions,
+    /// True when no template is saved and the built-in questions apply
+    pub is_default: bool,
+}
+
+/// Jira settings as returned to the client (API token omitted)
+#[derive(Debug, Serialize)]
+pub struct JiraConfigResponse {
+    pub base_url: String,
+    pub email: String,
+    pub project_key: String,
+    pub issue_type: String,
+}
+
+impl From<JiraSettings> for JiraConfigResponse {
+    fn from(settings: JiraSettings) -> Self {
+        let issue_type = settings.issue_type().to_string();
+        Self {
+            base_url: settings.base_url,
+            email: settings.email,
+            project_key: settings.project_key,
+            issue_type,
+        }
+    }
+}
+
 /// Project list item
 #[derive(Debug, Serialize)]
 pub struct ProjectListItem {
@@ -95,6 +369,62 @@ pub struct ProjectListItem {
     pub is_active: bool,
     pub require_auth: bool,
     pub analysis_questions: AnalysisQuestions,
+    pub outcome_mapping: OutcomeMapping,
+    pub slack_webhook_url: Option<String>,
+    pub discord_webhook_url: Option<String>,
+    pub report_language: String,
+    pub jira: Option<JiraConfigResponse>,
+    pub gemini_params: GeminiParams,
+    pub include_prior_sessions: bool,
+    pub max_duration_seconds: Option<i32>,
     pub created_at: DateTime<Utc>,
     pub ticket_count: i64,
 }
+
+impl ProjectListItem {
+    pub fn from_project(project: Project, ticket_count: i64) -> Self {
+        let require_auth = project.require_auth();
+        let analysis_questions = project.analysis_questions();
+        let outcome_mapping = project.outcome_mapping();
+        let slack_webhook_url = project.slack_webhook_url();
+        let discord_webhook_url = project.discord_webhook_url();
+        let report_language = project.report_language();
+        let jira = project.jira_settings().map(JiraConfigResponse::from);
+        let gemini_params = project.gemini_params();
+        let include_prior_sessions = project.include_prior_sessions();
+        let max_duration_seconds = project.max_duration_seconds();
+        Self {
+            id: project.id,
+            name: project.name,
+            domain: project.domain,
+            is_active: project.is_active,
+            require_auth,
+            analysis_questions,
+            outcome_mapping,
+            slack_webhook_url,
+            discord_webhook_url,
+            report_language,
+            jira,
+            gemini_params,
+            include_prior_sessions,
+            max_duration_seconds,
+            created_at: project.created_at,
+            ticket_count,
+        }
+    }
+}
+
+/// Project analytics: token usage totals plus ticket trends for the requested window
+#[derive(Debug, Serialize)]
+pub struct ProjectAnalyticsResponse {
+    #[serde(flatten)]
+    pub usage: ProjectAnalytics,
+    pub trends: ProjectTrends,
+}
+
+/// Unread ticket count for a project (for badge display)
+#[derive(Debug, Serialize)]
+pub struct ProjectUnreadResponse {
+    pub project_id: Uuid,
+    pub unread_count: i64,
+}
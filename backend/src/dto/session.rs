@@ -0,0 +1,100 @@
+//! Session DTOs
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::{FeedbackType, Session};
+
+// ============================================================================
+// Request DTOs
+// ============================================================================
+
+/// Create session request
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateSessionRequest {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Name must be between 1 and 255 characters"
+    ))]
+    pub name: String,
+    /// Feedback type of the session's tickets (defaults to feedback)
+    #[serde(rename = "type")]
+    pub session_type: Option<FeedbackType>,
+    #[validate(length(max = 20, message = "At most 20 questions"))]
+    pub questions: Option<Vec<String>>,
+}
+
+/// Update session request
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateSessionRequest {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Name must be between 1 and 255 characters"
+    ))]
+    pub name: Option<String>,
+    /// Replaces the session's questions
+    #[validate(length(max = 20, message = "At most 20 questions"))]
+    pub questions: Option<Vec<String>>,
+}
+
+/// Submit a ticket through a session's share link
+#[derive(Debug, Deserialize, Validate)]
+pub struct SessionSubmitRequest {
+    #[validate(length(max = 5000, message = "Description must be at most 5000 characters"))]
+    pub description: Option<String>,
+    pub page_url: Option<String>,
+}
+
+// ============================================================================
+// Response DTOs
+// ============================================================================
+
+/// Session response for its owner, share token included
+#[derive(Debug, Serialize)]
+pub struct SessionResponse {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub session_type: FeedbackType,
+    pub share_token: String,
+    pub questions: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Session> for SessionResponse {
+    fn from(session: Session) -> Self {
+        Self {
+            id: session.id,
+            name: session.name,
+            session_type: session.session_type,
+            share_token: session.share_token,
+            questions: session.questions.0,
+            created_at: session.created_at,
+            updated_at: session.updated_at,
+        }
+    }
+}
+
+/// What a submitter following a share link sees of the session
+#[derive(Debug, Serialize)]
+pub struct SharedSessionResponse {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub session_type: FeedbackType,
+    pub questions: Vec<String>,
+}
+
+impl From<Session> for SharedSessionResponse {
+    fn from(session: Session) -> Self {
+        Self {
+            name: session.name,
+            session_type: session.session_type,
+            questions: session.questions.0,
+        }
+    }
+}
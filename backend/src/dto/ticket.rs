@@ -1,12 +1,15 @@
 //! Ticket DTOs
 
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use uuid::Uuid;
+use validator::Validate;
 
+use crate::dto::ChatMessageResponse;
 use crate::models::{
-    Evidence, FeedbackType, IssueSeverity, ProcessingStatus, QuestionAnalysis, ReportOutcome,
-    TicketPriority, TicketStatus, TicketWithDetails,
+    Evidence, FeedbackType, IssueSeverity, IssueStatus, JobProgressStage, JobStatus,
+    ProcessingStatus, QuestionAnalysis, ReportOutcome, ReportSentiment, TicketPriority,
+    TicketShareToken, TicketStatus, TicketWithDetails,
 };
 
 // ============================================================================
@@ -21,13 +24,41 @@ pub struct TicketListQueryParams {
     pub feedback_type: Option<FeedbackType>,
     pub ticket_status: Option<TicketStatus>,
     pub priority: Option<TicketPriority>,
+    /// Words to find in the ticket description or its report overview
     pub search: Option<String>,
+    pub assignee_id: Option<Uuid>,
+    /// Only tickets created at or after this time
+    pub created_after: Option<DateTime<Utc>>,
+    /// Only tickets created before this time
+    pub created_before: Option<DateTime<Utc>>,
+    /// Only tickets carrying this label
+    pub label: Option<String>,
+    /// Only tickets whose report has this sentiment
+    pub sentiment: Option<ReportSentiment>,
+    /// `created_at` (default), `updated_at`, `priority`, `confidence` or `relevance`
+    /// (default when searching)
+    pub sort_by: Option<String>,
+    /// `asc` or `desc` (default)
+    pub sort_dir: Option<String>,
+    /// `next_cursor` from the previous page; switches to keyset pagination
+    pub cursor: Option<String>,
     #[serde(default = "default_page")]
     pub page: i32,
     #[serde(default = "default_per_page")]
     pub per_page: i32,
 }
 
+impl TicketListQueryParams {
+    pub fn validate(&self) -> Result<(), String> {
+        if let (Some(after), Some(before)) = (self.created_after, self.created_before) {
+            if after >= before {
+                return Err("created_after must be earlier than created_before".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
 fn default_page() -> i32 {
     1
 }
@@ -36,14 +67,96 @@ fn default_per_page() -> i32 {
     20
 }
 
-/// Update ticket request (status, priority, assignee)
+/// Query parameters for marking all of a project's tickets as read
+#[derive(Debug, Deserialize)]
+pub struct MarkAllReadQuery {
+    pub project_id: Uuid,
+}
+
+/// Update ticket request (status, priority, assignee, due date)
 #[derive(Debug, Deserialize)]
 pub struct UpdateTicketRequest {
     pub ticket_status: Option<TicketStatus>,
     pub priority: Option<TicketPriority>,
     pub assignee_id: Option<Uuid>,
+    /// Absent leaves the due date alone; `null` clears it
+    #[serde(default, deserialize_with = "double_option")]
+    pub due_date: Option<Option<DateTime<Utc>>>,
     #[allow(dead_code)]
     pub category: Option<String>,
+    /// The ticket's `updated_at` as last loaded; when set, the update is refused with a
+    /// 409 if the ticket has changed since
+    pub expected_updated_at: Option<DateTime<Utc>>,
+}
+
+/// Deserialize a field so an explicit `null` (`Some(None)`) differs from an absent one (`None`)
+fn double_option<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Log a ticket for feedback received outside the widget
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateTicketRequest {
+    pub project_id: Uuid,
+    pub feedback_type: FeedbackType,
+    #[validate(length(
+        min = 1,
+        max = 5000,
+        message = "Description must be between 1 and 5000 characters"
+    ))]
+    pub description: String,
+    #[validate(email(message = "Invalid email address"))]
+    pub submitter_email: Option<String>,
+    #[validate(length(max = 255, message = "Submitter name must be at most 255 characters"))]
+    pub submitter_name: Option<String>,
+    pub page_url: Option<String>,
+}
+
+/// Update a report issue's triage status
+#[derive(Debug, Deserialize)]
+pub struct UpdateIssueRequest {
+    pub issue_status: IssueStatus,
+}
+
+/// Correct an analysis report by hand; omitted fields are left as they are
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateReportRequest {
+    #[validate(length(
+        min = 1,
+        max = 5000,
+        message = "Overview must be between 1 and 5000 characters"
+    ))]
+    pub overview: Option<String>,
+    pub outcome: Option<ReportOutcome>,
+    #[validate(range(min = 0, max = 100, message = "Confidence must be between 0 and 100"))]
+    pub confidence: Option<i32>,
+    #[validate(length(max = 50, message = "At most 50 suggested actions"))]
+    pub suggested_actions: Option<Vec<String>>,
+}
+
+impl UpdateReportRequest {
+    pub fn is_empty(&self) -> bool {
+        self.overview.is_none()
+            && self.outcome.is_none()
+            && self.confidence.is_none()
+            && self.suggested_actions.is_none()
+    }
+}
+
+/// Merge a duplicate ticket into another one
+#[derive(Debug, Deserialize)]
+pub struct MergeTicketRequest {
+    pub into_ticket_id: Uuid,
+}
+
+/// Add ticket label request
+#[derive(Debug, Deserialize)]
+pub struct AddTicketLabelRequest {
+    pub label: String,
 }
 
 // ============================================================================
@@ -71,6 +184,8 @@ pub struct TicketListItem {
     pub duration_seconds: Option<i32>,
     pub issues_count: i64,
     pub ai_confidence: Option<i32>,
+    pub sentiment: Option<ReportSentiment>,
+    pub frustration_score: Option<i32>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -96,6 +211,8 @@ impl TicketListItem {
             duration_seconds: t.duration_seconds,
             issues_count: t.issues_count,
             ai_confidence: t.ai_confidence,
+            sentiment: t.sentiment,
+            frustration_score: t.frustration_score,
             created_at: t.created_at,
             updated_at: t.updated_at,
         }
@@ -107,6 +224,9 @@ impl TicketListItem {
 pub struct TicketDetailResponse {
     pub id: Uuid,
     pub project_id: Option<Uuid>,
+    /// Set for legacy session-based tickets (no project).
+    pub session_id: Option<Uuid>,
+    /// Project name, or the session name for legacy session-based tickets.
     pub project_name: Option<String>,
     pub feedback_type: FeedbackType,
     pub ticket_status: TicketStatus,
@@ -119,27 +239,117 @@ pub struct TicketDetailResponse {
     pub category: Option<String>,
     pub page_url: Option<String>,
     pub browser_info: serde_json::Value,
+    /// Submitter's own description of how familiar they are with the product
+    pub prior_experience: Option<String>,
     pub video_url: Option<String>,
+    pub screenshot_url: Option<String>,
     pub duration_seconds: Option<i32>,
     pub status: ProcessingStatus,
+    /// Current analysis stage while `status` is processing.
+    pub progress_stage: Option<JobProgressStage>,
+    /// Status of the latest analysis job for this ticket.
+    pub job_status: Option<JobStatus>,
+    /// Error from the latest analysis job; only returned to internal users.
+    pub job_error: Option<String>,
     pub ai_confidence: Option<i32>,
     pub due_date: Option<DateTime<Utc>>,
+    pub labels: Vec<String>,
+    /// Chat messages from others the current user has not read
+    pub unread_count: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// Everything stored about one ticket, for data-subject access requests
+#[derive(Debug, Serialize)]
+pub struct TicketExportResponse {
+    pub exported_at: DateTime<Utc>,
+    /// Ticket detail, browser info included
+    pub ticket: TicketDetailResponse,
+    /// Analysis report with its issues; `None` until analysis finishes
+    pub report: Option<ReportResponse>,
+    /// Chat transcript, oldest first
+    pub chat: Vec<ChatMessageResponse>,
+}
+
+/// A ticket's labels after a change
+#[derive(Debug, Serialize)]
+pub struct TicketLabelsResponse {
+    pub labels: Vec<String>,
+}
+
+/// Ticket share link (never includes the token)
+#[derive(Debug, Serialize)]
+pub struct TicketShareResponse {
+    pub id: Uuid,
+    pub ticket_id: Uuid,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl From<TicketShareToken> for TicketShareResponse {
+    fn from(share: TicketShareToken) -> Self {
+        Self {
+            id: share.id,
+            ticket_id: share.recording_id,
+            is_active: share.revoked_at.is_none(),
+            created_at: share.created_at,
+            revoked_at: share.revoked_at,
+        }
+    }
+}
+
+/// Newly minted share link; the only response that carries the token
+#[derive(Debug, Serialize)]
+pub struct CreatedTicketShareResponse {
+    pub token: String,
+    pub report_url: String,
+    pub video_url: String,
+    #[serde(flatten)]
+    pub share: TicketShareResponse,
+}
+
+impl CreatedTicketShareResponse {
+    pub fn new(share: TicketShareToken, token: String) -> Self {
+        Self {
+            report_url: format!("/api/v1/shared/{}/report", token),
+            video_url: format!("/api/v1/shared/{}/video", token),
+            token,
+            share: share.into(),
+        }
+    }
+}
+
+/// Mark-all-read response
+#[derive(Debug, Serialize)]
+pub struct MarkAllReadResponse {
+    pub project_id: Uuid,
+    pub marked_count: u64,
+}
+
 /// Full report response (for ticket detail)
 #[derive(Debug, Serialize)]
 pub struct ReportResponse {
     pub id: Uuid,
     pub recording_id: Uuid,
     pub executive_summary: ExecutiveSummary,
+    /// Submitter's overall mood; `null` for reports analyzed before it was recorded
+    pub sentiment: Option<ReportSentiment>,
+    /// How frustrated the submitter was, 0-100
+    pub frustration_score: Option<i32>,
     pub metrics: ReportMetrics,
     pub issues: Vec<IssueResponse>,
+    pub severity_breakdown: SeverityBreakdown,
     pub question_analysis: Vec<QuestionAnalysis>,
     pub suggested_actions: Vec<String>,
     /// Possible solutions to address the issues (from AI analysis).
     pub possible_solutions: Vec<String>,
+    /// Language the analysis was written in.
+    pub language: String,
+    /// Whether an internal user has corrected the report since analysis.
+    pub edited: bool,
+    pub edited_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Serialize)]
@@ -171,4 +381,287 @@ pub struct IssueResponse {
     pub reproduction_steps: Vec<String>,
     pub confidence: Option<i32>,
     pub external_ticket_url: Option<String>,
+    pub issue_status: IssueStatus,
+}
+
+/// How many of a report's issues have each severity
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct SeverityBreakdown {
+    pub critical: usize,
+    pub high: usize,
+    pub medium: usize,
+    pub low: usize,
+}
+
+impl SeverityBreakdown {
+    pub fn from_issues(issues: &[IssueResponse]) -> Self {
+        let mut breakdown = Self::default();
+        for issue in issues {
+            *match issue.severity {
+                IssueSeverity::Critical => &mut breakdown.critical,
+                IssueSeverity::High => &mut breakdown.high,
+                IssueSeverity::Medium => &mut breakdown.medium,
+                IssueSeverity::Low => &mut breakdown.low,
+            } += 1;
+        }
+        breakdown
+    }
+}
+
+/// A ticket's reports across reanalyses, newest first
+#[derive(Debug, Serialize)]
+pub struct ReportHistoryResponse {
+    pub versions: Vec<ReportVersionResponse>,
+    /// What the latest version changed from the one before; `null` with fewer than two
+    pub diff: Option<ReportDiff>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportVersionResponse {
+    pub report_version: i32,
+    /// Whether this is the ticket's live report
+    pub current: bool,
+    pub created_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub report: ReportResponse,
+}
+
+/// Differences between two report versions. Issues are matched by title, ignoring case
+/// and surrounding whitespace.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ReportDiff {
+    pub from_version: i32,
+    pub to_version: i32,
+    pub outcome_changed: bool,
+    pub overview_changed: bool,
+    pub added_issues: Vec<IssueSummary>,
+    pub removed_issues: Vec<IssueSummary>,
+    pub severity_changes: Vec<SeverityChange>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct IssueSummary {
+    pub title: String,
+    pub severity: IssueSeverity,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct SeverityChange {
+    pub title: String,
+    pub from: IssueSeverity,
+    pub to: IssueSeverity,
+}
+
+impl ReportDiff {
+    pub fn between(older: &ReportVersionResponse, newer: &ReportVersionResponse) -> Self {
+        let (old_issues, new_issues) = (&older.report.issues, &newer.report.issues);
+        let summary = |issue: &IssueResponse| IssueSummary {
+            title: issue.title.clone(),
+            severity: issue.severity,
+        };
+        Self {
+            from_version: older.report_version,
+            to_version: newer.report_version,
+            outcome_changed: older.report.executive_summary.outcome
+                != newer.report.executive_summary.outcome,
+            overview_changed: older.report.executive_summary.overview.trim()
+                != newer.report.executive_summary.overview.trim(),
+            added_issues: new_issues
+                .iter()
+                .filter(|issue| find_issue(old_issues, &issue.title).is_none())
+                .map(summary)
+                .collect(),
+            removed_issues: old_issues
+                .iter()
+                .filter(|issue| find_issue(new_issues, &issue.title).is_none())
+                .map(summary)
+                .collect(),
+            severity_changes: new_issues
+                .iter()
+                .filter_map(|issue| {
+                    let old = find_issue(old_issues, &issue.title)?;
+                    (old.severity != issue.severity).then(|| SeverityChange {
+                        title: issue.title.clone(),
+                        from: old.severity,
+                        to: issue.severity,
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+fn find_issue<'a>(issues: &'a [IssueResponse], title: &str) -> Option<&'a IssueResponse> {
+    let title = title.trim().to_lowercase();
+    issues
+        .iter()
+        .find(|issue| issue.title.trim().to_lowercase() == title)
+}
+
+/// Result of exporting a report issue to an external tracker
+#[derive(Debug, Serialize)]
+pub struct IssueExportResponse {
+    pub issue_id: Uuid,
+    pub external_ticket_url: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_request_distinguishes_absent_and_null_due_date() {
+        let absent: UpdateTicketRequest = serde_json::from_str(r#"{"priority":"high"}"#).unwrap();
+        assert_eq!(absent.due_date, None);
+
+        let cleared: UpdateTicketRequest = serde_json::from_str(r#"{"due_date":null}"#).unwrap();
+        assert_eq!(cleared.due_date, Some(None));
+
+        let set: UpdateTicketRequest =
+            serde_json::from_str(r#"{"due_date":"2026-11-01T12:00:00Z"}"#).unwrap();
+        assert_eq!(
+            set.due_date,
+            Some(Some("2026-11-01T12:00:00Z".parse().unwrap()))
+        );
+    }
+
+    fn issue(severity: IssueSeverity) -> IssueResponse {
+        IssueResponse {
+            id: Uuid::new_v4(),
+            title: "Issue".to_string(),
+            severity,
+            tags: Vec::new(),
+            observed_behavior: None,
+            expected_behavior: None,
+            evidence: Vec::new(),
+            screenshots: Vec::new(),
+            impact: Vec::new(),
+            reproduction_steps: Vec::new(),
+            confidence: None,
+            external_ticket_url: None,
+            issue_status: IssueStatus::Open,
+        }
+    }
+
+    fn version(
+        report_version: i32,
+        outcome: ReportOutcome,
+        overview: &str,
+        issues: &[(&str, IssueSeverity)],
+    ) -> ReportVersionResponse {
+        let issues: Vec<IssueResponse> = issues
+            .iter()
+            .map(|(title, severity)| IssueResponse {
+                title: title.to_string(),
+                ..issue(*severity)
+            })
+            .collect();
+        ReportVersionResponse {
+            report_version,
+            current: false,
+            created_at: Utc::now(),
+            report: ReportResponse {
+                id: Uuid::new_v4(),
+                recording_id: Uuid::new_v4(),
+                executive_summary: ExecutiveSummary {
+                    outcome,
+                    confidence: 80,
+                    overview: overview.to_string(),
+                },
+                sentiment: None,
+                frustration_score: None,
+                metrics: ReportMetrics {
+                    task_completion_rate: 0,
+                    total_hesitation_time: 0,
+                    retries_count: 0,
+                    abandonment_point: None,
+                },
+                severity_breakdown: SeverityBreakdown::from_issues(&issues),
+                issues,
+                question_analysis: Vec::new(),
+                suggested_actions: Vec::new(),
+                possible_solutions: Vec::new(),
+                language: "English".to_string(),
+                edited: false,
+                edited_at: None,
+            },
+        }
+    }
+
+    #[test]
+    fn report_diff_matches_issues_by_title() {
+        let older = version(
+            1,
+            ReportOutcome::Failed,
+            "Checkout failed.",
+            &[
+                ("Pay button spins forever", IssueSeverity::High),
+                ("Typo in footer", IssueSeverity::Low),
+            ],
+        );
+        let newer = version(
+            2,
+            ReportOutcome::Failed,
+            "Checkout failed. ",
+            &[
+                ("pay button spins forever ", IssueSeverity::Critical),
+                ("Coupon field hidden", IssueSeverity::Medium),
+            ],
+        );
+
+        assert_eq!(
+            ReportDiff::between(&older, &newer),
+            ReportDiff {
+                from_version: 1,
+                to_version: 2,
+                outcome_changed: false,
+                overview_changed: false,
+                added_issues: vec![IssueSummary {
+                    title: "Coupon field hidden".to_string(),
+                    severity: IssueSeverity::Medium,
+                }],
+                removed_issues: vec![IssueSummary {
+                    title: "Typo in footer".to_string(),
+                    severity: IssueSeverity::Low,
+                }],
+                severity_changes: vec![SeverityChange {
+                    title: "pay button spins forever ".to_string(),
+                    from: IssueSeverity::High,
+                    to: IssueSeverity::Critical,
+                }],
+            }
+        );
+
+        let newer = version(3, ReportOutcome::Success, "It worked.", &[]);
+        let diff = ReportDiff::between(&older, &newer);
+        assert!(diff.outcome_changed && diff.overview_changed);
+        assert_eq!(diff.removed_issues.len(), 2);
+    }
+
+    #[test]
+    fn severity_breakdown_counts_each_severity() {
+        let issues: Vec<IssueResponse> = [
+            IssueSeverity::High,
+            IssueSeverity::Critical,
+            IssueSeverity::High,
+            IssueSeverity::Low,
+            IssueSeverity::High,
+        ]
+        .into_iter()
+        .map(issue)
+        .collect();
+        assert_eq!(
+            SeverityBreakdown::from_issues(&issues),
+            SeverityBreakdown {
+                critical: 1,
+                high: 3,
+                medium: 0,
+                low: 1,
+            }
+        );
+        assert_eq!(
+            SeverityBreakdown::from_issues(&[]),
+            SeverityBreakdown::default()
+        );
+    }
 }
@@ -0,0 +1,114 @@
+//! JSON body extractor that also runs the DTO's `validator` rules
+
+use axum::{
+    async_trait,
+    extract::{FromRequest, Request},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::de::DeserializeOwned;
+use validator::Validate;
+
+use crate::error::AppError;
+
+/// Like `Json<T>`, but rejects bodies that fail `T::validate()` with a 422 listing the
+/// offending fields. Malformed JSON is rejected exactly as `Json` would.
+#[derive(Debug)]
+pub struct ValidatedJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        value
+            .validate()
+            .map_err(|e| AppError::invalid_fields(&e).into_response())?;
+        Ok(Self(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dto::RegisterRequest;
+    use axum::{body::Body, http::StatusCode, routing::post, Router};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new().route(
+            "/register",
+            post(|ValidatedJson(req): ValidatedJson<RegisterRequest>| async move { req.email }),
+        )
+    }
+
+    async fn post_json(body: serde_json::Value) -> Response {
+        app()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/register")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+
+    async fn json_body(response: Response) -> serde_json::Value {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn invalid_fields_are_listed() {
+        let response = post_json(serde_json::json!({
+            "email": "not-an-email",
+            "password": "short",
+        }))
+        .await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let body = json_body(response).await;
+        assert_eq!(body["code"], "VALIDATION_ERROR");
+        assert_eq!(body["fields"]["email"][0], "Invalid email address");
+        assert_eq!(
+            body["fields"]["password"][0],
+            "Password must be at least 8 characters"
+        );
+    }
+
+    #[tokio::test]
+    async fn valid_body_reaches_the_handler() {
+        let response = post_json(serde_json::json!({
+            "email": "dev@example.com",
+            "password": "long-enough",
+        }))
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"dev@example.com");
+    }
+
+    #[tokio::test]
+    async fn malformed_json_is_rejected_like_json() {
+        let response = post_json(serde_json::json!({ "email": 42 })).await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(serde_json::from_slice::<serde_json::Value>(&body).is_err());
+    }
+}
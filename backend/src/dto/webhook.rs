@@ -0,0 +1,188 @@
+//! Webhook DTOs
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::{Webhook, WebhookEvent};
+use crate::services::{check_public_host, WebhookTestDelivery};
+
+// ============================================================================
+// Request DTOs
+// ============================================================================
+
+/// Create webhook request
+#[derive(Debug, Deserialize)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub is_active: Option<bool>,
+}
+
+impl CreateWebhookRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_url(&self.url)?;
+        validate_events(&self.events)
+    }
+}
+
+/// Update webhook request
+#[derive(Debug, Deserialize)]
+pub struct UpdateWebhookRequest {
+    pub url: Option<String>,
+    pub events: Option<Vec<WebhookEvent>>,
+    pub is_active: Option<bool>,
+}
+
+impl UpdateWebhookRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(ref url) = self.url {
+            validate_url(url)?;
+        }
+        if let Some(ref events) = self.events {
+            validate_events(events)?;
+        }
+        Ok(())
+    }
+}
+
+fn validate_url(url: &str) -> Result<(), String> {
+    let parsed = reqwest::Url::parse(url.trim())
+        .map_err(|_| "Webhook URL is not a valid URL".to_string())?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        return Err("Webhook URL must be an http(s) URL".to_string());
+    }
+    // Resolved addresses are checked when the webhook is saved
+    check_public_host(&parsed).map_err(|e| format!("Webhook {}", e))?;
+    if url.len() > 2048 {
+        return Err("Webhook URL must be at most 2048 characters".to_string());
+    }
+    Ok(())
+}
+
+fn validate_events(events: &[WebhookEvent]) -> Result<(), String> {
+    if events.is_empty() {
+        return Err("Webhook must subscribe to at least one event".to_string());
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Response DTOs
+// ============================================================================
+
+/// Webhook response; includes the secret so the owner can verify signatures
+#[derive(Debug, Serialize)]
+pub struct WebhookResponse {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Webhook> for WebhookResponse {
+    fn from(webhook: Webhook) -> Self {
+        let events = webhook.event_list();
+        Self {
+            id: webhook.id,
+            project_id: webhook.project_id,
+            url: webhook.url,
+            secret: webhook.secret,
+            events,
+            is_active: webhook.is_active,
+            created_at: webhook.created_at,
+            updated_at: webhook.updated_at,
+        }
+    }
+}
+
+/// Result of a test delivery
+#[derive(Debug, Serialize)]
+pub struct WebhookTestResponse {
+    /// Sent as `X-Ortrace-Delivery`, to find the ping in the receiver's logs
+    pub delivery_id: Uuid,
+    pub success: bool,
+    /// Status the receiver answered with; `null` if it could not be reached
+    pub response_status: Option<u16>,
+    pub error: Option<String>,
+}
+
+impl From<WebhookTestDelivery> for WebhookTestResponse {
+    fn from(delivery: WebhookTestDelivery) -> Self {
+        Self {
+            delivery_id: delivery.delivery_id,
+            success: delivery.error.is_none(),
+            response_status: delivery.response_status,
+            error: delivery.error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create(url: &str, events: Vec<WebhookEvent>) -> CreateWebhookRequest {
+        CreateWebhookRequest {
+            url: url.to_string(),
+            events,
+            is_active: None,
+        }
+    }
+
+    #[test]
+    fn create_request_accepts_http_urls() {
+        let events = vec![WebhookEvent::TicketCreated];
+        assert!(create("https://hooks.example.com/ortrace", events.clone())
+            .validate()
+            .is_ok());
+        assert!(create("http://93.184.216.34:9000/hook", events)
+            .validate()
+            .is_ok());
+    }
+
+    #[test]
+    fn create_request_rejects_internal_hosts() {
+        let events = vec![WebhookEvent::TicketCreated];
+        for url in [
+            "http://localhost:9000/hook",
+            "http://127.0.0.1/hook",
+            "http://169.254.169.254/computeMetadata/v1/",
+            "http://metadata.google.internal/computeMetadata/v1/",
+            "http://192.168.0.10/hook",
+        ] {
+            assert!(create(url, events.clone()).validate().is_err(), "{url}");
+        }
+    }
+
+    #[test]
+    fn create_request_rejects_bad_url_or_no_events() {
+        let events = vec![WebhookEvent::TicketCreated];
+        assert!(create("not a url", events.clone()).validate().is_err());
+        assert!(create("ftp://example.com/hook", events).validate().is_err());
+        assert!(create("https://example.com/hook", vec![])
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn update_request_validates_only_present_fields() {
+        let empty = UpdateWebhookRequest {
+            url: None,
+            events: None,
+            is_active: Some(false),
+        };
+        assert!(empty.validate().is_ok());
+
+        let no_events = UpdateWebhookRequest {
+            url: None,
+            events: Some(vec![]),
+            is_active: None,
+        };
+        assert!(no_events.validate().is_err());
+    }
+}
@@ -1,10 +1,12 @@
 //! Widget DTOs - public API for end-user widget submissions
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::Validate;
 
-use crate::models::FeedbackType;
+use crate::models::{ConsoleEntry, FeedbackType, NetworkError};
 
 // ============================================================================
 // Request DTOs
@@ -30,6 +32,23 @@ pub struct WidgetSubmitRequest {
     pub submitter_name: Option<String>,
     pub page_url: Option<String>,
     pub browser_info: Option<serde_json::Value>,
+    /// How familiar the submitter is with the product, e.g. "first visit" or "daily user"
+    #[validate(length(max = 200, message = "prior_experience must be at most 200 characters"))]
+    pub prior_experience: Option<String>,
+    /// Recent browser console output; only the latest entries are kept
+    pub console_logs: Option<Vec<ConsoleEntry>>,
+    /// Failed network requests seen by the page
+    pub network_errors: Option<Vec<NetworkError>>,
+}
+
+/// Confirms a video uploaded straight to storage
+#[derive(Debug, Deserialize, Validate)]
+pub struct ConfirmUploadRequest {
+    /// Recording length in seconds, as measured by the widget
+    #[validate(range(min = 0, message = "duration must not be negative"))]
+    pub duration: Option<i32>,
+    /// MIME type the video was recorded in, e.g. `video/webm`
+    pub content_type: Option<String>,
 }
 
 // ============================================================================
@@ -43,6 +62,17 @@ pub struct WidgetSubmitResponse {
     pub message: String,
 }
 
+/// Where to upload a ticket's video directly, before confirming it
+#[derive(Debug, Serialize)]
+pub struct DirectUploadResponse {
+    pub ticket_id: Uuid,
+    /// Signed URL to `PUT` the video bytes to
+    pub upload_url: String,
+    /// Headers the `PUT` must send as given (they are signed)
+    pub upload_headers: BTreeMap<String, String>,
+    pub expires_in_secs: u64,
+}
+
 /// Widget config response (returned to widget on init)
 #[derive(Debug, Serialize)]
 pub struct WidgetConfigResponse {
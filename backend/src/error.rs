@@ -1,11 +1,13 @@
 //! Centralized error handling for the application
 
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
+use chrono::{DateTime, Utc};
 use serde::Serialize;
+use std::collections::BTreeMap;
 use thiserror::Error;
 
 /// Application error types
@@ -26,9 +28,14 @@ pub enum AppError {
     #[error("Conflict: {0}")]
     Conflict(String),
 
+    #[error("Stale update: changed at {current_updated_at}")]
+    StaleUpdate {
+        /// The record's `updated_at` now, for the client to reload against
+        current_updated_at: DateTime<Utc>,
+    },
+
     #[error("Validation error: {0}")]
-    #[allow(dead_code)] // Useful for validation error responses
-    Validation(String),
+    Validation(ValidationDetails),
 
     #[error("Internal server error: {0}")]
     Internal(String),
@@ -46,7 +53,22 @@ pub enum AppError {
     ExternalService(String),
 
     #[error("Service starting up")]
-    ServiceUnavailable,
+    ServiceUnavailable {
+        /// Seconds until the client should retry, sent as `Retry-After`
+        retry_after_secs: u64,
+    },
+
+    #[error("Analysis quota exceeded")]
+    QuotaExceeded {
+        /// The account's `quota_limit`
+        limit: i32,
+    },
+
+    #[error("Too many requests")]
+    TooManyRequests {
+        /// Seconds until the client may retry, sent as `Retry-After`
+        retry_after_secs: u64,
+    },
 }
 
 impl AppError {
@@ -70,13 +92,171 @@ impl AppError {
         Self::Forbidden
     }
 
+    pub fn service_unavailable(retry_after_secs: u64) -> Self {
+        Self::ServiceUnavailable { retry_after_secs }
+    }
+
+    pub fn quota_exceeded(limit: i32) -> Self {
+        Self::QuotaExceeded { limit }
+    }
+
+    pub fn too_many_requests(retry_after_secs: u64) -> Self {
+        Self::TooManyRequests { retry_after_secs }
+    }
+
     pub fn conflict(msg: impl Into<String>) -> Self {
         Self::Conflict(msg.into())
     }
 
-    #[allow(dead_code)] // Useful for validation error responses
+    pub fn stale_update(current_updated_at: DateTime<Utc>) -> Self {
+        Self::StaleUpdate { current_updated_at }
+    }
+
     pub fn validation(msg: impl Into<String>) -> Self {
-        Self::Validation(msg.into())
+        Self::Validation(ValidationDetails {
+            message: msg.into(),
+            fields: BTreeMap::new(),
+        })
+    }
+
+    /// Machine-readable code sent with this error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AppError::Unauthorized => ErrorCode::Unauthorized,
+            AppError::Forbidden => ErrorCode::Forbidden,
+            AppError::NotFound(_) => ErrorCode::NotFound,
+            AppError::BadRequest(_) => ErrorCode::BadRequest,
+            AppError::Conflict(_) => ErrorCode::Conflict,
+            AppError::StaleUpdate { .. } => ErrorCode::StaleUpdate,
+            AppError::Validation(_) => ErrorCode::ValidationError,
+            AppError::Internal(_) | AppError::PasswordHash => ErrorCode::InternalError,
+            AppError::Database(_) => ErrorCode::DatabaseError,
+            AppError::Jwt(_) => ErrorCode::InvalidToken,
+            AppError::ExternalService(_) => ErrorCode::ExternalServiceError,
+            AppError::ServiceUnavailable { .. } => ErrorCode::ServiceUnavailable,
+            AppError::QuotaExceeded { .. } => ErrorCode::QuotaExceeded,
+            AppError::TooManyRequests { .. } => ErrorCode::RateLimited,
+        }
+    }
+
+    /// Validation error listing each offending field with its messages
+    pub fn invalid_fields(errors: &validator::ValidationErrors) -> Self {
+        let fields: BTreeMap<String, Vec<String>> = errors
+            .field_errors()
+            .into_iter()
+            .map(|(field, errors)| {
+                let messages = errors
+                    .iter()
+                    .map(|e| match &e.message {
+                        Some(message) => message.to_string(),
+                        None => format!("Invalid {}", field),
+                    })
+                    .collect();
+                (field.to_string(), messages)
+            })
+            .collect();
+        let message = fields
+            .values()
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("; ");
+        Self::Validation(ValidationDetails { message, fields })
+    }
+}
+
+/// Machine-readable error codes, sent as `code` in every error response
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    BadRequest,
+    Conflict,
+    StaleUpdate,
+    ValidationError,
+    InternalError,
+    DatabaseError,
+    InvalidToken,
+    ExternalServiceError,
+    ServiceUnavailable,
+    QuotaExceeded,
+    RateLimited,
+}
+
+impl ErrorCode {
+    /// Every code, in the order the catalog lists them
+    pub const ALL: [ErrorCode; 14] = [
+        ErrorCode::Unauthorized,
+        ErrorCode::Forbidden,
+        ErrorCode::NotFound,
+        ErrorCode::BadRequest,
+        ErrorCode::Conflict,
+        ErrorCode::StaleUpdate,
+        ErrorCode::ValidationError,
+        ErrorCode::InternalError,
+        ErrorCode::DatabaseError,
+        ErrorCode::InvalidToken,
+        ErrorCode::ExternalServiceError,
+        ErrorCode::ServiceUnavailable,
+        ErrorCode::QuotaExceeded,
+        ErrorCode::RateLimited,
+    ];
+
+    /// HTTP status responses with this code are sent with
+    pub fn status(self) -> StatusCode {
+        match self {
+            ErrorCode::Unauthorized | ErrorCode::InvalidToken => StatusCode::UNAUTHORIZED,
+            ErrorCode::Forbidden => StatusCode::FORBIDDEN,
+            ErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ErrorCode::BadRequest => StatusCode::BAD_REQUEST,
+            ErrorCode::Conflict | ErrorCode::StaleUpdate => StatusCode::CONFLICT,
+            ErrorCode::ValidationError => StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorCode::InternalError | ErrorCode::DatabaseError => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            ErrorCode::ExternalServiceError => StatusCode::BAD_GATEWAY,
+            ErrorCode::ServiceUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::QuotaExceeded => StatusCode::PAYMENT_REQUIRED,
+            ErrorCode::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+
+    /// One-line explanation for the error catalog
+    pub fn description(self) -> &'static str {
+        match self {
+            ErrorCode::Unauthorized => "No valid login was sent",
+            ErrorCode::Forbidden => "The caller may not perform this action",
+            ErrorCode::NotFound => "The resource does not exist or is not visible to the caller",
+            ErrorCode::BadRequest => "The request cannot be processed as sent",
+            ErrorCode::Conflict => "The request conflicts with the resource's current state",
+            ErrorCode::StaleUpdate => {
+                "The record changed since it was loaded; see current_updated_at"
+            }
+            ErrorCode::ValidationError => "One or more fields are invalid; see fields",
+            ErrorCode::InternalError => "Unexpected server error",
+            ErrorCode::DatabaseError => "The database could not complete the request",
+            ErrorCode::InvalidToken => "The access token is invalid or expired",
+            ErrorCode::ExternalServiceError => "A service the API depends on failed",
+            ErrorCode::ServiceUnavailable => "The API is starting up; retry after Retry-After",
+            ErrorCode::QuotaExceeded => "The account's analysis quota is used up",
+            ErrorCode::RateLimited => "Too many requests; retry after Retry-After",
+        }
+    }
+}
+
+/// Message and per-field errors of a failed validation
+#[derive(Debug)]
+pub struct ValidationDetails {
+    message: String,
+    /// Field name -> messages; empty when the error is not tied to fields
+    fields: BTreeMap<String, Vec<String>>,
+}
+
+impl std::fmt::Display for ValidationDetails {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
     }
 }
 
@@ -85,77 +265,86 @@ impl AppError {
 struct ErrorResponse {
     success: bool,
     error: String,
+    code: ErrorCode,
+    /// Matches the `X-Request-Id` response header and the server logs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_id: Option<String>,
+    /// Offending fields and their messages, on validation errors
     #[serde(skip_serializing_if = "Option::is_none")]
-    code: Option<String>,
+    fields: Option<BTreeMap<String, Vec<String>>>,
+    /// The record's current `updated_at`, on stale-update conflicts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    current_updated_at: Option<DateTime<Utc>>,
 }
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        let (status, code, message) = match &self {
-            AppError::Unauthorized => (StatusCode::UNAUTHORIZED, "UNAUTHORIZED", self.to_string()),
-            AppError::Forbidden => (StatusCode::FORBIDDEN, "FORBIDDEN", self.to_string()),
-            AppError::NotFound(msg) => (StatusCode::NOT_FOUND, "NOT_FOUND", msg.clone()),
-            AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "BAD_REQUEST", msg.clone()),
-            AppError::Conflict(msg) => (StatusCode::CONFLICT, "CONFLICT", msg.clone()),
-            AppError::Validation(msg) => (
-                StatusCode::UNPROCESSABLE_ENTITY,
-                "VALIDATION_ERROR",
-                msg.clone(),
-            ),
+        let code = self.code();
+        let message = match &self {
+            AppError::Unauthorized | AppError::Forbidden => self.to_string(),
+            AppError::NotFound(msg) | AppError::BadRequest(msg) | AppError::Conflict(msg) => {
+                msg.clone()
+            }
+            AppError::StaleUpdate { .. } => {
+                "This was changed by someone else since you loaded it".to_string()
+            }
+            AppError::Validation(details) => details.message.clone(),
             AppError::Internal(msg) => {
                 tracing::error!("Internal error: {}", msg);
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    "INTERNAL_ERROR",
-                    "An internal error occurred".to_string(),
-                )
+                "An internal error occurred".to_string()
             }
             AppError::Database(e) => {
                 tracing::error!("Database error: {}", e);
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    "DATABASE_ERROR",
-                    "A database error occurred".to_string(),
-                )
+                "A database error occurred".to_string()
             }
             AppError::Jwt(e) => {
                 tracing::warn!("JWT error: {}", e);
-                (
-                    StatusCode::UNAUTHORIZED,
-                    "INVALID_TOKEN",
-                    "Invalid or expired token".to_string(),
-                )
+                "Invalid or expired token".to_string()
             }
             AppError::PasswordHash => {
                 tracing::error!("Password hash error");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    "INTERNAL_ERROR",
-                    "An internal error occurred".to_string(),
-                )
+                "An internal error occurred".to_string()
             }
             AppError::ExternalService(msg) => {
                 tracing::error!("External service error: {}", msg);
-                (
-                    StatusCode::BAD_GATEWAY,
-                    "EXTERNAL_SERVICE_ERROR",
-                    msg.clone(),
-                )
+                msg.clone()
+            }
+            AppError::ServiceUnavailable { .. } => "Service is starting up".to_string(),
+            AppError::QuotaExceeded { limit } => format!("Analysis quota of {} reached", limit),
+            AppError::TooManyRequests { .. } => {
+                "Too many requests, please try again later".to_string()
             }
-            AppError::ServiceUnavailable => (
-                StatusCode::SERVICE_UNAVAILABLE,
-                "SERVICE_UNAVAILABLE",
-                "Service is starting up".to_string(),
-            ),
         };
 
+        let retry_after_secs = match &self {
+            AppError::ServiceUnavailable { retry_after_secs }
+            | AppError::TooManyRequests { retry_after_secs } => Some(*retry_after_secs),
+            _ => None,
+        };
+        let current_updated_at = match &self {
+            AppError::StaleUpdate { current_updated_at } => Some(*current_updated_at),
+            _ => None,
+        };
+        let fields = match self {
+            AppError::Validation(details) if !details.fields.is_empty() => Some(details.fields),
+            _ => None,
+        };
         let body = Json(ErrorResponse {
             success: false,
             error: message,
-            code: Some(code.to_string()),
+            code,
+            request_id: crate::middleware::current_request_id(),
+            fields,
+            current_updated_at,
         });
 
-        (status, body).into_response()
+        let mut response = (code.status(), body).into_response();
+        if let Some(retry_after_secs) = retry_after_secs {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs));
+        }
+        response
     }
 }
 
@@ -209,6 +398,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn stale_update_returns_409() {
+        assert_eq!(
+            extract_status(AppError::stale_update(chrono::Utc::now())),
+            StatusCode::CONFLICT
+        );
+    }
+
     #[test]
     fn validation_returns_422() {
         assert_eq!(
@@ -233,6 +430,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn quota_exceeded_returns_402() {
+        assert_eq!(
+            extract_status(AppError::quota_exceeded(10)),
+            StatusCode::PAYMENT_REQUIRED
+        );
+    }
+
     #[test]
     fn external_service_returns_502() {
         assert_eq!(
@@ -241,6 +446,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn service_unavailable_returns_503_with_retry_after() {
+        let response = AppError::service_unavailable(5).into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "5");
+    }
+
+    #[test]
+    fn too_many_requests_returns_429_with_retry_after() {
+        let response = AppError::too_many_requests(12).into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "12");
+    }
+
     #[test]
     fn factory_methods_produce_correct_variants() {
         assert!(matches!(
@@ -274,6 +493,50 @@ mod tests {
         assert_eq!(AppError::PasswordHash.to_string(), "Password hash error");
     }
 
+    #[test]
+    fn each_variant_maps_to_its_error_code() {
+        let cases = [
+            (AppError::unauthorized(), ErrorCode::Unauthorized),
+            (AppError::forbidden(), ErrorCode::Forbidden),
+            (AppError::not_found("x"), ErrorCode::NotFound),
+            (AppError::bad_request("x"), ErrorCode::BadRequest),
+            (AppError::conflict("x"), ErrorCode::Conflict),
+            (
+                AppError::stale_update(chrono::Utc::now()),
+                ErrorCode::StaleUpdate,
+            ),
+            (AppError::validation("x"), ErrorCode::ValidationError),
+            (AppError::internal("x"), ErrorCode::InternalError),
+            (AppError::PasswordHash, ErrorCode::InternalError),
+            (
+                AppError::Database(sqlx::Error::RowNotFound),
+                ErrorCode::DatabaseError,
+            ),
+            (
+                AppError::Jwt(jsonwebtoken::errors::ErrorKind::InvalidToken.into()),
+                ErrorCode::InvalidToken,
+            ),
+            (
+                AppError::ExternalService("x".to_string()),
+                ErrorCode::ExternalServiceError,
+            ),
+            (
+                AppError::service_unavailable(1),
+                ErrorCode::ServiceUnavailable,
+            ),
+            (AppError::quota_exceeded(1), ErrorCode::QuotaExceeded),
+            (AppError::too_many_requests(1), ErrorCode::RateLimited),
+        ];
+        for (error, code) in cases {
+            assert_eq!(error.code(), code);
+            assert_eq!(error.into_response().status(), code.status());
+        }
+        assert_eq!(
+            serde_json::to_value(ErrorCode::ExternalServiceError).unwrap(),
+            "EXTERNAL_SERVICE_ERROR"
+        );
+    }
+
     #[test]
     fn error_response_body_structure() {
         let response = AppError::not_found("thing").into_response();
@@ -0,0 +1,114 @@
+//! Log output: human-readable lines for local development, or one JSON object per line
+//! (LOG_FORMAT=json) for collectors such as Cloud Logging
+
+use tracing::Subscriber;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFormat {
+    Pretty,
+    Json,
+}
+
+impl LogFormat {
+    /// LOG_FORMAT value; anything but `json` keeps the readable default
+    fn parse(value: Option<&str>) -> Self {
+        match value.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+            Some("json") => Self::Json,
+            _ => Self::Pretty,
+        }
+    }
+}
+
+/// Install the global subscriber, filtered by RUST_LOG
+pub fn init() {
+    let format = LogFormat::parse(std::env::var("LOG_FORMAT").ok().as_deref());
+    tracing_subscriber::registry()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| "info,tower_http=debug".into()))
+        .with(fmt_layer(format, std::io::stdout))
+        .init();
+}
+
+/// Formatting layer writing to `writer`. JSON events list the fields of every enclosing
+/// span under `spans`, so request and job logs keep their `request_id` and `job_id`.
+fn fmt_layer<S, W>(format: LogFormat, writer: W) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    match format {
+        LogFormat::Pretty => tracing_subscriber::fmt::layer().with_writer(writer).boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_current_span(false)
+            .with_span_list(true)
+            .with_writer(writer)
+            .boxed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn only_json_selects_json_output() {
+        assert_eq!(LogFormat::parse(Some("json")), LogFormat::Json);
+        assert_eq!(LogFormat::parse(Some(" JSON ")), LogFormat::Json);
+        assert_eq!(LogFormat::parse(Some("pretty")), LogFormat::Pretty);
+        assert_eq!(LogFormat::parse(Some("yaml")), LogFormat::Pretty);
+        assert_eq!(LogFormat::parse(None), LogFormat::Pretty);
+    }
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Captured {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn json_format_emits_one_parseable_object_per_event() {
+        let captured = Captured::default();
+        let subscriber =
+            tracing_subscriber::registry().with(fmt_layer(LogFormat::Json, captured.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("request", request_id = "req-1");
+            let _entered = span.enter();
+            tracing::info!(job_id = "job-1", "Processing job");
+            tracing::warn!("Retrying");
+        });
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["level"], "INFO");
+        assert_eq!(lines[0]["message"], "Processing job");
+        assert_eq!(lines[0]["job_id"], "job-1");
+        assert_eq!(lines[0]["spans"][0]["request_id"], "req-1");
+        assert_eq!(lines[1]["level"], "WARN");
+    }
+}
@@ -4,32 +4,31 @@
 
 mod config;
 mod controllers;
+mod crypto;
 mod dto;
 mod error;
+mod logging;
 mod middleware;
 mod models;
 mod router;
 mod services;
 mod state;
+#[cfg(test)]
+mod test_support;
 
 use anyhow::Context;
-use sqlx::PgPool;
+use sqlx::postgres::PgPoolOptions;
 use std::sync::Arc;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 
-use crate::services::Worker;
+use crate::services::{WebhookService, Worker};
 use crate::state::{AppState, ReadyAppState};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Initialize tracing
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "info,tower_http=debug".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    logging::init();
 
     // Load .env from project root (Cargo.toml directory) so it works regardless of process cwd.
     // If GOOGLE_* vars are already set (e.g. empty from shell), dotenv won't override — so we
@@ -78,44 +77,105 @@ async fn main() -> anyhow::Result<()> {
         dotenv::dotenv().ok();
     }
 
-    // Load configuration (fail fast before binding)
+    // Load and validate configuration (fail fast before binding; dev only warns)
     let config = config::Config::from_env()?;
-
-    if config.google_client_id.is_empty() || config.google_client_secret.is_empty() {
-        tracing::warn!(
-            "Google OAuth not configured (GOOGLE_CLIENT_ID or GOOGLE_CLIENT_SECRET missing or empty). \
-            Sign in with Google will return 500."
-        );
-    } else {
-        tracing::info!("Google OAuth configured");
-    }
+    config.validate()?;
 
     // Bind immediately so Cloud Run sees the container listening on PORT
     let addr = format!("0.0.0.0:{}", config.port);
     let listener = tokio::net::TcpListener::bind(&addr).await?;
     tracing::info!("Listening on http://{}", addr);
 
-    let ready = ReadyAppState::new();
+    let ready = ReadyAppState::new().with_retry_after(config.startup_retry_after_secs);
+    let shutdown = CancellationToken::new();
 
     // Initialize DB, migrations, and state in background (handlers return 503 until ready)
     let ready_clone = ready.clone();
     let config_clone = config.clone();
-    tokio::spawn(async move {
-        if let Err(e) = init_and_set_state(ready_clone, config_clone).await {
-            tracing::error!("Startup failed: {}", e);
+    let shutdown_clone = shutdown.clone();
+    let init = tokio::spawn(async move {
+        match init_and_set_state(ready_clone, config_clone, shutdown_clone).await {
+            Ok(workers) => workers,
+            Err(e) => {
+                tracing::error!("Startup failed: {}", e);
+                Vec::new()
+            }
         }
     });
 
-    let app = router::create_router(ready);
-    tracing::info!("API Routes: GET /health, POST /api/v1/auth/register, ...");
-
-    axum::serve(listener, app).await?;
+    let app = router::create_router(ready, &config);
+    tracing::info!("API Routes: GET /health, GET /health/live, POST /api/v1/auth/register, ...");
+
+    // Peer addresses feed widget rate limiting when there is no X-Forwarded-For
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal(shutdown))
+    .await?;
+
+    // In-flight requests are done; let workers finish (or release) their current job
+    if init.is_finished() {
+        for worker in init.await.unwrap_or_default() {
+            let _ = worker.await;
+        }
+    } else {
+        init.abort();
+    }
+    tracing::info!("Shutdown complete");
     Ok(())
 }
 
-async fn init_and_set_state(ready: ReadyAppState, config: config::Config) -> anyhow::Result<()> {
-    tracing::info!("Connecting to database...");
-    let db_pool = PgPool::connect(&config.database_url)
+/// Resolves on SIGTERM (Cloud Run scale-down) or Ctrl+C, and tells the workers to drain
+async fn shutdown_signal(shutdown: CancellationToken) {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+    tracing::info!("Shutdown signal received, draining in-flight requests and jobs");
+    shutdown.cancel();
+}
+
+async fn init_and_set_state(
+    ready: ReadyAppState,
+    config: config::Config,
+    shutdown: CancellationToken,
+) -> anyhow::Result<Vec<JoinHandle<()>>> {
+    tracing::info!(
+        max_connections = config.db_max_connections,
+        min_connections = config.db_min_connections,
+        acquire_timeout_secs = config.db_acquire_timeout_secs,
+        "Connecting to database..."
+    );
+    let db_pool = PgPoolOptions::new()
+        .max_connections(config.db_max_connections)
+        .min_connections(config.db_min_connections)
+        .acquire_timeout(std::time::Duration::from_secs(
+            config.db_acquire_timeout_secs,
+        ))
+        .connect(&config.database_url)
         .await
         .context("Failed to connect to database")?;
 
@@ -129,13 +189,9 @@ async fn init_and_set_state(ready: ReadyAppState, config: config::Config) -> any
     let state = Arc::new(AppState::new(config.clone(), db_pool).await?);
     ready.set(state.clone()).await;
 
-    let worker = Worker::new(state);
-    tokio::spawn(async move {
-        if let Err(e) = worker.start().await {
-            tracing::error!("Worker error: {}", e);
-        }
-    });
+    WebhookService::spawn_delivery_loop(state.webhooks.clone());
+    let workers = Worker::spawn_pool(state, config.worker_concurrency, shutdown);
 
     tracing::info!("Startup complete");
-    Ok(())
+    Ok(workers)
 }
@@ -9,7 +9,8 @@ use axum::{
 };
 
 use crate::error::AppError;
-use crate::state::ReadyAppState;
+use crate::models::User;
+use crate::state::{AppState, ReadyAppState};
 
 /// Extract and validate JWT token from Authorization header
 pub async fn auth_middleware(
@@ -17,7 +18,7 @@ pub async fn auth_middleware(
     mut request: Request,
     next: Next,
 ) -> Result<Response, AppError> {
-    let state = ready.get().await.ok_or(AppError::ServiceUnavailable)?;
+    let state = ready.get_or_unavailable().await?;
     let auth_header = request
         .headers()
         .get(header::AUTHORIZATION)
@@ -28,13 +29,7 @@ pub async fn auth_middleware(
         _ => return Err(AppError::unauthorized()),
     };
 
-    let claims = state.auth.validate_access_token(token)?;
-
-    let user = state
-        .auth
-        .find_user_by_id(&claims.sub)
-        .await?
-        .ok_or_else(AppError::unauthorized)?;
+    let user = authenticate_token(&state, token).await?;
 
     // Add user to request extensions
     request.extensions_mut().insert(user);
@@ -42,6 +37,18 @@ pub async fn auth_middleware(
     Ok(next.run(request).await)
 }
 
+/// Resolve the user an access token belongs to. Used directly by routes that cannot
+/// send an Authorization header (WebSocket upgrades from browsers).
+pub async fn authenticate_token(state: &AppState, token: &str) -> Result<User, AppError> {
+    let claims = state.auth.validate_access_token(token)?;
+
+    state
+        .auth
+        .find_user_by_id(&claims.sub)
+        .await?
+        .ok_or_else(AppError::unauthorized)
+}
+
 /// Optional auth - doesn't fail if no token, but adds user if valid
 #[allow(dead_code)] // Reserved for future public endpoints that optionally use auth
 pub async fn optional_auth_middleware(
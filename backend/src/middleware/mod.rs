@@ -1,5 +1,11 @@
 //! Middleware
 
 mod auth;
+mod rate_limit;
+mod request_id;
+mod widget_cors;
 
 pub use auth::*;
+pub use rate_limit::*;
+pub use request_id::*;
+pub use widget_cors::*;
@@ -0,0 +1,190 @@
+//! Rate limiting middleware for public widget submissions
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::num::NonZeroU32;
+
+use axum::{
+    extract::{ConnectInfo, Path, Request, State},
+    http::HeaderMap,
+    middleware::Next,
+    response::Response,
+};
+use governor::{
+    clock::{Clock, DefaultClock},
+    state::keyed::DefaultKeyedStateStore,
+    Quota, RateLimiter,
+};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::state::ReadyAppState;
+
+/// Tracked (client, project) pairs before idle ones are pruned
+const PRUNE_AFTER_KEYS: usize = 10_000;
+
+type Key = (IpAddr, Uuid);
+
+/// Token bucket per client IP and project: `per_minute` requests a minute, refilled evenly,
+/// with bursts of up to `per_minute`
+pub struct WidgetRateLimiter {
+    limiter: Option<RateLimiter<Key, DefaultKeyedStateStore<Key>, DefaultClock>>,
+}
+
+impl WidgetRateLimiter {
+    /// A limit of 0 disables rate limiting
+    pub fn new(per_minute: u32) -> Self {
+        Self {
+            limiter: NonZeroU32::new(per_minute).map(|n| RateLimiter::keyed(Quota::per_minute(n))),
+        }
+    }
+
+    /// Take a token for the pair; `Err` carries the seconds until the next one is available
+    pub fn check(&self, ip: IpAddr, project_id: Uuid) -> Result<(), u64> {
+        let Some(limiter) = &self.limiter else {
+            return Ok(());
+        };
+        if limiter.len() > PRUNE_AFTER_KEYS {
+            limiter.retain_recent();
+        }
+        limiter.check_key(&(ip, project_id)).map_err(|not_until| {
+            let wait = not_until.wait_time_from(DefaultClock::default().now());
+            // Round up so clients never retry before a token is back
+            wait.as_secs() + u64::from(wait.subsec_nanos() > 0)
+        })
+    }
+}
+
+/// Throttle widget submissions per client IP and project; over the limit answers
+/// `429 Too Many Requests` with `Retry-After`
+pub async fn widget_rate_limit_middleware(
+    State(ready): State<ReadyAppState>,
+    Path(params): Path<HashMap<String, String>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let state = ready.get_or_unavailable().await?;
+    // Unparseable ids fall through to the handler's own 400/404
+    let Some(project_id) = params
+        .get("project_id")
+        .and_then(|id| Uuid::parse_str(id).ok())
+    else {
+        return Ok(next.run(request).await);
+    };
+
+    let connect_addr = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let ip = client_ip(request.headers(), connect_addr);
+    if let Err(retry_after_secs) = state.widget_rate_limiter.check(ip, project_id) {
+        tracing::info!(%ip, %project_id, "Widget submission rate limited");
+        return Err(AppError::too_many_requests(retry_after_secs));
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Client address: the last `X-Forwarded-For` hop, which our load balancer (Cloud Run)
+/// appends and the client cannot forge, else the socket peer
+fn client_ip(headers: &HeaderMap, connect_addr: Option<IpAddr>) -> IpAddr {
+    headers
+        .get("x-forwarded-for")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.rsplit(',').next())
+        .and_then(|ip| ip.trim().parse().ok())
+        .or(connect_addr)
+        .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::test_support::test_app;
+    use axum::body::Body;
+    use axum::http::{header, HeaderValue, Method, StatusCode};
+    use sqlx::PgPool;
+    use tower::ServiceExt;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn requests_over_the_limit_are_throttled() {
+        let limiter = WidgetRateLimiter::new(3);
+        let project_id = Uuid::new_v4();
+        for _ in 0..3 {
+            assert!(limiter.check(ip("203.0.113.7"), project_id).is_ok());
+        }
+        let retry_after = limiter.check(ip("203.0.113.7"), project_id).unwrap_err();
+        // One token refills every 20 seconds
+        assert!((1..=20).contains(&retry_after), "{retry_after}");
+    }
+
+    #[test]
+    fn limits_are_per_client_and_project() {
+        let limiter = WidgetRateLimiter::new(1);
+        let project_id = Uuid::new_v4();
+        assert!(limiter.check(ip("203.0.113.7"), project_id).is_ok());
+        assert!(limiter.check(ip("203.0.113.7"), project_id).is_err());
+        assert!(limiter.check(ip("203.0.113.8"), project_id).is_ok());
+        assert!(limiter.check(ip("203.0.113.7"), Uuid::new_v4()).is_ok());
+    }
+
+    #[test]
+    fn zero_disables_the_limit() {
+        let limiter = WidgetRateLimiter::new(0);
+        let project_id = Uuid::new_v4();
+        for _ in 0..100 {
+            assert!(limiter.check(ip("203.0.113.7"), project_id).is_ok());
+        }
+    }
+
+    #[test]
+    fn client_ip_prefers_last_forwarded_hop() {
+        let peer = Some(ip("10.0.0.1"));
+        let mut headers = HeaderMap::new();
+        assert_eq!(client_ip(&headers, peer), ip("10.0.0.1"));
+        assert_eq!(client_ip(&headers, None), ip("0.0.0.0"));
+
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("1.2.3.4, 203.0.113.7"),
+        );
+        assert_eq!(client_ip(&headers, peer), ip("203.0.113.7"));
+
+        headers.insert("x-forwarded-for", HeaderValue::from_static("garbage"));
+        assert_eq!(client_ip(&headers, peer), ip("10.0.0.1"));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn third_submission_in_a_minute_gets_429(pool: PgPool) {
+        let config = Config {
+            widget_rate_limit: 2,
+            ..Config::test_default()
+        };
+        let (app, _) = test_app(pool, &config).await;
+
+        let project_id = Uuid::new_v4();
+        let submit = || {
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("/api/v1/widget/{}/submit", project_id))
+                .header(header::CONTENT_TYPE, "application/json")
+                .header("x-forwarded-for", "203.0.113.7")
+                .body(Body::from("{}"))
+                .unwrap()
+        };
+
+        for _ in 0..2 {
+            let response = app.clone().oneshot(submit()).await.unwrap();
+            assert_ne!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        }
+        let response = app.oneshot(submit()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(header::RETRY_AFTER));
+    }
+}
@@ -0,0 +1,124 @@
+//! Request ID middleware - tags each request with an ID carried in logs, the response
+//! header and error bodies, so a user-reported error can be matched to server logs
+
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use uuid::Uuid;
+
+pub static REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest client-supplied ID that is kept; longer ones are replaced
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static CURRENT_REQUEST_ID: String;
+}
+
+/// ID of the request being handled, stored in request extensions (the HTTP trace span
+/// reads it from there)
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+/// ID of the request the current task is handling, if any
+pub fn current_request_id() -> Option<String> {
+    CURRENT_REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Use the caller's `X-Request-Id` (e.g. from the load balancer) or generate one, and echo
+/// it on the response
+pub async fn request_id_middleware(mut request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|id| is_valid_request_id(id))
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    request.extensions_mut().insert(RequestId(id.clone()));
+    let mut response = CURRENT_REQUEST_ID
+        .scope(id.clone(), next.run(request))
+        .await;
+
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response
+            .headers_mut()
+            .insert(REQUEST_ID_HEADER.clone(), value);
+    }
+    response
+}
+
+/// IDs end up in logs and headers, so only short printable ASCII is accepted
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN && id.bytes().all(|b| b.is_ascii_graphic())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::AppError;
+    use axum::{body::Body, http::StatusCode, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route("/ok", get(|| async { "ok" }))
+            .route(
+                "/missing",
+                get(|| async { Err::<(), _>(AppError::not_found("Ticket not found")) }),
+            )
+            .layer(middleware::from_fn(request_id_middleware))
+    }
+
+    fn get_request(uri: &str, request_id: Option<&str>) -> Request {
+        let mut builder = Request::builder().uri(uri);
+        if let Some(id) = request_id {
+            builder = builder.header(&REQUEST_ID_HEADER, id);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn supplied_id_is_echoed() {
+        let response = app()
+            .oneshot(get_request("/ok", Some("lb-trace-42")))
+            .await
+            .unwrap();
+        assert_eq!(response.headers()[&REQUEST_ID_HEADER], "lb-trace-42");
+    }
+
+    #[tokio::test]
+    async fn missing_or_invalid_ids_are_generated() {
+        for supplied in [None, Some(""), Some("has spaces"), Some(&*"x".repeat(200))] {
+            let response = app().oneshot(get_request("/ok", supplied)).await.unwrap();
+            let id = response.headers()[&REQUEST_ID_HEADER].to_str().unwrap();
+            assert!(Uuid::parse_str(id).is_ok(), "{id}");
+        }
+    }
+
+    #[tokio::test]
+    async fn error_bodies_carry_the_id() {
+        let response = app()
+            .oneshot(get_request("/missing", Some("support-123")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.headers()[&REQUEST_ID_HEADER], "support-123");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["request_id"], "support-123");
+        assert_eq!(body["code"], "NOT_FOUND");
+    }
+
+    #[test]
+    fn errors_outside_a_request_have_no_id() {
+        assert_eq!(current_request_id(), None);
+    }
+}
@@ -0,0 +1,257 @@
+//! Widget CORS middleware - restricts browser calls to a project's widget endpoints to
+//! pages on the project's domain
+
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, Request, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::services::ProjectService;
+use crate::state::ReadyAppState;
+
+/// Methods and headers the widget sends cross-origin
+const ALLOWED_METHODS: &str = "GET, POST, OPTIONS";
+const ALLOWED_HEADERS: &str = "content-type, x-ortrace-key, idempotency-key";
+/// How long browsers may cache a preflight answer (seconds)
+const PREFLIGHT_MAX_AGE: &str = "3600";
+
+/// CORS for `/api/v1/widget/:project_id/*`: a browser `Origin` must match the project's
+/// domain (or an origin allowed on the authenticated API). Requests without an `Origin`
+/// header, e.g. server-side submissions, pass through unchanged.
+pub async fn widget_cors_middleware(
+    State(ready): State<ReadyAppState>,
+    Path(params): Path<HashMap<String, String>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let Some(origin) = request.headers().get(header::ORIGIN).cloned() else {
+        return Ok(next.run(request).await);
+    };
+    let state = ready.get_or_unavailable().await?;
+
+    let project_id = params
+        .get("project_id")
+        .and_then(|id| Uuid::parse_str(id).ok())
+        .ok_or_else(|| AppError::not_found("Project not found or inactive"))?;
+    let project = state
+        .projects
+        .get_active(project_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Project not found or inactive"))?;
+
+    let allowed = origin.to_str().is_ok_and(|o| {
+        project
+            .domain
+            .as_deref()
+            .is_some_and(|domain| origin_matches_domain(o, domain))
+            || state
+                .config
+                .cors_allowed_origins
+                .iter()
+                .any(|a| a == o.trim_end_matches('/'))
+    });
+    if !allowed {
+        tracing::debug!(%project_id, ?origin, "Rejected widget request from foreign origin");
+        return Err(AppError::forbidden());
+    }
+
+    let is_preflight = request.method() == Method::OPTIONS
+        && request
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_REQUEST_METHOD);
+    let mut response = if is_preflight {
+        let mut response = StatusCode::NO_CONTENT.into_response();
+        let headers = response.headers_mut();
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_METHODS,
+            HeaderValue::from_static(ALLOWED_METHODS),
+        );
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_HEADERS,
+            HeaderValue::from_static(ALLOWED_HEADERS),
+        );
+        headers.insert(
+            header::ACCESS_CONTROL_MAX_AGE,
+            HeaderValue::from_static(PREFLIGHT_MAX_AGE),
+        );
+        response
+    } else {
+        next.run(request).await
+    };
+    allow_origin(response.headers_mut(), origin);
+    Ok(response)
+}
+
+fn allow_origin(headers: &mut HeaderMap, origin: HeaderValue) {
+    headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+    headers.append(header::VARY, HeaderValue::from_static("origin"));
+}
+
+/// Whether a browser origin (`scheme://host[:port]`) is the project's domain. Both sides are
+/// normalized like project domains (scheme, `www.` and case ignored); a path on the project
+/// domain (e.g. `localhost:8080/demo`) is not part of the comparison.
+fn origin_matches_domain(origin: &str, domain: &str) -> bool {
+    let origin = ProjectService::normalize_domain(origin);
+    let domain = ProjectService::normalize_domain(domain);
+    let domain_host = domain.split('/').next().unwrap_or(&domain);
+    !origin.is_empty() && !origin.contains('/') && origin == domain_host
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::models::Project;
+    use crate::test_support::{seed_user, test_app};
+    use axum::body::Body;
+    use sqlx::PgPool;
+    use tower::ServiceExt;
+
+    #[test]
+    fn matching_origins_are_allowed() {
+        assert!(origin_matches_domain(
+            "https://shop.example.com",
+            "shop.example.com"
+        ));
+        assert!(origin_matches_domain(
+            "https://www.Shop.example.com",
+            "https://shop.example.com/"
+        ));
+        assert!(origin_matches_domain(
+            "http://localhost:8080",
+            "localhost:8080/dummy"
+        ));
+    }
+
+    #[test]
+    fn mismatched_origins_are_rejected() {
+        assert!(!origin_matches_domain(
+            "https://evil.example.net",
+            "shop.example.com"
+        ));
+        assert!(!origin_matches_domain(
+            "https://shop.example.com.evil.net",
+            "shop.example.com"
+        ));
+        assert!(!origin_matches_domain(
+            "https://api.shop.example.com",
+            "shop.example.com"
+        ));
+        assert!(!origin_matches_domain(
+            "http://localhost:3000",
+            "localhost:8080"
+        ));
+        assert!(!origin_matches_domain("null", "shop.example.com"));
+        assert!(!origin_matches_domain("", ""));
+    }
+
+    async fn ready_app(pool: &PgPool) -> (axum::Router, Uuid) {
+        let owner_id = seed_user(pool, "owner@example.com", "internal").await.id;
+        let project = sqlx::query_as::<_, Project>(
+            "INSERT INTO projects (owner_id, name, domain, settings) VALUES ($1, 'App', 'shop.example.com', '{}') RETURNING *",
+        )
+        .bind(owner_id)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+
+        let config = Config::test_default();
+        (test_app(pool.clone(), &config).await.0, project.id)
+    }
+
+    fn preflight(project_id: Uuid, origin: &str) -> Request {
+        Request::builder()
+            .method(Method::OPTIONS)
+            .uri(format!("/api/v1/widget/{}/submit", project_id))
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn widget_routes_follow_project_domain(pool: PgPool) {
+        let (app, project_id) = ready_app(&pool).await;
+
+        let response = app
+            .clone()
+            .oneshot(preflight(project_id, "https://shop.example.com"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://shop.example.com"
+        );
+
+        let response = app
+            .clone()
+            .oneshot(preflight(project_id, "https://evil.example.net"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert!(!response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+
+        let config = Request::builder()
+            .uri(format!("/api/v1/widget/{}/config", project_id))
+            .header(header::ORIGIN, "https://shop.example.com")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(config).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://shop.example.com"
+        );
+
+        // Server-side callers send no Origin
+        let config = Request::builder()
+            .uri(format!("/api/v1/widget/{}/config", project_id))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(config).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn authenticated_api_only_allows_configured_origins(pool: PgPool) {
+        let (app, _) = ready_app(&pool).await;
+        let preflight = |origin: &str| {
+            Request::builder()
+                .method(Method::OPTIONS)
+                .uri("/api/v1/projects")
+                .header(header::ORIGIN, origin)
+                .header(header::ACCESS_CONTROL_REQUEST_METHOD, "GET")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(preflight("http://localhost:8080"))
+            .await
+            .unwrap();
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "http://localhost:8080"
+        );
+
+        let response = app
+            .oneshot(preflight("https://shop.example.com"))
+            .await
+            .unwrap();
+        assert!(!response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+}
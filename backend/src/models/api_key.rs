@@ -0,0 +1,25 @@
+//! Project API key model
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Project API key database model; only a hash of the key is stored, and the key itself
+/// is shown once, at creation
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ProjectApiKey {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub label: String,
+    /// Leading characters of the key, so owners can tell keys apart
+    pub key_prefix: String,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl ProjectApiKey {
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none()
+    }
+}
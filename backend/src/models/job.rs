@@ -14,6 +14,10 @@ pub enum JobStatus {
     Processing,
     Completed,
     Failed,
+    /// Failed too many times; will not be retried without manual intervention
+    #[sqlx(rename = "dead_letter")]
+    #[serde(rename = "dead_letter")]
+    DeadLetter,
 }
 
 impl std::fmt::Display for JobStatus {
@@ -23,10 +27,22 @@ impl std::fmt::Display for JobStatus {
             JobStatus::Processing => write!(f, "processing"),
             JobStatus::Completed => write!(f, "completed"),
             JobStatus::Failed => write!(f, "failed"),
+            JobStatus::DeadLetter => write!(f, "dead_letter"),
         }
     }
 }
 
+/// Step of `Worker::process_next_job` a running job is in, in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum JobProgressStage {
+    Downloading,
+    UploadingToGemini,
+    Analyzing,
+    Saving,
+}
+
 /// Analysis job database model
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct AnalysisJob {
@@ -36,21 +52,39 @@ pub struct AnalysisJob {
     pub status: JobStatus,
     pub video_storage_path: String,
     pub video_size_bytes: i64,
+    /// MIME type of the stored media; `None` on older jobs, which are all videos
+    pub mime_type: Option<String>,
     pub prompt: Option<String>,
     pub analysis_result: Option<String>,
     pub error_message: Option<String>,
     pub retry_count: i32,
+    pub prompt_tokens: Option<i32>,
+    pub candidates_tokens: Option<i32>,
+    pub total_tokens: Option<i32>,
+    pub progress_stage: Option<JobProgressStage>,
     pub created_at: DateTime<Utc>,
     pub started_at: Option<DateTime<Utc>>,
+    pub heartbeat_at: Option<DateTime<Utc>>,
     pub completed_at: Option<DateTime<Utc>>,
     pub updated_at: DateTime<Utc>,
 }
 
+impl AnalysisJob {
+    /// Whether the job analyzes a still image (a screenshot) rather than a video
+    pub fn is_image(&self) -> bool {
+        self.mime_type
+            .as_deref()
+            .is_some_and(|mime| mime.starts_with("image/"))
+    }
+}
+
 /// Request to create a new job
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateJobRequest {
     pub video_storage_path: String,
     pub video_size_bytes: i64,
+    #[serde(default)]
+    pub mime_type: Option<String>,
     pub prompt: Option<String>,
     pub user_id: Option<Uuid>,
     pub recording_id: Option<Uuid>,
@@ -66,6 +100,7 @@ mod tests {
         assert_eq!(JobStatus::Processing.to_string(), "processing");
         assert_eq!(JobStatus::Completed.to_string(), "completed");
         assert_eq!(JobStatus::Failed.to_string(), "failed");
+        assert_eq!(JobStatus::DeadLetter.to_string(), "dead_letter");
     }
 
     #[test]
@@ -86,6 +121,10 @@ mod tests {
             serde_json::to_string(&JobStatus::Failed).unwrap(),
             "\"failed\""
         );
+        assert_eq!(
+            serde_json::to_string(&JobStatus::DeadLetter).unwrap(),
+            "\"dead_letter\""
+        );
     }
 
     #[test]
@@ -98,6 +137,30 @@ mod tests {
             serde_json::from_str::<JobStatus>("\"failed\"").unwrap(),
             JobStatus::Failed
         );
+        assert_eq!(
+            serde_json::from_str::<JobStatus>("\"dead_letter\"").unwrap(),
+            JobStatus::DeadLetter
+        );
+    }
+
+    #[test]
+    fn job_progress_stage_serialization() {
+        assert_eq!(
+            serde_json::to_string(&JobProgressStage::Downloading).unwrap(),
+            "\"downloading\""
+        );
+        assert_eq!(
+            serde_json::to_string(&JobProgressStage::UploadingToGemini).unwrap(),
+            "\"uploading_to_gemini\""
+        );
+        assert_eq!(
+            serde_json::to_string(&JobProgressStage::Analyzing).unwrap(),
+            "\"analyzing\""
+        );
+        assert_eq!(
+            serde_json::to_string(&JobProgressStage::Saving).unwrap(),
+            "\"saving\""
+        );
     }
 
     #[test]
@@ -107,6 +170,7 @@ mod tests {
         let req = CreateJobRequest {
             video_storage_path: "recordings/session1/vid.webm".to_string(),
             video_size_bytes: 1024000,
+            mime_type: None,
             prompt: Some("Analyze this video".to_string()),
             user_id: Some(user_id),
             recording_id: Some(recording_id),
@@ -128,6 +192,7 @@ mod tests {
         let req = CreateJobRequest {
             video_storage_path: "test.webm".to_string(),
             video_size_bytes: 500,
+            mime_type: None,
             prompt: None,
             user_id: None,
             recording_id: None,
@@ -1,13 +1,19 @@
 //! Domain models
 
+pub mod api_key;
 pub mod job;
 pub mod project;
 pub mod report;
+pub mod session;
 pub mod ticket;
 pub mod user;
+pub mod webhook;
 
+pub use api_key::*;
 pub use job::*;
 pub use project::*;
 pub use report::*;
+pub use session::*;
 pub use ticket::*;
 pub use user::*;
+pub use webhook::*;
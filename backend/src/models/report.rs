@@ -15,6 +15,36 @@ pub enum ReportOutcome {
     Failed,
 }
 
+impl std::fmt::Display for ReportOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReportOutcome::Success => write!(f, "success"),
+            ReportOutcome::Partial => write!(f, "partial"),
+            ReportOutcome::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+/// Overall mood of the person in the recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ReportSentiment {
+    Frustrated,
+    Neutral,
+    Satisfied,
+}
+
+impl std::fmt::Display for ReportSentiment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReportSentiment::Frustrated => write!(f, "frustrated"),
+            ReportSentiment::Neutral => write!(f, "neutral"),
+            ReportSentiment::Satisfied => write!(f, "satisfied"),
+        }
+    }
+}
+
 /// Question analysis item
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QuestionAnalysis {
@@ -65,10 +95,31 @@ pub struct Report {
     /// Possible solutions to address the issues (raw JSON: array or string from Gemini).
     pub possible_solutions: sqlx::types::Json<serde_json::Value>,
     pub raw_analysis: Option<String>,
+    /// Language the analysis was written in
+    pub language: String,
+    pub sentiment: Option<ReportSentiment>,
+    /// 0-100
+    pub frustration_score: Option<i32>,
+    /// Internal user who last corrected the report by hand
+    pub edited_by: Option<Uuid>,
+    pub edited_at: Option<DateTime<Utc>>,
+    /// 1 for the first analysis, one more for each reanalysis
+    pub report_version: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// A report replaced by a reanalysis, kept with the issues it had at the time
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ReportVersion {
+    pub id: Uuid,
+    pub recording_id: Uuid,
+    pub report_version: i32,
+    pub report: sqlx::types::Json<Report>,
+    pub issues: sqlx::types::Json<Vec<Issue>>,
+    pub archived_at: DateTime<Utc>,
+}
+
 /// Issue severity enum
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "varchar", rename_all = "lowercase")]
@@ -91,6 +142,27 @@ impl std::fmt::Display for IssueSeverity {
     }
 }
 
+/// Triage state of a single issue within a report
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum IssueStatus {
+    #[default]
+    Open,
+    Resolved,
+    Dismissed,
+}
+
+impl std::fmt::Display for IssueStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IssueStatus::Open => write!(f, "open"),
+            IssueStatus::Resolved => write!(f, "resolved"),
+            IssueStatus::Dismissed => write!(f, "dismissed"),
+        }
+    }
+}
+
 /// Issue tag (for categorization)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -166,10 +238,226 @@ pub struct Issue {
     pub reproduction_steps: sqlx::types::Json<serde_json::Value>,
     pub confidence: Option<i32>,
     pub external_ticket_url: Option<String>,
+    pub issue_status: IssueStatus,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// Longest issue title kept; longer titles are cut
+const MAX_ISSUE_TITLE_CHARS: usize = 255;
+
+/// Longest overview kept from an analysis that could not be parsed at all
+const MAX_FALLBACK_OVERVIEW_CHARS: usize = 2000;
+/// Top-level analysis fields whose absence leaves a report visibly incomplete
+const EXPECTED_SECTIONS: [&str; 4] = ["outcome", "overview", "metrics", "issues"];
+
+/// Gemini's analysis JSON, checked and normalized before it is saved as a report.
+/// Scores are clamped to range, unknown severities become `medium`, an unknown outcome or
+/// sentiment is left unset, and issues without a title are dropped.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ParsedAnalysis {
+    pub outcome: Option<ReportOutcome>,
+    /// 0-100
+    pub confidence: Option<i32>,
+    pub overview: Option<String>,
+    pub sentiment: Option<ReportSentiment>,
+    /// 0-100
+    pub frustration_score: Option<i32>,
+    /// 0-100
+    pub task_completion_rate: Option<i32>,
+    pub total_hesitation_time: Option<i32>,
+    pub retries_count: Option<i32>,
+    pub abandonment_point: Option<String>,
+    /// Array or string, as accepted by [`question_analysis_from_value`]
+    pub question_analysis: serde_json::Value,
+    pub suggested_actions: Vec<String>,
+    /// Array or string
+    pub possible_solutions: serde_json::Value,
+    pub issues: Vec<ParsedIssue>,
+}
+
+/// One issue from [`ParsedAnalysis`]
+#[derive(Debug, Clone, Serialize)]
+pub struct ParsedIssue {
+    pub title: String,
+    pub severity: IssueSeverity,
+    pub tags: serde_json::Value,
+    pub observed_behavior: Option<String>,
+    pub expected_behavior: Option<String>,
+    pub evidence: serde_json::Value,
+    pub screenshots: serde_json::Value,
+    pub impact: serde_json::Value,
+    pub reproduction_steps: serde_json::Value,
+    /// 0-100
+    pub confidence: Option<i32>,
+}
+
+impl ParsedAnalysis {
+    pub fn from_value(value: &serde_json::Value) -> Self {
+        let metrics = value.get("metrics");
+        let metric = |name: &str| metrics.and_then(|m| m.get(name));
+        let issues = value
+            .get("issues")
+            .and_then(|v| v.as_array())
+            .map(|issues| {
+                issues
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, issue)| {
+                        let parsed = ParsedIssue::from_value(issue);
+                        if parsed.is_none() {
+                            tracing::warn!(index, "Skipping analysis issue without a title");
+                        }
+                        parsed
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            outcome: value.get("outcome").and_then(parse_outcome),
+            confidence: value.get("confidence").and_then(|v| score(v, 0, 100)),
+            overview: value.get("overview").and_then(text),
+            sentiment: value.get("sentiment").and_then(parse_sentiment),
+            frustration_score: value
+                .get("frustration_score")
+                .and_then(|v| score(v, 0, 100)),
+            task_completion_rate: metric("task_completion_rate").and_then(|v| score(v, 0, 100)),
+            total_hesitation_time: metric("total_hesitation_time")
+                .and_then(|v| score(v, 0, i32::MAX)),
+            retries_count: metric("retries_count").and_then(|v| score(v, 0, i32::MAX)),
+            abandonment_point: metric("abandonment_point").and_then(text),
+            question_analysis: list_or_text(value.get("question_analysis")),
+            suggested_actions: value
+                .get("suggested_actions")
+                .map(string_array_from_value)
+                .unwrap_or_default(),
+            possible_solutions: list_or_text(value.get("possible_solutions")),
+            issues,
+        }
+    }
+}
+
+impl ParsedAnalysis {
+    /// Minimal report for output that holds no JSON object: the raw text, cut to length,
+    /// becomes the overview and the outcome is `partial`, so the ticket is not left
+    /// analyzed without a report
+    pub fn fallback(raw: &str) -> Self {
+        let raw = raw.trim();
+        let mut overview: String = raw.chars().take(MAX_FALLBACK_OVERVIEW_CHARS).collect();
+        if overview.len() < raw.len() {
+            overview.push('…');
+        }
+        Self {
+            outcome: Some(ReportOutcome::Partial),
+            overview: (!overview.is_empty()).then_some(overview),
+            ..Self::from_value(&serde_json::json!({}))
+        }
+    }
+
+    /// Expected top-level fields the analysis left out
+    pub fn missing_sections(value: &serde_json::Value) -> Vec<&'static str> {
+        EXPECTED_SECTIONS
+            .into_iter()
+            .filter(|section| value.get(section).is_none_or(|v| v.is_null()))
+            .collect()
+    }
+}
+
+impl ParsedIssue {
+    /// `None` when the entry is not an object with a non-empty title
+    fn from_value(value: &serde_json::Value) -> Option<Self> {
+        let title = value.get("title").and_then(text)?;
+        Some(Self {
+            title: title.chars().take(MAX_ISSUE_TITLE_CHARS).collect(),
+            severity: value
+                .get("severity")
+                .and_then(|v| v.as_str())
+                .map(parse_severity)
+                .unwrap_or(IssueSeverity::Medium),
+            tags: list_or_text(value.get("tags")),
+            observed_behavior: value.get("observed_behavior").and_then(text),
+            expected_behavior: value.get("expected_behavior").and_then(text),
+            evidence: list_or_text(value.get("evidence")),
+            screenshots: list_or_text(value.get("screenshots")),
+            impact: list_or_text(value.get("impact")),
+            reproduction_steps: list_or_text(value.get("reproduction_steps")),
+            confidence: value.get("confidence").and_then(|v| score(v, 0, 100)),
+        })
+    }
+}
+
+fn parse_outcome(value: &serde_json::Value) -> Option<ReportOutcome> {
+    let raw = value.as_str()?.trim().to_ascii_lowercase();
+    let outcome = match raw.as_str() {
+        "success" | "succeeded" | "successful" | "completed" => ReportOutcome::Success,
+        "partial" | "partial_success" | "partially_successful" => ReportOutcome::Partial,
+        "failed" | "failure" | "fail" | "abandoned" => ReportOutcome::Failed,
+        _ => {
+            tracing::warn!(outcome = %raw, "Ignoring unknown analysis outcome");
+            return None;
+        }
+    };
+    Some(outcome)
+}
+
+fn parse_sentiment(value: &serde_json::Value) -> Option<ReportSentiment> {
+    let raw = value.as_str()?.trim().to_ascii_lowercase();
+    let sentiment = match raw.as_str() {
+        "frustrated" | "angry" | "annoyed" | "negative" => ReportSentiment::Frustrated,
+        "neutral" | "mixed" => ReportSentiment::Neutral,
+        "satisfied" | "happy" | "pleased" | "positive" => ReportSentiment::Satisfied,
+        _ => {
+            tracing::warn!(sentiment = %raw, "Ignoring unknown analysis sentiment");
+            return None;
+        }
+    };
+    Some(sentiment)
+}
+
+fn parse_severity(raw: &str) -> IssueSeverity {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "critical" | "blocker" => IssueSeverity::Critical,
+        "high" | "major" | "severe" => IssueSeverity::High,
+        "low" | "minor" | "trivial" => IssueSeverity::Low,
+        "medium" | "moderate" => IssueSeverity::Medium,
+        other => {
+            tracing::warn!(severity = %other, "Unknown issue severity, using medium");
+            IssueSeverity::Medium
+        }
+    }
+}
+
+/// Whole number within `[min, max]`; floats are rounded and numeric strings accepted
+fn score(value: &serde_json::Value, min: i32, max: i32) -> Option<i32> {
+    let number = match value {
+        serde_json::Value::Number(n) => n.as_f64()?,
+        serde_json::Value::String(s) => s.trim().trim_end_matches('%').parse().ok()?,
+        _ => return None,
+    };
+    if !number.is_finite() {
+        return None;
+    }
+    Some(number.round().clamp(min as f64, max as f64) as i32)
+}
+
+/// Non-empty trimmed string
+fn text(value: &serde_json::Value) -> Option<String> {
+    value
+        .as_str()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+}
+
+/// Arrays and strings pass through (readers accept both); anything else becomes `[]`
+fn list_or_text(value: Option<&serde_json::Value>) -> serde_json::Value {
+    match value {
+        Some(v @ (serde_json::Value::Array(_) | serde_json::Value::String(_))) => v.clone(),
+        _ => serde_json::Value::Array(Vec::new()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -281,4 +569,144 @@ mod tests {
         assert_eq!(deserialized.confidence, 85);
         assert_eq!(deserialized.observations.len(), 1);
     }
+
+    #[test]
+    fn parsed_analysis_clamps_and_normalizes() {
+        let parsed = ParsedAnalysis::from_value(&serde_json::json!({
+            "outcome": "Failure",
+            "confidence": 500,
+            "overview": "  The user gave up.  ",
+            "metrics": {
+                "task_completion_rate": "-20",
+                "total_hesitation_time": 12.6,
+                "retries_count": -3,
+                "abandonment_point": ""
+            },
+            "question_analysis": 42,
+            "suggested_actions": "Fix the button",
+            "possible_solutions": ["Add a spinner"],
+            "issues": [
+                { "title": "Button dead", "severity": "BLOCKER", "confidence": 101, "tags": {"x": 1} },
+                { "title": "Typo", "severity": "cosmetic" },
+                { "severity": "high" },
+                { "title": "   " },
+                "not an object"
+            ]
+        }));
+        assert_eq!(parsed.outcome, Some(ReportOutcome::Failed));
+        assert_eq!(parsed.confidence, Some(100));
+        assert_eq!(parsed.overview.as_deref(), Some("The user gave up."));
+        assert_eq!(parsed.task_completion_rate, Some(0));
+        assert_eq!(parsed.total_hesitation_time, Some(13));
+        assert_eq!(parsed.retries_count, Some(0));
+        assert_eq!(parsed.abandonment_point, None);
+        assert_eq!(parsed.question_analysis, serde_json::json!([]));
+        assert_eq!(parsed.suggested_actions, ["Fix the button"]);
+        assert_eq!(
+            parsed.possible_solutions,
+            serde_json::json!(["Add a spinner"])
+        );
+
+        let issues: Vec<(&str, IssueSeverity)> = parsed
+            .issues
+            .iter()
+            .map(|i| (i.title.as_str(), i.severity))
+            .collect();
+        assert_eq!(
+            issues,
+            [
+                ("Button dead", IssueSeverity::Critical),
+                ("Typo", IssueSeverity::Medium)
+            ]
+        );
+        assert_eq!(parsed.issues[0].confidence, Some(100));
+        assert_eq!(parsed.issues[0].tags, serde_json::json!([]));
+    }
+
+    #[test]
+    fn parsed_analysis_leaves_unknown_outcome_unset() {
+        let parsed = ParsedAnalysis::from_value(&serde_json::json!({
+            "outcome": "great",
+            "confidence": "n/a",
+            "issues": "none"
+        }));
+        assert_eq!(parsed.outcome, None);
+        assert_eq!(parsed.confidence, None);
+        assert!(parsed.issues.is_empty());
+        assert_eq!(parsed.question_analysis, serde_json::json!([]));
+    }
+
+    #[test]
+    fn parsed_analysis_reads_sentiment() {
+        let parsed = ParsedAnalysis::from_value(&serde_json::json!({
+            "sentiment": " Annoyed ",
+            "frustration_score": 140
+        }));
+        assert_eq!(parsed.sentiment, Some(ReportSentiment::Frustrated));
+        assert_eq!(parsed.frustration_score, Some(100));
+
+        let parsed = ParsedAnalysis::from_value(&serde_json::json!({
+            "sentiment": "satisfied",
+            "frustration_score": "12%"
+        }));
+        assert_eq!(parsed.sentiment, Some(ReportSentiment::Satisfied));
+        assert_eq!(parsed.frustration_score, Some(12));
+    }
+
+    #[test]
+    fn parsed_analysis_without_sentiment_leaves_it_unset() {
+        let parsed = ParsedAnalysis::from_value(&serde_json::json!({ "outcome": "success" }));
+        assert_eq!(parsed.sentiment, None);
+        assert_eq!(parsed.frustration_score, None);
+
+        let parsed = ParsedAnalysis::from_value(&serde_json::json!({
+            "sentiment": "ecstatic",
+            "frustration_score": null
+        }));
+        assert_eq!(parsed.sentiment, None);
+        assert_eq!(parsed.frustration_score, None);
+    }
+
+    #[test]
+    fn parsed_issue_titles_are_capped() {
+        let parsed = ParsedAnalysis::from_value(&serde_json::json!({
+            "issues": [{ "title": "x".repeat(400) }]
+        }));
+        assert_eq!(
+            parsed.issues[0].title.chars().count(),
+            MAX_ISSUE_TITLE_CHARS
+        );
+    }
+
+    #[test]
+    fn fallback_keeps_the_raw_text_as_a_partial_overview() {
+        let parsed = ParsedAnalysis::fallback("  The user could not find the export button.\n");
+        assert_eq!(parsed.outcome, Some(ReportOutcome::Partial));
+        assert_eq!(
+            parsed.overview.as_deref(),
+            Some("The user could not find the export button.")
+        );
+        assert!(parsed.issues.is_empty());
+        assert_eq!(parsed.question_analysis, serde_json::json!([]));
+
+        let long = "é".repeat(MAX_FALLBACK_OVERVIEW_CHARS + 10);
+        let overview = ParsedAnalysis::fallback(&long).overview.unwrap();
+        assert_eq!(overview.chars().count(), MAX_FALLBACK_OVERVIEW_CHARS + 1);
+        assert!(overview.ends_with('…'));
+
+        assert_eq!(ParsedAnalysis::fallback("   ").overview, None);
+    }
+
+    #[test]
+    fn missing_sections_lists_absent_or_null_fields() {
+        let value = serde_json::json!({ "outcome": "failed", "overview": "Broke", "issues": null });
+        assert_eq!(
+            ParsedAnalysis::missing_sections(&value),
+            ["metrics", "issues"]
+        );
+        assert!(ParsedAnalysis::missing_sections(&serde_json::json!({
+            "outcome": "success", "overview": "Fine", "metrics": {}, "issues": []
+        }))
+        .is_empty());
+    }
 }
@@ -0,0 +1,25 @@
+//! Legacy feedback session model
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::FeedbackType;
+
+/// Session database model: a standalone feedback round reached through its share link,
+/// predating projects and the widget. Tickets submitted through it carry its `session_id`.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Session {
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    pub name: String,
+    /// Feedback type given to every ticket submitted through the session
+    #[sqlx(rename = "type")]
+    pub session_type: FeedbackType,
+    pub share_token: String,
+    /// Questions shown to submitters
+    pub questions: sqlx::types::Json<Vec<String>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -5,6 +5,8 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::models::ReportSentiment;
+
 /// Feedback type enum
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "varchar", rename_all = "lowercase")]
@@ -133,6 +135,131 @@ pub struct FeedbackTicket {
     pub screenshot_url: Option<String>,
     pub assignee_id: Option<Uuid>,
     pub due_date: Option<DateTime<Utc>>,
+    pub video_mime_type: Option<String>,
+    /// Console logs and network errors from the submitter's browser; analysis input only,
+    /// so kept out of serialized tickets (webhooks, notifications)
+    #[serde(default, skip_serializing)]
+    pub client_diagnostics: sqlx::types::Json<ClientDiagnostics>,
+}
+
+impl FeedbackTicket {
+    /// Content-Type to stream the stored video with (widget recordings are webm)
+    pub fn video_content_type(&self) -> &str {
+        self.video_mime_type.as_deref().unwrap_or("video/webm")
+    }
+
+    /// Normalize a team label to its stored form (trimmed, lowercase)
+    pub fn normalize_label(label: &str) -> Result<String, String> {
+        let label = label.trim().to_lowercase();
+        if label.is_empty() {
+            return Err("Label must not be empty".to_string());
+        }
+        if label.chars().count() > MAX_LABEL_CHARS {
+            return Err(format!(
+                "Label must be at most {} characters",
+                MAX_LABEL_CHARS
+            ));
+        }
+        Ok(label)
+    }
+}
+
+/// Read-only share link for one ticket; only a hash of the token is stored
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TicketShareToken {
+    pub id: Uuid,
+    pub recording_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Longest label a ticket can carry (matches the `ticket_labels.label` column)
+pub const MAX_LABEL_CHARS: usize = 50;
+
+/// Console entries kept per submission (the most recent ones)
+pub const MAX_CONSOLE_ENTRIES: usize = 200;
+/// Network errors kept per submission (the most recent ones)
+pub const MAX_NETWORK_ERRORS: usize = 100;
+/// Longest console message or URL kept
+const MAX_DIAGNOSTIC_TEXT_CHARS: usize = 2000;
+
+/// Browser diagnostics the widget attaches to a submission
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClientDiagnostics {
+    #[serde(default)]
+    pub console_logs: Vec<ConsoleEntry>,
+    #[serde(default)]
+    pub network_errors: Vec<NetworkError>,
+}
+
+/// One browser console message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsoleEntry {
+    /// Console method, e.g. `error`, `warn`, `log`
+    pub level: String,
+    pub message: String,
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+/// A failed network request (error status or no response)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkError {
+    pub method: Option<String>,
+    pub url: String,
+    /// HTTP status; absent when the request never got a response
+    pub status: Option<u16>,
+    pub message: Option<String>,
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+impl ClientDiagnostics {
+    /// Diagnostics capped to the most recent `MAX_CONSOLE_ENTRIES` / `MAX_NETWORK_ERRORS`
+    /// entries, with long messages and URLs cut
+    pub fn capped(
+        console_logs: Option<Vec<ConsoleEntry>>,
+        network_errors: Option<Vec<NetworkError>>,
+    ) -> Self {
+        let console_logs = last_n(console_logs.unwrap_or_default(), MAX_CONSOLE_ENTRIES)
+            .map(|mut entry| {
+                entry.level = truncate_chars(entry.level.trim(), 20).to_lowercase();
+                entry.message = truncate_chars(&entry.message, MAX_DIAGNOSTIC_TEXT_CHARS);
+                entry
+            })
+            .collect();
+        let network_errors = last_n(network_errors.unwrap_or_default(), MAX_NETWORK_ERRORS)
+            .map(|mut error| {
+                error.method = error
+                    .method
+                    .map(|m| truncate_chars(m.trim(), 10).to_uppercase());
+                error.url = truncate_chars(&error.url, MAX_DIAGNOSTIC_TEXT_CHARS);
+                error.message = error
+                    .message
+                    .map(|m| truncate_chars(&m, MAX_DIAGNOSTIC_TEXT_CHARS));
+                error
+            })
+            .collect();
+        Self {
+            console_logs,
+            network_errors,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.console_logs.is_empty() && self.network_errors.is_empty()
+    }
+}
+
+fn last_n<T>(items: Vec<T>, n: usize) -> impl Iterator<Item = T> {
+    let skip = items.len().saturating_sub(n);
+    items.into_iter().skip(skip)
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((i, _)) => format!("{}…", &text[..i]),
+        None => text.to_string(),
+    }
 }
 
 /// Legacy session_status field (open/closed for backward compat)
@@ -185,9 +312,129 @@ pub struct TicketWithDetails {
     pub external_ticket_url: Option<String>,
     pub external_ticket_id: Option<String>,
     pub ai_confidence: Option<i32>,
+    pub sentiment: Option<ReportSentiment>,
+    pub frustration_score: Option<i32>,
     // Joined fields
     pub project_name: Option<String>,
     pub customer_name: Option<String>,
     pub assignee_name: Option<String>,
     pub issues_count: i64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticket_with_mime(mime: Option<&str>) -> FeedbackTicket {
+        let now = Utc::now();
+        FeedbackTicket {
+            id: Uuid::new_v4(),
+            project_id: Some(Uuid::new_v4()),
+            session_id: None,
+            customer_id: Uuid::new_v4(),
+            analysis_job_id: None,
+            video_storage_path: Some("recordings/p/t.mp4".to_string()),
+            video_size_bytes: Some(1024),
+            duration_seconds: Some(10),
+            task_description: None,
+            prior_experience: None,
+            status: ProcessingStatus::Analyzed,
+            session_status: TicketSessionStatus::Open,
+            closed_at: None,
+            closed_reason: None,
+            external_ticket_url: None,
+            external_ticket_id: None,
+            recorded_at: Some(now),
+            created_at: now,
+            updated_at: now,
+            feedback_type: FeedbackType::Bug,
+            ticket_status: TicketStatus::Open,
+            priority: TicketPriority::Neutral,
+            category: None,
+            submitter_email: None,
+            submitter_name: None,
+            page_url: None,
+            browser_info: sqlx::types::Json(serde_json::json!({})),
+            screenshot_url: None,
+            assignee_id: None,
+            due_date: None,
+            video_mime_type: mime.map(String::from),
+            client_diagnostics: Default::default(),
+        }
+    }
+
+    #[test]
+    fn mp4_ticket_streams_as_mp4() {
+        let ticket = ticket_with_mime(Some("video/mp4"));
+        assert_eq!(ticket.video_content_type(), "video/mp4");
+    }
+
+    #[test]
+    fn unknown_mime_falls_back_to_webm() {
+        let ticket = ticket_with_mime(None);
+        assert_eq!(ticket.video_content_type(), "video/webm");
+    }
+
+    #[test]
+    fn client_diagnostics_deserialize_from_widget_json() {
+        let diagnostics: ClientDiagnostics = serde_json::from_value(serde_json::json!({
+            "console_logs": [
+                { "level": "error", "message": "Uncaught TypeError: x is undefined", "timestamp": "2026-10-17T12:00:01Z" },
+                { "level": "log", "message": "clicked pay" }
+            ],
+            "network_errors": [
+                { "method": "POST", "url": "https://shop.example.com/api/pay", "status": 500 },
+                { "url": "https://cdn.example.com/app.js", "message": "net::ERR_FAILED" }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(diagnostics.console_logs.len(), 2);
+        assert!(diagnostics.console_logs[1].timestamp.is_none());
+        assert_eq!(diagnostics.network_errors[0].status, Some(500));
+        assert!(diagnostics.network_errors[1].method.is_none());
+
+        // Stored rows predating the column hold `{}`
+        let empty: ClientDiagnostics = serde_json::from_str("{}").unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn client_diagnostics_keep_most_recent_entries() {
+        let logs = (0..MAX_CONSOLE_ENTRIES + 5)
+            .map(|i| ConsoleEntry {
+                level: " WARN ".to_string(),
+                message: format!("message {}", i),
+                timestamp: None,
+            })
+            .collect();
+        let long_url = format!("https://example.com/{}", "a".repeat(3000));
+        let errors = vec![NetworkError {
+            method: Some("get".to_string()),
+            url: long_url,
+            status: None,
+            message: None,
+            timestamp: None,
+        }];
+
+        let diagnostics = ClientDiagnostics::capped(Some(logs), Some(errors));
+        assert_eq!(diagnostics.console_logs.len(), MAX_CONSOLE_ENTRIES);
+        assert_eq!(diagnostics.console_logs[0].message, "message 5");
+        assert_eq!(diagnostics.console_logs[0].level, "warn");
+        assert_eq!(diagnostics.network_errors[0].method.as_deref(), Some("GET"));
+        assert_eq!(
+            diagnostics.network_errors[0].url.chars().count(),
+            MAX_DIAGNOSTIC_TEXT_CHARS + 1
+        );
+        assert!(ClientDiagnostics::capped(None, None).is_empty());
+    }
+
+    #[test]
+    fn labels_are_trimmed_and_lowercased() {
+        assert_eq!(
+            FeedbackTicket::normalize_label("  Mobile ").as_deref(),
+            Ok("mobile")
+        );
+        assert!(FeedbackTicket::normalize_label("   ").is_err());
+        assert!(FeedbackTicket::normalize_label(&"x".repeat(MAX_LABEL_CHARS + 1)).is_err());
+    }
+}
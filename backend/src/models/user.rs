@@ -38,11 +38,17 @@ pub struct User {
     pub refresh_token_hash: Option<String>,
     pub quota_limit: i32,
     pub quota_used: i32,
+    /// Created for a widget submission; has no credentials and is never matched to a
+    /// registered account by email
+    pub is_anonymous: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
 impl User {
+    /// Bootstrap user that authors system chat messages (see the `system_user` migration)
+    pub const SYSTEM_ID: Uuid = Uuid::from_u128(1);
+
     /// Check if user is internal (admin/team member)
     pub fn is_internal(&self) -> bool {
         self.role == UserRole::Internal
@@ -89,6 +95,7 @@ mod tests {
             refresh_token_hash: None,
             quota_limit: 10,
             quota_used: 0,
+            is_anonymous: false,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
@@ -0,0 +1,144 @@
+//! Project webhook model
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::{FeedbackTicket, FeedbackType, ProcessingStatus, TicketPriority, TicketStatus};
+
+/// Ticket lifecycle events a webhook can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(clippy::enum_variant_names)] // mirrors the dotted `ticket.*` names
+pub enum WebhookEvent {
+    #[serde(rename = "ticket.created")]
+    TicketCreated,
+    #[serde(rename = "ticket.analyzed")]
+    TicketAnalyzed,
+    #[serde(rename = "ticket.status_changed")]
+    TicketStatusChanged,
+}
+
+impl WebhookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::TicketCreated => "ticket.created",
+            WebhookEvent::TicketAnalyzed => "ticket.analyzed",
+            WebhookEvent::TicketStatusChanged => "ticket.status_changed",
+        }
+    }
+}
+
+impl std::fmt::Display for WebhookEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Webhook database model
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub url: String,
+    pub secret: String,
+    /// Event names (see `WebhookEvent::as_str`); stored as text so unknown names survive
+    pub events: Vec<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Webhook {
+    /// Subscribed events this build knows about
+    pub fn event_list(&self) -> Vec<WebhookEvent> {
+        self.events
+            .iter()
+            .filter_map(|e| serde_json::from_value(serde_json::Value::String(e.clone())).ok())
+            .collect()
+    }
+
+    pub fn subscribes_to(&self, event: WebhookEvent) -> bool {
+        self.is_active && self.events.iter().any(|e| e == event.as_str())
+    }
+}
+
+/// Ticket fields sent in webhook payloads
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookTicket {
+    pub id: Uuid,
+    pub project_id: Option<Uuid>,
+    pub feedback_type: FeedbackType,
+    pub ticket_status: TicketStatus,
+    pub priority: TicketPriority,
+    pub status: ProcessingStatus,
+    pub task_description: Option<String>,
+    pub page_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&FeedbackTicket> for WebhookTicket {
+    fn from(ticket: &FeedbackTicket) -> Self {
+        Self {
+            id: ticket.id,
+            project_id: ticket.project_id,
+            feedback_type: ticket.feedback_type,
+            ticket_status: ticket.ticket_status,
+            priority: ticket.priority,
+            status: ticket.status,
+            task_description: ticket.task_description.clone(),
+            page_url: ticket.page_url.clone(),
+            created_at: ticket.created_at,
+            updated_at: ticket.updated_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn webhook(events: &[&str], is_active: bool) -> Webhook {
+        Webhook {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            url: "https://example.com/hook".to_string(),
+            secret: "secret".to_string(),
+            events: events.iter().map(|e| e.to_string()).collect(),
+            is_active,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn event_serializes_as_dotted_name() {
+        for event in [
+            WebhookEvent::TicketCreated,
+            WebhookEvent::TicketAnalyzed,
+            WebhookEvent::TicketStatusChanged,
+        ] {
+            assert_eq!(
+                serde_json::to_value(event).unwrap(),
+                serde_json::json!(event.as_str())
+            );
+        }
+    }
+
+    #[test]
+    fn subscribes_only_to_listed_events_while_active() {
+        let hook = webhook(&["ticket.created"], true);
+        assert!(hook.subscribes_to(WebhookEvent::TicketCreated));
+        assert!(!hook.subscribes_to(WebhookEvent::TicketAnalyzed));
+
+        let inactive = webhook(&["ticket.created"], false);
+        assert!(!inactive.subscribes_to(WebhookEvent::TicketCreated));
+    }
+
+    #[test]
+    fn event_list_skips_unknown_names() {
+        let hook = webhook(&["ticket.analyzed", "ticket.deleted"], true);
+        assert_eq!(hook.event_list(), vec![WebhookEvent::TicketAnalyzed]);
+    }
+}
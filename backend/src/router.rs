@@ -1,57 +1,178 @@
 //! Router configuration
 
 use axum::{
-    extract::DefaultBodyLimit,
+    extract::{DefaultBodyLimit, Request},
+    http::{header, HeaderValue, Method},
     middleware,
     routing::{delete, get, post, put},
     Router,
 };
-use tower_http::cors::{Any, CorsLayer};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
 use tower_http::trace::TraceLayer;
+use tracing::Span;
 
+use crate::config::Config;
 use crate::controllers;
-use crate::middleware::auth_middleware;
+use crate::middleware::{
+    auth_middleware, request_id_middleware, widget_cors_middleware, widget_rate_limit_middleware,
+    RequestId, REQUEST_ID_HEADER,
+};
 use crate::state::ReadyAppState;
 
+/// Body limit for every route without its own: JSON bodies are small
+const DEFAULT_BODY_LIMIT_BYTES: usize = 1024 * 1024;
+/// Room above MAX_UPLOAD_MB for the multipart framing and the other form fields
+const MULTIPART_OVERHEAD_BYTES: usize = 64 * 1024;
+
 /// Create the application router
-pub fn create_router(ready: ReadyAppState) -> Router {
-    let cors = CorsLayer::new()
+pub fn create_router(ready: ReadyAppState, config: &Config) -> Router {
+    // Health, domain lookup and share links are open to any page; the widget looks up its
+    // project from there
+    let public_cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
         .allow_headers(Any);
+    let api_cors = CorsLayer::new()
+        .allow_origin(AllowOrigin::list(
+            config
+                .cors_allowed_origins
+                .iter()
+                .filter_map(|o| HeaderValue::from_str(o).ok()),
+        ))
+        // Only what the frontend sends; the bearer token must not be offered to arbitrary
+        // headers or methods
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+        .allow_headers([
+            header::AUTHORIZATION,
+            header::CONTENT_TYPE,
+            header::ACCEPT,
+            REQUEST_ID_HEADER.clone(),
+        ]);
 
     Router::new()
         .route("/health", get(controllers::health))
+        .route("/health/ready", get(controllers::health))
+        .route("/health/live", get(controllers::health_live))
         .route(
             "/api/v1/widget/config",
             get(controllers::get_widget_config_by_domain),
         )
         .route(
-            "/api/v1/widget/:project_id/config",
-            get(controllers::get_widget_config),
+            "/api/v1/meta/error-codes",
+            get(controllers::list_error_codes),
+        )
+        // Share links carry their own token instead of a login
+        .route(
+            "/api/v1/shared/:token/report",
+            get(controllers::get_shared_report),
         )
+        .route(
+            "/api/v1/shared/:token/video",
+            get(controllers::get_shared_video),
+        )
+        .layer(public_cors)
+        .merge(widget_routes(ready.clone(), config))
+        .nest(
+            "/api/v1",
+            authenticated_routes(ready.clone(), config).layer(api_cors),
+        )
+        .layer(DefaultBodyLimit::max(DEFAULT_BODY_LIMIT_BYTES))
+        .layer(TraceLayer::new_for_http().make_span_with(http_span))
+        // Outermost, so the trace span and every error response see the ID
+        .layer(middleware::from_fn(request_id_middleware))
+        .with_state(ready)
+}
+
+/// Span for one HTTP request, tagged with its request ID so its logs can be correlated
+fn http_span(request: &Request) -> Span {
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(|RequestId(id)| id.as_str())
+        .unwrap_or_default();
+    tracing::info_span!(
+        "request",
+        method = %request.method(),
+        uri = %request.uri(),
+        request_id,
+    )
+}
+
+/// Public widget routes for one project; browser origins must match the project's domain
+/// and submissions and status polls are rate limited per client
+fn widget_routes(ready: ReadyAppState, config: &Config) -> Router<ReadyAppState> {
+    // Only the multipart uploads may exceed the default limit; the handlers enforce the
+    // exact per-file caps with a friendlier error
+    let upload_limit =
+        DefaultBodyLimit::max(config.max_upload_mb * 1024 * 1024 + MULTIPART_OVERHEAD_BYTES);
+    let limited_routes = Router::new()
         .route(
             "/api/v1/widget/:project_id/submit",
             post(controllers::submit_feedback),
         )
         .route(
             "/api/v1/widget/:project_id/tickets/:id/upload",
-            post(controllers::upload_widget_video),
+            post(controllers::upload_widget_video).layer(upload_limit),
         )
-        .nest("/api/v1", authenticated_routes(ready.clone()))
-        .layer(DefaultBodyLimit::max(100 * 1024 * 1024))
-        .layer(TraceLayer::new_for_http())
-        .layer(cors)
-        .with_state(ready)
+        .route(
+            "/api/v1/widget/:project_id/tickets/:id/upload-url",
+            post(controllers::widget_upload_url),
+        )
+        .route(
+            "/api/v1/widget/:project_id/tickets/:id/confirm-upload",
+            post(controllers::confirm_widget_upload),
+        )
+        .route(
+            "/api/v1/widget/:project_id/tickets/:id/screenshot",
+            post(controllers::upload_widget_screenshot).layer(upload_limit),
+        )
+        .route(
+            "/api/v1/widget/:project_id/tickets/:id/status",
+            get(controllers::get_widget_ticket_status),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            ready.clone(),
+            widget_rate_limit_middleware,
+        ));
+
+    Router::new()
+        .route(
+            "/api/v1/widget/:project_id/config",
+            get(controllers::get_widget_config),
+        )
+        .merge(limited_routes)
+        .layer(middleware::from_fn_with_state(
+            ready,
+            widget_cors_middleware,
+        ))
 }
 
-fn authenticated_routes(ready: ReadyAppState) -> Router<ReadyAppState> {
+fn authenticated_routes(ready: ReadyAppState, config: &Config) -> Router<ReadyAppState> {
     Router::new()
+        .nest("/admin", admin_routes(ready.clone()))
         .nest("/auth", auth_routes(ready.clone()))
+        .nest("/me", me_routes(ready.clone()))
+        .nest("/my", my_routes(ready.clone()))
         .nest("/projects", project_routes(ready.clone()))
+        .nest("/sessions", session_routes(ready.clone(), config))
         .nest("/tickets", ticket_routes(ready.clone()))
 }
 
+/// Admin diagnostics routes (internal users only)
+fn admin_routes(ready: ReadyAppState) -> Router<ReadyAppState> {
+    Router::new()
+        .route("/oauth/config", get(controllers::get_oauth_config))
+        .route("/jobs", get(controllers::list_jobs))
+        .route("/jobs/dead-letter", get(controllers::list_dead_letter_jobs))
+        .route("/jobs/:id/retry", post(controllers::retry_job))
+        .route("/users/:id/quota", put(controllers::update_user_quota))
+        .route(
+            "/submitters/export",
+            get(controllers::export_submitter_data),
+        )
+        .route_layer(middleware::from_fn_with_state(ready, auth_middleware))
+}
+
 /// Authentication routes
 fn auth_routes(ready: ReadyAppState) -> Router<ReadyAppState> {
     let public_routes = Router::new()
@@ -60,16 +181,42 @@ fn auth_routes(ready: ReadyAppState) -> Router<ReadyAppState> {
         .route("/google", post(controllers::google_auth))
         .route("/google/start", get(controllers::google_start))
         .route("/google/callback", get(controllers::google_callback))
-        .route("/refresh", post(controllers::refresh_token));
+        .route("/refresh", post(controllers::refresh_token))
+        .route("/introspect", post(controllers::introspect_token));
 
     let protected_routes = Router::new()
-        .route("/me", get(controllers::get_current_user))
+        .route(
+            "/me",
+            get(controllers::get_current_user).delete(controllers::delete_account),
+        )
         .route("/onboarding", post(controllers::complete_onboarding))
         .route_layer(middleware::from_fn_with_state(ready, auth_middleware));
 
     public_routes.merge(protected_routes)
 }
 
+/// Per-user settings (internal users only)
+fn me_routes(ready: ReadyAppState) -> Router<ReadyAppState> {
+    Router::new()
+        .route(
+            "/question-template",
+            get(controllers::get_question_template),
+        )
+        .route(
+            "/question-template",
+            put(controllers::set_question_template),
+        )
+        .route_layer(middleware::from_fn_with_state(ready, auth_middleware))
+}
+
+/// Routes scoped to the current user's own submissions (customers included)
+fn my_routes(ready: ReadyAppState) -> Router<ReadyAppState> {
+    Router::new()
+        .route("/tickets", get(controllers::list_my_tickets))
+        .route("/tickets/:id/reopen", post(controllers::reopen_my_ticket))
+        .route_layer(middleware::from_fn_with_state(ready, auth_middleware))
+}
+
 /// Project routes (internal users only)
 fn project_routes(ready: ReadyAppState) -> Router<ReadyAppState> {
     Router::new()
@@ -78,6 +225,65 @@ fn project_routes(ready: ReadyAppState) -> Router<ReadyAppState> {
         .route("/:id", get(controllers::get_project))
         .route("/:id", put(controllers::update_project))
         .route("/:id", delete(controllers::delete_project))
+        .route("/:id/transfer", post(controllers::transfer_project))
+        .route(
+            "/:id/questions/reorder",
+            put(controllers::reorder_project_questions),
+        )
+        .route("/:id/members", get(controllers::list_project_members))
+        .route("/:id/members", post(controllers::add_project_member))
+        .route(
+            "/:id/members/:user_id",
+            delete(controllers::remove_project_member),
+        )
+        .route("/:id/unread", get(controllers::get_project_unread))
+        .route("/:id/analytics", get(controllers::get_project_analytics))
+        .route("/:id/issues", get(controllers::list_project_issues))
+        .route("/:id/issues/top", get(controllers::get_project_top_issues))
+        .route(
+            "/:id/tickets/export.csv",
+            get(controllers::export_project_tickets_csv),
+        )
+        .route("/:id/api-keys", get(controllers::list_api_keys))
+        .route("/:id/api-keys", post(controllers::create_api_key))
+        .route("/:id/api-keys/:key_id", delete(controllers::revoke_api_key))
+        .route("/:id/webhooks", get(controllers::list_webhooks))
+        .route("/:id/webhooks", post(controllers::create_webhook))
+        .route(
+            "/:id/webhooks/:webhook_id",
+            put(controllers::update_webhook),
+        )
+        .route(
+            "/:id/webhooks/:webhook_id",
+            delete(controllers::delete_webhook),
+        )
+        .route(
+            "/:id/webhooks/:webhook_id/test",
+            post(controllers::test_webhook),
+        )
+        .route_layer(middleware::from_fn_with_state(ready, auth_middleware))
+}
+
+/// Legacy session routes: management for internal users, share-link submission for any
+/// signed-in user
+fn session_routes(ready: ReadyAppState, config: &Config) -> Router<ReadyAppState> {
+    let upload_limit =
+        DefaultBodyLimit::max(config.max_upload_mb * 1024 * 1024 + MULTIPART_OVERHEAD_BYTES);
+    Router::new()
+        .route("/", get(controllers::list_sessions))
+        .route("/", post(controllers::create_session))
+        .route("/:id", get(controllers::get_session))
+        .route("/:id", put(controllers::update_session))
+        .route("/:id", delete(controllers::delete_session))
+        .route("/join/:token", get(controllers::get_shared_session))
+        .route(
+            "/join/:token/tickets",
+            post(controllers::submit_session_ticket),
+        )
+        .route(
+            "/join/:token/tickets/:id/upload",
+            post(controllers::upload_session_video).layer(upload_limit),
+        )
         .route_layer(middleware::from_fn_with_state(ready, auth_middleware))
 }
 
@@ -85,17 +291,47 @@ fn project_routes(ready: ReadyAppState) -> Router<ReadyAppState> {
 fn ticket_routes(ready: ReadyAppState) -> Router<ReadyAppState> {
     Router::new()
         .route("/overview", get(controllers::get_overview))
-        .route("/", get(controllers::list_tickets))
+        .route(
+            "/",
+            get(controllers::list_tickets).post(controllers::create_ticket),
+        )
+        .route("/read-all", post(controllers::mark_all_tickets_read))
         .route("/:id", get(controllers::get_ticket))
         .route("/:id", put(controllers::update_ticket))
         .route("/:id/close", post(controllers::close_ticket))
         .route("/:id/reopen", post(controllers::reopen_ticket))
+        .route("/:id/merge", post(controllers::merge_ticket))
+        .route("/:id/export", get(controllers::export_ticket))
+        .route("/:id/share", post(controllers::share_ticket))
+        .route(
+            "/:id/share/:share_id",
+            delete(controllers::revoke_ticket_share),
+        )
+        .route("/:id/reanalyze", post(controllers::reanalyze_ticket))
+        .route("/:id/labels", post(controllers::add_ticket_label))
+        .route(
+            "/:id/labels/:label",
+            delete(controllers::remove_ticket_label),
+        )
         .route("/:id", delete(controllers::delete_ticket))
         .route("/:id/video", get(controllers::get_video))
-        .route("/:id/report", get(controllers::get_report))
+        .route("/:id/screenshot", get(controllers::get_screenshot))
+        .route(
+            "/:id/report",
+            get(controllers::get_report).put(controllers::update_report),
+        )
+        .route("/:id/report.pdf", get(controllers::get_report_pdf))
+        .route("/:id/report/raw", get(controllers::get_raw_report))
+        .route("/:id/report/history", get(controllers::get_report_history))
+        .route("/:id/issues/:issue_id", put(controllers::update_issue))
+        .route(
+            "/:id/issues/:issue_id/export/jira",
+            post(controllers::export_issue_to_jira),
+        )
         // Chat messages
         .route("/:id/messages", get(controllers::get_messages))
         .route("/:id/messages", post(controllers::send_message))
+        .route("/:id/messages/read", post(controllers::mark_messages_read))
         .route(
             "/:ticket_id/messages/:message_id",
             put(controllers::edit_message),
@@ -105,4 +341,122 @@ fn ticket_routes(ready: ReadyAppState) -> Router<ReadyAppState> {
             delete(controllers::delete_message),
         )
         .route_layer(middleware::from_fn_with_state(ready, auth_middleware))
+        // Authenticates itself: browsers cannot set headers on WebSocket upgrades
+        .route("/:id/ws", get(controllers::chat_ws))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{seed_project, seed_ticket, seed_user, test_app};
+    use axum::body::Body;
+    use axum::http::{header, StatusCode};
+    use sqlx::PgPool;
+    use tower::ServiceExt;
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn only_upload_routes_accept_large_bodies(pool: PgPool) {
+        let owner_id = seed_user(&pool, "owner@example.com", "internal").await.id;
+        let project_id = seed_project(&pool, owner_id).await;
+        let ticket_id = seed_ticket(&pool, project_id, owner_id, "pending").await;
+
+        let config = Config {
+            max_upload_mb: 3,
+            ..Config::test_default()
+        };
+        let (app, _) = test_app(pool, &config).await;
+
+        let padding = "x".repeat(2 * 1024 * 1024);
+        let login = Request::builder()
+            .method("POST")
+            .uri("/api/v1/auth/login")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::json!({ "email": "owner@example.com", "password": padding })
+                    .to_string(),
+            ))
+            .unwrap();
+        let response = app.clone().oneshot(login).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let upload = |video: Vec<u8>| {
+            let mut body =
+                b"--X\r\nContent-Disposition: form-data; name=\"video\"; filename=\"a.webm\"\r\n\r\n"
+                    .to_vec();
+            body.extend_from_slice(&video);
+            body.extend_from_slice(b"\r\n--X--\r\n");
+            Request::builder()
+                .method("POST")
+                .uri(format!(
+                    "/api/v1/widget/{}/tickets/{}/upload",
+                    project_id, ticket_id
+                ))
+                .header(header::CONTENT_TYPE, "multipart/form-data; boundary=X")
+                .body(Body::from(body))
+                .unwrap()
+        };
+        let webm = |len: usize| {
+            let mut video = vec![0u8; len];
+            video[..4].copy_from_slice(&[0x1A, 0x45, 0xDF, 0xA3]);
+            video
+        };
+
+        let response = app
+            .clone()
+            .oneshot(upload(webm(2 * 1024 * 1024)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Past the cap the multipart read fails in the handler
+        let response = app.oneshot(upload(webm(4 * 1024 * 1024))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    fn preflight(origin: &str) -> Request<Body> {
+        Request::builder()
+            .method("OPTIONS")
+            .uri("/api/v1/projects")
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "GET")
+            .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "authorization")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn api_cors_reflects_only_allowed_origins() {
+        let config = Config {
+            cors_allowed_origins: vec![
+                "http://localhost:8080".to_string(),
+                "https://admin.example.com".to_string(),
+            ],
+            ..Config::test_default()
+        };
+        let app = create_router(ReadyAppState::new(), &config);
+
+        for origin in ["http://localhost:8080", "https://admin.example.com"] {
+            let response = app.clone().oneshot(preflight(origin)).await.unwrap();
+            assert_eq!(
+                response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+                origin
+            );
+            let allowed_headers = response.headers()[header::ACCESS_CONTROL_ALLOW_HEADERS]
+                .to_str()
+                .unwrap()
+                .to_string();
+            assert!(allowed_headers.contains("authorization"));
+            assert_ne!(allowed_headers, "*");
+        }
+
+        let response = app
+            .oneshot(preflight("https://evil.example.net"))
+            .await
+            .unwrap();
+        assert!(response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .is_none());
+    }
 }
@@ -0,0 +1,235 @@
+//! API key service - per-project keys that authenticate widget/server submissions
+
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::{Project, ProjectApiKey};
+use crate::services::AuthService;
+
+/// Marks Ortrace project keys so they are recognizable in configs and secret scanners
+const KEY_PREFIX: &str = "ork_";
+/// Characters of the key kept in clear (`ork_` plus four random characters)
+const DISPLAY_PREFIX_CHARS: usize = 8;
+
+/// API key service for generating, revoking and checking project keys
+pub struct ApiKeyService {
+    db: PgPool,
+}
+
+impl ApiKeyService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Create a key for a project; returns the stored record and the plaintext key,
+    /// which is not kept and cannot be shown again
+    pub async fn create(&self, project_id: Uuid, label: &str) -> Result<(ProjectApiKey, String)> {
+        let key = generate_key();
+        let api_key = sqlx::query_as::<_, ProjectApiKey>(
+            r#"
+            INSERT INTO project_api_keys (project_id, label, key_prefix, key_hash)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+        )
+        .bind(project_id)
+        .bind(label.trim())
+        .bind(&key[..DISPLAY_PREFIX_CHARS])
+        .bind(hash_key(&key))
+        .fetch_one(&self.db)
+        .await?;
+        Ok((api_key, key))
+    }
+
+    /// List a project's keys, revoked ones included
+    pub async fn list(&self, project_id: Uuid) -> Result<Vec<ProjectApiKey>> {
+        let keys = sqlx::query_as::<_, ProjectApiKey>(
+            "SELECT * FROM project_api_keys WHERE project_id = $1 ORDER BY created_at ASC",
+        )
+        .bind(project_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(keys)
+    }
+
+    /// Revoke a key; revoking an already revoked key keeps its original revocation time
+    pub async fn revoke(&self, id: Uuid, project_id: Uuid) -> Result<ProjectApiKey> {
+        sqlx::query_as::<_, ProjectApiKey>(
+            r#"
+            UPDATE project_api_keys SET revoked_at = COALESCE(revoked_at, NOW())
+            WHERE id = $1 AND project_id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(project_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::not_found("API key not found"))
+    }
+
+    /// Whether `key` is an unrevoked key of the project
+    pub async fn verify(&self, project_id: Uuid, key: &str) -> Result<bool> {
+        let valid: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM project_api_keys
+                WHERE project_id = $1 AND key_hash = $2 AND revoked_at IS NULL
+            )
+            "#,
+        )
+        .bind(project_id)
+        .bind(hash_key(key.trim()))
+        .fetch_one(&self.db)
+        .await?;
+        Ok(valid)
+    }
+
+    /// Check a widget submission's key. A presented key must be valid; a missing key is only
+    /// accepted while the project neither requires auth nor has any active keys.
+    pub async fn authorize_submission(&self, project: &Project, key: Option<&str>) -> Result<()> {
+        if let Some(key) = key {
+            if self.verify(project.id, key).await? {
+                return Ok(());
+            }
+            return Err(AppError::Unauthorized);
+        }
+
+        if project.require_auth() || self.has_active_keys(project.id).await? {
+            return Err(AppError::Unauthorized);
+        }
+        Ok(())
+    }
+
+    async fn has_active_keys(&self, project_id: Uuid) -> Result<bool> {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM project_api_keys WHERE project_id = $1 AND revoked_at IS NULL)",
+        )
+        .bind(project_id)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(exists)
+    }
+}
+
+fn generate_key() -> String {
+    format!("{}{}", KEY_PREFIX, AuthService::generate_share_token())
+}
+
+/// Keys are 256-bit random values, so a fast unsalted hash is enough at rest
+pub(crate) fn hash_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{seed_project_with, seed_user};
+
+    #[test]
+    fn generated_keys_are_prefixed_and_unique() {
+        let a = generate_key();
+        let b = generate_key();
+        assert!(a.starts_with(KEY_PREFIX));
+        assert_ne!(a, b);
+        assert_eq!(hash_key(&a), hash_key(&a));
+        assert_ne!(hash_key(&a), hash_key(&b));
+        assert_eq!(hash_key(&a).len(), 64);
+    }
+
+    /// Project with the given settings; returns it loaded from the database
+    async fn owned_project(pool: &PgPool, settings: serde_json::Value) -> Project {
+        let owner_id = seed_user(pool, "owner@example.com", "internal").await.id;
+        let project_id = seed_project_with(pool, owner_id, settings).await;
+        sqlx::query_as::<_, Project>("SELECT * FROM projects WHERE id = $1")
+            .bind(project_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn keys_verify_until_revoked(pool: PgPool) {
+        let project = owned_project(&pool, serde_json::json!({})).await;
+        let service = ApiKeyService::new(pool.clone());
+
+        let (api_key, key) = service.create(project.id, " server ").await.unwrap();
+        assert_eq!(api_key.label, "server");
+        assert!(key.starts_with(&api_key.key_prefix));
+        let stored: String =
+            sqlx::query_scalar("SELECT key_hash FROM project_api_keys WHERE id = $1")
+                .bind(api_key.id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(stored, hash_key(&key));
+
+        assert!(service.verify(project.id, &key).await.unwrap());
+        assert!(!service.verify(project.id, "ork_wrong").await.unwrap());
+        assert!(!service.verify(Uuid::new_v4(), &key).await.unwrap());
+
+        let revoked = service.revoke(api_key.id, project.id).await.unwrap();
+        assert!(!revoked.is_active());
+        assert!(!service.verify(project.id, &key).await.unwrap());
+        // Revoking again is harmless
+        let again = service.revoke(api_key.id, project.id).await.unwrap();
+        assert_eq!(again.revoked_at, revoked.revoked_at);
+
+        assert!(matches!(
+            service.revoke(api_key.id, Uuid::new_v4()).await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn submissions_need_a_key_once_keys_exist(pool: PgPool) {
+        let project = owned_project(&pool, serde_json::json!({})).await;
+        let service = ApiKeyService::new(pool.clone());
+
+        // Open project: anonymous submissions pass, bad keys do not
+        service.authorize_submission(&project, None).await.unwrap();
+        assert!(service
+            .authorize_submission(&project, Some("ork_wrong"))
+            .await
+            .is_err());
+
+        let (api_key, key) = service.create(project.id, "widget").await.unwrap();
+        assert!(matches!(
+            service.authorize_submission(&project, None).await,
+            Err(AppError::Unauthorized)
+        ));
+        service
+            .authorize_submission(&project, Some(&key))
+            .await
+            .unwrap();
+
+        // Revoking the last key reopens the project
+        service.revoke(api_key.id, project.id).await.unwrap();
+        service.authorize_submission(&project, None).await.unwrap();
+        assert!(service
+            .authorize_submission(&project, Some(&key))
+            .await
+            .is_err());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn require_auth_projects_reject_keyless_submissions(pool: PgPool) {
+        let project = owned_project(&pool, serde_json::json!({ "require_auth": true })).await;
+        let service = ApiKeyService::new(pool.clone());
+
+        assert!(matches!(
+            service.authorize_submission(&project, None).await,
+            Err(AppError::Unauthorized)
+        ));
+        let (_, key) = service.create(project.id, "").await.unwrap();
+        service
+            .authorize_submission(&project, Some(&key))
+            .await
+            .unwrap();
+    }
+}
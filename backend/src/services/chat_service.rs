@@ -1,12 +1,16 @@
 //! Chat service - handles chat messages between team and customers
 
+use base64::Engine;
 use chrono::{DateTime, Utc};
 use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::sync::broadcast;
 use uuid::Uuid;
 
 use crate::dto::{ChatMessageResponse, SendMessageRequest};
 use crate::error::{AppError, Result};
-use crate::models::UserRole;
+use crate::models::{User, UserRole};
 
 /// Database model for chat messages
 #[derive(Debug, sqlx::FromRow)]
@@ -21,23 +25,122 @@ struct ChatMessageRow {
     // Joined fields
     sender_name: Option<String>,
     sender_user_role: String,
+    seen_by_other: bool,
+}
+
+impl ChatMessageRow {
+    fn into_response(self, current_user_id: Uuid) -> ChatMessageResponse {
+        let sender_type = if self.sender_role.as_deref() == Some("system") {
+            "system".to_string()
+        } else if self.sender_user_role == "internal" {
+            "team".to_string()
+        } else {
+            "user".to_string()
+        };
+
+        ChatMessageResponse {
+            id: self.id,
+            recording_id: self.recording_id,
+            sender_type,
+            sender_name: self.sender_name.unwrap_or_else(|| "Unknown".to_string()),
+            sender_role: self.sender_role,
+            message: self.message,
+            sent_at: self.created_at,
+            edited_at: self.edited_at,
+            is_own: self.sender_id == current_user_id,
+            seen_by_other: self.seen_by_other,
+        }
+    }
+}
+
+/// Position in a newest-first message thread, sent to clients as an opaque token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl MessageCursor {
+    /// URL-safe base64 of `<created_at micros>:<id>`
+    pub fn encode(&self) -> String {
+        let raw = format!("{}:{}", self.created_at.timestamp_micros(), self.id);
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    pub fn decode(token: &str) -> Option<Self> {
+        let raw = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(token.trim())
+            .ok()?;
+        let raw = String::from_utf8(raw).ok()?;
+        let (micros, id) = raw.split_once(':')?;
+        Some(Self {
+            created_at: DateTime::from_timestamp_micros(micros.parse().ok()?)?,
+            id: id.parse().ok()?,
+        })
+    }
+}
+
+/// Messages buffered per ticket for slow live subscribers before they lag
+const LIVE_CHANNEL_CAPACITY: usize = 64;
+
+/// A message published to a ticket's live subscribers
+#[derive(Debug, Clone)]
+pub struct ChatEvent {
+    pub sender_id: Uuid,
+    pub message: ChatMessageResponse,
+}
+
+impl ChatEvent {
+    /// The message as `user_id` should see it
+    pub fn message_for(&self, user_id: Uuid) -> ChatMessageResponse {
+        ChatMessageResponse {
+            is_own: self.sender_id == user_id,
+            ..self.message.clone()
+        }
+    }
 }
 
 /// Chat service
 pub struct ChatService {
     db: PgPool,
+    /// Live channel per ticket (recording) with at least one subscriber
+    channels: Mutex<HashMap<Uuid, broadcast::Sender<ChatEvent>>>,
 }
 
 impl ChatService {
     pub fn new(db: PgPool) -> Self {
-        Self { db }
+        Self {
+            db,
+            channels: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Receive messages sent to a ticket from now on (this instance only)
+    pub fn subscribe(&self, recording_id: Uuid) -> broadcast::Receiver<ChatEvent> {
+        let mut channels = self.channels.lock().unwrap_or_else(|e| e.into_inner());
+        // Drop channels whose subscribers have all gone
+        channels.retain(|_, sender| sender.receiver_count() > 0);
+        channels
+            .entry(recording_id)
+            .or_insert_with(|| broadcast::channel(LIVE_CHANNEL_CAPACITY).0)
+            .subscribe()
     }
 
-    /// Get all messages for a ticket (recording)
+    fn publish(&self, recording_id: Uuid, event: ChatEvent) {
+        let channels = self.channels.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(sender) = channels.get(&recording_id) {
+            // Errors only mean nobody is listening
+            let _ = sender.send(event);
+        }
+    }
+
+    /// Get all messages for a ticket (recording). Copies merged in from another ticket are
+    /// only shown to the team.
     pub async fn get_messages(
         &self,
         recording_id: Uuid,
         current_user_id: Uuid,
+        user_role: UserRole,
     ) -> Result<Vec<ChatMessageResponse>> {
         let rows = sqlx::query_as::<_, ChatMessageRow>(
             r#"
@@ -50,43 +153,99 @@ impl ChatService {
                 cm.created_at,
                 cm.edited_at,
                 u.name as sender_name,
-                u.role as sender_user_role
+                u.role as sender_user_role,
+                EXISTS (
+                    SELECT 1 FROM chat_reads cr
+                    JOIN chat_messages lm ON lm.id = cr.last_read_message_id
+                    WHERE cr.recording_id = cm.recording_id
+                      AND cr.user_id <> cm.sender_id
+                      AND (lm.created_at, lm.id) >= (cm.created_at, cm.id)
+                ) as seen_by_other
             FROM chat_messages cm
             JOIN users u ON cm.sender_id = u.id
             WHERE cm.recording_id = $1
+              AND (cm.merged_from IS NULL OR $2)
             ORDER BY cm.created_at ASC
             "#,
         )
         .bind(recording_id)
+        .bind(user_role == UserRole::Internal)
         .fetch_all(&self.db)
         .await?;
 
         let messages = rows
             .into_iter()
-            .map(|row| {
-                let sender_type = if row.sender_role.as_deref() == Some("system") {
-                    "system".to_string()
-                } else if row.sender_user_role == "internal" {
-                    "team".to_string()
-                } else {
-                    "user".to_string()
-                };
-
-                ChatMessageResponse {
+            .map(|row| row.into_response(current_user_id))
+            .collect();
+
+        Ok(messages)
+    }
+
+    /// Get up to `limit` messages older than `before` (or the newest ones), newest first.
+    /// Returns the cursor for the next, older page when there is one.
+    pub async fn get_messages_paged(
+        &self,
+        recording_id: Uuid,
+        current_user_id: Uuid,
+        user_role: UserRole,
+        before: Option<MessageCursor>,
+        limit: i64,
+    ) -> Result<(Vec<ChatMessageResponse>, Option<String>)> {
+        let mut rows = sqlx::query_as::<_, ChatMessageRow>(
+            r#"
+            SELECT 
+                cm.id,
+                cm.recording_id,
+                cm.sender_id,
+                cm.sender_role,
+                cm.message,
+                cm.created_at,
+                cm.edited_at,
+                u.name as sender_name,
+                u.role as sender_user_role,
+                EXISTS (
+                    SELECT 1 FROM chat_reads cr
+                    JOIN chat_messages lm ON lm.id = cr.last_read_message_id
+                    WHERE cr.recording_id = cm.recording_id
+                      AND cr.user_id <> cm.sender_id
+                      AND (lm.created_at, lm.id) >= (cm.created_at, cm.id)
+                ) as seen_by_other
+            FROM chat_messages cm
+            JOIN users u ON cm.sender_id = u.id
+            WHERE cm.recording_id = $1
+              AND ($2::timestamptz IS NULL OR (cm.created_at, cm.id) < ($2, $3))
+              AND (cm.merged_from IS NULL OR $5)
+            ORDER BY cm.created_at DESC, cm.id DESC
+            LIMIT $4
+            "#,
+        )
+        .bind(recording_id)
+        .bind(before.map(|c| c.created_at))
+        .bind(before.map(|c| c.id))
+        // One extra row tells whether an older page exists
+        .bind(limit + 1)
+        .bind(user_role == UserRole::Internal)
+        .fetch_all(&self.db)
+        .await?;
+
+        let next_cursor = if rows.len() as i64 > limit {
+            rows.truncate(limit as usize);
+            rows.last().map(|row| {
+                MessageCursor {
+                    created_at: row.created_at,
                     id: row.id,
-                    recording_id: row.recording_id,
-                    sender_type,
-                    sender_name: row.sender_name.unwrap_or_else(|| "Unknown".to_string()),
-                    sender_role: row.sender_role,
-                    message: row.message,
-                    sent_at: row.created_at,
-                    edited_at: row.edited_at,
-                    is_own: row.sender_id == current_user_id,
                 }
+                .encode()
             })
+        } else {
+            None
+        };
+        let messages = rows
+            .into_iter()
+            .map(|row| row.into_response(current_user_id))
             .collect();
 
-        Ok(messages)
+        Ok((messages, next_cursor))
     }
 
     /// Send a new message
@@ -125,7 +284,7 @@ impl ChatService {
             "user".to_string()
         };
 
-        Ok(ChatMessageResponse {
+        let message = ChatMessageResponse {
             id: row.0,
             recording_id,
             sender_type,
@@ -135,33 +294,151 @@ impl ChatService {
             sent_at: row.1,
             edited_at: None,
             is_own: true,
-        })
+            seen_by_other: false,
+        };
+        self.publish(
+            recording_id,
+            ChatEvent {
+                sender_id,
+                message: message.clone(),
+            },
+        );
+
+        Ok(message)
     }
 
-    /// Create a system message (from Ortrace)
-    #[allow(dead_code)]
+    /// Create a system message (from Ortrace) and push it to live subscribers
     pub async fn create_system_message(
         &self,
-        db: &PgPool,
         recording_id: Uuid,
-        system_user_id: Uuid,
         message: &str,
-    ) -> Result<()> {
-        sqlx::query(
+    ) -> Result<ChatMessageResponse> {
+        let row = sqlx::query_as::<_, (Uuid, DateTime<Utc>)>(
             r#"
             INSERT INTO chat_messages (recording_id, sender_id, sender_role, message)
             VALUES ($1, $2, 'system', $3)
+            RETURNING id, created_at
             "#,
         )
         .bind(recording_id)
-        .bind(system_user_id)
+        .bind(User::SYSTEM_ID)
         .bind(message)
-        .execute(db)
+        .fetch_one(&self.db)
+        .await?;
+
+        let message = ChatMessageResponse {
+            id: row.0,
+            recording_id,
+            sender_type: "system".to_string(),
+            sender_name: "Ortrace".to_string(),
+            sender_role: Some("system".to_string()),
+            message: message.to_string(),
+            sent_at: row.1,
+            edited_at: None,
+            is_own: false,
+            seen_by_other: false,
+        };
+        self.publish(
+            recording_id,
+            ChatEvent {
+                sender_id: User::SYSTEM_ID,
+                message: message.clone(),
+            },
+        );
+
+        Ok(message)
+    }
+
+    /// Mark a user's chat as read up to `message_id`, or the newest message when `None`.
+    /// The marker never moves backwards.
+    pub async fn mark_read(
+        &self,
+        recording_id: Uuid,
+        user_id: Uuid,
+        message_id: Option<Uuid>,
+    ) -> Result<()> {
+        let message_id = match message_id {
+            Some(id) => {
+                let exists: bool = sqlx::query_scalar(
+                    "SELECT EXISTS(SELECT 1 FROM chat_messages WHERE id = $1 AND recording_id = $2)",
+                )
+                .bind(id)
+                .bind(recording_id)
+                .fetch_one(&self.db)
+                .await?;
+                if !exists {
+                    return Err(AppError::not_found("Message not found"));
+                }
+                id
+            }
+            None => {
+                let newest: Option<Uuid> = sqlx::query_scalar(
+                    r#"
+                    SELECT id FROM chat_messages WHERE recording_id = $1
+                    ORDER BY created_at DESC, id DESC LIMIT 1
+                    "#,
+                )
+                .bind(recording_id)
+                .fetch_optional(&self.db)
+                .await?;
+                match newest {
+                    Some(id) => id,
+                    // Nothing to read yet
+                    None => return Ok(()),
+                }
+            }
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO chat_reads (recording_id, user_id, last_read_message_id, updated_at)
+            VALUES ($1, $2, $3, NOW())
+            ON CONFLICT (recording_id, user_id) DO UPDATE SET
+                last_read_message_id = EXCLUDED.last_read_message_id,
+                updated_at = EXCLUDED.updated_at
+            WHERE NOT EXISTS (
+                SELECT 1 FROM chat_messages old, chat_messages new
+                WHERE old.id = chat_reads.last_read_message_id
+                  AND new.id = EXCLUDED.last_read_message_id
+                  AND (old.created_at, old.id) >= (new.created_at, new.id)
+            )
+            "#,
+        )
+        .bind(recording_id)
+        .bind(user_id)
+        .bind(message_id)
+        .execute(&self.db)
         .await?;
 
         Ok(())
     }
 
+    /// Messages from others the user has not read yet
+    pub async fn unread_count(
+        &self,
+        recording_id: Uuid,
+        user_id: Uuid,
+        user_role: UserRole,
+    ) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM chat_messages cm
+            LEFT JOIN chat_reads cr ON cr.recording_id = cm.recording_id AND cr.user_id = $2
+            LEFT JOIN chat_messages lm ON lm.id = cr.last_read_message_id
+            WHERE cm.recording_id = $1
+              AND cm.sender_id <> $2
+              AND (lm.id IS NULL OR (cm.created_at, cm.id) > (lm.created_at, lm.id))
+              AND (cm.merged_from IS NULL OR $3)
+            "#,
+        )
+        .bind(recording_id)
+        .bind(user_id)
+        .bind(user_role == UserRole::Internal)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(count)
+    }
+
     /// Edit a message (only own messages)
     pub async fn edit_message(
         &self,
@@ -217,9 +494,11 @@ impl ChatService {
                 r#"
                 SELECT EXISTS(
                     SELECT 1 FROM recordings r
-                    LEFT JOIN projects p ON r.project_id = p.id
                     LEFT JOIN sessions s ON r.session_id = s.id
-                    WHERE r.id = $1 AND (p.owner_id = $2 OR s.owner_id = $2)
+                    WHERE r.id = $1 AND (
+                        r.project_id IN (SELECT accessible_project_ids($2))
+                        OR s.owner_id = $2
+                    )
                 )
                 "#,
             )
@@ -249,3 +528,182 @@ impl ChatService {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{seed_project, seed_ticket, seed_user};
+
+    #[test]
+    fn cursor_round_trips_and_rejects_garbage() {
+        let cursor = MessageCursor {
+            created_at: DateTime::from_timestamp_micros(1_760_000_000_123_456).unwrap(),
+            id: Uuid::new_v4(),
+        };
+        assert_eq!(MessageCursor::decode(&cursor.encode()), Some(cursor));
+        assert_eq!(MessageCursor::decode("not a cursor"), None);
+        assert_eq!(MessageCursor::decode(""), None);
+    }
+
+    /// Ticket with seven messages, two of them sharing a timestamp; returns the user,
+    /// ticket and message ids oldest first
+    async fn seed_thread(pool: &PgPool) -> (Uuid, Uuid, Vec<Uuid>) {
+        let user_id = seed_user(pool, "owner@example.com", "internal").await.id;
+        let project_id = seed_project(pool, user_id).await;
+        let ticket_id = seed_ticket(pool, project_id, user_id, "completed").await;
+
+        let mut ids = Vec::new();
+        for minutes in [1, 2, 3, 3, 4, 5, 6] {
+            let id = sqlx::query_scalar::<_, Uuid>(
+                r#"
+                INSERT INTO chat_messages (recording_id, sender_id, message, created_at)
+                VALUES ($1, $2, 'hello', TIMESTAMPTZ '2026-01-01' + make_interval(mins => $3))
+                RETURNING id
+                "#,
+            )
+            .bind(ticket_id)
+            .bind(user_id)
+            .bind(minutes)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+            ids.push(id);
+        }
+        // Same-timestamp messages are ordered by id
+        ids[2..4].sort();
+        (user_id, ticket_id, ids)
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn pages_are_contiguous_and_do_not_overlap(pool: PgPool) {
+        let (user_id, ticket_id, ids) = seed_thread(&pool).await;
+        let service = ChatService::new(pool);
+
+        let mut seen = Vec::new();
+        let mut before = None;
+        let mut pages = 0;
+        loop {
+            let (messages, next_cursor) = service
+                .get_messages_paged(ticket_id, user_id, UserRole::Internal, before, 3)
+                .await
+                .unwrap();
+            assert!(messages.len() <= 3);
+            seen.extend(messages.iter().map(|m| m.id));
+            pages += 1;
+            match next_cursor {
+                Some(cursor) => before = Some(MessageCursor::decode(&cursor).unwrap()),
+                None => break,
+            }
+        }
+
+        assert_eq!(pages, 3);
+        let newest_first: Vec<Uuid> = ids.iter().rev().copied().collect();
+        assert_eq!(seen, newest_first);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn exact_last_page_has_no_cursor(pool: PgPool) {
+        let (user_id, ticket_id, ids) = seed_thread(&pool).await;
+        let service = ChatService::new(pool);
+
+        let (messages, next_cursor) = service
+            .get_messages_paged(
+                ticket_id,
+                user_id,
+                UserRole::Internal,
+                None,
+                ids.len() as i64,
+            )
+            .await
+            .unwrap();
+        assert_eq!(messages.len(), ids.len());
+        assert!(next_cursor.is_none());
+        assert!(messages.iter().all(|m| m.is_own));
+
+        // The unpaged thread is unchanged: everything, oldest first
+        let all = service
+            .get_messages(ticket_id, user_id, UserRole::Internal)
+            .await
+            .unwrap();
+        assert_eq!(all.first().map(|m| m.id), Some(ids[0]));
+        assert_eq!(all.len(), ids.len());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn marking_read_lowers_unread_count(pool: PgPool) {
+        let (owner_id, ticket_id, ids) = seed_thread(&pool).await;
+        let reader_id = seed_user(&pool, "customer@example.com", "customer")
+            .await
+            .id;
+        let service = ChatService::new(pool);
+
+        assert_eq!(
+            service
+                .unread_count(ticket_id, reader_id, UserRole::Customer)
+                .await
+                .unwrap(),
+            7
+        );
+        // Own messages are never unread
+        assert_eq!(
+            service
+                .unread_count(ticket_id, owner_id, UserRole::Internal)
+                .await
+                .unwrap(),
+            0
+        );
+
+        service
+            .mark_read(ticket_id, reader_id, Some(ids[3]))
+            .await
+            .unwrap();
+        assert_eq!(
+            service
+                .unread_count(ticket_id, reader_id, UserRole::Customer)
+                .await
+                .unwrap(),
+            3
+        );
+
+        let seen: Vec<bool> = service
+            .get_messages(ticket_id, owner_id, UserRole::Internal)
+            .await
+            .unwrap()
+            .iter()
+            .map(|m| m.seen_by_other)
+            .collect();
+        assert_eq!(seen, vec![true, true, true, true, false, false, false]);
+
+        // Marking an older message does not move the marker back
+        service
+            .mark_read(ticket_id, reader_id, Some(ids[1]))
+            .await
+            .unwrap();
+        assert_eq!(
+            service
+                .unread_count(ticket_id, reader_id, UserRole::Customer)
+                .await
+                .unwrap(),
+            3
+        );
+
+        service.mark_read(ticket_id, reader_id, None).await.unwrap();
+        assert_eq!(
+            service
+                .unread_count(ticket_id, reader_id, UserRole::Customer)
+                .await
+                .unwrap(),
+            0
+        );
+
+        assert!(matches!(
+            service
+                .mark_read(ticket_id, reader_id, Some(Uuid::new_v4()))
+                .await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,275 @@
+//! Email service - tells widget submitters when their ticket is resolved
+//!
+//! Sending happens in the background; an unreachable SMTP server is logged and never
+//! fails the ticket operation.
+
+use anyhow::{Context, Result};
+use lettre::message::{header::ContentType, Mailbox};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::transport::smtp::client::{Tls, TlsParameters};
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use crate::config::{Config, EmailConfig};
+use crate::models::FeedbackTicket;
+
+/// Longest task description quoted back to the submitter
+const SNIPPET_CHARS: usize = 300;
+
+/// Email service for submitter notifications; a no-op when SMTP is not configured
+pub struct EmailService {
+    mailer: Option<Mailer>,
+    frontend_url: String,
+}
+
+struct Mailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    link_report: bool,
+}
+
+impl EmailService {
+    pub fn new(config: &Config) -> Result<Self> {
+        let mailer = config.email.as_ref().map(Mailer::new).transpose()?;
+        if mailer.is_none() {
+            tracing::info!("SMTP not configured; submitter emails are disabled");
+        }
+        Ok(Self {
+            mailer,
+            frontend_url: config.frontend_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    /// Email the submitter that their ticket was resolved, in the background.
+    /// Returns whether an email was queued (false without SMTP or a submitter address).
+    pub fn ticket_resolved(&self, ticket: &FeedbackTicket) -> bool {
+        let Some(mailer) = &self.mailer else {
+            return false;
+        };
+        let report_link = mailer
+            .link_report
+            .then(|| format!("{}/tickets/{}", self.frontend_url, ticket.id));
+        let Some(message) = resolved_email(&mailer.from, ticket, report_link.as_deref()) else {
+            return false;
+        };
+
+        let transport = mailer.transport.clone();
+        let ticket_id = ticket.id;
+        tokio::spawn(async move {
+            if let Err(e) = transport.send(message).await {
+                tracing::warn!(%ticket_id, "Failed to send resolution email: {}", e);
+            }
+        });
+        true
+    }
+}
+
+impl Mailer {
+    fn new(config: &EmailConfig) -> Result<Self> {
+        let from: Mailbox = config
+            .from
+            .parse()
+            .with_context(|| format!("Invalid EMAIL_FROM address: {}", config.from))?;
+
+        let credentials = config
+            .smtp_user
+            .clone()
+            .map(|user| Credentials::new(user, config.smtp_pass.clone().unwrap_or_default()));
+        let tls_parameters = TlsParameters::new(config.smtp_host.clone())
+            .context("Failed to build SMTP TLS parameters")?;
+        let tls = if config.smtp_port == 465 {
+            Tls::Wrapper(tls_parameters)
+        } else if credentials.is_some() {
+            // Never send credentials over a plaintext connection
+            Tls::Required(tls_parameters)
+        } else {
+            // Local relays (e.g. MailHog) often lack STARTTLS
+            Tls::Opportunistic(tls_parameters)
+        };
+
+        let mut builder =
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.smtp_host)
+                .port(config.smtp_port)
+                .tls(tls);
+        if let Some(credentials) = credentials {
+            builder = builder.credentials(credentials);
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from,
+            link_report: config.link_report,
+        })
+    }
+}
+
+/// Resolution email for the ticket's submitter; `None` without a usable address
+fn resolved_email(
+    from: &Mailbox,
+    ticket: &FeedbackTicket,
+    report_link: Option<&str>,
+) -> Option<Message> {
+    let to: Mailbox = ticket.submitter_email.as_deref()?.trim().parse().ok()?;
+
+    let greeting = match ticket.submitter_name.as_deref().map(str::trim) {
+        Some(name) if !name.is_empty() => format!("Hi {},", name),
+        _ => "Hi,".to_string(),
+    };
+    let mut body = vec![
+        greeting,
+        String::new(),
+        "Thanks for taking the time to send us feedback. The issue you reported has been resolved."
+            .to_string(),
+    ];
+    if let Some(description) = ticket
+        .task_description
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+    {
+        body.push(String::new());
+        body.push(format!("> {}", snippet(description)));
+    }
+    if let Some(page_url) = ticket.page_url.as_deref().filter(|u| !u.is_empty()) {
+        body.push(String::new());
+        body.push(format!("Reported on: {}", page_url));
+    }
+    if let Some(link) = report_link {
+        body.push(String::new());
+        body.push(format!("View the report: {}", link));
+    }
+    body.push(String::new());
+    body.push("Thanks for helping us improve!".to_string());
+
+    Message::builder()
+        .from(from.clone())
+        .to(to)
+        .subject("Your feedback has been resolved")
+        .header(ContentType::TEXT_PLAIN)
+        .body(body.join("\n"))
+        .ok()
+}
+
+/// First `SNIPPET_CHARS` characters on one line, with an ellipsis when cut
+fn snippet(text: &str) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() <= SNIPPET_CHARS {
+        return flat;
+    }
+    let cut: String = flat.chars().take(SNIPPET_CHARS).collect();
+    format!("{}…", cut.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn ticket(email: Option<&str>) -> FeedbackTicket {
+        serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(),
+            "project_id": Uuid::new_v4(),
+            "session_id": null,
+            "customer_id": Uuid::new_v4(),
+            "analysis_job_id": null,
+            "video_storage_path": null,
+            "video_size_bytes": null,
+            "duration_seconds": null,
+            "task_description": "Checkout button does nothing",
+            "prior_experience": null,
+            "status": "analyzed",
+            "session_status": "closed",
+            "closed_at": Utc::now(),
+            "closed_reason": "resolved",
+            "external_ticket_url": null,
+            "external_ticket_id": null,
+            "recorded_at": null,
+            "created_at": Utc::now(),
+            "updated_at": Utc::now(),
+            "feedback_type": "bug",
+            "ticket_status": "resolved",
+            "priority": "neutral",
+            "category": null,
+            "submitter_email": email,
+            "submitter_name": "Ada",
+            "page_url": "https://shop.example.com/checkout",
+            "browser_info": {},
+            "screenshot_url": null,
+            "assignee_id": null,
+            "due_date": null,
+            "video_mime_type": null,
+        }))
+        .unwrap()
+    }
+
+    fn from() -> Mailbox {
+        "Ortrace <support@example.com>".parse().unwrap()
+    }
+
+    fn email_config() -> EmailConfig {
+        EmailConfig {
+            smtp_host: "localhost".to_string(),
+            smtp_port: 2525,
+            smtp_user: None,
+            smtp_pass: None,
+            from: "support@example.com".to_string(),
+            link_report: false,
+        }
+    }
+
+    #[test]
+    fn resolved_email_addresses_submitter() {
+        let ticket = ticket(Some("ada@example.com"));
+        let message = resolved_email(&from(), &ticket, None).unwrap();
+        let raw = String::from_utf8(message.formatted()).unwrap();
+
+        assert!(raw.contains("To: ada@example.com"));
+        assert!(raw.contains("Subject: Your feedback has been resolved"));
+        assert!(raw.contains("Hi Ada,"));
+        assert!(raw.contains("> Checkout button does nothing"));
+        assert!(raw.contains("Reported on: https://shop.example.com/checkout"));
+        assert!(!raw.contains("View the report"));
+    }
+
+    #[test]
+    fn resolved_email_links_report_when_given() {
+        let ticket = ticket(Some("ada@example.com"));
+        let message =
+            resolved_email(&from(), &ticket, Some("https://app.example.com/tickets/1")).unwrap();
+        let raw = String::from_utf8(message.formatted()).unwrap();
+        assert!(raw.contains("View the report: https://app.example.com/tickets/1"));
+    }
+
+    #[test]
+    fn resolved_email_requires_an_address() {
+        assert!(resolved_email(&from(), &ticket(None), None).is_none());
+        assert!(resolved_email(&from(), &ticket(Some("not-an-email")), None).is_none());
+    }
+
+    #[tokio::test]
+    async fn ticket_resolved_only_queues_with_smtp_and_address() {
+        let disabled = EmailService::new(&Config::test_default()).unwrap();
+        assert!(!disabled.ticket_resolved(&ticket(Some("ada@example.com"))));
+
+        let config = Config {
+            email: Some(email_config()),
+            ..Config::test_default()
+        };
+        let enabled = EmailService::new(&config).unwrap();
+        assert!(!enabled.ticket_resolved(&ticket(None)));
+        // Queued; the background send fails quietly since nothing listens on the port
+        assert!(enabled.ticket_resolved(&ticket(Some("ada@example.com"))));
+    }
+
+    #[test]
+    fn invalid_from_address_is_rejected() {
+        let config = Config {
+            email: Some(EmailConfig {
+                from: "not an address".to_string(),
+                ..email_config()
+            }),
+            ..Config::test_default()
+        };
+        assert!(EmailService::new(&config).is_err());
+    }
+}
@@ -1,11 +1,14 @@
-//! Google Gemini AI service for video analysis
+//! Google Gemini AI service for video and screenshot analysis
 
 use anyhow::{Context, Result};
+use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
+use std::time::{Duration, Instant};
 
-use crate::config::Config;
+use crate::config::{Config, OversizePolicy};
+use crate::models::GeminiParams;
 
 // ============================================================================
 // API Types
@@ -31,6 +34,40 @@ struct Part {
     text: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     inline_data: Option<InlineData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_data: Option<FileData>,
+}
+
+impl Part {
+    fn text(text: &str) -> Self {
+        Self {
+            text: Some(text.to_string()),
+            inline_data: None,
+            file_data: None,
+        }
+    }
+
+    fn inline(mime: &str, data: &str) -> Self {
+        Self {
+            text: None,
+            inline_data: Some(InlineData {
+                mime_type: mime.to_string(),
+                data: data.to_string(),
+            }),
+            file_data: None,
+        }
+    }
+
+    fn file(mime: &str, uri: &str) -> Self {
+        Self {
+            text: None,
+            inline_data: None,
+            file_data: Some(FileData {
+                mime_type: mime.to_string(),
+                file_uri: uri.to_string(),
+            }),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -39,6 +76,29 @@ struct InlineData {
     data: String,
 }
 
+/// Reference to media uploaded through the Files API
+#[derive(Serialize, Deserialize)]
+struct FileData {
+    mime_type: String,
+    file_uri: String,
+}
+
+/// Files API upload response
+#[derive(Deserialize)]
+struct UploadResponse {
+    file: UploadedFile,
+}
+
+#[derive(Deserialize)]
+struct UploadedFile {
+    /// `files/{id}`
+    name: String,
+    uri: String,
+    /// PROCESSING, ACTIVE or FAILED
+    #[serde(default)]
+    state: String,
+}
+
 #[derive(Serialize)]
 struct GenerationConfig {
     temperature: f32,
@@ -47,9 +107,38 @@ struct GenerationConfig {
     max_output_tokens: i32,
 }
 
+impl GenerationConfig {
+    /// Service defaults with a project's (clamped) overrides applied
+    fn with_params(params: GeminiParams) -> Self {
+        let params = params.clamped();
+        Self {
+            temperature: params.temperature.unwrap_or(0.4),
+            top_p: 0.95,
+            top_k: 40,
+            max_output_tokens: params
+                .max_output_tokens
+                .unwrap_or(GeminiParams::MAX_OUTPUT_TOKENS),
+        }
+    }
+}
+
 #[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct Response {
     candidates: Vec<Candidate>,
+    #[serde(default)]
+    usage_metadata: Option<UsageMetadata>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UsageMetadata {
+    #[serde(default)]
+    prompt_token_count: i32,
+    #[serde(default)]
+    candidates_token_count: i32,
+    #[serde(default)]
+    total_token_count: i32,
 }
 
 #[derive(Deserialize)]
@@ -57,17 +146,108 @@ struct Candidate {
     content: Content,
 }
 
+/// Token counts reported by Gemini for a single generateContent call
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenUsage {
+    pub prompt_tokens: i32,
+    pub candidates_tokens: i32,
+    pub total_tokens: i32,
+}
+
+/// Analysis text together with the tokens it consumed
+#[derive(Debug, Clone)]
+pub struct AnalysisOutput {
+    pub text: String,
+    pub usage: TokenUsage,
+}
+
+/// Media ready to send to Gemini: base64 data for an inline request, or the raw bytes
+/// to upload through the Files API first
+pub struct PreparedMedia {
+    mime: String,
+    payload: MediaPayload,
+}
+
+enum MediaPayload {
+    Inline(String),
+    Upload(Bytes),
+}
+
+/// How one piece of media reaches Gemini
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaDelivery {
+    Inline,
+    FilesApi,
+}
+
+/// Size limits for media sent to Gemini: what fits inline, and what happens to the rest
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GeminiSizePolicy {
+    /// Largest media sent inline, in bytes
+    pub inline_max_bytes: usize,
+    pub oversize: OversizePolicy,
+}
+
+impl Default for GeminiSizePolicy {
+    fn default() -> Self {
+        Self {
+            inline_max_bytes: 20 * BYTES_PER_MB,
+            oversize: OversizePolicy::Reject,
+        }
+    }
+}
+
+impl GeminiSizePolicy {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            inline_max_bytes: config.gemini_inline_max_mb * BYTES_PER_MB,
+            oversize: config.gemini_oversize_policy,
+        }
+    }
+
+    /// How to send `size` bytes of media; `kind` ("Video", "Image") names it in the error
+    /// when the media cannot be sent at all
+    pub fn delivery(&self, size: usize, kind: &str) -> Result<MediaDelivery> {
+        if size <= self.inline_max_bytes {
+            return Ok(MediaDelivery::Inline);
+        }
+        let max_bytes = match self.oversize {
+            OversizePolicy::UseFilesApi if size <= FILES_API_MAX_BYTES => {
+                return Ok(MediaDelivery::FilesApi)
+            }
+            OversizePolicy::UseFilesApi => FILES_API_MAX_BYTES,
+            OversizePolicy::Reject => self.inline_max_bytes,
+        };
+        anyhow::bail!(
+            "{} too large ({:.1}MB). Max: {}MB",
+            kind,
+            size as f64 / BYTES_PER_MB as f64,
+            max_bytes / BYTES_PER_MB
+        )
+    }
+}
+
 // ============================================================================
 // Service
 // ============================================================================
 
+const API_BASE: &str = "https://generativelanguage.googleapis.com/v1beta";
+const UPLOAD_BASE: &str = "https://generativelanguage.googleapis.com/upload/v1beta";
 const MODEL: &str = "gemini-2.0-flash-lite";
-const MAX_SIZE_MB: f64 = 20.0;
-
-/// Gemini AI service for video analysis
+const BYTES_PER_MB: usize = 1024 * 1024;
+/// Largest file the Files API accepts
+const FILES_API_MAX_BYTES: usize = 2048 * BYTES_PER_MB;
+/// How long an uploaded video may take to become usable
+const FILE_PROCESSING_TIMEOUT: Duration = Duration::from_secs(300);
+const FILE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Gemini AI service for video and screenshot analysis
 #[derive(Clone)]
 pub struct GeminiService {
     api_key: String,
+    api_base: String,
+    upload_base: String,
+    size_policy: GeminiSizePolicy,
 }
 
 impl GeminiService {
@@ -75,26 +255,60 @@ impl GeminiService {
     pub async fn new(config: &Config) -> Result<Self> {
         Ok(Self {
             api_key: config.gemini_api_key.clone(),
+            api_base: API_BASE.to_string(),
+            upload_base: UPLOAD_BASE.to_string(),
+            size_policy: GeminiSizePolicy::from_config(config),
         })
     }
 
-    /// Analyze a video file with custom prompt
-    pub async fn analyze(&self, path: &Path, prompt: &str) -> Result<String> {
-        // Read and validate file
-        let bytes =
-            fs::read(path).with_context(|| format!("Failed to read: {}", path.display()))?;
+    /// Service that sends requests to a stand-in for the Gemini API
+    #[cfg(test)]
+    pub fn with_api_base(api_key: &str, api_base: &str) -> Self {
+        let api_base = api_base.trim_end_matches('/');
+        Self {
+            api_key: api_key.to_string(),
+            api_base: api_base.to_string(),
+            upload_base: format!("{}/upload", api_base),
+            size_policy: GeminiSizePolicy::default(),
+        }
+    }
 
-        let size_mb = bytes.len() as f64 / (1024.0 * 1024.0);
-        if size_mb > MAX_SIZE_MB {
-            anyhow::bail!("Video too large ({:.1}MB). Max: {}MB", size_mb, MAX_SIZE_MB);
+    #[cfg(test)]
+    pub fn with_size_policy(self, size_policy: GeminiSizePolicy) -> Self {
+        Self {
+            size_policy,
+            ..self
         }
+    }
 
-        // Encode and analyze
-        #[allow(deprecated)]
-        let base64_data = base64::encode(&bytes);
-        let mime = Self::mime_type(path);
+    /// Read and size-check a video file, encoding it when it is sent inline
+    pub fn prepare_video(&self, path: &Path) -> Result<PreparedMedia> {
+        let bytes =
+            fs::read(path).with_context(|| format!("Failed to read: {}", path.display()))?;
+        self.prepare(bytes, Self::mime_type(path), "Video")
+    }
 
-        self.call_api(&base64_data, &mime, prompt).await
+    /// Apply the size policy to media bytes
+    fn prepare(&self, bytes: Vec<u8>, mime: String, kind: &str) -> Result<PreparedMedia> {
+        let payload = match self.size_policy.delivery(bytes.len(), kind)? {
+            MediaDelivery::Inline => {
+                #[allow(deprecated)]
+                let data = base64::encode(&bytes);
+                MediaPayload::Inline(data)
+            }
+            MediaDelivery::FilesApi => MediaPayload::Upload(Bytes::from(bytes)),
+        };
+        Ok(PreparedMedia { mime, payload })
+    }
+
+    /// Analyze a prepared video with custom prompt
+    pub async fn analyze_video(
+        &self,
+        video: &PreparedMedia,
+        prompt: &str,
+        params: GeminiParams,
+    ) -> Result<AnalysisOutput> {
+        self.generate(video, prompt, params).await
     }
 
     /// Analyze video bytes directly
@@ -104,53 +318,161 @@ impl GeminiService {
         bytes: &[u8],
         mime_type: &str,
         prompt: &str,
-    ) -> Result<String> {
-        let size_mb = bytes.len() as f64 / (1024.0 * 1024.0);
-        if size_mb > MAX_SIZE_MB {
-            anyhow::bail!("Video too large ({:.1}MB). Max: {}MB", size_mb, MAX_SIZE_MB);
+    ) -> Result<AnalysisOutput> {
+        let video = self.prepare(bytes.to_vec(), mime_type.to_string(), "Video")?;
+        self.generate(&video, prompt, GeminiParams::default()).await
+    }
+
+    /// Analyze a still image, such as a widget screenshot, with custom prompt
+    pub async fn analyze_image(
+        &self,
+        bytes: &[u8],
+        mime: &str,
+        prompt: &str,
+        params: GeminiParams,
+    ) -> Result<AnalysisOutput> {
+        Self::check_image(bytes, mime)?;
+        let image = self.prepare(bytes.to_vec(), mime.to_string(), "Image")?;
+        self.generate(&image, prompt, params).await
+    }
+
+    /// Check media claimed to be an image is one and not empty
+    fn check_image(bytes: &[u8], mime: &str) -> Result<()> {
+        if !mime.starts_with("image/") {
+            anyhow::bail!("Not an image: {}", mime);
+        }
+        if bytes.is_empty() {
+            anyhow::bail!("Image is empty");
         }
+        Ok(())
+    }
 
-        #[allow(deprecated)]
-        let base64_data = base64::encode(bytes);
-        self.call_api(&base64_data, mime_type, prompt).await
+    /// Send prepared media with the prompt, uploading it first when it is too large to
+    /// go inline. Uploaded files are deleted afterwards.
+    async fn generate(
+        &self,
+        media: &PreparedMedia,
+        prompt: &str,
+        params: GeminiParams,
+    ) -> Result<AnalysisOutput> {
+        match &media.payload {
+            MediaPayload::Inline(data) => {
+                self.call_api(Part::inline(&media.mime, data), prompt, params)
+                    .await
+            }
+            MediaPayload::Upload(bytes) => {
+                let file = self.upload_file(bytes.clone(), &media.mime).await?;
+                let result = self
+                    .call_api(Part::file(&media.mime, &file.uri), prompt, params)
+                    .await;
+                self.delete_file(&file.name).await;
+                result
+            }
+        }
+    }
+
+    /// Upload media through the Files API (resumable protocol, in one chunk) and wait
+    /// until Gemini has finished processing it
+    async fn upload_file(&self, bytes: Bytes, mime: &str) -> Result<UploadedFile> {
+        let client = reqwest::Client::new();
+        let start = client
+            .post(format!("{}/files?key={}", self.upload_base, self.api_key))
+            .header("X-Goog-Upload-Protocol", "resumable")
+            .header("X-Goog-Upload-Command", "start")
+            .header(
+                "X-Goog-Upload-Header-Content-Length",
+                bytes.len().to_string(),
+            )
+            .header("X-Goog-Upload-Header-Content-Type", mime)
+            .json(&serde_json::json!({ "file": { "display_name": "ortrace-analysis" } }))
+            .send()
+            .await
+            .context("File upload failed")?;
+        if !start.status().is_success() {
+            let err = start.text().await.unwrap_or_default();
+            anyhow::bail!("File upload error: {}", err);
+        }
+        let upload_url = start
+            .headers()
+            .get("x-goog-upload-url")
+            .and_then(|v| v.to_str().ok())
+            .context("File upload returned no upload URL")?
+            .to_string();
+
+        let response = client
+            .post(&upload_url)
+            .header("X-Goog-Upload-Offset", "0")
+            .header("X-Goog-Upload-Command", "upload, finalize")
+            .body(bytes)
+            .send()
+            .await
+            .context("File upload failed")?;
+        if !response.status().is_success() {
+            let err = response.text().await.unwrap_or_default();
+            anyhow::bail!("File upload error: {}", err);
+        }
+        let mut file = response
+            .json::<UploadResponse>()
+            .await
+            .context("Parse error")?
+            .file;
+
+        let deadline = Instant::now() + FILE_PROCESSING_TIMEOUT;
+        while file.state == "PROCESSING" {
+            if Instant::now() >= deadline {
+                anyhow::bail!(
+                    "Uploaded file still processing after {}s",
+                    FILE_PROCESSING_TIMEOUT.as_secs()
+                );
+            }
+            tokio::time::sleep(FILE_POLL_INTERVAL).await;
+            file = client
+                .get(format!(
+                    "{}/{}?key={}",
+                    self.api_base, file.name, self.api_key
+                ))
+                .send()
+                .await
+                .context("File status request failed")?
+                .json::<UploadedFile>()
+                .await
+                .context("Parse error")?;
+        }
+        if file.state == "FAILED" {
+            anyhow::bail!("Gemini could not process the uploaded file");
+        }
+        Ok(file)
+    }
+
+    /// Delete an uploaded file (best-effort; the Files API expires them after 48 hours)
+    async fn delete_file(&self, name: &str) {
+        let result = reqwest::Client::new()
+            .delete(format!("{}/{}?key={}", self.api_base, name, self.api_key))
+            .send()
+            .await
+            .and_then(|r| r.error_for_status());
+        if let Err(e) = result {
+            tracing::warn!(file = name, "Failed to delete uploaded Gemini file: {}", e);
+        }
     }
 
     /// Call Gemini API
-    async fn call_api(&self, data: &str, mime: &str, prompt: &str) -> Result<String> {
+    async fn call_api(
+        &self,
+        media: Part,
+        prompt: &str,
+        params: GeminiParams,
+    ) -> Result<AnalysisOutput> {
         let url = format!(
-            "https://generativelanguage.googleapis.com/v1beta/models/{MODEL}:generateContent?key={key}",
+            "{base}/models/{MODEL}:generateContent?key={key}",
+            base = self.api_base,
             key = self.api_key,
         );
 
-        let request = Request {
-            contents: vec![Content {
-                role: Some("user".to_string()),
-                parts: vec![
-                    Part {
-                        text: Some(prompt.to_string()),
-                        inline_data: None,
-                    },
-                    Part {
-                        text: None,
-                        inline_data: Some(InlineData {
-                            mime_type: mime.to_string(),
-                            data: data.to_string(),
-                        }),
-                    },
-                ],
-            }],
-            generation_config: GenerationConfig {
-                temperature: 0.4,
-                top_p: 0.95,
-                top_k: 40,
-                max_output_tokens: 8192,
-            },
-        };
-
         let response = reqwest::Client::new()
             .post(&url)
             .header("Content-Type", "application/json")
-            .json(&request)
+            .json(&Self::build_request(media, prompt, params))
             .send()
             .await
             .context("Request failed")?;
@@ -160,27 +482,63 @@ impl GeminiService {
             anyhow::bail!("API error: {}", err);
         }
 
-        let result: Response = response.json().await.context("Parse error")?;
+        let body = response.text().await.context("Failed to read response")?;
+        Self::parse_response(&body)
+    }
+
+    /// generateContent request with the prompt followed by the media
+    fn build_request(media: Part, prompt: &str, params: GeminiParams) -> Request {
+        Request {
+            contents: vec![Content {
+                role: Some("user".to_string()),
+                parts: vec![Part::text(prompt), media],
+            }],
+            generation_config: GenerationConfig::with_params(params),
+        }
+    }
 
-        result
+    /// Parse a generateContent response body into the analysis text and token usage
+    fn parse_response(body: &str) -> Result<AnalysisOutput> {
+        let result: Response = serde_json::from_str(body).context("Parse error")?;
+
+        let text = result
             .candidates
             .first()
             .and_then(|c| c.content.parts.first())
             .and_then(|p| p.text.clone())
-            .context("No response text")
+            .context("No response text")?;
+
+        let usage = result
+            .usage_metadata
+            .map(|u| TokenUsage {
+                prompt_tokens: u.prompt_token_count,
+                candidates_tokens: u.candidates_token_count,
+                total_tokens: u.total_token_count,
+            })
+            .unwrap_or_default();
+
+        Ok(AnalysisOutput { text, usage })
     }
 
     /// Detect MIME type from extension
     fn mime_type(path: &Path) -> String {
-        match path.extension().and_then(|e| e.to_str()) {
-            Some("mp4") => "video/mp4",
-            Some("mov") => "video/quicktime",
-            Some("avi") => "video/x-msvideo",
-            Some("webm") => "video/webm",
-            Some("mkv") => "video/x-matroska",
-            _ => "video/mp4",
+        path.extension()
+            .and_then(|e| e.to_str())
+            .and_then(Self::mime_type_for_extension)
+            .unwrap_or("video/mp4")
+            .to_string()
+    }
+
+    /// Video MIME type for a known file extension
+    pub fn mime_type_for_extension(ext: &str) -> Option<&'static str> {
+        match ext.to_ascii_lowercase().as_str() {
+            "mp4" => Some("video/mp4"),
+            "mov" => Some("video/quicktime"),
+            "avi" => Some("video/x-msvideo"),
+            "webm" => Some("video/webm"),
+            "mkv" => Some("video/x-matroska"),
+            _ => None,
         }
-        .to_string()
     }
 
     /// Build analysis prompt for a session's configuration
@@ -222,6 +580,8 @@ impl GeminiService {
               \"outcome\": \"success|partial|failed\",\n\
               \"confidence\": 0-100,\n\
               \"overview\": \"Executive summary of the session\",\n\
+              \"sentiment\": \"frustrated|neutral|satisfied\",\n\
+              \"frustration_score\": 0-100,\n\
               \"metrics\": {\n\
                 \"task_completion_rate\": 0-100,\n\
                 \"total_hesitation_time\": seconds,\n\
@@ -312,6 +672,220 @@ mod tests {
         assert_eq!(GeminiService::mime_type(Path::new("noext")), "video/mp4");
     }
 
+    #[test]
+    fn check_image_accepts_non_empty_images_only() {
+        let png = b"\x89PNG\r\n\x1a\nrest";
+        assert!(GeminiService::check_image(png, "image/png").is_ok());
+        assert!(GeminiService::check_image(png, "video/webm").is_err());
+        assert!(GeminiService::check_image(&[], "image/png").is_err());
+    }
+
+    #[test]
+    fn default_policy_encodes_small_media_and_rejects_huge_media() {
+        let gemini = GeminiService::with_api_base("test-key", "http://localhost");
+        let png = b"\x89PNG\r\n\x1a\nrest";
+        let image = gemini
+            .prepare(png.to_vec(), "image/png".to_string(), "Image")
+            .unwrap();
+        #[allow(deprecated)]
+        let expected = base64::encode(png);
+        assert!(matches!(image.payload, MediaPayload::Inline(data) if data == expected));
+
+        let huge = vec![0u8; 21 * BYTES_PER_MB];
+        let err = gemini
+            .prepare(huge, "image/png".to_string(), "Image")
+            .err()
+            .unwrap();
+        assert_eq!(err.to_string(), "Image too large (21.0MB). Max: 20MB");
+    }
+
+    fn policy(oversize: OversizePolicy) -> GeminiSizePolicy {
+        GeminiSizePolicy {
+            inline_max_bytes: 4 * BYTES_PER_MB,
+            oversize,
+        }
+    }
+
+    #[test]
+    fn reject_policy_sends_up_to_the_limit_inline() {
+        let policy = policy(OversizePolicy::Reject);
+        assert_eq!(
+            policy.delivery(4 * BYTES_PER_MB, "Video").unwrap(),
+            MediaDelivery::Inline
+        );
+        let err = policy.delivery(4 * BYTES_PER_MB + 1, "Video").unwrap_err();
+        assert_eq!(err.to_string(), "Video too large (4.0MB). Max: 4MB");
+    }
+
+    #[test]
+    fn files_api_policy_uploads_media_over_the_limit() {
+        let policy = policy(OversizePolicy::UseFilesApi);
+        assert_eq!(
+            policy.delivery(4 * BYTES_PER_MB, "Video").unwrap(),
+            MediaDelivery::Inline
+        );
+        assert_eq!(
+            policy.delivery(4 * BYTES_PER_MB + 1, "Video").unwrap(),
+            MediaDelivery::FilesApi
+        );
+        assert_eq!(
+            policy.delivery(FILES_API_MAX_BYTES, "Video").unwrap(),
+            MediaDelivery::FilesApi
+        );
+        let err = policy
+            .delivery(FILES_API_MAX_BYTES + 1, "Video")
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Video too large (2048.0MB). Max: 2048MB");
+    }
+
+    #[tokio::test]
+    async fn oversize_media_is_uploaded_referenced_and_deleted() {
+        use axum::extract::Request as HttpRequest;
+        use axum::http::Method;
+        use axum::response::IntoResponse;
+        use std::sync::{Arc, Mutex};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let api_base = format!("http://{}", listener.local_addr().unwrap());
+        let session_url = format!("{}/session", api_base);
+        let calls = Arc::new(Mutex::new(Vec::<String>::new()));
+        let seen = calls.clone();
+        let app = axum::Router::new().fallback(move |request: HttpRequest| {
+            let seen = seen.clone();
+            let session_url = session_url.clone();
+            async move {
+                let method = request.method().clone();
+                let path = request.uri().path().to_string();
+                let command = request
+                    .headers()
+                    .get("x-goog-upload-command")
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or_default()
+                    .to_string();
+                let body = axum::body::to_bytes(request.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                seen.lock()
+                    .unwrap()
+                    .push(format!("{} {} {}", method, path, command).trim().to_string());
+                match (method, path.as_str()) {
+                    (Method::POST, "/upload/files") => axum::response::Response::builder()
+                        .header("x-goog-upload-url", session_url)
+                        .body(axum::body::Body::empty())
+                        .unwrap(),
+                    (Method::POST, "/session") => {
+                        assert_eq!(&body[..], b"0123456789");
+                        axum::Json(serde_json::json!({
+                            "file": {"name": "files/abc", "uri": "https://files/abc", "state": "ACTIVE"}
+                        }))
+                        .into_response()
+                    }
+                    (Method::POST, _) => {
+                        let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                        let part = &request["contents"][0]["parts"][1];
+                        assert_eq!(part["fileData"]["file_uri"], "https://files/abc");
+                        assert!(part.get("inlineData").is_none());
+                        axum::Json(serde_json::json!({
+                            "candidates": [{"content": {"parts": [{"text": "ok"}]}}]
+                        }))
+                        .into_response()
+                    }
+                    _ => axum::Json(serde_json::json!({})).into_response(),
+                }
+            }
+        });
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let gemini = GeminiService::with_api_base("test-key", &api_base).with_size_policy(
+            GeminiSizePolicy {
+                inline_max_bytes: 8,
+                oversize: OversizePolicy::UseFilesApi,
+            },
+        );
+        let output = gemini
+            .analyze_bytes(b"0123456789", "video/webm", "Describe")
+            .await
+            .unwrap();
+        assert_eq!(output.text, "ok");
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                "POST /upload/files start".to_string(),
+                "POST /session upload, finalize".to_string(),
+                format!("POST /models/{}:generateContent", MODEL),
+                "DELETE /files/abc".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn build_request_sends_media_with_its_mime_type() {
+        let request = serde_json::to_value(GeminiService::build_request(
+            Part::inline("image/png", "aGk="),
+            "Describe",
+            GeminiParams::default(),
+        ))
+        .unwrap();
+        let parts = &request["contents"][0]["parts"];
+        assert_eq!(parts[0]["text"], "Describe");
+        assert_eq!(parts[1]["inlineData"]["mime_type"], "image/png");
+        assert_eq!(parts[1]["inlineData"]["data"], "aGk=");
+        let config = &request["generation_config"];
+        assert_eq!(config["temperature"], 0.4f32 as f64);
+        assert_eq!(config["top_k"], 40);
+        assert_eq!(config["max_output_tokens"], 8192);
+    }
+
+    #[test]
+    fn build_request_applies_clamped_project_params() {
+        let params = GeminiParams {
+            temperature: Some(9.0),
+            max_output_tokens: Some(2048),
+        };
+        let request = serde_json::to_value(GeminiService::build_request(
+            Part::inline("image/png", "aGk="),
+            "Describe",
+            params,
+        ))
+        .unwrap();
+        let config = &request["generation_config"];
+        assert_eq!(config["temperature"], 2.0);
+        assert_eq!(config["max_output_tokens"], 2048);
+        assert_eq!(config["top_p"], 0.95f32 as f64);
+    }
+
+    #[test]
+    fn parse_response_extracts_text_and_usage() {
+        let body = r#"{
+            "candidates": [
+                {"content": {"role": "model", "parts": [{"text": "{\"outcome\": \"success\"}"}]}}
+            ],
+            "usageMetadata": {
+                "promptTokenCount": 1200,
+                "candidatesTokenCount": 350,
+                "totalTokenCount": 1550
+            }
+        }"#;
+        let output = GeminiService::parse_response(body).unwrap();
+        assert_eq!(output.text, "{\"outcome\": \"success\"}");
+        assert_eq!(
+            output.usage,
+            TokenUsage {
+                prompt_tokens: 1200,
+                candidates_tokens: 350,
+                total_tokens: 1550,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_response_without_usage_defaults_to_zero() {
+        let body = r#"{"candidates": [{"content": {"parts": [{"text": "ok"}]}}]}"#;
+        let output = GeminiService::parse_response(body).unwrap();
+        assert_eq!(output.text, "ok");
+        assert_eq!(output.usage, TokenUsage::default());
+    }
+
     #[test]
     fn build_prompt_empty_inputs() {
         let prompt = GeminiService::build_analysis_prompt(&[], &[], &[]);
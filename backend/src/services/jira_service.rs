@@ -0,0 +1,219 @@
+//! Jira service - exports report issues to a project's Jira Cloud backlog
+
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::error::{AppError, Result};
+use crate::models::report::string_array_from_value;
+use crate::models::{Issue, JiraSettings};
+
+/// Jira rejects summaries longer than this
+const MAX_SUMMARY_CHARS: usize = 255;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Jira service for creating issues via the REST API
+pub struct JiraService {
+    client: reqwest::Client,
+    frontend_url: String,
+}
+
+#[derive(serde::Deserialize)]
+struct CreatedIssue {
+    key: String,
+}
+
+impl JiraService {
+    pub fn new(config: &Config) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            frontend_url: config.frontend_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Create a Jira issue for a report issue; returns the browse URL of the new issue
+    pub async fn create_issue(
+        &self,
+        settings: &JiraSettings,
+        issue: &Issue,
+        ticket_id: uuid::Uuid,
+    ) -> Result<String> {
+        let base_url = settings.base_url.trim().trim_end_matches('/');
+        let ticket_link = format!("{}/tickets/{}", self.frontend_url, ticket_id);
+        let payload = issue_payload(settings, issue, &ticket_link);
+
+        let response = self
+            .client
+            .post(format!("{}/rest/api/2/issue", base_url))
+            .basic_auth(settings.email.trim(), Some(settings.api_token.trim()))
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|e| AppError::ExternalService(format!("Jira request failed: {}", e)))?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+            return Err(AppError::ExternalService(format!(
+                "Jira rejected the project's credentials ({}); check the Jira email and API token",
+                status
+            )));
+        }
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(AppError::ExternalService(format!(
+                "Jira returned {}: {}",
+                status,
+                body.chars().take(500).collect::<String>()
+            )));
+        }
+
+        let created: CreatedIssue = response
+            .json()
+            .await
+            .map_err(|e| AppError::ExternalService(format!("Invalid Jira response: {}", e)))?;
+        Ok(format!("{}/browse/{}", base_url, created.key))
+    }
+}
+
+/// `POST /rest/api/2/issue` body; v2 takes the description as wiki markup
+fn issue_payload(settings: &JiraSettings, issue: &Issue, ticket_link: &str) -> serde_json::Value {
+    serde_json::json!({
+        "fields": {
+            "project": { "key": settings.project_key.trim() },
+            "issuetype": { "name": settings.issue_type() },
+            "summary": summary(&issue.title),
+            "description": description(issue, ticket_link),
+            "labels": ["ortrace"],
+        }
+    })
+}
+
+fn summary(title: &str) -> String {
+    // Summaries are single-line
+    let flat = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.is_empty() {
+        return "Issue reported via Ortrace".to_string();
+    }
+    flat.chars().take(MAX_SUMMARY_CHARS).collect()
+}
+
+fn description(issue: &Issue, ticket_link: &str) -> String {
+    let mut sections = Vec::new();
+    if let Some(observed) = issue
+        .observed_behavior
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+    {
+        sections.push(format!("h3. Observed behavior\n{}", observed.trim()));
+    }
+    if let Some(expected) = issue
+        .expected_behavior
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+    {
+        sections.push(format!("h3. Expected behavior\n{}", expected.trim()));
+    }
+    let steps = string_array_from_value(&issue.reproduction_steps.0);
+    if !steps.is_empty() {
+        let list = steps
+            .iter()
+            .map(|step| format!("# {}", step.trim()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        sections.push(format!("h3. Steps to reproduce\n{}", list));
+    }
+    sections.push(format!(
+        "----\nSeverity: {} | [View session in Ortrace|{}]",
+        issue.severity, ticket_link
+    ));
+    sections.join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{IssueSeverity, IssueStatus};
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn settings() -> JiraSettings {
+        JiraSettings {
+            base_url: "https://acme.atlassian.net".to_string(),
+            email: "dev@acme.com".to_string(),
+            api_token: "token".to_string(),
+            project_key: "WEB".to_string(),
+            issue_type: None,
+        }
+    }
+
+    fn issue() -> Issue {
+        Issue {
+            id: Uuid::new_v4(),
+            report_id: Uuid::new_v4(),
+            title: "Checkout button\nunresponsive".to_string(),
+            severity: IssueSeverity::High,
+            tags: sqlx::types::Json(serde_json::json!(["frontend"])),
+            observed_behavior: Some("Clicking Pay does nothing.".to_string()),
+            expected_behavior: Some("The payment form submits.".to_string()),
+            evidence: sqlx::types::Json(serde_json::json!([])),
+            screenshots: sqlx::types::Json(serde_json::json!([])),
+            impact: sqlx::types::Json(serde_json::json!([])),
+            reproduction_steps: sqlx::types::Json(serde_json::json!([
+                "Add an item to the cart",
+                "Click Pay"
+            ])),
+            confidence: Some(80),
+            external_ticket_url: None,
+            issue_status: IssueStatus::Open,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn payload_maps_issue_fields() {
+        let payload = issue_payload(&settings(), &issue(), "https://app.example.com/tickets/1");
+        let fields = &payload["fields"];
+        assert_eq!(fields["project"]["key"], "WEB");
+        assert_eq!(fields["issuetype"]["name"], "Bug");
+        assert_eq!(fields["summary"], "Checkout button unresponsive");
+        assert_eq!(fields["labels"], serde_json::json!(["ortrace"]));
+        assert_eq!(
+            fields["description"],
+            "h3. Observed behavior\nClicking Pay does nothing.\n\n\
+             h3. Expected behavior\nThe payment form submits.\n\n\
+             h3. Steps to reproduce\n# Add an item to the cart\n# Click Pay\n\n\
+             ----\nSeverity: high | [View session in Ortrace|https://app.example.com/tickets/1]"
+        );
+    }
+
+    #[test]
+    fn payload_skips_missing_sections_and_uses_custom_issue_type() {
+        let settings = JiraSettings {
+            issue_type: Some("Task".to_string()),
+            ..settings()
+        };
+        let issue = Issue {
+            observed_behavior: None,
+            expected_behavior: Some("  ".to_string()),
+            reproduction_steps: sqlx::types::Json(serde_json::json!("Open the page")),
+            ..issue()
+        };
+        let payload = issue_payload(&settings, &issue, "https://app.example.com/tickets/1");
+        assert_eq!(payload["fields"]["issuetype"]["name"], "Task");
+        assert_eq!(
+            payload["fields"]["description"],
+            "h3. Steps to reproduce\n# Open the page\n\n\
+             ----\nSeverity: high | [View session in Ortrace|https://app.example.com/tickets/1]"
+        );
+    }
+
+    #[test]
+    fn summary_is_capped() {
+        assert_eq!(summary(&"x".repeat(300)).chars().count(), MAX_SUMMARY_CHARS);
+        assert_eq!(summary("   "), "Issue reported via Ortrace");
+    }
+}
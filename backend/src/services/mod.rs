@@ -1,19 +1,40 @@
 //! Business logic services
 
+mod api_key_service;
 mod auth_service;
 mod chat_service;
+mod email_service;
 mod gemini_service;
+mod jira_service;
+mod notification_service;
+mod outbound_guard;
 mod project_service;
 mod queue_service;
+mod report_pdf;
+mod session_service;
 mod storage_service;
 mod ticket_service;
+mod video_probe;
+mod webhook_service;
 mod worker;
 
+pub use api_key_service::ApiKeyService;
 pub use auth_service::AuthService;
-pub use chat_service::ChatService;
-pub use gemini_service::GeminiService;
-pub use project_service::ProjectService;
-pub use queue_service::QueueService;
-pub use storage_service::StorageService;
-pub use ticket_service::{OverviewStats, TicketListQuery, TicketService};
+pub use chat_service::{ChatEvent, ChatService, MessageCursor};
+pub use email_service::EmailService;
+pub use gemini_service::{GeminiService, TokenUsage};
+pub use jira_service::JiraService;
+pub use notification_service::{NotificationService, TicketNotification};
+pub use outbound_guard::check_public_host;
+pub use project_service::{ProjectAnalytics, ProjectService, ProjectTrends};
+pub use queue_service::{JobListQuery, QueueService};
+pub use report_pdf::render_report_pdf;
+pub use session_service::SessionService;
+pub use storage_service::{ByteStream, SignedUpload, StorageService};
+pub use ticket_service::{
+    OverviewStats, PriorTicket, ProjectIssue, SortDirection, TicketCursor, TicketListQuery,
+    TicketService, TicketSortKey, TopIssue, WidgetTicketStatus, DIRECT_UPLOAD_URL_EXPIRY_SECS,
+};
+pub use video_probe::{is_video_container, probe_duration_secs};
+pub use webhook_service::{WebhookService, WebhookTestDelivery};
 pub use worker::Worker;
@@ -0,0 +1,443 @@
+//! Notification service - Slack and Discord messages for new and resolved tickets
+//!
+//! Sent in the background so a slow or broken webhook never delays or fails the
+//! ticket operation that triggered it.
+
+use anyhow::{Context, Result};
+use sqlx::PgPool;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::models::{FeedbackTicket, FeedbackType, Project};
+
+/// Longest task description quoted in a notification
+const SNIPPET_CHARS: usize = 200;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Discord embed side colours
+const DISCORD_CREATED_COLOR: u32 = 0x5865F2;
+const DISCORD_RESOLVED_COLOR: u32 = 0x57F287;
+
+/// Ticket event a project channel is told about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TicketNotification {
+    Created,
+    Resolved,
+}
+
+/// Notification service for project Slack and Discord channels
+#[derive(Clone)]
+pub struct NotificationService {
+    db: PgPool,
+    client: reqwest::Client,
+    frontend_url: String,
+}
+
+impl NotificationService {
+    pub fn new(config: &Config, db: PgPool) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self {
+            db,
+            client,
+            frontend_url: config.frontend_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Notify the ticket's project channels in the background; failures are only logged
+    pub fn notify(&self, kind: TicketNotification, ticket: &FeedbackTicket) {
+        let service = self.clone();
+        let ticket = ticket.clone();
+        tokio::spawn(async move {
+            service.notify_now(kind, &ticket).await;
+        });
+    }
+
+    /// Send to each configured channel; one failing does not stop the other
+    async fn notify_now(&self, kind: TicketNotification, ticket: &FeedbackTicket) {
+        let Some(project_id) = ticket.project_id else {
+            return;
+        };
+        let project = sqlx::query_as::<_, Project>("SELECT * FROM projects WHERE id = $1")
+            .bind(project_id)
+            .fetch_optional(&self.db)
+            .await
+            .context("Failed to load project")
+            .and_then(|project| project.context("Project not found"));
+        let project = match project {
+            Ok(project) => project,
+            Err(e) => {
+                tracing::warn!(ticket_id = %ticket.id, ?kind, "Skipping ticket notifications: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = self.send_to_slack(kind, &project, ticket).await {
+            tracing::warn!(ticket_id = %ticket.id, ?kind, "Failed to send Slack notification: {}", e);
+        }
+        if let Err(e) = self.send_to_discord(kind, &project, ticket).await {
+            tracing::warn!(ticket_id = %ticket.id, ?kind, "Failed to send Discord notification: {}", e);
+        }
+    }
+
+    /// Post to the project's Slack webhook; returns false when none is configured
+    async fn send_to_slack(
+        &self,
+        kind: TicketNotification,
+        project: &Project,
+        ticket: &FeedbackTicket,
+    ) -> Result<bool> {
+        let Some(webhook_url) = project.slack_webhook_url() else {
+            return Ok(false);
+        };
+
+        let link = format!("{}/tickets/{}", self.frontend_url, ticket.id);
+        self.client
+            .post(&webhook_url)
+            .json(&slack_message(kind, &project.name, ticket, &link))
+            .send()
+            .await
+            .context("Slack webhook request failed")?
+            .error_for_status()
+            .context("Slack webhook rejected the message")?;
+        Ok(true)
+    }
+
+    /// Post to the project's Discord webhook; returns false when none is configured
+    async fn send_to_discord(
+        &self,
+        kind: TicketNotification,
+        project: &Project,
+        ticket: &FeedbackTicket,
+    ) -> Result<bool> {
+        let Some(webhook_url) = project.discord_webhook_url() else {
+            return Ok(false);
+        };
+
+        let link = format!("{}/tickets/{}", self.frontend_url, ticket.id);
+        self.client
+            .post(&webhook_url)
+            .json(&discord_message(kind, &project.name, ticket, &link))
+            .send()
+            .await
+            .context("Discord webhook request failed")?
+            .error_for_status()
+            .context("Discord webhook rejected the message")?;
+        Ok(true)
+    }
+}
+
+/// What a notification says, before channel-specific formatting
+struct Summary {
+    headline: String,
+    description: String,
+    submitter: String,
+}
+
+impl Summary {
+    fn new(kind: TicketNotification, project_name: &str, ticket: &FeedbackTicket) -> Self {
+        let label = match ticket.feedback_type {
+            FeedbackType::Bug => "bug report",
+            FeedbackType::Feedback => "feedback",
+            FeedbackType::Idea => "idea",
+        };
+        let headline = match kind {
+            TicketNotification::Created => format!("New {} in {}", label, project_name),
+            TicketNotification::Resolved => format!("Resolved {} in {}", label, project_name),
+        };
+        let description = ticket
+            .task_description
+            .as_deref()
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .map(snippet)
+            .unwrap_or_else(|| "(no description)".to_string());
+        let submitter = match (
+            ticket.submitter_name.as_deref().filter(|s| !s.is_empty()),
+            ticket.submitter_email.as_deref().filter(|s| !s.is_empty()),
+        ) {
+            (Some(name), Some(email)) => format!("{} ({})", name, email),
+            (Some(name), None) => name.to_string(),
+            (None, Some(email)) => email.to_string(),
+            (None, None) => "Anonymous".to_string(),
+        };
+        Self {
+            headline,
+            description,
+            submitter,
+        }
+    }
+}
+
+/// Slack incoming-webhook payload: `text` for notifications, `blocks` for the channel
+fn slack_message(
+    kind: TicketNotification,
+    project_name: &str,
+    ticket: &FeedbackTicket,
+    link: &str,
+) -> serde_json::Value {
+    let Summary {
+        headline,
+        description,
+        submitter,
+    } = Summary::new(kind, project_name, ticket);
+
+    serde_json::json!({
+        "text": format!("{}: {}", escape(&headline), escape(&description)),
+        "blocks": [
+            {
+                "type": "section",
+                "text": {
+                    "type": "mrkdwn",
+                    "text": format!("*{}*\n>{}", escape(&headline), escape(&description)),
+                },
+            },
+            {
+                "type": "context",
+                "elements": [
+                    { "type": "mrkdwn", "text": format!("Submitted by {}", escape(&submitter)) },
+                    { "type": "mrkdwn", "text": format!("<{}|View ticket>", link) },
+                ],
+            },
+        ],
+    })
+}
+
+/// Discord webhook payload: one embed linking to the ticket. Mentions are disabled so a
+/// description containing `@everyone` cannot ping the channel.
+fn discord_message(
+    kind: TicketNotification,
+    project_name: &str,
+    ticket: &FeedbackTicket,
+    link: &str,
+) -> serde_json::Value {
+    let Summary {
+        headline,
+        description,
+        submitter,
+    } = Summary::new(kind, project_name, ticket);
+    let color = match kind {
+        TicketNotification::Created => DISCORD_CREATED_COLOR,
+        TicketNotification::Resolved => DISCORD_RESOLVED_COLOR,
+    };
+
+    serde_json::json!({
+        "embeds": [
+            {
+                "title": headline,
+                "description": description,
+                "url": link,
+                "color": color,
+                "fields": [
+                    { "name": "Submitted by", "value": submitter, "inline": true },
+                ],
+                "timestamp": ticket.closed_at.unwrap_or(ticket.created_at).to_rfc3339(),
+            },
+        ],
+        "allowed_mentions": { "parse": [] },
+    })
+}
+
+/// First `SNIPPET_CHARS` characters on one line, with an ellipsis when cut
+fn snippet(text: &str) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() <= SNIPPET_CHARS {
+        return flat;
+    }
+    let cut: String = flat.chars().take(SNIPPET_CHARS).collect();
+    format!("{}…", cut.trim_end())
+}
+
+/// Escape the characters Slack mrkdwn treats as control sequences
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn ticket(
+        description: Option<&str>,
+        name: Option<&str>,
+        email: Option<&str>,
+    ) -> FeedbackTicket {
+        serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(),
+            "project_id": Uuid::new_v4(),
+            "session_id": null,
+            "customer_id": Uuid::new_v4(),
+            "analysis_job_id": null,
+            "video_storage_path": null,
+            "video_size_bytes": null,
+            "duration_seconds": null,
+            "task_description": description,
+            "prior_experience": null,
+            "status": "recording",
+            "session_status": "open",
+            "closed_at": null,
+            "closed_reason": null,
+            "external_ticket_url": null,
+            "external_ticket_id": null,
+            "recorded_at": null,
+            "created_at": Utc::now(),
+            "updated_at": Utc::now(),
+            "feedback_type": "bug",
+            "ticket_status": "open",
+            "priority": "neutral",
+            "category": null,
+            "submitter_email": email,
+            "submitter_name": name,
+            "page_url": null,
+            "browser_info": {},
+            "screenshot_url": null,
+            "assignee_id": null,
+            "due_date": null,
+            "video_mime_type": null,
+        }))
+        .unwrap()
+    }
+
+    fn project(settings: serde_json::Value) -> Project {
+        Project {
+            id: Uuid::new_v4(),
+            owner_id: Uuid::new_v4(),
+            name: "Checkout".to_string(),
+            domain: None,
+            settings: sqlx::types::Json(settings),
+            is_active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn slack_message_has_text_and_blocks() {
+        let ticket = ticket(
+            Some("Pay button <does nothing>"),
+            Some("Ada"),
+            Some("ada@example.com"),
+        );
+        let message = slack_message(
+            TicketNotification::Created,
+            "Checkout",
+            &ticket,
+            "https://app.example.com/tickets/1",
+        );
+
+        assert_eq!(
+            message["text"],
+            "New bug report in Checkout: Pay button &lt;does nothing&gt;"
+        );
+        let blocks = message["blocks"].as_array().unwrap();
+        assert_eq!(blocks[0]["type"], "section");
+        assert_eq!(blocks[0]["text"]["type"], "mrkdwn");
+        assert_eq!(
+            blocks[0]["text"]["text"],
+            "*New bug report in Checkout*\n>Pay button &lt;does nothing&gt;"
+        );
+        assert_eq!(blocks[1]["type"], "context");
+        assert_eq!(
+            blocks[1]["elements"][0]["text"],
+            "Submitted by Ada (ada@example.com)"
+        );
+        assert_eq!(
+            blocks[1]["elements"][1]["text"],
+            "<https://app.example.com/tickets/1|View ticket>"
+        );
+    }
+
+    #[test]
+    fn slack_message_for_resolved_anonymous_ticket() {
+        let message = slack_message(
+            TicketNotification::Resolved,
+            "Checkout",
+            &ticket(None, None, None),
+            "https://app.example.com/tickets/1",
+        );
+        assert_eq!(
+            message["text"],
+            "Resolved bug report in Checkout: (no description)"
+        );
+        assert_eq!(
+            message["blocks"][1]["elements"][0]["text"],
+            "Submitted by Anonymous"
+        );
+    }
+
+    #[test]
+    fn discord_message_has_one_embed() {
+        let ticket = ticket(
+            Some("Pay button does nothing @everyone"),
+            Some("Ada"),
+            Some("ada@example.com"),
+        );
+        let message = discord_message(
+            TicketNotification::Created,
+            "Checkout",
+            &ticket,
+            "https://app.example.com/tickets/1",
+        );
+
+        let embeds = message["embeds"].as_array().unwrap();
+        assert_eq!(embeds.len(), 1);
+        let embed = &embeds[0];
+        assert_eq!(embed["title"], "New bug report in Checkout");
+        assert_eq!(embed["description"], "Pay button does nothing @everyone");
+        assert_eq!(embed["url"], "https://app.example.com/tickets/1");
+        assert_eq!(embed["color"], DISCORD_CREATED_COLOR);
+        assert_eq!(embed["fields"][0]["name"], "Submitted by");
+        assert_eq!(embed["fields"][0]["value"], "Ada (ada@example.com)");
+        assert!(embed["timestamp"].as_str().is_some());
+        assert_eq!(message["allowed_mentions"]["parse"], serde_json::json!([]));
+
+        let resolved = discord_message(
+            TicketNotification::Resolved,
+            "Checkout",
+            &ticket,
+            "https://app.example.com/tickets/1",
+        );
+        assert_eq!(
+            resolved["embeds"][0]["title"],
+            "Resolved bug report in Checkout"
+        );
+        assert_eq!(resolved["embeds"][0]["color"], DISCORD_RESOLVED_COLOR);
+    }
+
+    #[test]
+    fn snippet_truncates_long_descriptions() {
+        let long = "word ".repeat(100);
+        let cut = snippet(&long);
+        assert!(cut.ends_with('…'));
+        assert!(cut.chars().count() <= SNIPPET_CHARS + 1);
+        assert_eq!(snippet("  line one\nline two "), "line one line two");
+    }
+
+    #[tokio::test]
+    async fn missing_webhook_url_is_a_no_op() {
+        let db = PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        let service = NotificationService::new(&Config::test_default(), db);
+        let sent = service
+            .send_to_slack(
+                TicketNotification::Created,
+                &project(serde_json::json!({})),
+                &ticket(Some("Broken"), None, None),
+            )
+            .await
+            .unwrap();
+        assert!(!sent);
+        let sent = service
+            .send_to_discord(
+                TicketNotification::Created,
+                &project(serde_json::json!({ "discord_webhook_url": " " })),
+                &ticket(Some("Broken"), None, None),
+            )
+            .await
+            .unwrap();
+        assert!(!sent);
+    }
+}
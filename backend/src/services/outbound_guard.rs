@@ -0,0 +1,159 @@
+//! Guard for user-supplied URLs the server sends requests to (webhooks)
+//!
+//! Only public internet hosts are allowed, so a project owner cannot make the server
+//! reach loopback, private networks or the cloud metadata endpoint. Named hosts are
+//! checked against every address they resolve to, both when a URL is saved and before
+//! each request; senders then connect only to the checked addresses, so a DNS change in
+//! between cannot point the request inward.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use reqwest::Url;
+
+const NON_PUBLIC: &str = "URL must point to a public internet host";
+
+/// Host names that always mean "this machine" or the cloud internal network
+fn is_internal_name(host: &str) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    host == "localhost"
+        || [".localhost", ".internal", ".local"]
+            .iter()
+            .any(|suffix| host.ends_with(suffix))
+        || host == "metadata"
+}
+
+/// Whether an address is routable on the public internet
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(v4) => is_public_ipv4(v4),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        || a == 0
+        // Carrier-grade NAT 100.64.0.0/10
+        || (a == 100 && (64..128).contains(&b))
+        // Benchmarking 198.18.0.0/15
+        || (a == 198 && (b == 18 || b == 19))
+        // Reserved 240.0.0.0/4
+        || a >= 240)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // Unique local fc00::/7
+        || (first & 0xfe00) == 0xfc00
+        // Link-local fe80::/10
+        || (first & 0xffc0) == 0xfe80
+        // Documentation 2001:db8::/32
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+}
+
+/// Check a URL's host without resolving it: IP literals must be public and internal
+/// names are refused
+pub fn check_public_host(url: &Url) -> Result<(), String> {
+    let public = match literal_ip(url) {
+        Some(ip) => is_public_ip(ip),
+        None => url.host_str().is_some_and(|host| !is_internal_name(host)),
+    };
+    if public {
+        Ok(())
+    } else {
+        Err(NON_PUBLIC.to_string())
+    }
+}
+
+/// The host when it is an IP address (`Url` has already normalised forms like `0x7f000001`)
+pub fn literal_ip(url: &Url) -> Option<IpAddr> {
+    let host = url.host_str()?;
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse()
+        .ok()
+}
+
+/// Resolve a URL's host, failing unless every address is public
+pub async fn resolve_public(url: &Url) -> Result<Vec<SocketAddr>, String> {
+    check_public_host(url)?;
+    let port = url.port_or_known_default().unwrap_or(443);
+    if let Some(ip) = literal_ip(url) {
+        return Ok(vec![SocketAddr::new(ip, port)]);
+    }
+    let host = url.host_str().ok_or_else(|| NON_PUBLIC.to_string())?;
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|_| "URL host could not be resolved".to_string())?
+        .collect();
+    if addrs.is_empty() || !addrs.iter().all(|addr| is_public_ip(addr.ip())) {
+        return Err(NON_PUBLIC.to_string());
+    }
+    Ok(addrs)
+}
+
+/// `resolve_public` for a URL string
+pub async fn check_public_url(url: &str) -> Result<(), String> {
+    let url = Url::parse(url.trim()).map_err(|_| "URL is not valid".to_string())?;
+    resolve_public(&url).await.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_public_addresses_pass() {
+        for ip in ["8.8.8.8", "93.184.216.34", "2606:4700::1111"] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{ip}");
+        }
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "255.255.255.255",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "::ffff:169.254.169.254",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{ip}");
+        }
+    }
+
+    #[tokio::test]
+    async fn internal_targets_are_rejected() {
+        for url in [
+            "http://127.0.0.1:8080/hook",
+            "http://169.254.169.254/computeMetadata/v1/",
+            "http://metadata.google.internal/computeMetadata/v1/",
+            "http://[::1]/hook",
+            "http://10.0.0.5/hook",
+            "http://localhost:9000/hook",
+            "http://api.localhost/hook",
+            "http://0x7f000001/hook",
+        ] {
+            assert!(check_public_url(url).await.is_err(), "{url}");
+        }
+        assert!(check_public_url("https://93.184.216.34/hook").await.is_ok());
+    }
+}
@@ -1,22 +1,33 @@
 //! Project service - handles project CRUD
 
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
 
+use crate::dto::AnalyticsBucket;
 use crate::error::{AppError, Result};
-use crate::models::{AnalysisQuestions, Project};
+use crate::models::{
+    AnalysisQuestions, FeedbackType, GeminiParams, JiraSettings, OutcomeMapping, Project,
+    ProjectMember, ProjectRole, User,
+};
 
 /// Project service for managing projects
 pub struct ProjectService {
     db: PgPool,
+    /// Cap on analysis questions per feedback type
+    max_analysis_questions: usize,
 }
 
 impl ProjectService {
-    pub fn new(db: PgPool) -> Self {
-        Self { db }
+    pub fn new(db: PgPool, max_analysis_questions: usize) -> Self {
+        Self {
+            db,
+            max_analysis_questions,
+        }
     }
 
     /// Create a new project
+    #[allow(clippy::too_many_arguments)]
     pub async fn create(
         &self,
         owner_id: Uuid,
@@ -25,13 +36,36 @@ impl ProjectService {
         require_auth: bool,
         is_active: bool,
         analysis_questions: Option<AnalysisQuestions>,
+        outcome_mapping: Option<OutcomeMapping>,
+        slack_webhook_url: Option<&str>,
+        discord_webhook_url: Option<&str>,
+        report_language: Option<&str>,
+        jira: Option<JiraSettings>,
+        gemini_params: Option<GeminiParams>,
+        include_prior_sessions: bool,
+        max_duration_seconds: Option<i32>,
     ) -> Result<Project> {
-        let questions = analysis_questions.unwrap_or_default();
+        let questions = match analysis_questions {
+            Some(questions) => questions,
+            None => self.question_template(owner_id).await?.unwrap_or_default(),
+        };
+        let questions = questions
+            .normalized(self.max_analysis_questions)
+            .map_err(AppError::bad_request)?;
         let settings = serde_json::json!({
             "require_auth": require_auth,
             "analysis_questions": questions,
+            "outcome_mapping": outcome_mapping.unwrap_or_default(),
+            "slack_webhook_url": slack_webhook_url.map(str::trim).filter(|url| !url.is_empty()),
+            "discord_webhook_url": discord_webhook_url.map(str::trim).filter(|url| !url.is_empty()),
+            "report_language": report_language.map(str::trim).filter(|l| !l.is_empty()),
+            "jira": jira,
+            "gemini_params": gemini_params.map(GeminiParams::clamped),
+            "include_prior_sessions": include_prior_sessions,
+            "max_duration_seconds": max_duration_seconds.filter(|secs| *secs > 0),
         });
         let normalized_domain = Self::normalize_domain(domain);
+        Self::validate_domain(&normalized_domain)?;
 
         let project = sqlx::query_as::<_, Project>(
             r#"
@@ -51,6 +85,42 @@ impl ProjectService {
         Ok(project)
     }
 
+    /// The owner's saved default analysis questions, if any
+    pub async fn question_template(&self, owner_id: Uuid) -> Result<Option<AnalysisQuestions>> {
+        let stored = sqlx::query_scalar::<_, sqlx::types::Json<serde_json::Value>>(
+            "SELECT analysis_questions FROM question_templates WHERE owner_id = $1",
+        )
+        .bind(owner_id)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(stored.and_then(|v| serde_json::from_value::<AnalysisQuestions>(v.0).ok()))
+    }
+
+    /// Save the owner's default analysis questions for projects created without their own
+    pub async fn set_question_template(
+        &self,
+        owner_id: Uuid,
+        analysis_questions: AnalysisQuestions,
+    ) -> Result<AnalysisQuestions> {
+        let questions = analysis_questions
+            .normalized(self.max_analysis_questions)
+            .map_err(AppError::bad_request)?;
+        sqlx::query(
+            r#"
+            INSERT INTO question_templates (owner_id, analysis_questions)
+            VALUES ($1, $2)
+            ON CONFLICT (owner_id) DO UPDATE SET
+                analysis_questions = EXCLUDED.analysis_questions,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(owner_id)
+        .bind(sqlx::types::Json(&questions))
+        .execute(&self.db)
+        .await?;
+        Ok(questions)
+    }
+
     /// Get a project by ID
     pub async fn get_by_id(&self, id: Uuid) -> Result<Option<Project>> {
         let project = sqlx::query_as::<_, Project>("SELECT * FROM projects WHERE id = $1")
@@ -108,15 +178,96 @@ impl ProjectService {
         Ok(project)
     }
 
-    /// List projects for an owner
-    pub async fn list(&self, owner_id: Uuid) -> Result<Vec<Project>> {
-        let projects = sqlx::query_as::<_, Project>(
-            "SELECT * FROM projects WHERE owner_id = $1 ORDER BY created_at DESC",
+    /// Get project by ID if the user owns it or it is shared with them
+    pub async fn get_accessible(&self, id: Uuid, user_id: Uuid) -> Result<Project> {
+        self.get_with_access(id, user_id, false).await
+    }
+
+    /// Get project by ID if the user may manage it: its owner or a member with role `owner`
+    pub async fn get_managed(&self, id: Uuid, user_id: Uuid) -> Result<Project> {
+        self.get_with_access(id, user_id, true).await
+    }
+
+    async fn get_with_access(&self, id: Uuid, user_id: Uuid, manage: bool) -> Result<Project> {
+        let project = sqlx::query_as::<_, Project>(
+            r#"
+            SELECT p.* FROM projects p
+            WHERE p.id = $1 AND (
+                p.owner_id = $2
+                OR EXISTS (
+                    SELECT 1 FROM project_members m
+                    WHERE m.project_id = p.id AND m.user_id = $2
+                      AND (NOT $3 OR m.role = $4)
+                )
+            )
+            "#,
         )
-        .bind(owner_id)
+        .bind(id)
+        .bind(user_id)
+        .bind(manage)
+        .bind(ProjectRole::Owner)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::not_found("Project not found"))?;
+        Ok(project)
+    }
+
+    /// One page of the projects the user owns or is a member of, newest first, each with
+    /// its ticket count, plus the total number of matching projects
+    pub async fn list(
+        &self,
+        user_id: Uuid,
+        search: Option<&str>,
+        is_active: Option<bool>,
+        page: i32,
+        per_page: i32,
+    ) -> Result<(Vec<ProjectWithTicketCount>, i64)> {
+        let limit = per_page as i64;
+        let offset = ((page - 1) * per_page) as i64;
+
+        let projects = sqlx::query_as::<_, ProjectWithTicketCount>(
+            r#"
+            WITH page AS (
+                SELECT * FROM projects
+                WHERE id IN (SELECT accessible_project_ids($1))
+                AND ($2::varchar IS NULL OR name ILIKE '%' || $2 || '%' OR domain ILIKE '%' || $2 || '%')
+                AND ($3::boolean IS NULL OR is_active = $3)
+                ORDER BY created_at DESC, id
+                LIMIT $4 OFFSET $5
+            ),
+            counts AS (
+                SELECT project_id, COUNT(*) AS ticket_count FROM recordings
+                WHERE project_id IN (SELECT id FROM page)
+                GROUP BY project_id
+            )
+            SELECT page.*, COALESCE(counts.ticket_count, 0) AS ticket_count
+            FROM page LEFT JOIN counts ON counts.project_id = page.id
+            ORDER BY page.created_at DESC, page.id
+            "#,
+        )
+        .bind(user_id)
+        .bind(search)
+        .bind(is_active)
+        .bind(limit)
+        .bind(offset)
         .fetch_all(&self.db)
         .await?;
-        Ok(projects)
+
+        let total: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM projects
+            WHERE id IN (SELECT accessible_project_ids($1))
+            AND ($2::varchar IS NULL OR name ILIKE '%' || $2 || '%' OR domain ILIKE '%' || $2 || '%')
+            AND ($3::boolean IS NULL OR is_active = $3)
+            "#,
+        )
+        .bind(user_id)
+        .bind(search)
+        .bind(is_active)
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok((projects, total))
     }
 
     /// Update a project
@@ -130,14 +281,39 @@ impl ProjectService {
         is_active: Option<bool>,
         require_auth: Option<bool>,
         analysis_questions: Option<AnalysisQuestions>,
+        outcome_mapping: Option<OutcomeMapping>,
+        slack_webhook_url: Option<&str>,
+        discord_webhook_url: Option<&str>,
+        report_language: Option<&str>,
+        jira: Option<JiraSettings>,
+        gemini_params: Option<GeminiParams>,
+        include_prior_sessions: Option<bool>,
+        max_duration_seconds: Option<i32>,
     ) -> Result<Project> {
         tracing::info!(%id, "project update: verifying ownership");
-        // Verify ownership
-        let existing = self.get_owned(id, owner_id).await?;
+        // Verify the caller may manage the project
+        let existing = self.get_managed(id, owner_id).await?;
 
         let normalized_domain = domain.map(Self::normalize_domain);
+        if let Some(domain) = &normalized_domain {
+            Self::validate_domain(domain)?;
+        }
+        let analysis_questions = analysis_questions
+            .map(|q| q.normalized(self.max_analysis_questions))
+            .transpose()
+            .map_err(AppError::bad_request)?;
 
-        let settings = if require_auth.is_some() || analysis_questions.is_some() {
+        let settings = if require_auth.is_some()
+            || analysis_questions.is_some()
+            || outcome_mapping.is_some()
+            || slack_webhook_url.is_some()
+            || discord_webhook_url.is_some()
+            || report_language.is_some()
+            || jira.is_some()
+            || gemini_params.is_some()
+            || include_prior_sessions.is_some()
+            || max_duration_seconds.is_some()
+        {
             let mut s = existing.settings.0.clone();
             if let Some(require_auth) = require_auth {
                 s["require_auth"] = serde_json::Value::Bool(require_auth);
@@ -156,9 +332,70 @@ impl ProjectService {
             } else {
                 tracing::debug!(%id, "project update: no analysis_questions in request");
             }
+            if let Some(ref mapping) = outcome_mapping {
+                match serde_json::to_value(mapping) {
+                    Ok(value) => {
+                        s["outcome_mapping"] = value;
+                        tracing::info!(%id, "project update: merged outcome_mapping into settings");
+                    }
+                    Err(e) => {
+                        tracing::error!(%id, error = %e, "project update: failed to serialize outcome_mapping, skipping");
+                    }
+                }
+            }
+            if let Some(url) = slack_webhook_url {
+                let url = url.trim();
+                s["slack_webhook_url"] = if url.is_empty() {
+                    serde_json::Value::Null
+                } else {
+                    serde_json::Value::String(url.to_string())
+                };
+            }
+            if let Some(url) = discord_webhook_url {
+                let url = url.trim();
+                s["discord_webhook_url"] = if url.is_empty() {
+                    serde_json::Value::Null
+                } else {
+                    serde_json::Value::String(url.to_string())
+                };
+            }
+            if let Some(language) = report_language {
+                let language = language.trim();
+                s["report_language"] = if language.is_empty() {
+                    serde_json::Value::Null
+                } else {
+                    serde_json::Value::String(language.to_string())
+                };
+            }
+            if let Some(ref jira) = jira {
+                match serde_json::to_value(jira) {
+                    Ok(value) => s["jira"] = value,
+                    Err(e) => {
+                        tracing::error!(%id, error = %e, "project update: failed to serialize jira settings, skipping");
+                    }
+                }
+            }
+            if let Some(include) = include_prior_sessions {
+                s["include_prior_sessions"] = serde_json::Value::Bool(include);
+            }
+            if let Some(secs) = max_duration_seconds {
+                s["max_duration_seconds"] = if secs > 0 {
+                    serde_json::Value::from(secs)
+                } else {
+                    serde_json::Value::Null
+                };
+            }
+            if let Some(params) = gemini_params {
+                match serde_json::to_value(params.clamped()) {
+                    Ok(value) => s["gemini_params"] = value,
+                    Err(e) => {
+                        tracing::error!(%id, error = %e, "project update: failed to serialize gemini_params, skipping");
+                    }
+                }
+            }
             Some(s)
         } else {
-            tracing::info!(%id, "project update: no require_auth or analysis_questions, keeping existing settings");
+            tracing::info!(%id, "project update: no settings fields in request, keeping existing settings");
             None
         };
 
@@ -170,7 +407,7 @@ impl ProjectService {
                 is_active = COALESCE($3, is_active),
                 settings = COALESCE($4, settings),
                 updated_at = NOW()
-            WHERE id = $5 AND owner_id = $6
+            WHERE id = $5
             RETURNING *
             "#,
         )
@@ -179,13 +416,168 @@ impl ProjectService {
         .bind(is_active)
         .bind(settings)
         .bind(id)
-        .bind(owner_id)
         .fetch_one(&self.db)
         .await?;
 
         Ok(project)
     }
 
+    /// Put a project's analysis questions in the given order (see
+    /// [`AnalysisQuestions::reorder`]) and store it.
+    pub async fn reorder_questions(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        ids: &[String],
+    ) -> Result<AnalysisQuestions> {
+        let existing = self.get_managed(id, user_id).await?;
+        let mut questions = existing.analysis_questions();
+        questions.reorder(ids).map_err(AppError::bad_request)?;
+
+        let value =
+            serde_json::to_value(&questions).map_err(|e| AppError::internal(e.to_string()))?;
+        sqlx::query(
+            r#"
+            UPDATE projects SET
+                settings = jsonb_set(settings, '{analysis_questions}', $1),
+                updated_at = NOW()
+            WHERE id = $2
+            "#,
+        )
+        .bind(value)
+        .bind(id)
+        .execute(&self.db)
+        .await?;
+
+        Ok(questions)
+    }
+
+    /// Hand a project to another internal user, found by email. Only the current owner
+    /// may do this; tickets, keys and webhooks follow since they hang off the project.
+    pub async fn transfer(
+        &self,
+        id: Uuid,
+        owner_id: Uuid,
+        new_owner_email: &str,
+    ) -> Result<Project> {
+        self.get_owned(id, owner_id).await?;
+
+        let new_owner =
+            sqlx::query_as::<_, User>("SELECT * FROM users WHERE LOWER(email) = LOWER($1)")
+                .bind(new_owner_email.trim())
+                .fetch_optional(&self.db)
+                .await?
+                .ok_or_else(|| AppError::not_found("User not found"))?;
+        if !new_owner.is_internal() {
+            return Err(AppError::bad_request(
+                "Projects can only be transferred to internal users",
+            ));
+        }
+        if new_owner.id == owner_id {
+            return Err(AppError::bad_request("You already own this project"));
+        }
+
+        let project = sqlx::query_as::<_, Project>(
+            r#"
+            UPDATE projects SET owner_id = $1, updated_at = NOW()
+            WHERE id = $2 AND owner_id = $3
+            RETURNING *
+            "#,
+        )
+        .bind(new_owner.id)
+        .bind(id)
+        .bind(owner_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::not_found("Project not found"))?;
+        // The new owner no longer needs a separate membership
+        sqlx::query("DELETE FROM project_members WHERE project_id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(new_owner.id)
+            .execute(&self.db)
+            .await?;
+
+        tracing::info!(project_id = %id, from = %owner_id, to = %new_owner.id, "Project transferred");
+        Ok(project)
+    }
+
+    /// Everyone with access to a project: the owner first, then members by join date
+    pub async fn list_members(&self, project_id: Uuid) -> Result<Vec<ProjectMember>> {
+        let members = sqlx::query_as::<_, ProjectMember>(
+            r#"
+            SELECT u.id AS user_id, u.email, u.name, $2 AS role,
+                   TRUE AS is_primary_owner, p.created_at
+            FROM projects p JOIN users u ON u.id = p.owner_id
+            WHERE p.id = $1
+            UNION ALL
+            SELECT u.id, u.email, u.name, m.role, FALSE, m.created_at
+            FROM project_members m JOIN users u ON u.id = m.user_id
+            WHERE m.project_id = $1
+            ORDER BY is_primary_owner DESC, created_at ASC
+            "#,
+        )
+        .bind(project_id)
+        .bind(ProjectRole::Owner)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(members)
+    }
+
+    /// Share a project with an internal user, found by email. Re-adding an existing
+    /// member changes their role.
+    pub async fn add_member(
+        &self,
+        project_id: Uuid,
+        email: &str,
+        role: ProjectRole,
+    ) -> Result<ProjectMember> {
+        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE LOWER(email) = LOWER($1)")
+            .bind(email.trim())
+            .fetch_optional(&self.db)
+            .await?
+            .ok_or_else(|| AppError::not_found("User not found"))?;
+        if !user.is_internal() {
+            return Err(AppError::bad_request(
+                "Projects can only be shared with internal users",
+            ));
+        }
+
+        let member = sqlx::query_as::<_, ProjectMember>(
+            r#"
+            WITH upserted AS (
+                INSERT INTO project_members (project_id, user_id, role)
+                SELECT id, $2, $3 FROM projects WHERE id = $1 AND owner_id <> $2
+                ON CONFLICT (project_id, user_id) DO UPDATE SET role = EXCLUDED.role
+                RETURNING user_id, role, created_at
+            )
+            SELECT u.id AS user_id, u.email, u.name, m.role,
+                   FALSE AS is_primary_owner, m.created_at
+            FROM upserted m JOIN users u ON u.id = m.user_id
+            "#,
+        )
+        .bind(project_id)
+        .bind(user.id)
+        .bind(role)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::bad_request("The project owner is already a member"))?;
+        Ok(member)
+    }
+
+    /// Stop sharing a project with a user; the owner cannot be removed
+    pub async fn remove_member(&self, project_id: Uuid, user_id: Uuid) -> Result<()> {
+        let result =
+            sqlx::query("DELETE FROM project_members WHERE project_id = $1 AND user_id = $2")
+                .bind(project_id)
+                .bind(user_id)
+                .execute(&self.db)
+                .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Member not found"));
+        }
+        Ok(())
+    }
+
     /// Delete a project
     pub async fn delete(&self, id: Uuid, owner_id: Uuid) -> Result<()> {
         let result = sqlx::query("DELETE FROM projects WHERE id = $1 AND owner_id = $2")
@@ -211,7 +603,107 @@ impl ProjectService {
         Ok(count)
     }
 
-    fn normalize_domain(input: &str) -> String {
+    /// Aggregate analytics for a project (Gemini token usage across its analysis jobs)
+    pub async fn get_analytics(&self, project_id: Uuid) -> Result<ProjectAnalytics> {
+        let analytics = sqlx::query_as::<_, ProjectAnalytics>(
+            r#"
+            SELECT
+                $1 as project_id,
+                COUNT(j.id) FILTER (WHERE j.status = 'completed') as analyzed_jobs,
+                COALESCE(SUM(j.prompt_tokens), 0)::BIGINT as prompt_tokens,
+                COALESCE(SUM(j.candidates_tokens), 0)::BIGINT as candidates_tokens,
+                COALESCE(SUM(j.total_tokens), 0)::BIGINT as total_tokens
+            FROM analysis_jobs j
+            JOIN recordings r ON j.recording_id = r.id
+            WHERE r.project_id = $1
+            "#,
+        )
+        .bind(project_id)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(analytics)
+    }
+
+    /// Ticket trends for an accessible project over `[from, to)`, bucketed by UTC day or week.
+    /// Every bucket in the window is present, with zero counts when nothing happened.
+    pub async fn get_trends(
+        &self,
+        project_id: Uuid,
+        owner_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        bucket: AnalyticsBucket,
+    ) -> Result<ProjectTrends> {
+        let project = self.get_accessible(project_id, owner_id).await?;
+
+        let series = sqlx::query_as::<_, TrendPoint>(
+            r#"
+            WITH buckets AS (
+                SELECT generate_series(
+                    date_trunc($2, $3 AT TIME ZONE 'UTC'),
+                    ($4 AT TIME ZONE 'UTC') - INTERVAL '1 microsecond',
+                    ('1 ' || $2)::interval
+                ) AS bucket
+            ),
+            created AS (
+                SELECT date_trunc($2, r.created_at AT TIME ZONE 'UTC') AS bucket,
+                       COUNT(*) AS created,
+                       AVG(rp.confidence)::FLOAT8 AS avg_confidence
+                FROM recordings r
+                LEFT JOIN reports rp ON rp.recording_id = r.id
+                WHERE r.project_id = $1 AND r.created_at >= $3 AND r.created_at < $4
+                GROUP BY 1
+            ),
+            resolved AS (
+                SELECT date_trunc($2, r.closed_at AT TIME ZONE 'UTC') AS bucket,
+                       COUNT(*) AS resolved
+                FROM recordings r
+                WHERE r.project_id = $1 AND r.ticket_status = 'resolved'
+                AND r.closed_at >= $3 AND r.closed_at < $4
+                GROUP BY 1
+            )
+            SELECT b.bucket AT TIME ZONE 'UTC' AS bucket_start,
+                   COALESCE(c.created, 0) AS created,
+                   COALESCE(x.resolved, 0) AS resolved,
+                   c.avg_confidence
+            FROM buckets b
+            LEFT JOIN created c ON c.bucket = b.bucket
+            LEFT JOIN resolved x ON x.bucket = b.bucket
+            ORDER BY b.bucket
+            "#,
+        )
+        .bind(project.id)
+        .bind(bucket.as_str())
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.db)
+        .await?;
+
+        let by_feedback_type = sqlx::query_as::<_, FeedbackTypeCount>(
+            r#"
+            SELECT feedback_type, COUNT(*) AS count
+            FROM recordings
+            WHERE project_id = $1 AND created_at >= $2 AND created_at < $3
+            GROUP BY feedback_type
+            ORDER BY feedback_type
+            "#,
+        )
+        .bind(project.id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(ProjectTrends {
+            bucket,
+            from,
+            to,
+            series,
+            by_feedback_type,
+        })
+    }
+
+    pub(crate) fn normalize_domain(input: &str) -> String {
         let mut d = input.trim().to_lowercase();
         if let Some(rest) = d.strip_prefix("https://") {
             d = rest.to_string();
@@ -224,4 +716,341 @@ impl ProjectService {
         // Keep path (e.g. localhost:8080/dummy) so project settings domain saves correctly
         d.trim_end_matches('.').to_string()
     }
+
+    /// Reject normalized domains the widget could never match. The host must be
+    /// `localhost`, an IPv4 address or a dotted hostname; a port and path are allowed.
+    pub(crate) fn validate_domain(normalized: &str) -> Result<()> {
+        let invalid = || AppError::bad_request(format!("Invalid domain: {:?}", normalized));
+        let authority = normalized.split(['/', '?', '#']).next().unwrap_or_default();
+        let host = match authority.split_once(':') {
+            Some((host, port)) => {
+                port.parse::<u16>()
+                    .ok()
+                    .filter(|p| *p > 0)
+                    .ok_or_else(invalid)?;
+                host
+            }
+            None => authority,
+        };
+        if host == "localhost" || host.parse::<std::net::Ipv4Addr>().is_ok() {
+            return Ok(());
+        }
+
+        let labels: Vec<&str> = host.split('.').collect();
+        let valid_label = |label: &&str| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+        };
+        let tld_ok = labels
+            .last()
+            .is_some_and(|tld| !tld.chars().all(|c| c.is_ascii_digit()));
+        if host.len() > 253 || labels.len() < 2 || !labels.iter().all(valid_label) || !tld_ok {
+            return Err(invalid());
+        }
+        Ok(())
+    }
+}
+
+/// Project row with the number of tickets submitted to it
+#[derive(Debug, sqlx::FromRow)]
+pub struct ProjectWithTicketCount {
+    #[sqlx(flatten)]
+    pub project: Project,
+    pub ticket_count: i64,
+}
+
+/// Project analytics (token usage totals for cost reporting)
+#[derive(Debug, sqlx::FromRow, serde::Serialize)]
+pub struct ProjectAnalytics {
+    pub project_id: Uuid,
+    pub analyzed_jobs: i64,
+    pub prompt_tokens: i64,
+    pub candidates_tokens: i64,
+    pub total_tokens: i64,
+}
+
+/// Ticket activity over a time window, for dashboard charts
+#[derive(Debug, serde::Serialize)]
+pub struct ProjectTrends {
+    pub bucket: AnalyticsBucket,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub series: Vec<TrendPoint>,
+    pub by_feedback_type: Vec<FeedbackTypeCount>,
+}
+
+/// One bucket of a trend series; `avg_confidence` covers tickets created in the bucket
+#[derive(Debug, sqlx::FromRow, serde::Serialize)]
+pub struct TrendPoint {
+    pub bucket_start: DateTime<Utc>,
+    pub created: i64,
+    pub resolved: i64,
+    pub avg_confidence: Option<f64>,
+}
+
+/// Tickets created in the window with a given feedback type
+#[derive(Debug, sqlx::FromRow, serde::Serialize)]
+pub struct FeedbackTypeCount {
+    pub feedback_type: FeedbackType,
+    pub count: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{seed_project, seed_user};
+
+    fn check(domain: &str) -> bool {
+        ProjectService::validate_domain(&ProjectService::normalize_domain(domain)).is_ok()
+    }
+
+    #[test]
+    fn plausible_domains_are_accepted() {
+        for domain in [
+            "example.com",
+            "https://www.Shop.Example.com/",
+            "app.example.co.uk",
+            "my-app.example.io:8443",
+            "localhost",
+            "localhost:8080/dummy",
+            "http://127.0.0.1:3000",
+            "staging.example.com/path?x=1",
+            "xn--bcher-kva.de",
+        ] {
+            assert!(check(domain), "{domain}");
+        }
+    }
+
+    #[test]
+    fn garbage_domains_are_rejected() {
+        for domain in [
+            "",
+            "not a domain",
+            "http://",
+            "https:///path",
+            "example",
+            "-bad.example.com",
+            "bad-.example.com",
+            "example..com",
+            "example.com:notaport",
+            "example.com:0",
+            "example.com:70000",
+            "under_score.example.com",
+            "10.0.0",
+        ] {
+            assert!(!check(domain), "{domain}");
+        }
+    }
+
+    /// Owner with a project; returns (owner_id, project_id)
+    async fn seed_owned_project(pool: &PgPool) -> (Uuid, Uuid) {
+        let owner_id = seed_user(pool, "owner@example.com", "internal").await.id;
+        let project_id = seed_project(pool, owner_id).await;
+        (owner_id, project_id)
+    }
+
+    /// Ticket created (and optionally closed) at the given times, with a report when
+    /// `confidence` is set
+    async fn seed_dated_ticket(
+        pool: &PgPool,
+        project_id: Uuid,
+        owner_id: Uuid,
+        feedback_type: &str,
+        created_at: &str,
+        closed_at: Option<&str>,
+        confidence: Option<i32>,
+    ) {
+        let created_at: DateTime<Utc> = created_at.parse().unwrap();
+        let closed_at: Option<DateTime<Utc>> = closed_at.map(|c| c.parse().unwrap());
+        let ticket_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO recordings (project_id, customer_id, status, feedback_type, ticket_status, created_at, closed_at)
+            VALUES ($1, $2, 'analyzed', $3, CASE WHEN $5::timestamptz IS NULL THEN 'open' ELSE 'resolved' END, $4, $5)
+            RETURNING id
+            "#,
+        )
+        .bind(project_id)
+        .bind(owner_id)
+        .bind(feedback_type)
+        .bind(created_at)
+        .bind(closed_at)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        if let Some(confidence) = confidence {
+            sqlx::query(
+                "INSERT INTO reports (recording_id, outcome, confidence) VALUES ($1, 'success', $2)",
+            )
+            .bind(ticket_id)
+            .bind(confidence)
+            .execute(pool)
+            .await
+            .unwrap();
+        }
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn trends_bucket_tickets_by_day(pool: PgPool) {
+        let (owner_id, project_id) = seed_owned_project(&pool).await;
+        let day = |d: u32, h: u32| format!("2026-10-{:02}T{:02}:00:00Z", d, h);
+        for (feedback_type, created, closed, confidence) in [
+            ("bug", day(1, 9), Some(day(2, 10)), Some(80)),
+            ("bug", day(1, 18), None, Some(60)),
+            ("idea", day(3, 12), Some(day(3, 15)), None),
+            // Outside the window
+            ("bug", day(9, 12), None, None),
+        ] {
+            seed_dated_ticket(
+                &pool,
+                project_id,
+                owner_id,
+                feedback_type,
+                &created,
+                closed.as_deref(),
+                confidence,
+            )
+            .await;
+        }
+
+        let trends = ProjectService::new(pool.clone(), 15)
+            .get_trends(
+                project_id,
+                owner_id,
+                day(1, 0).parse().unwrap(),
+                day(5, 0).parse().unwrap(),
+                AnalyticsBucket::Day,
+            )
+            .await
+            .unwrap();
+
+        let counts: Vec<(i64, i64)> = trends
+            .series
+            .iter()
+            .map(|p| (p.created, p.resolved))
+            .collect();
+        assert_eq!(counts, vec![(2, 0), (0, 1), (1, 1), (0, 0)]);
+        assert_eq!(
+            trends.series[0].bucket_start,
+            day(1, 0).parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(trends.series[0].avg_confidence, Some(70.0));
+        assert_eq!(trends.series[1].avg_confidence, None);
+
+        let by_type: Vec<(FeedbackType, i64)> = trends
+            .by_feedback_type
+            .iter()
+            .map(|c| (c.feedback_type, c.count))
+            .collect();
+        assert_eq!(
+            by_type,
+            vec![(FeedbackType::Bug, 2), (FeedbackType::Idea, 1)]
+        );
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn trends_bucket_by_week_and_require_ownership(pool: PgPool) {
+        let (owner_id, project_id) = seed_owned_project(&pool).await;
+        // 2026-10-05 and 2026-10-12 are Mondays
+        for created in [
+            "2026-10-05T09:00:00Z",
+            "2026-10-11T23:00:00Z",
+            "2026-10-12T01:00:00Z",
+        ] {
+            seed_dated_ticket(&pool, project_id, owner_id, "feedback", created, None, None).await;
+        }
+        let service = ProjectService::new(pool.clone(), 15);
+        let from: DateTime<Utc> = "2026-10-05T00:00:00Z".parse().unwrap();
+        let to: DateTime<Utc> = "2026-10-19T00:00:00Z".parse().unwrap();
+
+        let trends = service
+            .get_trends(project_id, owner_id, from, to, AnalyticsBucket::Week)
+            .await
+            .unwrap();
+        let created: Vec<i64> = trends.series.iter().map(|p| p.created).collect();
+        assert_eq!(created, vec![2, 1]);
+
+        let result = service
+            .get_trends(project_id, Uuid::new_v4(), from, to, AnalyticsBucket::Week)
+            .await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn list_pages_filters_and_counts_tickets(pool: PgPool) {
+        let (owner_id, first) = seed_owned_project(&pool).await;
+        let mut ids = vec![first];
+        for (name, active) in [("Shop", true), ("Old shop", false), ("Blog", true)] {
+            let id = sqlx::query_scalar::<_, Uuid>(
+                "INSERT INTO projects (owner_id, name, domain, settings, is_active, created_at) VALUES ($1, $2, 'example.com', '{}', $3, NOW() + make_interval(secs => $4)) RETURNING id",
+            )
+            .bind(owner_id)
+            .bind(name)
+            .bind(active)
+            .bind(ids.len() as f64)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            ids.push(id);
+        }
+        for _ in 0..3 {
+            seed_dated_ticket(
+                &pool,
+                ids[1],
+                owner_id,
+                "bug",
+                "2026-03-02T10:00:00Z",
+                None,
+                None,
+            )
+            .await;
+        }
+        seed_dated_ticket(
+            &pool,
+            ids[3],
+            owner_id,
+            "bug",
+            "2026-03-02T10:00:00Z",
+            None,
+            None,
+        )
+        .await;
+        let service = ProjectService::new(pool.clone(), 15);
+
+        // Newest first: Blog, Old shop, Shop, App
+        let (page, total) = service.list(owner_id, None, None, 1, 3).await.unwrap();
+        assert_eq!(total, 4);
+        let names: Vec<&str> = page.iter().map(|p| p.project.name.as_str()).collect();
+        assert_eq!(names, ["Blog", "Old shop", "Shop"]);
+        let counts: Vec<i64> = page.iter().map(|p| p.ticket_count).collect();
+        assert_eq!(counts, [1, 0, 3]);
+        for p in &page {
+            assert_eq!(
+                p.ticket_count,
+                service.count_tickets(p.project.id).await.unwrap()
+            );
+        }
+        let (page, total) = service.list(owner_id, None, None, 2, 3).await.unwrap();
+        assert_eq!(total, 4);
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].project.id, first);
+
+        let (page, total) = service
+            .list(owner_id, None, Some(false), 1, 20)
+            .await
+            .unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(page[0].project.name, "Old shop");
+        let (page, total) = service
+            .list(owner_id, Some("SHOP"), Some(true), 1, 20)
+            .await
+            .unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(page[0].project.name, "Shop");
+    }
 }
@@ -1,27 +1,69 @@
 //! PostgreSQL-based job queue service
 
 use anyhow::{Context, Result};
-use chrono::Utc;
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgListener;
+use sqlx::{PgConnection, PgPool};
+use std::time::Duration;
 use uuid::Uuid;
 
-use crate::models::{AnalysisJob, CreateJobRequest, JobStatus};
+use crate::models::{AnalysisJob, CreateJobRequest, JobProgressStage, JobStatus};
+use crate::services::TokenUsage;
+
+/// Postgres channel notified whenever a job is enqueued
+pub const NEW_JOB_CHANNEL: &str = "analysis_jobs_new";
+
+/// Filters and page for listing analysis jobs
+#[derive(Debug, Clone)]
+pub struct JobListQuery {
+    pub status: Option<JobStatus>,
+    pub recording_id: Option<Uuid>,
+    /// Only jobs created at or after this time
+    pub created_after: Option<DateTime<Utc>>,
+    /// Only jobs created before this time
+    pub created_before: Option<DateTime<Utc>>,
+    pub page: i32,
+    pub per_page: i32,
+}
 
 pub struct QueueService {
     pool: PgPool,
+    max_retries: i32,
 }
 
 impl QueueService {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, max_retries: i32) -> Self {
+        Self { pool, max_retries }
     }
 
-    /// Create a new job and return its ID
+    /// Create a new job on its own and return its ID. The app always queues jobs with
+    /// `enqueue_in`, alongside the ticket update; tests seed jobs with this.
+    #[cfg(test)]
     pub async fn enqueue(&self, request: CreateJobRequest) -> Result<Uuid> {
+        let mut conn = self.pool.acquire().await.context("Failed to create job")?;
+        let job_id = Self::insert_job(&mut conn, &request).await?;
+        self.notify_workers(job_id).await;
+        Ok(job_id)
+    }
+
+    /// Create a job inside the caller's transaction. Workers are woken when it commits, and
+    /// nothing is queued if it rolls back.
+    pub async fn enqueue_in(conn: &mut PgConnection, request: CreateJobRequest) -> Result<Uuid> {
+        let job_id = Self::insert_job(conn, &request).await?;
+        sqlx::query("SELECT pg_notify($1, $2)")
+            .bind(NEW_JOB_CHANNEL)
+            .bind(job_id.to_string())
+            .execute(&mut *conn)
+            .await
+            .context("Failed to notify workers of new job")?;
+        Ok(job_id)
+    }
+
+    async fn insert_job(conn: &mut PgConnection, request: &CreateJobRequest) -> Result<Uuid> {
         let job_id = sqlx::query_scalar::<_, Uuid>(
             r#"
-            INSERT INTO analysis_jobs (user_id, recording_id, status, video_storage_path, video_size_bytes, prompt)
-            VALUES ($1, $2, $3, $4, $5, $6)
+            INSERT INTO analysis_jobs (user_id, recording_id, status, video_storage_path, video_size_bytes, mime_type, prompt)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
             RETURNING id
             "#,
         )
@@ -30,20 +72,44 @@ impl QueueService {
         .bind(JobStatus::Pending)
         .bind(&request.video_storage_path)
         .bind(request.video_size_bytes)
+        .bind(&request.mime_type)
         .bind(&request.prompt)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *conn)
         .await
         .context("Failed to create job")?;
-
         Ok(job_id)
     }
 
+    /// Wake idle workers; they fall back to a safety poll if this is missed
+    async fn notify_workers(&self, job_id: Uuid) {
+        if let Err(e) = sqlx::query("SELECT pg_notify($1, $2)")
+            .bind(NEW_JOB_CHANNEL)
+            .bind(job_id.to_string())
+            .execute(&self.pool)
+            .await
+        {
+            tracing::warn!(%job_id, "Failed to notify workers of new job: {}", e);
+        }
+    }
+
+    /// Subscribe to new-job notifications (one listener per worker)
+    pub async fn listen(&self) -> Result<PgListener> {
+        let mut listener = PgListener::connect_with(&self.pool)
+            .await
+            .context("Failed to connect job listener")?;
+        listener
+            .listen(NEW_JOB_CHANNEL)
+            .await
+            .context("Failed to listen for new jobs")?;
+        Ok(listener)
+    }
+
     /// Dequeue the next pending job (for workers)
     pub async fn dequeue(&self) -> Result<Option<AnalysisJob>> {
         let job = sqlx::query_as::<_, AnalysisJob>(
             r#"
             UPDATE analysis_jobs
-            SET status = $1, started_at = $2
+            SET status = $1, started_at = $2, heartbeat_at = $2, progress_stage = NULL
             WHERE id = (
                 SELECT id FROM analysis_jobs
                 WHERE status = $3
@@ -64,8 +130,120 @@ impl QueueService {
         Ok(job)
     }
 
+    /// Record which stage of processing a job has reached
+    pub async fn set_progress(&self, job_id: Uuid, stage: JobProgressStage) -> Result<()> {
+        sqlx::query("UPDATE analysis_jobs SET progress_stage = $1 WHERE id = $2")
+            .bind(stage)
+            .bind(job_id)
+            .execute(&self.pool)
+            .await
+            .context("Failed to record job progress")?;
+
+        Ok(())
+    }
+
+    /// Record that the worker running this job is still alive
+    pub async fn heartbeat(&self, job_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE analysis_jobs SET heartbeat_at = $1 WHERE id = $2 AND status = $3")
+            .bind(Utc::now())
+            .bind(job_id)
+            .bind(JobStatus::Processing)
+            .execute(&self.pool)
+            .await
+            .context("Failed to record job heartbeat")?;
+
+        Ok(())
+    }
+
+    /// Put a job the worker is abandoning (e.g. on shutdown) back to pending.
+    /// Unlike a failure or a stale reclaim, this does not count as an attempt.
+    pub async fn release_job(&self, job_id: Uuid) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE analysis_jobs
+            SET status = $1, started_at = NULL, heartbeat_at = NULL, progress_stage = NULL
+            WHERE id = $2 AND status = $3
+            "#,
+        )
+        .bind(JobStatus::Pending)
+        .bind(job_id)
+        .bind(JobStatus::Processing)
+        .execute(&self.pool)
+        .await
+        .context("Failed to release job")?;
+
+        let _ = sqlx::query("SELECT pg_notify($1, '')")
+            .bind(NEW_JOB_CHANNEL)
+            .execute(&self.pool)
+            .await;
+
+        Ok(())
+    }
+
+    /// Reclaim processing jobs whose worker stopped heartbeating (e.g. crashed).
+    /// Each counts as a failed attempt: the job goes back to pending, or to the
+    /// dead letter once the retry cap is hit. Returns the reclaimed jobs.
+    pub async fn recover_stale_jobs(&self, stale_after: Duration) -> Result<Vec<AnalysisJob>> {
+        let cutoff = Utc::now()
+            - chrono::Duration::from_std(stale_after).context("Invalid stale threshold")?;
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to recover stale jobs")?;
+
+        let stale = sqlx::query_as::<_, (Uuid, i32)>(
+            r#"
+            SELECT id, retry_count FROM analysis_jobs
+            WHERE status = $1 AND COALESCE(heartbeat_at, started_at, created_at) < $2
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .bind(JobStatus::Processing)
+        .bind(cutoff)
+        .fetch_all(&mut *tx)
+        .await
+        .context("Failed to recover stale jobs")?;
+
+        let mut recovered = Vec::with_capacity(stale.len());
+        for (job_id, retry_count) in stale {
+            let status = match status_after_failure(retry_count, self.max_retries) {
+                JobStatus::DeadLetter => JobStatus::DeadLetter,
+                _ => JobStatus::Pending,
+            };
+            let job = sqlx::query_as::<_, AnalysisJob>(
+                r#"
+                UPDATE analysis_jobs
+                SET status = $1, retry_count = retry_count + 1,
+                    error_message = 'Worker stopped responding',
+                    started_at = NULL, heartbeat_at = NULL,
+                    completed_at = CASE WHEN $1 = 'dead_letter' THEN $2 ELSE NULL END
+                WHERE id = $3
+                RETURNING *
+                "#,
+            )
+            .bind(status)
+            .bind(Utc::now())
+            .bind(job_id)
+            .fetch_one(&mut *tx)
+            .await
+            .context("Failed to recover stale jobs")?;
+            recovered.push(job);
+        }
+
+        tx.commit().await.context("Failed to recover stale jobs")?;
+
+        if recovered.iter().any(|j| j.status == JobStatus::Pending) {
+            let _ = sqlx::query("SELECT pg_notify($1, '')")
+                .bind(NEW_JOB_CHANNEL)
+                .execute(&self.pool)
+                .await;
+        }
+
+        Ok(recovered)
+    }
+
     /// Get job by ID
-    #[allow(dead_code)] // Useful for admin/debugging endpoints
     pub async fn get_job(&self, job_id: Uuid) -> Result<Option<AnalysisJob>> {
         let job = sqlx::query_as::<_, AnalysisJob>("SELECT * FROM analysis_jobs WHERE id = $1")
             .bind(job_id)
@@ -76,8 +254,7 @@ impl QueueService {
         Ok(job)
     }
 
-    /// Get job by recording ID
-    #[allow(dead_code)] // Useful for admin/debugging endpoints
+    /// Latest job for a recording
     pub async fn get_job_by_recording(&self, recording_id: Uuid) -> Result<Option<AnalysisJob>> {
         let job = sqlx::query_as::<_, AnalysisJob>(
             "SELECT * FROM analysis_jobs WHERE recording_id = $1 ORDER BY created_at DESC LIMIT 1",
@@ -90,18 +267,27 @@ impl QueueService {
         Ok(job)
     }
 
-    /// Mark job as completed with result
-    pub async fn complete_job(&self, job_id: Uuid, result: String) -> Result<()> {
+    /// Mark job as completed with result and the Gemini tokens it consumed
+    pub async fn complete_job(
+        &self,
+        job_id: Uuid,
+        result: String,
+        usage: TokenUsage,
+    ) -> Result<()> {
         sqlx::query(
             r#"
             UPDATE analysis_jobs
-            SET status = $1, analysis_result = $2, completed_at = $3
-            WHERE id = $4
+            SET status = $1, analysis_result = $2, completed_at = $3,
+                prompt_tokens = $4, candidates_tokens = $5, total_tokens = $6
+            WHERE id = $7
             "#,
         )
         .bind(JobStatus::Completed)
         .bind(&result)
         .bind(Utc::now())
+        .bind(usage.prompt_tokens)
+        .bind(usage.candidates_tokens)
+        .bind(usage.total_tokens)
         .bind(job_id)
         .execute(&self.pool)
         .await
@@ -110,8 +296,21 @@ impl QueueService {
         Ok(())
     }
 
-    /// Mark job as failed with error message
-    pub async fn fail_job(&self, job_id: Uuid, error: String) -> Result<()> {
+    /// Mark job as failed with error message.
+    /// Once the job has failed `max_retries` times it is dead-lettered instead.
+    /// Returns the status the job ended up in.
+    pub async fn fail_job(&self, job_id: Uuid, error: String) -> Result<JobStatus> {
+        let mut tx = self.pool.begin().await.context("Failed to fail job")?;
+
+        let retry_count = sqlx::query_scalar::<_, i32>(
+            "SELECT retry_count FROM analysis_jobs WHERE id = $1 FOR UPDATE",
+        )
+        .bind(job_id)
+        .fetch_one(&mut *tx)
+        .await
+        .context("Failed to fail job")?;
+        let status = status_after_failure(retry_count, self.max_retries);
+
         sqlx::query(
             r#"
             UPDATE analysis_jobs
@@ -119,34 +318,321 @@ impl QueueService {
             WHERE id = $4
             "#,
         )
-        .bind(JobStatus::Failed)
+        .bind(status)
         .bind(&error)
         .bind(Utc::now())
         .bind(job_id)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await
         .context("Failed to fail job")?;
 
-        Ok(())
+        tx.commit().await.context("Failed to fail job")?;
+        Ok(status)
     }
 
-    /// Reset a failed job back to pending for retry
-    #[allow(dead_code)] // Useful for admin retry functionality
-    pub async fn retry_job(&self, job_id: Uuid) -> Result<()> {
-        sqlx::query(
+    /// Dead-lettered jobs for tickets owned by the given user, most recent first
+    pub async fn list_dead_letter(&self, owner_id: Uuid) -> Result<Vec<AnalysisJob>> {
+        let jobs = sqlx::query_as::<_, AnalysisJob>(
+            r#"
+            SELECT j.* FROM analysis_jobs j
+            JOIN recordings r ON r.id = j.recording_id
+            WHERE j.status = $1 AND (
+                r.project_id IN (SELECT accessible_project_ids($2))
+                OR r.session_id IN (SELECT id FROM sessions WHERE owner_id = $2)
+            )
+            ORDER BY j.completed_at DESC NULLS LAST
+            "#,
+        )
+        .bind(JobStatus::DeadLetter)
+        .bind(owner_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list dead-letter jobs")?;
+
+        Ok(jobs)
+    }
+
+    /// One page of the jobs for tickets owned by the given user, newest first, with the
+    /// total number of matching jobs
+    pub async fn list_jobs(
+        &self,
+        owner_id: Uuid,
+        query: &JobListQuery,
+    ) -> Result<(Vec<AnalysisJob>, i64)> {
+        const FILTER: &str = r#"
+            FROM analysis_jobs j
+            JOIN recordings r ON r.id = j.recording_id
+            WHERE (
+                r.project_id IN (SELECT accessible_project_ids($1))
+                OR r.session_id IN (SELECT id FROM sessions WHERE owner_id = $1)
+            )
+            AND ($2::varchar IS NULL OR j.status = $2)
+            AND ($3::uuid IS NULL OR j.recording_id = $3)
+            AND ($4::timestamptz IS NULL OR j.created_at >= $4)
+            AND ($5::timestamptz IS NULL OR j.created_at < $5)
+        "#;
+
+        let jobs = sqlx::query_as::<_, AnalysisJob>(&format!(
+            "SELECT j.* {} ORDER BY j.created_at DESC, j.id LIMIT $6 OFFSET $7",
+            FILTER
+        ))
+        .bind(owner_id)
+        .bind(query.status)
+        .bind(query.recording_id)
+        .bind(query.created_after)
+        .bind(query.created_before)
+        .bind(query.per_page as i64)
+        .bind(((query.page - 1) * query.per_page) as i64)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list jobs")?;
+
+        let total: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) {}", FILTER))
+            .bind(owner_id)
+            .bind(query.status)
+            .bind(query.recording_id)
+            .bind(query.created_after)
+            .bind(query.created_before)
+            .fetch_one(&self.pool)
+            .await
+            .context("Failed to count jobs")?;
+
+        Ok((jobs, total))
+    }
+
+    /// Reset a failed job back to pending and its ticket back to processing.
+    /// Returns `None` when the job is not in the failed state.
+    pub async fn retry_job(&self, job_id: Uuid) -> Result<Option<AnalysisJob>> {
+        let mut tx = self.pool.begin().await.context("Failed to retry job")?;
+
+        let job = sqlx::query_as::<_, AnalysisJob>(
             r#"
             UPDATE analysis_jobs
-            SET status = $1, error_message = NULL, started_at = NULL
+            SET status = $1, error_message = NULL, started_at = NULL, completed_at = NULL
             WHERE id = $2 AND status = $3
+            RETURNING *
             "#,
         )
         .bind(JobStatus::Pending)
         .bind(job_id)
         .bind(JobStatus::Failed)
-        .execute(&self.pool)
+        .fetch_optional(&mut *tx)
         .await
         .context("Failed to retry job")?;
 
-        Ok(())
+        if let Some(recording_id) = job.as_ref().and_then(|j| j.recording_id) {
+            sqlx::query(
+                "UPDATE recordings SET status = 'processing', updated_at = NOW() WHERE id = $1",
+            )
+            .bind(recording_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to retry job")?;
+        }
+
+        tx.commit().await.context("Failed to retry job")?;
+        if job.is_some() {
+            self.notify_workers(job_id).await;
+        }
+        Ok(job)
+    }
+}
+
+/// Status for a job that just failed, given how many times it had failed before
+fn status_after_failure(retry_count: i32, max_retries: i32) -> JobStatus {
+    if retry_count + 1 >= max_retries {
+        JobStatus::DeadLetter
+    } else {
+        JobStatus::Failed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn failure_below_limit_stays_failed() {
+        assert_eq!(status_after_failure(0, 3), JobStatus::Failed);
+        assert_eq!(status_after_failure(1, 3), JobStatus::Failed);
+    }
+
+    #[test]
+    fn failure_reaching_limit_is_dead_lettered() {
+        assert_eq!(status_after_failure(2, 3), JobStatus::DeadLetter);
+        assert_eq!(status_after_failure(5, 3), JobStatus::DeadLetter);
+    }
+
+    #[test]
+    fn single_attempt_limit_dead_letters_first_failure() {
+        assert_eq!(status_after_failure(0, 1), JobStatus::DeadLetter);
+    }
+
+    async fn enqueue_test_job(queue: &QueueService) -> Uuid {
+        queue
+            .enqueue(CreateJobRequest {
+                video_storage_path: "test/video.webm".to_string(),
+                video_size_bytes: 1024,
+                mime_type: None,
+                prompt: None,
+                user_id: None,
+                recording_id: None,
+            })
+            .await
+            .unwrap()
+    }
+
+    /// Several workers dequeuing at once never claim the same job, which is what lets
+    /// `WORKER_CONCURRENCY` > 1 run safely
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn concurrent_dequeue_claims_each_job_once(pool: PgPool) {
+        let queue = std::sync::Arc::new(QueueService::new(pool, 3));
+        let mut enqueued = HashSet::new();
+        for _ in 0..20 {
+            enqueued.insert(enqueue_test_job(&queue).await);
+        }
+
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let queue = queue.clone();
+                tokio::spawn(async move {
+                    let mut claimed = Vec::new();
+                    while let Some(job) = queue.dequeue().await.unwrap() {
+                        assert_eq!(job.status, JobStatus::Processing);
+                        claimed.push(job.id);
+                    }
+                    claimed
+                })
+            })
+            .collect();
+        let mut claimed = Vec::new();
+        for worker in workers {
+            claimed.extend(worker.await.unwrap());
+        }
+
+        let unique: HashSet<_> = claimed.iter().copied().collect();
+        assert_eq!(claimed.len(), unique.len(), "a job was dequeued twice");
+        assert_eq!(unique, enqueued);
+    }
+
+    /// Idle workers wait on `listen`; enqueueing must wake them with the new job's id
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn enqueue_wakes_listeners(pool: PgPool) {
+        let queue = QueueService::new(pool, 3);
+        let mut listener = queue.listen().await.unwrap();
+        let waiter = tokio::spawn(async move {
+            tokio::time::timeout(Duration::from_secs(5), listener.recv()).await
+        });
+
+        let job_id = enqueue_test_job(&queue).await;
+
+        let notification = waiter
+            .await
+            .unwrap()
+            .expect("listener was not woken")
+            .unwrap();
+        assert_eq!(notification.channel(), NEW_JOB_CHANNEL);
+        assert_eq!(notification.payload(), job_id.to_string());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn recover_stale_jobs_reclaims_only_stale(pool: PgPool) {
+        let queue = QueueService::new(pool.clone(), 3);
+        let stale_id = enqueue_test_job(&queue).await;
+        let fresh_id = enqueue_test_job(&queue).await;
+        queue.dequeue().await.unwrap().unwrap();
+        queue.dequeue().await.unwrap().unwrap();
+
+        sqlx::query(
+            "UPDATE analysis_jobs SET heartbeat_at = NOW() - INTERVAL '1 hour' WHERE id = $1",
+        )
+        .bind(stale_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let recovered = queue
+            .recover_stale_jobs(Duration::from_secs(300))
+            .await
+            .unwrap();
+        assert_eq!(recovered.len(), 1);
+        assert_eq!(recovered[0].id, stale_id);
+
+        let stale = queue.get_job(stale_id).await.unwrap().unwrap();
+        assert_eq!(stale.status, JobStatus::Pending);
+        assert_eq!(stale.retry_count, 1);
+        assert!(stale.heartbeat_at.is_none());
+
+        let fresh = queue.get_job(fresh_id).await.unwrap().unwrap();
+        assert_eq!(fresh.status, JobStatus::Processing);
+        assert_eq!(fresh.retry_count, 0);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn retried_job_starts_its_progress_over(pool: PgPool) {
+        let queue = QueueService::new(pool.clone(), 3);
+        let job_id = enqueue_test_job(&queue).await;
+        let job = queue.dequeue().await.unwrap().unwrap();
+        assert_eq!(job.progress_stage, None);
+        queue
+            .set_progress(job_id, JobProgressStage::Analyzing)
+            .await
+            .unwrap();
+
+        queue.fail_job(job_id, "boom".to_string()).await.unwrap();
+        queue.retry_job(job_id).await.unwrap();
+        let job = queue.dequeue().await.unwrap().unwrap();
+        assert_eq!(job.id, job_id);
+        assert_eq!(job.progress_stage, None);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn recover_stale_jobs_respects_retry_cap(pool: PgPool) {
+        let queue = QueueService::new(pool.clone(), 3);
+        let job_id = enqueue_test_job(&queue).await;
+        queue.dequeue().await.unwrap().unwrap();
+
+        sqlx::query(
+            "UPDATE analysis_jobs SET retry_count = 2, heartbeat_at = NOW() - INTERVAL '1 hour' WHERE id = $1",
+        )
+        .bind(job_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let recovered = queue
+            .recover_stale_jobs(Duration::from_secs(300))
+            .await
+            .unwrap();
+        assert_eq!(recovered.len(), 1);
+        assert_eq!(recovered[0].status, JobStatus::DeadLetter);
+        assert!(recovered[0].completed_at.is_some());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn released_job_returns_to_pending_without_an_attempt(pool: PgPool) {
+        let queue = QueueService::new(pool.clone(), 3);
+        let job_id = enqueue_test_job(&queue).await;
+        queue.dequeue().await.unwrap().unwrap();
+        queue
+            .set_progress(job_id, JobProgressStage::Analyzing)
+            .await
+            .unwrap();
+
+        queue.release_job(job_id).await.unwrap();
+
+        let job = queue.get_job(job_id).await.unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Pending);
+        assert_eq!(job.retry_count, 0);
+        assert_eq!(job.progress_stage, None);
+        assert!(job.started_at.is_none());
+        assert_eq!(queue.dequeue().await.unwrap().unwrap().id, job_id);
     }
 }
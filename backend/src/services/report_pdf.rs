@@ -0,0 +1,394 @@
+//! Report PDF rendering - lays out a ticket's analysis report as a plain-text A4 document
+
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Document, Object, Stream};
+use uuid::Uuid;
+
+use crate::dto::ReportResponse;
+use crate::error::{AppError, Result};
+
+/// A4 in points
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 50.0;
+
+const TITLE_SIZE: f32 = 18.0;
+const HEADING_SIZE: f32 = 13.0;
+const BODY_SIZE: f32 = 10.0;
+/// Baseline-to-baseline distance as a multiple of the font size
+const LINE_HEIGHT: f32 = 1.4;
+/// Average Helvetica glyph width as a fraction of the font size, used for wrapping
+const AVG_CHAR_WIDTH: f32 = 0.5;
+
+#[derive(Clone, Copy)]
+enum Font {
+    Regular,
+    Bold,
+}
+
+impl Font {
+    fn resource_name(self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+        }
+    }
+}
+
+/// Render a report as PDF bytes
+pub fn render_report_pdf(ticket_id: Uuid, report: &ReportResponse) -> Result<Vec<u8>> {
+    let mut layout = Layout::new();
+    layout.line("Ortrace analysis report", Font::Bold, TITLE_SIZE, 0.0);
+    layout.paragraph(&format!("Ticket {}", ticket_id), Font::Regular, BODY_SIZE);
+
+    let summary = &report.executive_summary;
+    layout.heading("Executive summary");
+    layout.paragraph(
+        &format!(
+            "Outcome: {}    Confidence: {}%",
+            summary.outcome, summary.confidence
+        ),
+        Font::Bold,
+        BODY_SIZE,
+    );
+    layout.paragraph(&summary.overview, Font::Regular, BODY_SIZE);
+    if let Some(sentiment) = report.sentiment {
+        let score = report
+            .frustration_score
+            .map(|score| format!("    Frustration: {}/100", score))
+            .unwrap_or_default();
+        layout.paragraph(
+            &format!("Sentiment: {}{}", sentiment, score),
+            Font::Regular,
+            BODY_SIZE,
+        );
+    }
+
+    let metrics = &report.metrics;
+    layout.heading("Metrics");
+    layout.bullet(&format!(
+        "Task completion rate: {}%",
+        metrics.task_completion_rate
+    ));
+    layout.bullet(&format!(
+        "Total hesitation time: {}s",
+        metrics.total_hesitation_time
+    ));
+    layout.bullet(&format!("Retries: {}", metrics.retries_count));
+    if let Some(point) = &metrics.abandonment_point {
+        layout.bullet(&format!("Abandonment point: {}", point));
+    }
+
+    if !report.issues.is_empty() {
+        layout.heading("Issues");
+        for (n, issue) in report.issues.iter().enumerate() {
+            layout.paragraph(
+                &format!(
+                    "{}. {} ({}, {})",
+                    n + 1,
+                    issue.title,
+                    issue.severity,
+                    issue.issue_status
+                ),
+                Font::Bold,
+                BODY_SIZE,
+            );
+            if let Some(observed) = &issue.observed_behavior {
+                layout.bullet(&format!("Observed: {}", observed));
+            }
+            if let Some(expected) = &issue.expected_behavior {
+                layout.bullet(&format!("Expected: {}", expected));
+            }
+            for (step, text) in issue.reproduction_steps.iter().enumerate() {
+                layout.bullet(&format!("Step {}: {}", step + 1, text));
+            }
+            for impact in &issue.impact {
+                layout.bullet(&format!("Impact: {}", impact));
+            }
+            if let Some(url) = &issue.external_ticket_url {
+                layout.bullet(&format!("Tracked at: {}", url));
+            }
+        }
+    }
+
+    if !report.question_analysis.is_empty() {
+        layout.heading("Question analysis");
+        for qa in &report.question_analysis {
+            layout.paragraph(&qa.question, Font::Bold, BODY_SIZE);
+            layout.paragraph(
+                &format!("{} (confidence {}%)", qa.answer, qa.confidence),
+                Font::Regular,
+                BODY_SIZE,
+            );
+            for observation in &qa.observations {
+                layout.bullet(observation);
+            }
+        }
+    }
+
+    for (title, items) in [
+        ("Suggested actions", &report.suggested_actions),
+        ("Possible solutions", &report.possible_solutions),
+    ] {
+        if !items.is_empty() {
+            layout.heading(title);
+            for item in items {
+                layout.bullet(item);
+            }
+        }
+    }
+
+    layout.into_pdf()
+}
+
+/// Top-to-bottom text flow that starts a new page when one fills up
+struct Layout {
+    pages: Vec<Vec<Operation>>,
+    /// Baseline of the next line on the current page
+    y: f32,
+}
+
+impl Layout {
+    fn new() -> Self {
+        Self {
+            pages: vec![Vec::new()],
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    fn heading(&mut self, text: &str) {
+        self.y -= HEADING_SIZE * 0.6;
+        self.paragraph(text, Font::Bold, HEADING_SIZE);
+    }
+
+    fn bullet(&mut self, text: &str) {
+        let indent = BODY_SIZE * 1.5;
+        for (i, line) in wrap(text, BODY_SIZE, indent).into_iter().enumerate() {
+            if i == 0 {
+                self.line("-", Font::Regular, BODY_SIZE, BODY_SIZE * 0.5);
+                self.y += BODY_SIZE * LINE_HEIGHT;
+            }
+            self.line(&line, Font::Regular, BODY_SIZE, indent);
+        }
+    }
+
+    fn paragraph(&mut self, text: &str, font: Font, size: f32) {
+        for line in wrap(text, size, 0.0) {
+            self.line(&line, font, size, 0.0);
+        }
+        self.y -= size * 0.4;
+    }
+
+    fn line(&mut self, text: &str, font: Font, size: f32, indent: f32) {
+        if self.y < MARGIN + size {
+            self.pages.push(Vec::new());
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+        self.y -= size;
+        let page = self.pages.last_mut().expect("layout always has a page");
+        page.extend([
+            Operation::new("BT", vec![]),
+            Operation::new("Tf", vec![font.resource_name().into(), size.into()]),
+            Operation::new("Td", vec![(MARGIN + indent).into(), self.y.into()]),
+            Operation::new("Tj", vec![Object::string_literal(win_ansi(text))]),
+            Operation::new("ET", vec![]),
+        ]);
+        self.y -= size * (LINE_HEIGHT - 1.0);
+    }
+
+    fn into_pdf(self) -> Result<Vec<u8>> {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let font = |name: &str| {
+            dictionary! {
+                "Type" => "Font",
+                "Subtype" => "Type1",
+                "BaseFont" => name.to_string(),
+                "Encoding" => "WinAnsiEncoding",
+            }
+        };
+        let regular_id = doc.add_object(font("Helvetica"));
+        let bold_id = doc.add_object(font("Helvetica-Bold"));
+        let resources_id = doc.add_object(dictionary! {
+            "Font" => dictionary! {
+                "F1" => regular_id,
+                "F2" => bold_id,
+            },
+        });
+
+        let mut kids = Vec::with_capacity(self.pages.len());
+        for operations in self.pages {
+            let content = Content { operations }
+                .encode()
+                .map_err(|e| AppError::internal(format!("Failed to encode PDF page: {}", e)))?;
+            let content_id = doc.add_object(Stream::new(dictionary! {}, content));
+            let page_id = doc.add_object(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "Contents" => content_id,
+            });
+            kids.push(Object::from(page_id));
+        }
+        let count = kids.len() as i64;
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => kids,
+                "Count" => count,
+                "Resources" => resources_id,
+                "MediaBox" => vec![0.into(), 0.into(), PAGE_WIDTH.into(), PAGE_HEIGHT.into()],
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+        doc.compress();
+
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes)
+            .map_err(|e| AppError::internal(format!("Failed to write PDF: {}", e)))?;
+        Ok(bytes)
+    }
+}
+
+/// Split text into lines that fit the page width, breaking on whitespace (and inside words
+/// longer than a line). Blank input yields no lines.
+fn wrap(text: &str, size: f32, indent: f32) -> Vec<String> {
+    let max_chars = ((PAGE_WIDTH - 2.0 * MARGIN - indent) / (size * AVG_CHAR_WIDTH)) as usize;
+    let mut lines = Vec::new();
+    for source_line in text.lines() {
+        let mut line = String::new();
+        for word in source_line.split_whitespace() {
+            let mut word: Vec<char> = word.chars().collect();
+            while word.len() > max_chars {
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                lines.push(word.drain(..max_chars).collect());
+            }
+            let word: String = word.into_iter().collect();
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > max_chars {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&word);
+        }
+        if !line.is_empty() {
+            lines.push(line);
+        }
+    }
+    lines
+}
+
+/// Encode for the standard fonts' WinAnsiEncoding: typographic punctuation is mapped to
+/// ASCII and anything else outside Latin-1 becomes `?`
+fn win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            '\u{2018}' | '\u{2019}' => b'\'',
+            '\u{201C}' | '\u{201D}' => b'"',
+            '\u{2013}' | '\u{2014}' => b'-',
+            '\u{2022}' => b'*',
+            c if (c as u32) < 0x20 => b' ',
+            c if (c as u32) <= 0xFF && !(0x7F..0xA0).contains(&(c as u32)) => c as u8,
+            _ => b'?',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dto::{ExecutiveSummary, IssueResponse, ReportMetrics, SeverityBreakdown};
+    use crate::models::{IssueSeverity, IssueStatus, ReportOutcome, ReportSentiment};
+
+    fn report(issue_count: usize) -> ReportResponse {
+        ReportResponse {
+            id: Uuid::new_v4(),
+            recording_id: Uuid::new_v4(),
+            executive_summary: ExecutiveSummary {
+                outcome: ReportOutcome::Partial,
+                confidence: 72,
+                overview: "The user could not finish checkout.".to_string(),
+            },
+            sentiment: Some(ReportSentiment::Frustrated),
+            frustration_score: Some(80),
+            metrics: ReportMetrics {
+                task_completion_rate: 40,
+                total_hesitation_time: 12,
+                retries_count: 3,
+                abandonment_point: Some("Payment form".to_string()),
+            },
+            issues: (0..issue_count)
+                .map(|n| IssueResponse {
+                    id: Uuid::new_v4(),
+                    title: format!("Checkout button unresponsive {}", n),
+                    severity: IssueSeverity::High,
+                    tags: vec![],
+                    observed_behavior: Some("Clicking Pay does nothing. ".repeat(10)),
+                    expected_behavior: Some("The form submits.".to_string()),
+                    evidence: vec![],
+                    screenshots: vec![],
+                    impact: vec![],
+                    reproduction_steps: vec!["Open cart".to_string(), "Click Pay".to_string()],
+                    confidence: Some(80),
+                    external_ticket_url: None,
+                    issue_status: IssueStatus::Open,
+                })
+                .collect(),
+            severity_breakdown: SeverityBreakdown {
+                high: issue_count,
+                ..SeverityBreakdown::default()
+            },
+            question_analysis: vec![],
+            suggested_actions: vec!["Show a spinner while paying".to_string()],
+            possible_solutions: vec![],
+            language: "English".to_string(),
+            edited: false,
+            edited_at: None,
+        }
+    }
+
+    #[test]
+    fn renders_a_pdf() {
+        let pdf = render_report_pdf(Uuid::new_v4(), &report(2)).unwrap();
+        assert!(pdf.starts_with(b"%PDF-"));
+        let doc = Document::load_mem(&pdf).unwrap();
+        assert_eq!(doc.get_pages().len(), 1);
+    }
+
+    #[test]
+    fn long_reports_span_pages() {
+        let pdf = render_report_pdf(Uuid::new_v4(), &report(40)).unwrap();
+        let doc = Document::load_mem(&pdf).unwrap();
+        assert!(doc.get_pages().len() > 1);
+    }
+
+    #[test]
+    fn wrap_breaks_on_whitespace_and_splits_long_words() {
+        let lines = wrap(&"word ".repeat(100), BODY_SIZE, 0.0);
+        assert!(lines.len() > 1);
+        assert!(lines
+            .iter()
+            .all(|l| !l.starts_with(' ') && !l.ends_with(' ')));
+
+        let lines = wrap(&"x".repeat(250), BODY_SIZE, 0.0);
+        assert_eq!(lines.concat(), "x".repeat(250));
+        assert!(lines.len() >= 3);
+
+        assert!(wrap("   ", BODY_SIZE, 0.0).is_empty());
+    }
+
+    #[test]
+    fn win_ansi_replaces_unencodable_characters() {
+        assert_eq!(
+            win_ansi("caf\u{e9} \u{201C}ok\u{201D} \u{1F600}"),
+            b"caf\xe9 \"ok\" ?"
+        );
+    }
+}
@@ -0,0 +1,125 @@
+//! Session service - legacy share-link feedback sessions owned by an internal user
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::{FeedbackType, Session};
+use crate::services::AuthService;
+
+/// Session service for creating and managing feedback sessions
+pub struct SessionService {
+    db: PgPool,
+}
+
+impl SessionService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Create a session with a fresh share token
+    pub async fn create(
+        &self,
+        owner_id: Uuid,
+        name: &str,
+        session_type: FeedbackType,
+        questions: &[String],
+    ) -> Result<Session> {
+        let session = sqlx::query_as::<_, Session>(
+            r#"
+            INSERT INTO sessions (owner_id, name, type, share_token, questions)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(owner_id)
+        .bind(name.trim())
+        .bind(session_type)
+        .bind(AuthService::generate_share_token())
+        .bind(sqlx::types::Json(trimmed(questions)))
+        .fetch_one(&self.db)
+        .await?;
+        Ok(session)
+    }
+
+    /// List the owner's sessions, newest first
+    pub async fn list(&self, owner_id: Uuid) -> Result<Vec<Session>> {
+        let sessions = sqlx::query_as::<_, Session>(
+            "SELECT * FROM sessions WHERE owner_id = $1 ORDER BY created_at DESC",
+        )
+        .bind(owner_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(sessions)
+    }
+
+    /// Get a session owned by `owner_id`
+    pub async fn get_owned(&self, id: Uuid, owner_id: Uuid) -> Result<Session> {
+        sqlx::query_as::<_, Session>("SELECT * FROM sessions WHERE id = $1 AND owner_id = $2")
+            .bind(id)
+            .bind(owner_id)
+            .fetch_optional(&self.db)
+            .await?
+            .ok_or_else(|| AppError::not_found("Session not found"))
+    }
+
+    /// Session a share link points to
+    pub async fn get_by_share_token(&self, share_token: &str) -> Result<Session> {
+        sqlx::query_as::<_, Session>("SELECT * FROM sessions WHERE share_token = $1")
+            .bind(share_token)
+            .fetch_optional(&self.db)
+            .await?
+            .ok_or_else(|| AppError::not_found("Session not found"))
+    }
+
+    /// Update a session; `None` fields are left unchanged
+    pub async fn update(
+        &self,
+        id: Uuid,
+        owner_id: Uuid,
+        name: Option<&str>,
+        questions: Option<&[String]>,
+    ) -> Result<Session> {
+        sqlx::query_as::<_, Session>(
+            r#"
+            UPDATE sessions SET
+                name = COALESCE($1, name),
+                questions = COALESCE($2, questions),
+                updated_at = NOW()
+            WHERE id = $3 AND owner_id = $4
+            RETURNING *
+            "#,
+        )
+        .bind(name.map(str::trim))
+        .bind(questions.map(|q| sqlx::types::Json(trimmed(q))))
+        .bind(id)
+        .bind(owner_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::not_found("Session not found"))
+    }
+
+    /// Delete a session; its tickets go with it
+    pub async fn delete(&self, id: Uuid, owner_id: Uuid) -> Result<()> {
+        let result = sqlx::query("DELETE FROM sessions WHERE id = $1 AND owner_id = $2")
+            .bind(id)
+            .bind(owner_id)
+            .execute(&self.db)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Session not found"));
+        }
+
+        Ok(())
+    }
+}
+
+/// Questions without surrounding whitespace, blank ones dropped
+fn trimmed(questions: &[String]) -> Vec<String> {
+    questions
+        .iter()
+        .map(|q| q.trim().to_string())
+        .filter(|q| !q.is_empty())
+        .collect()
+}
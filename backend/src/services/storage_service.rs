@@ -2,18 +2,53 @@
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::stream::{BoxStream, StreamExt};
+use rsa::pkcs1v15::SigningKey;
+use rsa::pkcs8::DecodePrivateKey;
+use rsa::signature::{SignatureEncoding, Signer};
+use rsa::RsaPrivateKey;
+use sha2::{Digest, Sha256};
 
 use crate::config::{Config, StorageConfig, StorageType};
 
+/// Object contents delivered chunk by chunk
+pub type ByteStream = BoxStream<'static, Result<Bytes>>;
+
+/// Object the readiness probe looks up; it never needs to exist
+const HEALTH_PROBE_PATH: &str = "health/probe";
+
+/// Where and how a client may PUT an object without credentials
+#[derive(Debug, Clone)]
+pub struct SignedUpload {
+    pub url: String,
+    /// Headers the PUT must send exactly as given; they are part of the signature
+    pub headers: Vec<(String, String)>,
+}
+
 #[async_trait]
 pub trait StorageBackend: Send + Sync {
     async fn upload(&self, path: &str, data: &[u8]) -> Result<String>;
+    #[allow(dead_code)] // Callers stream via download_stream; kept for small objects
     async fn download(&self, path: &str) -> Result<Vec<u8>>;
+    /// Like `download`, without buffering the whole object in memory
+    async fn download_stream(&self, path: &str) -> Result<ByteStream>;
     async fn delete(&self, path: &str) -> Result<()>;
     #[allow(dead_code)] // Useful for production file management
     async fn exists(&self, path: &str) -> Result<bool>;
+    /// Size in bytes of the object at `path`, or `None` when there is none
+    async fn object_size(&self, path: &str) -> Result<Option<u64>>;
     #[allow(dead_code)] // Useful for secure file access in production
     async fn get_signed_url(&self, path: &str, expires_in_secs: u64) -> Result<String>;
+    /// Signed PUT for the object, refused by storage above `max_bytes`; `None` when the
+    /// backend cannot take uploads from clients
+    async fn get_signed_upload_url(
+        &self,
+        path: &str,
+        expires_in_secs: u64,
+        max_bytes: u64,
+    ) -> Result<Option<SignedUpload>>;
 }
 
 pub struct StorageService {
@@ -40,10 +75,15 @@ impl StorageService {
         self.backend.upload(path, data).await
     }
 
+    #[allow(dead_code)] // Callers stream via download_stream; kept for small objects
     pub async fn download(&self, path: &str) -> Result<Vec<u8>> {
         self.backend.download(path).await
     }
 
+    pub async fn download_stream(&self, path: &str) -> Result<ByteStream> {
+        self.backend.download_stream(path).await
+    }
+
     pub async fn delete(&self, path: &str) -> Result<()> {
         self.backend.delete(path).await
     }
@@ -53,10 +93,49 @@ impl StorageService {
         self.backend.exists(path).await
     }
 
+    pub async fn object_size(&self, path: &str) -> Result<Option<u64>> {
+        self.backend.object_size(path).await
+    }
+
+    /// Cheap round trip to the backend for readiness checks: a metadata lookup on a
+    /// sentinel object, where "not found" still proves the backend answers
+    pub async fn probe(&self) -> Result<()> {
+        self.backend
+            .object_size(HEALTH_PROBE_PATH)
+            .await
+            .map(|_| ())
+            .context("Storage probe failed")
+    }
+
     #[allow(dead_code)] // Useful for secure file access in production
     pub async fn get_signed_url(&self, path: &str, expires_in_secs: u64) -> Result<String> {
         self.backend.get_signed_url(path, expires_in_secs).await
     }
+
+    pub async fn get_signed_upload_url(
+        &self,
+        path: &str,
+        expires_in_secs: u64,
+        max_bytes: u64,
+    ) -> Result<Option<SignedUpload>> {
+        self.backend
+            .get_signed_upload_url(path, expires_in_secs, max_bytes)
+            .await
+    }
+
+    /// Up to the first `len` bytes of an object, without reading the rest
+    pub async fn read_head(&self, path: &str, len: usize) -> Result<Vec<u8>> {
+        let mut stream = self.backend.download_stream(path).await?;
+        let mut head = Vec::with_capacity(len);
+        while head.len() < len {
+            match stream.next().await {
+                Some(chunk) => head.extend_from_slice(&chunk?),
+                None => break,
+            }
+        }
+        head.truncate(len);
+        Ok(head)
+    }
 }
 
 // ============================================================================
@@ -68,18 +147,31 @@ struct GcsStorage {
     #[allow(dead_code)]
     project_id: String,
     client: reqwest::Client,
+    /// Signs URLs locally when a key file is configured; otherwise the IAM signBlob API is used
+    service_account: Option<ServiceAccountKey>,
 }
 
 impl GcsStorage {
     fn new(config: &StorageConfig) -> Result<Self> {
-        let StorageConfig::Gcs { bucket, project_id } = config else {
+        let StorageConfig::Gcs {
+            bucket,
+            project_id,
+            credentials_path,
+        } = config
+        else {
             anyhow::bail!("Invalid storage config for GcsStorage");
         };
 
+        let service_account = credentials_path
+            .as_deref()
+            .map(ServiceAccountKey::load)
+            .transpose()?;
+
         Ok(Self {
             bucket: bucket.clone(),
             project_id: project_id.clone(),
             client: reqwest::Client::new(),
+            service_account,
         })
     }
 
@@ -136,6 +228,239 @@ impl GcsStorage {
 
         anyhow::bail!("GCS authentication not configured")
     }
+
+    /// Email of the default service account (when running on GCP)
+    async fn metadata_service_account_email(&self) -> Result<String> {
+        let url = "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/email";
+        let email = self
+            .client
+            .get(url)
+            .header("Metadata-Flavor", "Google")
+            .send()
+            .await
+            .context("Failed to reach metadata server")?
+            .error_for_status()
+            .context("Metadata server returned an error")?
+            .text()
+            .await?;
+        Ok(email.trim().to_string())
+    }
+
+    /// Sign with the service account's Google-managed key via IAM Credentials
+    async fn sign_blob(&self, email: &str, payload: &[u8]) -> Result<Vec<u8>> {
+        use base64::Engine;
+
+        let url = format!(
+            "https://iamcredentials.googleapis.com/v1/projects/-/serviceAccounts/{}:signBlob",
+            urlencoding::encode(email)
+        );
+        let token = self.get_access_token().await?;
+        let engine = base64::engine::general_purpose::STANDARD;
+
+        let json: serde_json::Value = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .json(&serde_json::json!({ "payload": engine.encode(payload) }))
+            .send()
+            .await
+            .context("Failed to call signBlob")?
+            .error_for_status()
+            .context("signBlob failed")?
+            .json()
+            .await?;
+
+        let signed = json
+            .get("signedBlob")
+            .and_then(|v| v.as_str())
+            .context("signBlob response missing signedBlob")?;
+        engine
+            .decode(signed)
+            .context("signBlob returned invalid base64")
+    }
+
+    /// V4-sign `method` on the object at `path`, plus any extra headers the request must send
+    async fn signed_url(
+        &self,
+        method: &str,
+        path: &str,
+        expires_in_secs: u64,
+        headers: &[(String, String)],
+    ) -> Result<String> {
+        if expires_in_secs == 0 || expires_in_secs > MAX_SIGNED_URL_EXPIRY_SECS {
+            anyhow::bail!(
+                "Signed URL expiry must be between 1 and {} seconds",
+                MAX_SIGNED_URL_EXPIRY_SECS
+            );
+        }
+
+        let client_email = match &self.service_account {
+            Some(key) => key.client_email.clone(),
+            None => self.metadata_service_account_email().await?,
+        };
+        let request = V4SignedUrl::new(
+            method,
+            &self.bucket,
+            path,
+            &client_email,
+            Utc::now(),
+            expires_in_secs,
+            headers,
+        );
+        let string_to_sign = request.string_to_sign();
+        let signature = match &self.service_account {
+            Some(key) => key.sign(string_to_sign.as_bytes()),
+            None => {
+                self.sign_blob(&client_email, string_to_sign.as_bytes())
+                    .await?
+            }
+        };
+
+        Ok(request.url(&signature))
+    }
+}
+
+// ============================================================================
+// GCS V4 signed URLs
+// ============================================================================
+
+const GCS_HOST: &str = "storage.googleapis.com";
+/// Longest expiry GCS accepts for V4 signatures (7 days)
+const MAX_SIGNED_URL_EXPIRY_SECS: u64 = 604_800;
+
+/// Private key from a service account JSON key file
+struct ServiceAccountKey {
+    client_email: String,
+    signing_key: SigningKey<Sha256>,
+}
+
+impl ServiceAccountKey {
+    fn load(path: &str) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read service account key: {}", path))?;
+        Self::from_json(&json)
+    }
+
+    fn from_json(json: &str) -> Result<Self> {
+        #[derive(serde::Deserialize)]
+        struct KeyFile {
+            client_email: String,
+            private_key: String,
+        }
+
+        let key: KeyFile =
+            serde_json::from_str(json).context("Invalid service account key file")?;
+        let private_key = RsaPrivateKey::from_pkcs8_pem(&key.private_key)
+            .context("Invalid service account private key")?;
+        Ok(Self {
+            client_email: key.client_email,
+            signing_key: SigningKey::<Sha256>::new(private_key),
+        })
+    }
+
+    fn sign(&self, payload: &[u8]) -> Vec<u8> {
+        self.signing_key.sign(payload).to_vec()
+    }
+}
+
+/// An unsigned V4 request (GET or PUT) for one object; see
+/// https://cloud.google.com/storage/docs/access-control/signing-urls-manually
+struct V4SignedUrl {
+    method: String,
+    canonical_uri: String,
+    canonical_query: String,
+    /// `name:value` lines, sorted by name, `host` included
+    canonical_headers: String,
+    signed_headers: String,
+    credential_scope: String,
+    timestamp: String,
+}
+
+impl V4SignedUrl {
+    fn new(
+        method: &str,
+        bucket: &str,
+        path: &str,
+        client_email: &str,
+        now: DateTime<Utc>,
+        expires_in_secs: u64,
+        headers: &[(String, String)],
+    ) -> Self {
+        let timestamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let credential_scope = format!("{}/auto/storage/goog4_request", now.format("%Y%m%d"));
+        let object = path
+            .split('/')
+            .map(|segment| urlencoding::encode(segment).into_owned())
+            .collect::<Vec<_>>()
+            .join("/");
+        let credential = format!("{}/{}", client_email, credential_scope);
+        let mut headers: Vec<(String, String)> = std::iter::once(("host", GCS_HOST))
+            .chain(headers.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+            .map(|(k, v)| (k.to_ascii_lowercase(), v.trim().to_string()))
+            .collect();
+        headers.sort();
+        let canonical_headers: String = headers
+            .iter()
+            .map(|(k, v)| format!("{}:{}\n", k, v))
+            .collect();
+        let signed_headers = headers
+            .iter()
+            .map(|(k, _)| k.as_str())
+            .collect::<Vec<_>>()
+            .join(";");
+
+        // Already in sorted order, as the canonical query string requires
+        let canonical_query = [
+            ("X-Goog-Algorithm", "GOOG4-RSA-SHA256"),
+            ("X-Goog-Credential", credential.as_str()),
+            ("X-Goog-Date", timestamp.as_str()),
+            ("X-Goog-Expires", &expires_in_secs.to_string()),
+            ("X-Goog-SignedHeaders", signed_headers.as_str()),
+        ]
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, urlencoding::encode(v)))
+        .collect::<Vec<_>>()
+        .join("&");
+
+        Self {
+            method: method.to_string(),
+            canonical_uri: format!("/{}/{}", bucket, object),
+            canonical_query,
+            canonical_headers,
+            signed_headers,
+            credential_scope,
+            timestamp,
+        }
+    }
+
+    fn canonical_request(&self) -> String {
+        format!(
+            "{}\n{}\n{}\n{}\n{}\nUNSIGNED-PAYLOAD",
+            self.method,
+            self.canonical_uri,
+            self.canonical_query,
+            self.canonical_headers,
+            self.signed_headers
+        )
+    }
+
+    fn string_to_sign(&self) -> String {
+        let hashed_request = hex::encode(Sha256::digest(self.canonical_request().as_bytes()));
+        format!(
+            "GOOG4-RSA-SHA256\n{}\n{}\n{}",
+            self.timestamp, self.credential_scope, hashed_request
+        )
+    }
+
+    fn url(&self, signature: &[u8]) -> String {
+        format!(
+            "https://{}{}?{}&X-Goog-Signature={}",
+            GCS_HOST,
+            self.canonical_uri,
+            self.canonical_query,
+            hex::encode(signature)
+        )
+    }
 }
 
 #[async_trait]
@@ -176,6 +501,26 @@ impl StorageBackend for GcsStorage {
         Ok(bytes.to_vec())
     }
 
+    async fn download_stream(&self, path: &str) -> Result<ByteStream> {
+        let url = format!("{}?alt=media", self.object_url(path));
+        let token = self.get_access_token().await?;
+
+        let response = self
+            .client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .send()
+            .await
+            .context("Failed to download from GCS")?
+            .error_for_status()
+            .context("GCS download failed")?;
+
+        Ok(response
+            .bytes_stream()
+            .map(|chunk| chunk.context("Failed to read GCS response"))
+            .boxed())
+    }
+
     async fn delete(&self, path: &str) -> Result<()> {
         let url = self.object_url(path);
         let token = self.get_access_token().await?;
@@ -206,13 +551,53 @@ impl StorageBackend for GcsStorage {
         Ok(response.status().is_success())
     }
 
-    async fn get_signed_url(&self, path: &str, _expires_in_secs: u64) -> Result<String> {
-        // For GCS, we'd use signed URLs in production
-        // For now, return the authenticated download URL
-        Ok(format!(
-            "https://storage.googleapis.com/{}/{}",
-            self.bucket, path
-        ))
+    async fn object_size(&self, path: &str) -> Result<Option<u64>> {
+        let url = self.object_url(path);
+        let token = self.get_access_token().await?;
+
+        let response = self
+            .client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .send()
+            .await
+            .context("Failed to read GCS object metadata")?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        // The JSON API reports sizes as decimal strings
+        let metadata: serde_json::Value = response
+            .error_for_status()
+            .context("GCS metadata request failed")?
+            .json()
+            .await
+            .context("Failed to parse GCS object metadata")?;
+        let size = metadata["size"]
+            .as_str()
+            .and_then(|size| size.parse().ok())
+            .context("GCS object metadata has no size")?;
+        Ok(Some(size))
+    }
+
+    async fn get_signed_url(&self, path: &str, expires_in_secs: u64) -> Result<String> {
+        self.signed_url("GET", path, expires_in_secs, &[]).await
+    }
+
+    async fn get_signed_upload_url(
+        &self,
+        path: &str,
+        expires_in_secs: u64,
+        max_bytes: u64,
+    ) -> Result<Option<SignedUpload>> {
+        // GCS refuses a PUT whose body falls outside this signed range
+        let headers = vec![(
+            "x-goog-content-length-range".to_string(),
+            format!("0,{}", max_bytes),
+        )];
+        let url = self
+            .signed_url("PUT", path, expires_in_secs, &headers)
+            .await?;
+        Ok(Some(SignedUpload { url, headers }))
     }
 }
 
@@ -223,6 +608,7 @@ impl StorageBackend for GcsStorage {
 use std::path::PathBuf;
 use tokio::fs;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio_util::io::ReaderStream;
 
 struct LocalStorage {
     base_path: PathBuf,
@@ -273,6 +659,17 @@ impl StorageBackend for LocalStorage {
         Ok(buffer)
     }
 
+    async fn download_stream(&self, path: &str) -> Result<ByteStream> {
+        let full_path = self.base_path.join(path);
+        let file = fs::File::open(&full_path)
+            .await
+            .with_context(|| format!("Failed to open file: {}", path))?;
+
+        Ok(ReaderStream::new(file)
+            .map(|chunk| chunk.context("Failed to read file"))
+            .boxed())
+    }
+
     async fn delete(&self, path: &str) -> Result<()> {
         let full_path = self.base_path.join(path);
         fs::remove_file(&full_path)
@@ -286,8 +683,220 @@ impl StorageBackend for LocalStorage {
         Ok(full_path.exists())
     }
 
+    async fn object_size(&self, path: &str) -> Result<Option<u64>> {
+        match fs::metadata(self.base_path.join(path)).await {
+            Ok(metadata) if metadata.is_file() => Ok(Some(metadata.len())),
+            Ok(_) => Ok(None),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e).with_context(|| format!("Failed to stat file: {}", path)),
+        }
+    }
+
     async fn get_signed_url(&self, path: &str, _expires_in_secs: u64) -> Result<String> {
         // For local storage, just return a local file URL
         Ok(format!("/storage/{}", path))
     }
+
+    async fn get_signed_upload_url(
+        &self,
+        _path: &str,
+        _expires_in_secs: u64,
+        _max_bytes: u64,
+    ) -> Result<Option<SignedUpload>> {
+        // Nothing serves local files to clients, so they upload through the API
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rsa::pkcs1v15::{Signature, VerifyingKey};
+    use rsa::pkcs8::{EncodePrivateKey, LineEnding};
+    use rsa::signature::Verifier;
+
+    fn fixed_request() -> V4SignedUrl {
+        let now = Utc.with_ymd_and_hms(2026, 10, 17, 12, 30, 45).unwrap();
+        V4SignedUrl::new(
+            "GET",
+            "ortrace-videos",
+            "recordings/p 1/t.webm",
+            "signer@proj.iam.gserviceaccount.com",
+            now,
+            900,
+            &[],
+        )
+    }
+
+    #[test]
+    fn v4_canonical_request_construction() {
+        let expected = "GET\n\
+            /ortrace-videos/recordings/p%201/t.webm\n\
+            X-Goog-Algorithm=GOOG4-RSA-SHA256\
+            &X-Goog-Credential=signer%40proj.iam.gserviceaccount.com%2F20261017%2Fauto%2Fstorage%2Fgoog4_request\
+            &X-Goog-Date=20261017T123045Z\
+            &X-Goog-Expires=900\
+            &X-Goog-SignedHeaders=host\n\
+            host:storage.googleapis.com\n\
+            \n\
+            host\n\
+            UNSIGNED-PAYLOAD";
+        assert_eq!(fixed_request().canonical_request(), expected);
+    }
+
+    #[test]
+    fn v4_upload_request_signs_put_and_its_size_limit() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 17, 12, 30, 45).unwrap();
+        let request = V4SignedUrl::new(
+            "PUT",
+            "ortrace-videos",
+            "recordings/p 1/t.webm",
+            "signer@proj.iam.gserviceaccount.com",
+            now,
+            900,
+            &[(
+                "X-Goog-Content-Length-Range".to_string(),
+                "0,52428800".to_string(),
+            )],
+        );
+        let canonical = request.canonical_request();
+        assert!(canonical.starts_with("PUT\n/ortrace-videos/recordings/p%201/t.webm\n"));
+        assert!(canonical.contains(
+            "&X-Goog-SignedHeaders=host%3Bx-goog-content-length-range\n\
+            host:storage.googleapis.com\n\
+            x-goog-content-length-range:0,52428800\n\
+            \n\
+            host;x-goog-content-length-range\n\
+            UNSIGNED-PAYLOAD"
+        ));
+    }
+
+    #[tokio::test]
+    async fn local_storage_takes_no_direct_uploads() {
+        let storage = StorageService::new(&Config::test_default()).unwrap();
+        let upload = storage
+            .get_signed_upload_url("recordings/p/t.webm", 900, 1024)
+            .await
+            .unwrap();
+        assert!(upload.is_none());
+    }
+
+    #[tokio::test]
+    async fn read_head_stops_at_the_requested_length() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config::test_default();
+        config.storage_config = StorageConfig::Local {
+            path: dir.path().to_string_lossy().into_owned(),
+        };
+        let storage = StorageService::new(&config).unwrap();
+        let data: Vec<u8> = (0..20_000u32).map(|i| (i % 251) as u8).collect();
+        storage.upload("recordings/p/t.webm", &data).await.unwrap();
+
+        let head = storage.read_head("recordings/p/t.webm", 64).await.unwrap();
+        assert_eq!(head, data[..64]);
+        storage.upload("short.webm", b"abc").await.unwrap();
+        assert_eq!(storage.read_head("short.webm", 64).await.unwrap(), b"abc");
+    }
+
+    #[test]
+    fn v4_string_to_sign_hashes_canonical_request() {
+        let request = fixed_request();
+        let lines: Vec<_> = request.string_to_sign().lines().map(String::from).collect();
+        assert_eq!(lines[0], "GOOG4-RSA-SHA256");
+        assert_eq!(lines[1], "20261017T123045Z");
+        assert_eq!(lines[2], "20261017/auto/storage/goog4_request");
+        assert_eq!(
+            lines[3],
+            hex::encode(Sha256::digest(request.canonical_request().as_bytes()))
+        );
+    }
+
+    #[test]
+    fn v4_signed_url_is_verifiable_and_keeps_expiry() {
+        let private_key = RsaPrivateKey::new(&mut rand::thread_rng(), 1024).unwrap();
+        let pem = private_key.to_pkcs8_pem(LineEnding::LF).unwrap();
+        let key_json = serde_json::json!({
+            "client_email": "signer@proj.iam.gserviceaccount.com",
+            "private_key": pem.as_str(),
+        })
+        .to_string();
+        let key = ServiceAccountKey::from_json(&key_json).unwrap();
+
+        let request = fixed_request();
+        let signature = key.sign(request.string_to_sign().as_bytes());
+        let url = request.url(&signature);
+
+        assert!(url
+            .starts_with("https://storage.googleapis.com/ortrace-videos/recordings/p%201/t.webm?"));
+        assert!(url.contains("X-Goog-Expires=900&"));
+        assert!(url.ends_with(&format!("X-Goog-Signature={}", hex::encode(&signature))));
+
+        let verifying_key = VerifyingKey::<Sha256>::new(private_key.to_public_key());
+        let signature = Signature::try_from(signature.as_slice()).unwrap();
+        assert!(verifying_key
+            .verify(request.string_to_sign().as_bytes(), &signature)
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn local_download_stream_matches_uploaded_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::new(&StorageConfig::Local {
+            path: dir.path().to_string_lossy().into_owned(),
+        })
+        .unwrap();
+        // Larger than ReaderStream's chunk size so the body arrives in several pieces
+        let data: Vec<u8> = (0..20_000u32).map(|i| (i % 251) as u8).collect();
+        storage.upload("recordings/p/t.webm", &data).await.unwrap();
+
+        let mut stream = storage
+            .download_stream("recordings/p/t.webm")
+            .await
+            .unwrap();
+        let mut streamed = Vec::new();
+        let mut chunks = 0;
+        while let Some(chunk) = stream.next().await {
+            streamed.extend_from_slice(&chunk.unwrap());
+            chunks += 1;
+        }
+
+        assert_eq!(streamed, data);
+        assert!(chunks > 1);
+    }
+
+    #[tokio::test]
+    async fn local_download_stream_missing_file_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::new(&StorageConfig::Local {
+            path: dir.path().to_string_lossy().into_owned(),
+        })
+        .unwrap();
+        assert!(storage.download_stream("missing.webm").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn probe_succeeds_on_local_storage_without_the_sentinel() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config::test_default();
+        config.storage_config = StorageConfig::Local {
+            path: dir.path().to_string_lossy().into_owned(),
+        };
+        let storage = StorageService::new(&config).unwrap();
+        assert!(storage.probe().await.is_ok());
+
+        // A base path that is a file rather than a directory cannot be read
+        let file = dir.path().join("not-a-dir");
+        std::fs::write(&file, b"x").unwrap();
+        config.storage_config = StorageConfig::Local {
+            path: file.to_string_lossy().into_owned(),
+        };
+        let storage = StorageService::new(&config).unwrap();
+        assert!(storage.probe().await.is_err());
+    }
+
+    #[test]
+    fn service_account_key_rejects_malformed_json() {
+        assert!(ServiceAccountKey::from_json("{\"client_email\": \"x\"}").is_err());
+    }
 }
@@ -1,22 +1,67 @@
 //! Ticket service - handles feedback ticket lifecycle and video uploads
 //! Evolved from recording_service.rs to support project-based widget submissions
 
-use chrono::Utc;
-use sqlx::PgPool;
+use base64::Engine;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use sqlx::{PgPool, Postgres, QueryBuilder};
 use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::dto::UpdateReportRequest;
 use crate::error::{AppError, Result};
 use crate::models::{
-    CreateJobRequest, FeedbackTicket, FeedbackType, TicketPriority, TicketStatus, TicketWithDetails,
+    ClientDiagnostics, CreateJobRequest, FeedbackTicket, FeedbackType, Issue, IssueSeverity,
+    IssueStatus, JobProgressStage, OutcomeRule, ProcessingStatus, Project, Report, ReportOutcome,
+    ReportSentiment, ReportVersion, Session, TicketPriority, TicketShareToken, TicketStatus,
+    TicketWithDetails, User, WebhookEvent,
 };
-use crate::services::{QueueService, StorageService};
+use crate::services::api_key_service::hash_key;
+use crate::services::{
+    is_video_container, probe_duration_secs, AuthService, ByteStream, ChatService, EmailService,
+    GeminiService, NotificationService, QueueService, SignedUpload, StorageService,
+    TicketNotification, WebhookService,
+};
+
+/// Columns of the ticket CSV export, in order
+const CSV_HEADER: [&str; 9] = [
+    "id",
+    "feedback_type",
+    "ticket_status",
+    "priority",
+    "submitter",
+    "page_url",
+    "ai_confidence",
+    "created_at",
+    "description",
+];
+/// Longest description written to the CSV export
+const CSV_DESCRIPTION_CHARS: usize = 500;
+/// Rows encoded per streamed chunk
+const CSV_CHUNK_ROWS: usize = 200;
+/// Largest screenshot accepted from the widget
+const MAX_SCREENSHOT_BYTES: usize = 5 * 1024 * 1024;
+/// Allowed gap between the client-reported and probed video duration before it is logged
+/// (or a fifth of the probed duration, if larger)
+const DURATION_MISMATCH_TOLERANCE_SECS: i32 = 5;
+/// How long a widget `Idempotency-Key` keeps returning the ticket it created
+const IDEMPOTENCY_KEY_TTL_HOURS: i32 = 24;
+/// How long a signed direct-upload URL stays valid
+pub const DIRECT_UPLOAD_URL_EXPIRY_SECS: u64 = 15 * 60;
+/// Leading bytes of a directly uploaded object read to check it is a video
+const VIDEO_SNIFF_BYTES: usize = 64;
+/// Leading bytes of every PNG file
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
 
 /// Ticket service for managing feedback tickets
 pub struct TicketService {
     db: PgPool,
     storage: Arc<StorageService>,
-    queue: Arc<QueueService>,
+    webhooks: Arc<WebhookService>,
+    notifications: Arc<NotificationService>,
+    emails: Arc<EmailService>,
+    chat: Arc<ChatService>,
 }
 
 /// Query parameters for listing tickets
@@ -27,16 +72,235 @@ pub struct TicketListQuery {
     pub ticket_status: Option<TicketStatus>,
     pub priority: Option<TicketPriority>,
     pub search: Option<String>,
+    pub assignee_id: Option<Uuid>,
+    /// Inclusive lower bound on `created_at`
+    pub created_after: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on `created_at`
+    pub created_before: Option<DateTime<Utc>>,
+    /// Normalized label the ticket must carry
+    pub label: Option<String>,
+    /// Sentiment the ticket's report must have
+    pub sentiment: Option<ReportSentiment>,
+    pub sort_by: TicketSortKey,
+    pub sort_dir: SortDirection,
+    /// Keyset position; when set, `page` is ignored and results start after this ticket
+    pub cursor: Option<TicketCursor>,
     pub page: i32,
     pub per_page: i32,
 }
 
+/// Position in a newest-first ticket list, sent to clients as an opaque token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TicketCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl TicketCursor {
+    /// URL-safe base64 of `<created_at micros>:<id>`
+    pub fn encode(&self) -> String {
+        let raw = format!("{}:{}", self.created_at.timestamp_micros(), self.id);
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    pub fn decode(token: &str) -> Option<Self> {
+        let raw = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(token.trim())
+            .ok()?;
+        let raw = String::from_utf8(raw).ok()?;
+        let (micros, id) = raw.split_once(':')?;
+        Some(Self {
+            created_at: DateTime::from_timestamp_micros(micros.parse().ok()?)?,
+            id: id.parse().ok()?,
+        })
+    }
+}
+
+/// Column a ticket list can be sorted by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TicketSortKey {
+    #[default]
+    CreatedAt,
+    UpdatedAt,
+    Priority,
+    Confidence,
+    /// Full-text match rank against `search`; the default when searching
+    Relevance,
+}
+
+impl TicketSortKey {
+    /// Parse a `sort_by` query value; unknown or missing keys fall back to `created_at`
+    pub fn from_param(value: Option<&str>) -> Self {
+        match value.map(str::trim) {
+            Some("updated_at") => Self::UpdatedAt,
+            Some("priority") => Self::Priority,
+            Some("confidence") => Self::Confidence,
+            Some("relevance") => Self::Relevance,
+            _ => Self::CreatedAt,
+        }
+    }
+
+    /// SQL expression to order by; ranks priority so `desc` puts urgent first
+    fn push_sql(self, qb: &mut QueryBuilder<'_, Postgres>, search: &Option<String>) {
+        match self {
+            Self::CreatedAt => qb.push("r.created_at"),
+            Self::UpdatedAt => qb.push("r.updated_at"),
+            Self::Priority => qb.push(
+                "CASE r.priority WHEN 'urgent' THEN 4 WHEN 'high' THEN 3 WHEN 'neutral' THEN 2 WHEN 'low' THEN 1 ELSE 0 END",
+            ),
+            Self::Confidence => qb.push("rp.confidence"),
+            Self::Relevance => qb
+                .push("ts_rank(r.search_vector, websearch_to_tsquery('english', ")
+                .push_bind(search.clone())
+                .push("))"),
+        };
+    }
+}
+
+/// Sort direction for ticket lists
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortDirection {
+    Asc,
+    #[default]
+    Desc,
+}
+
+impl SortDirection {
+    /// Parse a `sort_dir` query value; anything but `asc` sorts descending
+    pub fn from_param(value: Option<&str>) -> Self {
+        match value.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+            Some("asc") => Self::Asc,
+            _ => Self::Desc,
+        }
+    }
+}
+
+impl TicketListQuery {
+    /// ORDER BY clause built only from whitelisted fragments; ties go newest first
+    fn push_order_by(&self, qb: &mut QueryBuilder<'_, Postgres>) {
+        let dir = match self.sort_dir {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        };
+        qb.push(" ORDER BY ");
+        self.sort_by.push_sql(qb, &self.search);
+        qb.push(format!(" {} NULLS LAST, r.created_at DESC, r.id DESC", dir));
+    }
+
+    /// Cursors follow `(created_at, id)`, so they only work with the default newest-first order
+    pub fn supports_cursor(&self) -> bool {
+        self.sort_by == TicketSortKey::CreatedAt && self.sort_dir == SortDirection::Desc
+    }
+
+    /// Cursor for the page after `tickets`, when it was full and the order supports cursors
+    pub fn next_cursor(&self, tickets: &[TicketWithDetails]) -> Option<String> {
+        if !self.supports_cursor() || tickets.len() < self.per_page as usize {
+            return None;
+        }
+        tickets.last().map(|t| {
+            TicketCursor {
+                created_at: t.created_at,
+                id: t.id,
+            }
+            .encode()
+        })
+    }
+
+    /// OFFSET for page-based pagination; keyset pages always start at zero
+    fn offset(&self) -> i64 {
+        if self.cursor.is_some() {
+            0
+        } else {
+            ((self.page - 1) * self.per_page) as i64
+        }
+    }
+
+    /// `WHERE` clause for the tickets in `scope` that match these filters (not the cursor),
+    /// shared by a list and its count. Expects `recordings r` and `reports rp` joined.
+    fn push_filters(&self, qb: &mut QueryBuilder<'_, Postgres>, scope: TicketScope) {
+        match scope {
+            TicketScope::Owner(owner_id) => {
+                qb.push(" WHERE (r.project_id IN (SELECT accessible_project_ids(")
+                    .push_bind(owner_id)
+                    .push(")) OR r.session_id IN (SELECT id FROM sessions WHERE owner_id = ")
+                    .push_bind(owner_id)
+                    .push("))");
+            }
+            TicketScope::Customer(customer_id) => {
+                qb.push(" WHERE r.customer_id = ").push_bind(customer_id);
+            }
+        }
+        if let Some(project_id) = self.project_id {
+            qb.push(" AND r.project_id = ").push_bind(project_id);
+        }
+        if let Some(feedback_type) = self.feedback_type {
+            qb.push(" AND r.feedback_type = ")
+                .push_bind(feedback_type.to_string());
+        }
+        if let Some(ticket_status) = self.ticket_status {
+            qb.push(" AND r.ticket_status = ")
+                .push_bind(ticket_status.to_string());
+        }
+        if let Some(priority) = self.priority {
+            qb.push(" AND r.priority = ")
+                .push_bind(priority.to_string());
+        }
+        if let Some(search) = &self.search {
+            qb.push(" AND r.search_vector @@ websearch_to_tsquery('english', ")
+                .push_bind(search.clone())
+                .push(")");
+        }
+        if let Some(assignee_id) = self.assignee_id {
+            qb.push(" AND r.assignee_id = ").push_bind(assignee_id);
+        }
+        if let Some(created_after) = self.created_after {
+            qb.push(" AND r.created_at >= ").push_bind(created_after);
+        }
+        if let Some(created_before) = self.created_before {
+            qb.push(" AND r.created_at < ").push_bind(created_before);
+        }
+        if let Some(label) = &self.label {
+            qb.push(" AND EXISTS (SELECT 1 FROM ticket_labels tl WHERE tl.ticket_id = r.id AND tl.label = ")
+                .push_bind(label.clone())
+                .push(")");
+        }
+        if let Some(sentiment) = self.sentiment {
+            qb.push(" AND rp.sentiment = ")
+                .push_bind(sentiment.to_string());
+        }
+    }
+}
+
+/// Whose tickets a list covers
+#[derive(Debug, Clone, Copy)]
+enum TicketScope {
+    /// Tickets in projects the user can access, and in their legacy sessions
+    Owner(Uuid),
+    /// Tickets the user submitted
+    Customer(Uuid),
+}
+
 impl TicketService {
-    pub fn new(db: PgPool, storage: Arc<StorageService>, queue: Arc<QueueService>) -> Self {
-        Self { db, storage, queue }
+    pub fn new(
+        db: PgPool,
+        storage: Arc<StorageService>,
+        webhooks: Arc<WebhookService>,
+        notifications: Arc<NotificationService>,
+        emails: Arc<EmailService>,
+        chat: Arc<ChatService>,
+    ) -> Self {
+        Self {
+            db,
+            storage,
+            webhooks,
+            notifications,
+            emails,
+            chat,
+        }
     }
 
-    /// Create a new ticket from widget submission
+    /// Create a new ticket from widget submission. With an `Idempotency-Key`, submissions
+    /// sharing the key (retries, even concurrent ones) all get the first one's ticket.
     #[allow(clippy::too_many_arguments)]
     pub async fn create_from_widget(
         &self,
@@ -48,15 +312,142 @@ impl TicketService {
         submitter_name: Option<&str>,
         page_url: Option<&str>,
         browser_info: Option<serde_json::Value>,
+        prior_experience: Option<&str>,
+        client_diagnostics: ClientDiagnostics,
+        idempotency_key: Option<&str>,
+    ) -> Result<FeedbackTicket> {
+        // The widget uploads the recording next
+        self.insert_ticket(
+            project_id,
+            customer_id,
+            feedback_type,
+            task_description,
+            submitter_email,
+            submitter_name,
+            page_url,
+            browser_info,
+            prior_experience,
+            client_diagnostics,
+            ProcessingStatus::Recording,
+            idempotency_key,
+        )
+        .await
+    }
+
+    /// Log feedback an internal user received outside the widget (email, calls, ...). The
+    /// ticket has no video, and the internal user stands in as its customer.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_manual(
+        &self,
+        project_id: Uuid,
+        creator_id: Uuid,
+        feedback_type: FeedbackType,
+        description: &str,
+        submitter_email: Option<&str>,
+        submitter_name: Option<&str>,
+        page_url: Option<&str>,
+    ) -> Result<FeedbackTicket> {
+        self.insert_ticket(
+            project_id,
+            creator_id,
+            feedback_type,
+            Some(description),
+            submitter_email,
+            submitter_name,
+            page_url,
+            None,
+            None,
+            ClientDiagnostics::default(),
+            ProcessingStatus::Pending,
+            None,
+        )
+        .await
+    }
+
+    /// Submit a ticket to a legacy session through its share link. The ticket takes the
+    /// session's feedback type; its video follows through `upload_video`.
+    pub async fn create_from_session(
+        &self,
+        session: &Session,
+        submitter: &User,
+        task_description: Option<&str>,
+        page_url: Option<&str>,
+    ) -> Result<FeedbackTicket> {
+        let ticket = sqlx::query_as::<_, FeedbackTicket>(
+            r#"
+            INSERT INTO recordings (
+                session_id, customer_id, feedback_type, task_description,
+                submitter_email, submitter_name, page_url,
+                status, session_status, ticket_status, priority
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, 'pending', 'open', 'open', 'neutral')
+            RETURNING *
+            "#,
+        )
+        .bind(session.id)
+        .bind(submitter.id)
+        .bind(session.session_type)
+        .bind(task_description.map(str::trim).filter(|d| !d.is_empty()))
+        .bind(&submitter.email)
+        .bind(&submitter.name)
+        .bind(page_url)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(ticket)
+    }
+
+    /// Insert an open ticket and announce it to webhooks and notification subscribers.
+    /// With an `idempotency_key` the key is claimed in the same transaction, under a lock
+    /// on (project, key), so a concurrent submission with the key waits and then gets this
+    /// ticket back instead of creating its own.
+    #[allow(clippy::too_many_arguments)]
+    async fn insert_ticket(
+        &self,
+        project_id: Uuid,
+        customer_id: Uuid,
+        feedback_type: FeedbackType,
+        task_description: Option<&str>,
+        submitter_email: Option<&str>,
+        submitter_name: Option<&str>,
+        page_url: Option<&str>,
+        browser_info: Option<serde_json::Value>,
+        prior_experience: Option<&str>,
+        client_diagnostics: ClientDiagnostics,
+        status: ProcessingStatus,
+        idempotency_key: Option<&str>,
     ) -> Result<FeedbackTicket> {
+        let mut tx = self.db.begin().await?;
+        if let Some(key) = idempotency_key {
+            sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1, 0))")
+                .bind(format!("idempotency:{}:{}", project_id, key))
+                .execute(&mut *tx)
+                .await?;
+            let existing = sqlx::query_as::<_, FeedbackTicket>(
+                r#"
+                SELECT r.* FROM idempotency_keys k
+                JOIN recordings r ON r.id = k.ticket_id
+                WHERE k.project_id = $1 AND k.key = $2
+                AND k.created_at > NOW() - make_interval(hours => $3)
+                "#,
+            )
+            .bind(project_id)
+            .bind(key)
+            .bind(IDEMPOTENCY_KEY_TTL_HOURS)
+            .fetch_optional(&mut *tx)
+            .await?;
+            if let Some(ticket) = existing {
+                return Ok(ticket);
+            }
+        }
+
         let ticket = sqlx::query_as::<_, FeedbackTicket>(
             r#"
             INSERT INTO recordings (
                 project_id, customer_id, feedback_type, task_description,
-                submitter_email, submitter_name, page_url, browser_info,
-                status, session_status, ticket_status, priority
+                submitter_email, submitter_name, page_url, browser_info, prior_experience,
+                client_diagnostics, status, session_status, ticket_status, priority
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 'recording', 'open', 'open', 'neutral')
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, 'open', 'open', 'neutral')
             RETURNING *
             "#,
         )
@@ -70,12 +461,75 @@ impl TicketService {
         .bind(sqlx::types::Json(
             browser_info.unwrap_or(serde_json::json!({})),
         ))
-        .fetch_one(&self.db)
+        .bind(prior_experience.map(str::trim).filter(|p| !p.is_empty()))
+        .bind(sqlx::types::Json(client_diagnostics))
+        .bind(status)
+        .fetch_one(&mut *tx)
         .await?;
+        if let Some(key) = idempotency_key {
+            Self::remember_idempotency_key(&mut tx, project_id, key, ticket.id).await?;
+        }
+        tx.commit().await?;
 
+        self.webhooks
+            .fire(WebhookEvent::TicketCreated, &ticket)
+            .await;
+        self.notifications
+            .notify(TicketNotification::Created, &ticket);
         Ok(ticket)
     }
 
+    /// Ticket already created for this project under an unexpired `Idempotency-Key`
+    pub async fn find_by_idempotency_key(
+        &self,
+        project_id: Uuid,
+        key: &str,
+    ) -> Result<Option<Uuid>> {
+        let ticket_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT ticket_id FROM idempotency_keys
+            WHERE project_id = $1 AND key = $2
+            AND created_at > NOW() - make_interval(hours => $3)
+            "#,
+        )
+        .bind(project_id)
+        .bind(key)
+        .bind(IDEMPOTENCY_KEY_TTL_HOURS)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(ticket_id)
+    }
+
+    /// Remember the ticket a submission created under `key`, replacing an expired entry and
+    /// pruning the project's other expired ones. The caller holds the key's lock and has
+    /// checked no unexpired entry exists.
+    async fn remember_idempotency_key(
+        conn: &mut sqlx::PgConnection,
+        project_id: Uuid,
+        key: &str,
+        ticket_id: Uuid,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM idempotency_keys
+            WHERE project_id = $1 AND created_at <= NOW() - make_interval(hours => $2)
+            "#,
+        )
+        .bind(project_id)
+        .bind(IDEMPOTENCY_KEY_TTL_HOURS)
+        .execute(&mut *conn)
+        .await?;
+        sqlx::query(
+            "INSERT INTO idempotency_keys (project_id, key, ticket_id) VALUES ($1, $2, $3)",
+        )
+        .bind(project_id)
+        .bind(key)
+        .bind(ticket_id)
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
     /// Upload video for a ticket
     pub async fn upload_video(
         &self,
@@ -83,54 +537,199 @@ impl TicketService {
         customer_id: Uuid,
         video_data: Vec<u8>,
         duration_seconds: i32,
+        content_type: Option<&str>,
+        file_name: Option<&str>,
     ) -> Result<FeedbackTicket> {
         // Verify ownership
         let ticket = self.get_owned(ticket_id, customer_id).await?;
-        let project_id = ticket
-            .project_id
-            .unwrap_or(ticket.session_id.unwrap_or(Uuid::nil()));
+        ensure_no_video_yet(&ticket)?;
+        let duration_seconds = verified_duration(
+            ticket_id,
+            probe_duration_secs(&video_data),
+            duration_seconds,
+        );
+        self.check_duration_limit(&ticket, duration_seconds).await?;
+        // Refuse before storing anything; the charge itself is made with the job
+        self.check_analysis_quota(ticket_id).await?;
 
         // Upload to storage
-        let storage_path = format!("recordings/{}/{}.webm", project_id, ticket_id);
+        let storage_path = video_storage_path(&ticket);
         self.storage
             .upload(&storage_path, &video_data)
             .await
             .map_err(|e| AppError::internal(format!("Failed to upload video: {}", e)))?;
 
         let video_size = video_data.len() as i64;
+        let mime_type = video_mime_type(content_type, file_name);
+        self.start_video_analysis(
+            ticket_id,
+            customer_id,
+            storage_path,
+            video_size,
+            duration_seconds,
+            mime_type,
+        )
+        .await
+    }
 
-        // Update ticket status
-        sqlx::query(
+    /// Signed PUT the client can upload the ticket's video with, for `confirm_upload`.
+    /// Storage refuses bodies over `max_upload_mb`.
+    pub async fn direct_upload_url(
+        &self,
+        ticket_id: Uuid,
+        customer_id: Uuid,
+        max_upload_mb: usize,
+    ) -> Result<SignedUpload> {
+        let ticket = self.get_owned(ticket_id, customer_id).await?;
+        ensure_no_video_yet(&ticket)?;
+        self.storage
+            .get_signed_upload_url(
+                &video_storage_path(&ticket),
+                DIRECT_UPLOAD_URL_EXPIRY_SECS,
+                (max_upload_mb * 1024 * 1024) as u64,
+            )
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to sign upload URL: {}", e)))?
+            .ok_or_else(|| {
+                AppError::bad_request("Direct uploads need Cloud Storage; upload through the API")
+            })
+    }
+
+    /// Start analysis of a video the client uploaded straight to storage (e.g. through a
+    /// signed URL) at the ticket's video path, instead of through `upload_video`. The object
+    /// gets the same size cap and container check as an upload through the API.
+    pub async fn confirm_upload(
+        &self,
+        ticket_id: Uuid,
+        customer_id: Uuid,
+        duration_seconds: i32,
+        content_type: Option<&str>,
+        max_upload_mb: usize,
+    ) -> Result<FeedbackTicket> {
+        let ticket = self.get_owned(ticket_id, customer_id).await?;
+        ensure_no_video_yet(&ticket)?;
+        let storage_path = video_storage_path(&ticket);
+        let video_size = self
+            .storage
+            .object_size(&storage_path)
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to check uploaded video: {}", e)))?
+            .ok_or_else(|| AppError::bad_request("No uploaded video found for this ticket"))?;
+        if video_size > (max_upload_mb * 1024 * 1024) as u64 {
+            self.discard_upload(ticket_id, &storage_path).await;
+            return Err(AppError::bad_request(format!(
+                "Video too large ({:.1}MB). Max: {}MB",
+                video_size as f64 / (1024.0 * 1024.0),
+                max_upload_mb
+            )));
+        }
+        let head = self
+            .storage
+            .read_head(&storage_path, VIDEO_SNIFF_BYTES)
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to check uploaded video: {}", e)))?;
+        if !is_video_container(&head) {
+            self.discard_upload(ticket_id, &storage_path).await;
+            return Err(AppError::bad_request("Unsupported video format"));
+        }
+        // Only the client's word is available here; the bytes never pass through the API
+        self.check_duration_limit(&ticket, duration_seconds).await?;
+
+        self.start_video_analysis(
+            ticket_id,
+            customer_id,
+            storage_path,
+            video_size as i64,
+            duration_seconds,
+            video_mime_type(content_type, None),
+        )
+        .await
+    }
+
+    /// Remove a refused direct upload so it does not linger in the bucket
+    async fn discard_upload(&self, ticket_id: Uuid, storage_path: &str) {
+        if let Err(e) = self.storage.delete(storage_path).await {
+            tracing::warn!(%ticket_id, error = %e, "Failed to delete refused upload");
+        }
+    }
+
+    /// Reject a video longer than the ticket's project allows (`max_duration_seconds`)
+    async fn check_duration_limit(
+        &self,
+        ticket: &FeedbackTicket,
+        duration_seconds: i32,
+    ) -> Result<()> {
+        let Some(project_id) = ticket.project_id else {
+            return Ok(());
+        };
+        let project = sqlx::query_as::<_, Project>("SELECT * FROM projects WHERE id = $1")
+            .bind(project_id)
+            .fetch_optional(&self.db)
+            .await?;
+        match project.and_then(|p| p.max_duration_seconds()) {
+            Some(max) if duration_seconds > max => Err(AppError::bad_request(format!(
+                "Video too long ({}s). Max: {}s",
+                duration_seconds, max
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Record a stored video on its ticket, charge the analysis to the quota and queue the
+    /// job, all in one transaction so a failure charges nothing. Claiming the ticket is
+    /// conditional on it having no job yet, so concurrent uploads queue one analysis.
+    async fn start_video_analysis(
+        &self,
+        ticket_id: Uuid,
+        customer_id: Uuid,
+        storage_path: String,
+        video_size: i64,
+        duration_seconds: i32,
+        mime_type: Option<String>,
+    ) -> Result<FeedbackTicket> {
+        let mut tx = self.db.begin().await?;
+
+        // Claim the ticket; a concurrent upload waits here and then finds it taken
+        let claimed = sqlx::query_scalar::<_, Uuid>(
             r#"
             UPDATE recordings SET
                 video_storage_path = $1,
                 video_size_bytes = $2,
                 duration_seconds = $3,
                 status = 'uploading',
-                recorded_at = $4
-            WHERE id = $5
+                recorded_at = $4,
+                video_mime_type = $5
+            WHERE id = $6 AND analysis_job_id IS NULL
+            RETURNING id
             "#,
         )
         .bind(&storage_path)
         .bind(video_size)
         .bind(duration_seconds)
         .bind(Utc::now())
+        .bind(&mime_type)
         .bind(ticket_id)
-        .execute(&self.db)
+        .fetch_optional(&mut *tx)
         .await?;
+        if claimed.is_none() {
+            return Err(AppError::conflict(
+                "A video was already uploaded for this ticket",
+            ));
+        }
+
+        Self::consume_analysis_quota(&mut tx, ticket_id).await?;
 
         // Create analysis job
         let job_request = CreateJobRequest {
             video_storage_path: storage_path,
             video_size_bytes: video_size,
+            mime_type,
             prompt: None,
             user_id: Some(customer_id),
             recording_id: Some(ticket_id),
         };
 
-        let job_id = self
-            .queue
-            .enqueue(job_request)
+        let job_id = QueueService::enqueue_in(&mut tx, job_request)
             .await
             .map_err(|e| AppError::internal(format!("Failed to create analysis job: {}", e)))?;
 
@@ -146,6 +745,106 @@ impl TicketService {
         )
         .bind(job_id)
         .bind(ticket_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(ticket)
+    }
+
+    /// Account an analysis of the ticket is charged to: the project's owner, or the
+    /// session's owner for legacy tickets. Never the submitter, since the widget creates a
+    /// fresh anonymous account whenever the email changes.
+    async fn quota_account(conn: &mut sqlx::PgConnection, ticket_id: Uuid) -> Result<Uuid> {
+        sqlx::query_scalar::<_, Option<Uuid>>(
+            r#"
+            SELECT COALESCE(p.owner_id, s.owner_id) FROM recordings r
+            LEFT JOIN projects p ON r.project_id = p.id
+            LEFT JOIN sessions s ON r.session_id = s.id
+            WHERE r.id = $1
+            "#,
+        )
+        .bind(ticket_id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .flatten()
+        .ok_or_else(|| AppError::not_found("Ticket not found"))
+    }
+
+    /// Refuse when the ticket's account has no analyses left, without charging it
+    async fn check_analysis_quota(&self, ticket_id: Uuid) -> Result<()> {
+        let mut conn = self.db.acquire().await?;
+        let account_id = Self::quota_account(&mut conn, ticket_id).await?;
+        let exhausted = sqlx::query_scalar::<_, i32>(
+            r#"
+            SELECT quota_limit FROM users
+            WHERE id = $1 AND role <> 'internal' AND quota_used >= quota_limit
+            "#,
+        )
+        .bind(account_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+        match exhausted {
+            Some(limit) => Err(AppError::quota_exceeded(limit)),
+            None => Ok(()),
+        }
+    }
+
+    /// Count one analysis against the ticket's account (see `quota_account`). Internal users
+    /// are counted but never refused; anyone else at `quota_limit` gets `QuotaExceeded`.
+    async fn consume_analysis_quota(conn: &mut sqlx::PgConnection, ticket_id: Uuid) -> Result<()> {
+        let account_id = Self::quota_account(conn, ticket_id).await?;
+        let charged = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            UPDATE users SET quota_used = quota_used + 1, updated_at = NOW()
+            WHERE id = $1 AND (role = 'internal' OR quota_used < quota_limit)
+            RETURNING id
+            "#,
+        )
+        .bind(account_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+        if charged.is_some() {
+            return Ok(());
+        }
+
+        let limit = sqlx::query_scalar::<_, i32>("SELECT quota_limit FROM users WHERE id = $1")
+            .bind(account_id)
+            .fetch_one(&mut *conn)
+            .await?;
+        Err(AppError::quota_exceeded(limit))
+    }
+
+    /// Attach a widget screenshot (PNG) to a ticket of the project, replacing any earlier one
+    pub async fn upload_screenshot(
+        &self,
+        ticket_id: Uuid,
+        project_id: Uuid,
+        data: Vec<u8>,
+    ) -> Result<FeedbackTicket> {
+        validate_screenshot(&data)?;
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM recordings WHERE id = $1 AND project_id = $2)",
+        )
+        .bind(ticket_id)
+        .bind(project_id)
+        .fetch_one(&self.db)
+        .await?;
+        if !exists {
+            return Err(AppError::not_found("Ticket not found"));
+        }
+
+        let storage_path = format!("screenshots/{}/{}.png", project_id, ticket_id);
+        self.storage
+            .upload(&storage_path, &data)
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to upload screenshot: {}", e)))?;
+
+        let ticket = sqlx::query_as::<_, FeedbackTicket>(
+            "UPDATE recordings SET screenshot_url = $1 WHERE id = $2 RETURNING *",
+        )
+        .bind(&storage_path)
+        .bind(ticket_id)
         .fetch_one(&self.db)
         .await?;
 
@@ -161,6 +860,32 @@ impl TicketService {
         Ok(ticket)
     }
 
+    /// Tickets in the owner's projects or sessions submitted under `email`, either as the
+    /// widget's submitter email or by an account with that email; oldest first
+    pub async fn list_for_submitter(
+        &self,
+        owner_id: Uuid,
+        email: &str,
+    ) -> Result<Vec<FeedbackTicket>> {
+        let tickets = sqlx::query_as::<_, FeedbackTicket>(
+            r#"
+            SELECT r.* FROM recordings r
+            JOIN users u ON u.id = r.customer_id
+            WHERE (LOWER(r.submitter_email) = LOWER($2) OR LOWER(u.email) = LOWER($2))
+            AND (
+                r.project_id IN (SELECT accessible_project_ids($1))
+                OR r.session_id IN (SELECT id FROM sessions WHERE owner_id = $1)
+            )
+            ORDER BY r.created_at ASC, r.id ASC
+            "#,
+        )
+        .bind(owner_id)
+        .bind(email.trim())
+        .fetch_all(&self.db)
+        .await?;
+        Ok(tickets)
+    }
+
     /// Get ticket owned by customer
     pub async fn get_owned(&self, id: Uuid, customer_id: Uuid) -> Result<FeedbackTicket> {
         let ticket = sqlx::query_as::<_, FeedbackTicket>(
@@ -174,73 +899,199 @@ impl TicketService {
         Ok(ticket)
     }
 
-    /// List tickets for internal user. When query.project_id is set, only tickets for that project are returned.
-    pub async fn list_for_owner(
-        &self,
-        owner_id: Uuid,
-        query: TicketListQuery,
-    ) -> Result<(Vec<TicketWithDetails>, i64)> {
-        let offset = ((query.page - 1) * query.per_page) as i64;
-        let limit = query.per_page as i64;
-
-        let tickets = sqlx::query_as::<_, TicketWithDetails>(
+    /// Get a ticket visible to an internal owner, either through a project they own
+    /// or (for legacy tickets) a session they own.
+    pub async fn get_for_owner(&self, id: Uuid, owner_id: Uuid) -> Result<FeedbackTicket> {
+        let ticket = sqlx::query_as::<_, FeedbackTicket>(
             r#"
-            SELECT r.*,
-                   p.name as project_name,
-                   u.name as customer_name,
-                   a.name as assignee_name,
-                   rp.confidence as ai_confidence,
-                   (SELECT COUNT(*) FROM issues i JOIN reports rp2 ON i.report_id = rp2.id WHERE rp2.recording_id = r.id) as issues_count
-            FROM recordings r
-            LEFT JOIN projects p ON r.project_id = p.id
-            LEFT JOIN users u ON r.customer_id = u.id
-            LEFT JOIN users a ON r.assignee_id = a.id
-            LEFT JOIN reports rp ON rp.recording_id = r.id
-            WHERE (p.owner_id = $1 OR r.session_id IN (SELECT id FROM sessions WHERE owner_id = $1))
-            AND ($2::uuid IS NULL OR r.project_id = $2)
-            AND ($3::varchar IS NULL OR r.feedback_type = $3)
-            AND ($4::varchar IS NULL OR r.ticket_status = $4)
-            AND ($5::varchar IS NULL OR r.priority = $5)
-            AND ($6::varchar IS NULL OR r.task_description ILIKE '%' || $6 || '%')
-            ORDER BY r.created_at DESC
-            LIMIT $7 OFFSET $8
-            "#,
+            SELECT r.* FROM recordings r
+            WHERE r.id = $1 AND (
+                r.project_id IN (SELECT accessible_project_ids($2))
+                OR r.session_id IN (SELECT id FROM sessions WHERE owner_id = $2)
+            )
+            "#,
         )
+        .bind(id)
         .bind(owner_id)
-        .bind(query.project_id)
-        .bind(query.feedback_type.map(|f| f.to_string()))
-        .bind(query.ticket_status.map(|s| s.to_string()))
-        .bind(query.priority.map(|p| p.to_string()))
-        .bind(&query.search)
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(&self.db)
-        .await?;
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::not_found("Ticket not found"))?;
+        Ok(ticket)
+    }
 
-        let total: i64 = sqlx::query_scalar(
+    /// Resolve the display name of the context a ticket belongs to: the project name for
+    /// widget tickets, or the session name for legacy session-based tickets.
+    pub async fn get_context_name(&self, ticket: &FeedbackTicket) -> Result<Option<String>> {
+        let name: Option<String> = sqlx::query_scalar(
             r#"
-            SELECT COUNT(*) FROM recordings r
-            LEFT JOIN projects p ON r.project_id = p.id
-            WHERE (p.owner_id = $1 OR r.session_id IN (SELECT id FROM sessions WHERE owner_id = $1))
-            AND ($2::uuid IS NULL OR r.project_id = $2)
-            AND ($3::varchar IS NULL OR r.feedback_type = $3)
-            AND ($4::varchar IS NULL OR r.ticket_status = $4)
-            AND ($5::varchar IS NULL OR r.priority = $5)
-            AND ($6::varchar IS NULL OR r.task_description ILIKE '%' || $6 || '%')
+            SELECT COALESCE(
+                (SELECT name FROM projects WHERE id = $1),
+                (SELECT name FROM sessions WHERE id = $2)
+            )
             "#,
         )
-        .bind(owner_id)
-        .bind(query.project_id)
-        .bind(query.feedback_type.map(|f| f.to_string()))
-        .bind(query.ticket_status.map(|s| s.to_string()))
-        .bind(query.priority.map(|p| p.to_string()))
-        .bind(&query.search)
+        .bind(ticket.project_id)
+        .bind(ticket.session_id)
         .fetch_one(&self.db)
         .await?;
+        Ok(name)
+    }
+
+    /// List tickets for internal user. When query.project_id is set, only tickets for that project are returned.
+    pub async fn list_for_owner(
+        &self,
+        owner_id: Uuid,
+        query: TicketListQuery,
+    ) -> Result<(Vec<TicketWithDetails>, i64)> {
+        self.list_tickets(TicketScope::Owner(owner_id), &query)
+            .await
+    }
+
+    /// List tickets a customer submitted. When query.project_id is set, only tickets for that project are returned.
+    pub async fn list_for_customer(
+        &self,
+        customer_id: Uuid,
+        query: TicketListQuery,
+    ) -> Result<(Vec<TicketWithDetails>, i64)> {
+        self.list_tickets(TicketScope::Customer(customer_id), &query)
+            .await
+    }
+
+    /// One page of the tickets in `scope` matching `query`, and how many match in all
+    async fn list_tickets(
+        &self,
+        scope: TicketScope,
+        query: &TicketListQuery,
+    ) -> Result<(Vec<TicketWithDetails>, i64)> {
+        let mut list = QueryBuilder::<Postgres>::new(
+            r#"
+            SELECT r.*,
+                   COALESCE(p.name, s.name) as project_name,
+                   u.name as customer_name,
+                   a.name as assignee_name,
+                   rp.confidence as ai_confidence,
+                   rp.sentiment, rp.frustration_score,
+                   (SELECT COUNT(*) FROM issues i JOIN reports rp2 ON i.report_id = rp2.id WHERE rp2.recording_id = r.id) as issues_count
+            FROM recordings r
+            LEFT JOIN projects p ON r.project_id = p.id
+            LEFT JOIN sessions s ON r.session_id = s.id
+            LEFT JOIN users u ON r.customer_id = u.id
+            LEFT JOIN users a ON r.assignee_id = a.id
+            LEFT JOIN reports rp ON rp.recording_id = r.id
+            "#,
+        );
+        query.push_filters(&mut list, scope);
+        if let Some(cursor) = query.cursor {
+            list.push(" AND (r.created_at, r.id) < (")
+                .push_bind(cursor.created_at)
+                .push(", ")
+                .push_bind(cursor.id)
+                .push(")");
+        }
+        query.push_order_by(&mut list);
+        list.push(" LIMIT ")
+            .push_bind(query.per_page as i64)
+            .push(" OFFSET ")
+            .push_bind(query.offset());
+        let tickets = list
+            .build_query_as::<TicketWithDetails>()
+            .fetch_all(&self.db)
+            .await?;
+
+        let mut count = QueryBuilder::<Postgres>::new(
+            "SELECT COUNT(*) FROM recordings r LEFT JOIN reports rp ON rp.recording_id = r.id",
+        );
+        query.push_filters(&mut count, scope);
+        let total: i64 = count.build_query_scalar().fetch_one(&self.db).await?;
 
         Ok((tickets, total))
     }
 
+    /// Labels on a ticket, alphabetically
+    pub async fn get_labels(&self, ticket_id: Uuid) -> Result<Vec<String>> {
+        let labels = sqlx::query_scalar::<_, String>(
+            "SELECT label FROM ticket_labels WHERE ticket_id = $1 ORDER BY label",
+        )
+        .bind(ticket_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(labels)
+    }
+
+    /// Add a normalized label to an owned ticket; adding an existing label is a no-op.
+    /// Returns the ticket's labels afterwards.
+    pub async fn add_label(&self, id: Uuid, owner_id: Uuid, label: &str) -> Result<Vec<String>> {
+        let ticket = self.get_for_owner(id, owner_id).await?;
+        sqlx::query(
+            "INSERT INTO ticket_labels (ticket_id, label) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+        )
+        .bind(ticket.id)
+        .bind(label)
+        .execute(&self.db)
+        .await?;
+        self.get_labels(ticket.id).await
+    }
+
+    /// Remove a normalized label from an owned ticket; removing a missing label is a no-op.
+    /// Returns the ticket's labels afterwards.
+    pub async fn remove_label(&self, id: Uuid, owner_id: Uuid, label: &str) -> Result<Vec<String>> {
+        let ticket = self.get_for_owner(id, owner_id).await?;
+        sqlx::query("DELETE FROM ticket_labels WHERE ticket_id = $1 AND label = $2")
+            .bind(ticket.id)
+            .bind(label)
+            .execute(&self.db)
+            .await?;
+        self.get_labels(ticket.id).await
+    }
+
+    /// Stream every ticket of a project as CSV, newest first. Rows are fetched and encoded
+    /// in chunks by a background task, so memory stays flat however large the project is.
+    pub fn export_csv(&self, project_id: Uuid) -> ByteStream {
+        let db = self.db.clone();
+        let (tx, rx) = tokio::sync::mpsc::channel::<anyhow::Result<Bytes>>(4);
+
+        tokio::spawn(async move {
+            let mut rows = sqlx::query_as::<_, TicketCsvRow>(
+                r#"
+                SELECT r.id, r.feedback_type, r.ticket_status, r.priority,
+                       r.submitter_name, r.submitter_email, r.page_url,
+                       rp.confidence as ai_confidence, r.created_at, r.task_description
+                FROM recordings r
+                LEFT JOIN reports rp ON rp.recording_id = r.id
+                WHERE r.project_id = $1
+                ORDER BY r.created_at DESC, r.id DESC
+                "#,
+            )
+            .bind(project_id)
+            .fetch(&db);
+
+            let mut chunk = vec![CSV_HEADER.map(String::from)];
+            let mut done = false;
+            while !done {
+                match rows.next().await {
+                    Some(Ok(row)) => chunk.push(row.record()),
+                    Some(Err(e)) => {
+                        let _ = tx.send(Err(e.into())).await;
+                        return;
+                    }
+                    None => done = true,
+                }
+                if chunk.len() >= CSV_CHUNK_ROWS || done {
+                    // A closed channel means the client went away
+                    if tx.send(encode_csv(&chunk)).await.is_err() {
+                        return;
+                    }
+                    chunk.clear();
+                }
+            }
+        });
+
+        futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|chunk| (chunk, rx))
+        })
+        .boxed()
+    }
+
     /// Update ticket status
     pub async fn update_status(
         &self,
@@ -254,7 +1105,7 @@ impl TicketService {
                 ticket_status = $1,
                 updated_at = NOW()
             WHERE r.id = $2 AND (
-                r.project_id IN (SELECT id FROM projects WHERE owner_id = $3)
+                r.project_id IN (SELECT accessible_project_ids($3))
                 OR r.session_id IN (SELECT id FROM sessions WHERE owner_id = $3)
             )
             RETURNING r.*
@@ -267,9 +1118,45 @@ impl TicketService {
         .await?
         .ok_or_else(|| AppError::not_found("Ticket not found"))?;
 
+        self.webhooks
+            .fire(WebhookEvent::TicketStatusChanged, &ticket)
+            .await;
+        self.post_status_message(&ticket, owner_id).await;
         Ok(ticket)
     }
 
+    /// Bump `updated_at` if the ticket is still at `expected_updated_at`, so concurrent
+    /// editors holding the same version cannot both go on to update it. A changed ticket
+    /// fails with a stale-update conflict carrying its current `updated_at`.
+    pub async fn claim_unchanged(
+        &self,
+        id: Uuid,
+        owner_id: Uuid,
+        expected_updated_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let claimed = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            UPDATE recordings r SET updated_at = NOW()
+            WHERE r.id = $1 AND r.updated_at = $2 AND (
+                r.project_id IN (SELECT accessible_project_ids($3))
+                OR r.session_id IN (SELECT id FROM sessions WHERE owner_id = $3)
+            )
+            RETURNING r.id
+            "#,
+        )
+        .bind(id)
+        .bind(expected_updated_at)
+        .bind(owner_id)
+        .fetch_optional(&self.db)
+        .await?;
+        if claimed.is_some() {
+            return Ok(());
+        }
+
+        let current = self.get_for_owner(id, owner_id).await?;
+        Err(AppError::stale_update(current.updated_at))
+    }
+
     /// Update ticket priority
     pub async fn update_priority(
         &self,
@@ -283,7 +1170,7 @@ impl TicketService {
                 priority = $1,
                 updated_at = NOW()
             WHERE r.id = $2 AND (
-                r.project_id IN (SELECT id FROM projects WHERE owner_id = $3)
+                r.project_id IN (SELECT accessible_project_ids($3))
                 OR r.session_id IN (SELECT id FROM sessions WHERE owner_id = $3)
             )
             RETURNING r.*
@@ -312,7 +1199,7 @@ impl TicketService {
                 assignee_id = $1,
                 updated_at = NOW()
             WHERE r.id = $2 AND (
-                r.project_id IN (SELECT id FROM projects WHERE owner_id = $3)
+                r.project_id IN (SELECT accessible_project_ids($3))
                 OR r.session_id IN (SELECT id FROM sessions WHERE owner_id = $3)
             )
             RETURNING r.*
@@ -328,6 +1215,35 @@ impl TicketService {
         Ok(ticket)
     }
 
+    /// Set or clear (`None`) ticket due date
+    pub async fn update_due_date(
+        &self,
+        id: Uuid,
+        owner_id: Uuid,
+        due_date: Option<DateTime<Utc>>,
+    ) -> Result<FeedbackTicket> {
+        let ticket = sqlx::query_as::<_, FeedbackTicket>(
+            r#"
+            UPDATE recordings r SET
+                due_date = $1,
+                updated_at = NOW()
+            WHERE r.id = $2 AND (
+                r.project_id IN (SELECT accessible_project_ids($3))
+                OR r.session_id IN (SELECT id FROM sessions WHERE owner_id = $3)
+            )
+            RETURNING r.*
+            "#,
+        )
+        .bind(due_date)
+        .bind(id)
+        .bind(owner_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::not_found("Ticket not found"))?;
+
+        Ok(ticket)
+    }
+
     /// Close a ticket (resolve)
     pub async fn close(&self, id: Uuid, owner_id: Uuid) -> Result<FeedbackTicket> {
         let ticket = sqlx::query_as::<_, FeedbackTicket>(
@@ -338,7 +1254,7 @@ impl TicketService {
                 closed_at = $1,
                 closed_reason = 'resolved'
             WHERE r.id = $2 AND (
-                r.project_id IN (SELECT id FROM projects WHERE owner_id = $3)
+                r.project_id IN (SELECT accessible_project_ids($3))
                 OR r.session_id IN (SELECT id FROM sessions WHERE owner_id = $3)
             )
             RETURNING r.*
@@ -351,6 +1267,13 @@ impl TicketService {
         .await?
         .ok_or_else(|| AppError::not_found("Ticket not found"))?;
 
+        self.webhooks
+            .fire(WebhookEvent::TicketStatusChanged, &ticket)
+            .await;
+        self.notifications
+            .notify(TicketNotification::Resolved, &ticket);
+        self.emails.ticket_resolved(&ticket);
+        self.post_status_message(&ticket, owner_id).await;
         Ok(ticket)
     }
 
@@ -364,7 +1287,7 @@ impl TicketService {
                 closed_at = NULL,
                 closed_reason = NULL
             WHERE r.id = $1 AND (
-                r.project_id IN (SELECT id FROM projects WHERE owner_id = $2)
+                r.project_id IN (SELECT accessible_project_ids($2))
                 OR r.session_id IN (SELECT id FROM sessions WHERE owner_id = $2)
             )
             RETURNING r.*
@@ -376,143 +1299,2440 @@ impl TicketService {
         .await?
         .ok_or_else(|| AppError::not_found("Ticket not found"))?;
 
+        self.webhooks
+            .fire(WebhookEvent::TicketStatusChanged, &ticket)
+            .await;
+        self.post_status_message(&ticket, owner_id).await;
         Ok(ticket)
     }
 
-    /// Delete a ticket
-    pub async fn delete(&self, id: Uuid, owner_id: Uuid) -> Result<()> {
+    /// Reopen a resolved ticket on behalf of the customer who submitted it
+    pub async fn reopen_for_customer(&self, id: Uuid, customer_id: Uuid) -> Result<FeedbackTicket> {
         let ticket = sqlx::query_as::<_, FeedbackTicket>(
             r#"
-            SELECT r.* FROM recordings r
-            WHERE r.id = $1 AND (
-                r.project_id IN (SELECT id FROM projects WHERE owner_id = $2)
-                OR r.session_id IN (SELECT id FROM sessions WHERE owner_id = $2)
-            )
+            UPDATE recordings SET
+                session_status = 'open',
+                ticket_status = 'open',
+                closed_at = NULL,
+                closed_reason = NULL
+            WHERE id = $1 AND customer_id = $2 AND ticket_status = 'resolved'
+            RETURNING *
             "#,
         )
         .bind(id)
-        .bind(owner_id)
+        .bind(customer_id)
         .fetch_optional(&self.db)
-        .await?
-        .ok_or_else(|| AppError::not_found("Ticket not found"))?;
+        .await?;
+        let Some(ticket) = ticket else {
+            return match self.get_by_id(id).await? {
+                Some(ticket) if ticket.customer_id == customer_id => {
+                    Err(AppError::conflict("Only resolved tickets can be reopened"))
+                }
+                _ => Err(AppError::not_found("Ticket not found")),
+            };
+        };
 
-        // Delete from storage if video exists
-        if let Some(path) = &ticket.video_storage_path {
-            let _ = self.storage.delete(path).await;
+        self.webhooks
+            .fire(WebhookEvent::TicketStatusChanged, &ticket)
+            .await;
+        if let Err(e) = self
+            .chat
+            .create_system_message(ticket.id, "Reopened by the customer")
+            .await
+        {
+            tracing::warn!(ticket_id = %ticket.id, error = %e, "Failed to post reopen chat message");
         }
+        Ok(ticket)
+    }
 
-        // Delete from database
-        sqlx::query("DELETE FROM recordings WHERE id = $1")
-            .bind(id)
-            .execute(&self.db)
+    /// Fold a duplicate ticket into another one: its chat is copied to the target as
+    /// team-only messages, the merge is recorded, and the source is closed as not relevant.
+    /// The target's status is untouched, and a ticket can only be merged once.
+    pub async fn merge(
+        &self,
+        source_id: Uuid,
+        target_id: Uuid,
+        owner_id: Uuid,
+    ) -> Result<FeedbackTicket> {
+        if source_id == target_id {
+            return Err(AppError::bad_request(
+                "A ticket cannot be merged into itself",
+            ));
+        }
+        self.get_for_owner(source_id, owner_id).await?;
+        let target = self.get_for_owner(target_id, owner_id).await?;
+
+        let mut tx = self.db.begin().await?;
+        // Lock the source so two merges of it cannot both pass the check
+        sqlx::query("SELECT id FROM recordings WHERE id = $1 FOR UPDATE")
+            .bind(source_id)
+            .execute(&mut *tx)
             .await?;
+        let already_merged: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM ticket_merges WHERE source_ticket_id = $1)",
+        )
+        .bind(source_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if already_merged {
+            return Err(AppError::bad_request("Ticket has already been merged"));
+        }
+        // The source's customer keeps their thread; the target gets team-only copies
+        sqlx::query(
+            r#"
+            INSERT INTO chat_messages
+                (recording_id, sender_id, sender_role, message, created_at, edited_at, merged_from)
+            SELECT $1, sender_id, sender_role, message, created_at, edited_at, COALESCE(merged_from, $2)
+            FROM chat_messages WHERE recording_id = $2
+            "#,
+        )
+        .bind(target_id)
+        .bind(source_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            "INSERT INTO ticket_merges (source_ticket_id, target_ticket_id, merged_by) VALUES ($1, $2, $3)",
+        )
+        .bind(source_id)
+        .bind(target_id)
+        .bind(owner_id)
+        .execute(&mut *tx)
+        .await?;
+        let source = sqlx::query_as::<_, FeedbackTicket>(
+            r#"
+            UPDATE recordings SET
+                session_status = 'closed',
+                ticket_status = 'resolved',
+                closed_at = $1,
+                closed_reason = 'not-relevant'
+            WHERE id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(Utc::now())
+        .bind(source_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
 
-        Ok(())
-    }
+        self.webhooks
+            .fire(WebhookEvent::TicketStatusChanged, &source)
+            .await;
 
-    /// Mark ticket as analyzed (called by worker)
-    pub async fn mark_analyzed(&self, ticket_id: Uuid) -> Result<()> {
-        sqlx::query("UPDATE recordings SET status = 'analyzed' WHERE id = $1")
-            .bind(ticket_id)
-            .execute(&self.db)
-            .await?;
-        Ok(())
+        let actor = self.actor_name(owner_id).await;
+        for (ticket_id, message) in [
+            (
+                source_id,
+                format!("Merged into ticket {} by {}", target_id, actor),
+            ),
+            (
+                target_id,
+                format!("Ticket {} was merged into this one by {}", source_id, actor),
+            ),
+        ] {
+            if let Err(e) = self.chat.create_system_message(ticket_id, &message).await {
+                tracing::warn!(ticket_id = %ticket_id, error = %e, "Failed to post merge chat message");
+            }
+        }
+        Ok(target)
     }
 
-    /// Mark ticket as failed (called by worker)
-    pub async fn mark_failed(&self, ticket_id: Uuid) -> Result<()> {
-        sqlx::query("UPDATE recordings SET status = 'failed' WHERE id = $1")
-            .bind(ticket_id)
-            .execute(&self.db)
-            .await?;
-        Ok(())
+    /// Record a status change in the ticket's chat. Best-effort: the change is already
+    /// committed, so failures are only logged.
+    async fn post_status_message(&self, ticket: &FeedbackTicket, actor_id: Uuid) {
+        let message = format!(
+            "Status changed to {} by {}",
+            ticket.ticket_status,
+            self.actor_name(actor_id).await
+        );
+        if let Err(e) = self.chat.create_system_message(ticket.id, &message).await {
+            tracing::warn!(ticket_id = %ticket.id, error = %e, "Failed to post status chat message");
+        }
     }
 
-    /// Generate video URL for a ticket
-    pub async fn get_video_url(&self, ticket: &FeedbackTicket) -> Result<Option<String>> {
-        if ticket.video_storage_path.is_some() {
-            Ok(Some(format!("/api/v1/tickets/{}/video", ticket.id)))
-        } else {
-            Ok(None)
-        }
+    /// Display name for a team member in system chat notes
+    async fn actor_name(&self, actor_id: Uuid) -> String {
+        sqlx::query_scalar::<_, Option<String>>(
+            "SELECT COALESCE(name, email) FROM users WHERE id = $1",
+        )
+        .bind(actor_id)
+        .fetch_optional(&self.db)
+        .await
+        .ok()
+        .flatten()
+        .flatten()
+        .unwrap_or_else(|| "a team member".to_string())
     }
 
-    /// Get overview stats for a project owner
-    pub async fn get_overview_stats(&self, owner_id: Uuid) -> Result<OverviewStats> {
-        let row = sqlx::query_as::<_, OverviewStatsRow>(
+    /// Mint a read-only share link for the ticket's report and video; returns the stored
+    /// record and the plaintext token, which is not kept and cannot be shown again
+    pub async fn create_share_token(
+        &self,
+        id: Uuid,
+        owner_id: Uuid,
+    ) -> Result<(TicketShareToken, String)> {
+        self.get_for_owner(id, owner_id).await?;
+        let token = AuthService::generate_share_token();
+        let share = sqlx::query_as::<_, TicketShareToken>(
             r#"
-            SELECT
-                COUNT(*) FILTER (WHERE r.feedback_type = 'feedback') as feedback_count,
-                COUNT(*) FILTER (WHERE r.feedback_type = 'bug') as bug_count,
-                COUNT(*) FILTER (WHERE r.feedback_type = 'idea') as idea_count,
-                COUNT(*) FILTER (WHERE r.ticket_status = 'open') as open_count,
-                COUNT(*) FILTER (WHERE r.ticket_status = 'in_progress') as in_progress_count,
-                COUNT(*) FILTER (WHERE r.ticket_status = 'in_qa') as in_qa_count,
-                COUNT(*) FILTER (WHERE r.ticket_status = 'todo') as todo_count,
-                COUNT(*) FILTER (WHERE r.ticket_status = 'backlog') as backlog_count,
-                COUNT(*) FILTER (WHERE r.ticket_status = 'resolved') as resolved_count,
-                COUNT(*) as total_count
-            FROM recordings r
-            LEFT JOIN projects p ON r.project_id = p.id
-            WHERE p.owner_id = $1 OR r.session_id IN (SELECT id FROM sessions WHERE owner_id = $1)
+            INSERT INTO ticket_share_tokens (recording_id, token_hash, created_by)
+            VALUES ($1, $2, $3)
+            RETURNING id, recording_id, created_by, created_at, revoked_at
             "#,
         )
+        .bind(id)
+        .bind(hash_key(&token))
         .bind(owner_id)
         .fetch_one(&self.db)
         .await?;
+        Ok((share, token))
+    }
 
-        let total = row.total_count.max(1) as f64;
-        Ok(OverviewStats {
-            feedback_count: row.feedback_count,
-            bug_count: row.bug_count,
-            idea_count: row.idea_count,
-            open_count: row.open_count,
-            open_pct: (row.open_count as f64 / total * 100.0).round() as i64,
-            in_progress_count: row.in_progress_count,
-            in_progress_pct: (row.in_progress_count as f64 / total * 100.0).round() as i64,
-            in_qa_count: row.in_qa_count,
-            in_qa_pct: (row.in_qa_count as f64 / total * 100.0).round() as i64,
-            todo_count: row.todo_count,
-            todo_pct: (row.todo_count as f64 / total * 100.0).round() as i64,
-            backlog_count: row.backlog_count,
-            backlog_pct: (row.backlog_count as f64 / total * 100.0).round() as i64,
-            resolved_count: row.resolved_count,
-            resolved_pct: (row.resolved_count as f64 / total * 100.0).round() as i64,
-            total_count: row.total_count,
-        })
+    /// Revoke a share link; revoking it again keeps the original revocation time
+    pub async fn revoke_share_token(
+        &self,
+        id: Uuid,
+        share_id: Uuid,
+        owner_id: Uuid,
+    ) -> Result<TicketShareToken> {
+        self.get_for_owner(id, owner_id).await?;
+        sqlx::query_as::<_, TicketShareToken>(
+            r#"
+            UPDATE ticket_share_tokens SET revoked_at = COALESCE(revoked_at, NOW())
+            WHERE id = $1 AND recording_id = $2
+            RETURNING id, recording_id, created_by, created_at, revoked_at
+            "#,
+        )
+        .bind(share_id)
+        .bind(id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::not_found("Share link not found"))
     }
-}
 
-#[derive(Debug, sqlx::FromRow)]
-struct OverviewStatsRow {
-    feedback_count: i64,
-    bug_count: i64,
-    idea_count: i64,
-    open_count: i64,
-    in_progress_count: i64,
-    in_qa_count: i64,
-    todo_count: i64,
-    backlog_count: i64,
+    /// The ticket an unrevoked share token was minted for
+    pub async fn get_by_share_token(&self, token: &str) -> Result<FeedbackTicket> {
+        sqlx::query_as::<_, FeedbackTicket>(
+            r#"
+            SELECT r.* FROM recordings r
+            JOIN ticket_share_tokens s ON s.recording_id = r.id
+            WHERE s.token_hash = $1 AND s.revoked_at IS NULL
+            "#,
+        )
+        .bind(hash_key(token))
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::not_found("Shared ticket not found"))
+    }
+
+    /// Delete a ticket
+    pub async fn delete(&self, id: Uuid, owner_id: Uuid) -> Result<()> {
+        let ticket = self.get_for_owner(id, owner_id).await?;
+
+        // Delete from storage if video or screenshot exists
+        for path in [&ticket.video_storage_path, &ticket.screenshot_url]
+            .into_iter()
+            .flatten()
+        {
+            let _ = self.storage.delete(path).await;
+        }
+
+        // Delete from database
+        sqlx::query("DELETE FROM recordings WHERE id = $1")
+            .bind(id)
+            .execute(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Archive the existing report and queue a fresh analysis of the stored video, or of
+    /// the screenshot when the ticket has no video
+    pub async fn reanalyze(&self, id: Uuid, owner_id: Uuid) -> Result<FeedbackTicket> {
+        let ticket = self.get_for_owner(id, owner_id).await?;
+        let (storage_path, mime_type, size) =
+            match (&ticket.video_storage_path, &ticket.screenshot_url) {
+                (Some(video), _) => (
+                    video.clone(),
+                    ticket.video_mime_type.clone(),
+                    ticket.video_size_bytes.unwrap_or(0),
+                ),
+                (None, Some(screenshot)) => (screenshot.clone(), Some("image/png".to_string()), 0),
+                (None, None) => {
+                    return Err(AppError::bad_request(
+                        "Ticket has no recorded video or screenshot to analyze",
+                    ))
+                }
+            };
+        if ticket.status == ProcessingStatus::Processing {
+            return Err(AppError::conflict("Ticket is already being analyzed"));
+        }
+
+        let job_request = CreateJobRequest {
+            video_storage_path: storage_path,
+            video_size_bytes: size,
+            mime_type,
+            prompt: None,
+            user_id: Some(ticket.customer_id),
+            recording_id: Some(id),
+        };
+        // Archive the old report before queueing so the new one can't be archived by
+        // mistake. One transaction, so a failed enqueue leaves the report and status as
+        // they were.
+        let mut tx = self.db.begin().await?;
+        Self::archive_report(&mut tx, id).await?;
+        let job_id = QueueService::enqueue_in(&mut tx, job_request)
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to create analysis job: {}", e)))?;
+        let ticket = sqlx::query_as::<_, FeedbackTicket>(
+            r#"
+            UPDATE recordings SET status = 'processing', analysis_job_id = $1, updated_at = NOW()
+            WHERE id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(job_id)
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(ticket)
+    }
+
+    /// Move a ticket's live report and its issues into `report_versions`, inside the
+    /// caller's transaction. Does nothing when the ticket has no report.
+    pub async fn archive_report(conn: &mut sqlx::PgConnection, ticket_id: Uuid) -> Result<()> {
+        sqlx::query(
+            r#"
+            WITH archived AS (
+                DELETE FROM reports r
+                WHERE r.recording_id = $1
+                RETURNING r.*, COALESCE(
+                    (
+                        SELECT jsonb_agg(to_jsonb(i) ORDER BY i.severity, i.created_at)
+                        FROM issues i
+                        WHERE i.report_id = r.id
+                    ),
+                    '[]'::jsonb
+                ) AS issue_rows
+            )
+            INSERT INTO report_versions (recording_id, report_version, report, issues)
+            SELECT recording_id, report_version, to_jsonb(archived) - 'issue_rows', issue_rows
+            FROM archived
+            "#,
+        )
+        .bind(ticket_id)
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Reports a ticket had before its latest reanalysis, newest first
+    pub async fn report_versions(&self, ticket_id: Uuid) -> Result<Vec<ReportVersion>> {
+        let versions = sqlx::query_as::<_, ReportVersion>(
+            "SELECT * FROM report_versions WHERE recording_id = $1 ORDER BY report_version DESC",
+        )
+        .bind(ticket_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(versions)
+    }
+
+    /// Record that a user has read a ticket
+    pub async fn mark_read(&self, id: Uuid, user_id: Uuid) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO ticket_reads (recording_id, user_id, read_at)
+            VALUES ($1, $2, NOW())
+            ON CONFLICT (recording_id, user_id) DO UPDATE SET read_at = EXCLUDED.read_at
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Mark every ticket in a project as read for a user. Returns the number of tickets marked.
+    pub async fn mark_all_read(&self, project_id: Uuid, user_id: Uuid) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO ticket_reads (recording_id, user_id, read_at)
+            SELECT r.id, $2, NOW() FROM recordings r
+            WHERE r.project_id = $1
+            ON CONFLICT (recording_id, user_id) DO UPDATE SET read_at = EXCLUDED.read_at
+            "#,
+        )
+        .bind(project_id)
+        .bind(user_id)
+        .execute(&self.db)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Count tickets in a project the user has not read since they were last updated
+    pub async fn count_unread(&self, project_id: Uuid, user_id: Uuid) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM recordings r
+            LEFT JOIN ticket_reads tr ON tr.recording_id = r.id AND tr.user_id = $2
+            WHERE r.project_id = $1
+            AND (tr.read_at IS NULL OR r.updated_at > tr.read_at)
+            "#,
+        )
+        .bind(project_id)
+        .bind(user_id)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(count)
+    }
+
+    /// Stage of the ticket's analysis job while it is running
+    pub async fn get_progress_stage(&self, ticket_id: Uuid) -> Result<Option<JobProgressStage>> {
+        let stage = sqlx::query_scalar::<_, Option<JobProgressStage>>(
+            r#"
+            SELECT j.progress_stage FROM recordings r
+            JOIN analysis_jobs j ON j.id = r.analysis_job_id
+            WHERE r.id = $1 AND j.status = 'processing'
+            "#,
+        )
+        .bind(ticket_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(stage.flatten())
+    }
+
+    /// Analysis progress of a project's ticket, for the submitter's widget
+    pub async fn get_widget_status(
+        &self,
+        ticket_id: Uuid,
+        project_id: Uuid,
+    ) -> Result<WidgetTicketStatus> {
+        let status = sqlx::query_as::<_, WidgetTicketStatus>(
+            r#"
+            SELECT
+                r.status,
+                j.progress_stage,
+                EXISTS (SELECT 1 FROM reports rp WHERE rp.recording_id = r.id) AS has_report
+            FROM recordings r
+            LEFT JOIN analysis_jobs j ON j.id = r.analysis_job_id AND j.status = 'processing'
+            WHERE r.id = $1 AND r.project_id = $2
+            "#,
+        )
+        .bind(ticket_id)
+        .bind(project_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::not_found("Ticket not found"))?;
+
+        Ok(status)
+    }
+
+    /// The submitter's most recent resolved tickets in the same project, newest first,
+    /// with each report's outcome and issue titles; for the analysis prompt
+    pub async fn prior_resolved_tickets(
+        &self,
+        ticket: &FeedbackTicket,
+        limit: i64,
+    ) -> Result<Vec<PriorTicket>> {
+        let Some(project_id) = ticket.project_id else {
+            return Ok(Vec::new());
+        };
+        let tickets = sqlx::query_as::<_, PriorTicket>(
+            r#"
+            SELECT
+                r.created_at,
+                r.task_description,
+                rp.outcome,
+                COALESCE(
+                    ARRAY(SELECT i.title FROM issues i WHERE i.report_id = rp.id ORDER BY i.created_at),
+                    '{}'
+                ) AS issue_titles
+            FROM recordings r
+            LEFT JOIN reports rp ON rp.recording_id = r.id
+            WHERE r.project_id = $1 AND r.customer_id = $2 AND r.id <> $3
+              AND r.ticket_status = 'resolved'
+            ORDER BY r.created_at DESC
+            LIMIT $4
+            "#,
+        )
+        .bind(project_id)
+        .bind(ticket.customer_id)
+        .bind(ticket.id)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(tickets)
+    }
+
+    /// Mark ticket as analyzed (called by worker)
+    pub async fn mark_analyzed(&self, ticket_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE recordings SET status = 'analyzed' WHERE id = $1")
+            .bind(ticket_id)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// Apply a project's outcome rule to a freshly analyzed ticket (called by worker).
+    /// Fields are only changed while still at their defaults (`neutral` / `open`),
+    /// so anything a human already triaged is left alone.
+    pub async fn apply_outcome_rule(
+        &self,
+        ticket_id: Uuid,
+        rule: &OutcomeRule,
+    ) -> Result<FeedbackTicket> {
+        let ticket = sqlx::query_as::<_, FeedbackTicket>(
+            r#"
+            UPDATE recordings SET
+                priority = CASE
+                    WHEN $2::varchar IS NOT NULL AND priority = 'neutral' THEN $2
+                    ELSE priority
+                END,
+                ticket_status = CASE
+                    WHEN $3::varchar IS NOT NULL AND ticket_status = 'open' THEN $3
+                    ELSE ticket_status
+                END,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(ticket_id)
+        .bind(rule.priority)
+        .bind(rule.ticket_status)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::not_found("Ticket not found"))?;
+
+        Ok(ticket)
+    }
+
+    /// Get an issue from the ticket's report
+    pub async fn get_issue(&self, ticket_id: Uuid, issue_id: Uuid) -> Result<Issue> {
+        let issue = sqlx::query_as::<_, Issue>(
+            r#"
+            SELECT i.* FROM issues i
+            JOIN reports rp ON rp.id = i.report_id
+            WHERE i.id = $1 AND rp.recording_id = $2
+            "#,
+        )
+        .bind(issue_id)
+        .bind(ticket_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::not_found("Issue not found"))?;
+        Ok(issue)
+    }
+
+    /// Recurring issues across a project's tickets, grouped by normalized title (case,
+    /// punctuation and spacing ignored) and ordered by how many tickets hit them
+    pub async fn top_issues(&self, project_id: Uuid, limit: i64) -> Result<Vec<TopIssue>> {
+        let issues = sqlx::query_as::<_, TopIssue>(
+            r#"
+            WITH project_issues AS (
+                SELECT i.title, i.severity, i.tags, r.id AS ticket_id, r.created_at AS ticket_created_at,
+                       btrim(regexp_replace(lower(i.title), '[^a-z0-9]+', ' ', 'g')) AS normalized_title
+                FROM issues i
+                JOIN reports rp ON rp.id = i.report_id
+                JOIN recordings r ON r.id = rp.recording_id
+                WHERE r.project_id = $1
+            )
+            SELECT
+                pi.normalized_title,
+                mode() WITHIN GROUP (ORDER BY pi.title) AS title,
+                COUNT(*) AS occurrences,
+                COUNT(DISTINCT pi.ticket_id) AS ticket_count,
+                AVG(CASE pi.severity
+                    WHEN 'critical' THEN 4 WHEN 'high' THEN 3 WHEN 'medium' THEN 2 ELSE 1
+                END)::FLOAT8 AS avg_severity,
+                ARRAY(
+                    SELECT DISTINCT t FROM project_issues p2, jsonb_array_elements_text(p2.tags) t
+                    WHERE p2.normalized_title = pi.normalized_title AND jsonb_typeof(p2.tags) = 'array'
+                    ORDER BY t
+                ) AS tags,
+                ARRAY(
+                    SELECT p2.ticket_id FROM project_issues p2
+                    WHERE p2.normalized_title = pi.normalized_title
+                    GROUP BY p2.ticket_id
+                    ORDER BY MAX(p2.ticket_created_at) DESC
+                    LIMIT $3
+                ) AS example_ticket_ids
+            FROM project_issues pi
+            WHERE pi.normalized_title <> ''
+            GROUP BY pi.normalized_title
+            ORDER BY ticket_count DESC, occurrences DESC, avg_severity DESC, pi.normalized_title
+            LIMIT $2
+            "#,
+        )
+        .bind(project_id)
+        .bind(limit)
+        .bind(TOP_ISSUE_EXAMPLES)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(issues)
+    }
+
+    /// Issues reported on a project's tickets, newest first, optionally limited to one
+    /// severity and/or a tag (matched case-insensitively); returns the page and the total
+    pub async fn list_project_issues(
+        &self,
+        project_id: Uuid,
+        severity: Option<IssueSeverity>,
+        tag: Option<&str>,
+        page: i32,
+        per_page: i32,
+    ) -> Result<(Vec<ProjectIssue>, i64)> {
+        const FILTER: &str = r#"
+            FROM issues i
+            JOIN reports rp ON rp.id = i.report_id
+            JOIN recordings r ON r.id = rp.recording_id
+            WHERE r.project_id = $1
+              AND ($2::varchar IS NULL OR i.severity = $2)
+              AND ($3::text IS NULL OR (
+                  jsonb_typeof(i.tags) = 'array'
+                  AND EXISTS (
+                      SELECT 1 FROM jsonb_array_elements_text(i.tags) t WHERE lower(t) = lower($3)
+                  )
+              ))
+        "#;
+
+        let issues = sqlx::query_as::<_, ProjectIssue>(&format!(
+            r#"
+            SELECT i.*, r.id AS ticket_id, r.ticket_status, r.feedback_type AS ticket_feedback_type,
+                   r.created_at AS ticket_created_at
+            {}
+            ORDER BY i.created_at DESC, i.id
+            LIMIT $4 OFFSET $5
+            "#,
+            FILTER
+        ))
+        .bind(project_id)
+        .bind(severity)
+        .bind(tag)
+        .bind(per_page as i64)
+        .bind(((page - 1) * per_page) as i64)
+        .fetch_all(&self.db)
+        .await?;
+
+        let total: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) {}", FILTER))
+            .bind(project_id)
+            .bind(severity)
+            .bind(tag)
+            .fetch_one(&self.db)
+            .await?;
+
+        Ok((issues, total))
+    }
+
+    /// Set the triage status of an issue on a ticket the owner controls
+    pub async fn update_issue_status(
+        &self,
+        ticket_id: Uuid,
+        issue_id: Uuid,
+        owner_id: Uuid,
+        issue_status: IssueStatus,
+    ) -> Result<Issue> {
+        let issue = sqlx::query_as::<_, Issue>(
+            r#"
+            UPDATE issues i SET issue_status = $1, updated_at = NOW()
+            FROM reports rp, recordings r
+            WHERE i.id = $2 AND rp.id = i.report_id AND rp.recording_id = $3
+              AND r.id = rp.recording_id AND (
+                r.project_id IN (SELECT accessible_project_ids($4))
+                OR r.session_id IN (SELECT id FROM sessions WHERE owner_id = $4)
+            )
+            RETURNING i.*
+            "#,
+        )
+        .bind(issue_status)
+        .bind(issue_id)
+        .bind(ticket_id)
+        .bind(owner_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::not_found("Issue not found"))?;
+        Ok(issue)
+    }
+
+    /// Apply a reviewer's corrections to a ticket's report and stamp who made them.
+    /// `raw_analysis` is never touched so the original output is kept.
+    pub async fn update_report(
+        &self,
+        ticket_id: Uuid,
+        editor_id: Uuid,
+        changes: &UpdateReportRequest,
+    ) -> Result<Report> {
+        let suggested_actions = changes.suggested_actions.as_ref().map(|actions| {
+            let actions: Vec<&str> = actions
+                .iter()
+                .map(|a| a.trim())
+                .filter(|a| !a.is_empty())
+                .collect();
+            sqlx::types::Json(actions)
+        });
+        let report = sqlx::query_as::<_, Report>(
+            r#"
+            UPDATE reports SET
+                overview = COALESCE($1, overview),
+                outcome = COALESCE($2, outcome),
+                confidence = COALESCE($3, confidence),
+                suggested_actions = COALESCE($4, suggested_actions),
+                edited_by = $5,
+                edited_at = NOW(),
+                updated_at = NOW()
+            WHERE recording_id = $6
+            RETURNING *
+            "#,
+        )
+        .bind(changes.overview.as_deref().map(str::trim))
+        .bind(changes.outcome)
+        .bind(changes.confidence)
+        .bind(suggested_actions)
+        .bind(editor_id)
+        .bind(ticket_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| {
+            AppError::not_found("Report not found - analysis may still be processing")
+        })?;
+        Ok(report)
+    }
+
+    /// Record where an issue was exported to (e.g. its Jira URL)
+    pub async fn set_issue_external_url(&self, issue_id: Uuid, url: &str) -> Result<Issue> {
+        let issue = sqlx::query_as::<_, Issue>(
+            r#"
+            UPDATE issues SET external_ticket_url = $1, updated_at = NOW()
+            WHERE id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(url)
+        .bind(issue_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::not_found("Issue not found"))?;
+        Ok(issue)
+    }
+
+    /// Mark ticket as failed (called by worker)
+    pub async fn mark_failed(&self, ticket_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE recordings SET status = 'failed' WHERE id = $1")
+            .bind(ticket_id)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// Generate video URL for a ticket
+    pub async fn get_video_url(&self, ticket: &FeedbackTicket) -> Result<Option<String>> {
+        if ticket.video_storage_path.is_some() {
+            Ok(Some(format!("/api/v1/tickets/{}/video", ticket.id)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Generate screenshot URL for a ticket (`screenshot_url` holds the storage path)
+    pub async fn get_screenshot_url(&self, ticket: &FeedbackTicket) -> Result<Option<String>> {
+        if ticket.screenshot_url.is_some() {
+            Ok(Some(format!("/api/v1/tickets/{}/screenshot", ticket.id)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Get overview stats for a project owner
+    pub async fn get_overview_stats(&self, owner_id: Uuid) -> Result<OverviewStats> {
+        let row = sqlx::query_as::<_, OverviewStatsRow>(
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE r.feedback_type = 'feedback') as feedback_count,
+                COUNT(*) FILTER (WHERE r.feedback_type = 'bug') as bug_count,
+                COUNT(*) FILTER (WHERE r.feedback_type = 'idea') as idea_count,
+                COUNT(*) FILTER (WHERE r.ticket_status = 'open') as open_count,
+                COUNT(*) FILTER (WHERE r.ticket_status = 'in_progress') as in_progress_count,
+                COUNT(*) FILTER (WHERE r.ticket_status = 'in_qa') as in_qa_count,
+                COUNT(*) FILTER (WHERE r.ticket_status = 'todo') as todo_count,
+                COUNT(*) FILTER (WHERE r.ticket_status = 'backlog') as backlog_count,
+                COUNT(*) FILTER (WHERE r.ticket_status = 'resolved') as resolved_count,
+                COUNT(*) FILTER (WHERE r.status = 'failed') as failed_count,
+                COUNT(*) FILTER (WHERE r.status = 'processing') as processing_count,
+                COUNT(*) as total_count
+            FROM recordings r
+            WHERE r.project_id IN (SELECT accessible_project_ids($1))
+               OR r.session_id IN (SELECT id FROM sessions WHERE owner_id = $1)
+            "#,
+        )
+        .bind(owner_id)
+        .fetch_one(&self.db)
+        .await?;
+
+        let total = row.total_count.max(1) as f64;
+        Ok(OverviewStats {
+            feedback_count: row.feedback_count,
+            bug_count: row.bug_count,
+            idea_count: row.idea_count,
+            open_count: row.open_count,
+            open_pct: (row.open_count as f64 / total * 100.0).round() as i64,
+            in_progress_count: row.in_progress_count,
+            in_progress_pct: (row.in_progress_count as f64 / total * 100.0).round() as i64,
+            in_qa_count: row.in_qa_count,
+            in_qa_pct: (row.in_qa_count as f64 / total * 100.0).round() as i64,
+            todo_count: row.todo_count,
+            todo_pct: (row.todo_count as f64 / total * 100.0).round() as i64,
+            backlog_count: row.backlog_count,
+            backlog_pct: (row.backlog_count as f64 / total * 100.0).round() as i64,
+            resolved_count: row.resolved_count,
+            resolved_pct: (row.resolved_count as f64 / total * 100.0).round() as i64,
+            failed_count: row.failed_count,
+            failed_pct: (row.failed_count as f64 / total * 100.0).round() as i64,
+            processing_count: row.processing_count,
+            processing_pct: (row.processing_count as f64 / total * 100.0).round() as i64,
+            total_count: row.total_count,
+        })
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct OverviewStatsRow {
+    feedback_count: i64,
+    bug_count: i64,
+    idea_count: i64,
+    open_count: i64,
+    in_progress_count: i64,
+    in_qa_count: i64,
+    todo_count: i64,
+    backlog_count: i64,
     resolved_count: i64,
+    failed_count: i64,
+    processing_count: i64,
     total_count: i64,
 }
 
-#[derive(Debug, serde::Serialize)]
-pub struct OverviewStats {
-    pub feedback_count: i64,
-    pub bug_count: i64,
-    pub idea_count: i64,
-    pub open_count: i64,
-    pub open_pct: i64,
-    pub in_progress_count: i64,
-    pub in_progress_pct: i64,
-    pub in_qa_count: i64,
-    pub in_qa_pct: i64,
-    pub todo_count: i64,
-    pub todo_pct: i64,
-    pub backlog_count: i64,
-    pub backlog_pct: i64,
-    pub resolved_count: i64,
-    pub resolved_pct: i64,
-    pub total_count: i64,
+#[derive(Debug, serde::Serialize)]
+pub struct OverviewStats {
+    pub feedback_count: i64,
+    pub bug_count: i64,
+    pub idea_count: i64,
+    pub open_count: i64,
+    pub open_pct: i64,
+    pub in_progress_count: i64,
+    pub in_progress_pct: i64,
+    pub in_qa_count: i64,
+    pub in_qa_pct: i64,
+    pub todo_count: i64,
+    pub todo_pct: i64,
+    pub backlog_count: i64,
+    pub backlog_pct: i64,
+    pub resolved_count: i64,
+    pub resolved_pct: i64,
+    /// Tickets whose analysis failed (processing status, not ticket status)
+    pub failed_count: i64,
+    pub failed_pct: i64,
+    /// Tickets whose analysis is still running
+    pub processing_count: i64,
+    pub processing_pct: i64,
+    pub total_count: i64,
+}
+
+/// An earlier resolved ticket from the same submitter, summarized for the analysis prompt
+#[derive(Debug, sqlx::FromRow)]
+pub struct PriorTicket {
+    pub created_at: DateTime<Utc>,
+    pub task_description: Option<String>,
+    pub outcome: Option<ReportOutcome>,
+    pub issue_titles: Vec<String>,
+}
+
+/// What the widget may show a submitter about their ticket's analysis
+#[derive(Debug, sqlx::FromRow, serde::Serialize)]
+pub struct WidgetTicketStatus {
+    pub status: ProcessingStatus,
+    /// Current analysis stage while the job is running
+    pub progress_stage: Option<JobProgressStage>,
+    pub has_report: bool,
+}
+
+/// Example tickets returned per recurring issue
+const TOP_ISSUE_EXAMPLES: i64 = 3;
+
+/// An issue with a reference to the ticket it was reported on
+#[derive(Debug, sqlx::FromRow, serde::Serialize)]
+pub struct ProjectIssue {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub issue: Issue,
+    pub ticket_id: Uuid,
+    pub ticket_status: TicketStatus,
+    pub ticket_feedback_type: FeedbackType,
+    pub ticket_created_at: DateTime<Utc>,
+}
+
+/// An issue recurring across a project's tickets
+#[derive(Debug, sqlx::FromRow, serde::Serialize)]
+pub struct TopIssue {
+    /// Grouping key: lowercase title with punctuation collapsed to single spaces
+    pub normalized_title: String,
+    /// Most common original title in the group
+    pub title: String,
+    /// Issues in the group (a ticket may report the same problem more than once)
+    pub occurrences: i64,
+    /// Tickets that hit the issue
+    pub ticket_count: i64,
+    /// Mean severity from 1 (low) to 4 (critical)
+    pub avg_severity: f64,
+    pub tags: Vec<String>,
+    /// Most recent tickets that hit the issue
+    pub example_ticket_ids: Vec<Uuid>,
+}
+
+/// Ticket fields written to the CSV export
+#[derive(Debug, sqlx::FromRow)]
+struct TicketCsvRow {
+    id: Uuid,
+    feedback_type: FeedbackType,
+    ticket_status: TicketStatus,
+    priority: TicketPriority,
+    submitter_name: Option<String>,
+    submitter_email: Option<String>,
+    page_url: Option<String>,
+    ai_confidence: Option<i32>,
+    created_at: DateTime<Utc>,
+    task_description: Option<String>,
+}
+
+impl TicketCsvRow {
+    /// Values in `CSV_HEADER` order
+    fn record(&self) -> [String; 9] {
+        let submitter = match (
+            self.submitter_name.as_deref().filter(|s| !s.is_empty()),
+            self.submitter_email.as_deref().filter(|s| !s.is_empty()),
+        ) {
+            (Some(name), Some(email)) => format!("{} <{}>", name, email),
+            (Some(name), None) => name.to_string(),
+            (None, Some(email)) => email.to_string(),
+            (None, None) => String::new(),
+        };
+        let description = self.task_description.as_deref().unwrap_or_default().trim();
+        let description = if description.chars().count() > CSV_DESCRIPTION_CHARS {
+            let cut: String = description.chars().take(CSV_DESCRIPTION_CHARS).collect();
+            format!("{}…", cut.trim_end())
+        } else {
+            description.to_string()
+        };
+        [
+            self.id.to_string(),
+            self.feedback_type.to_string(),
+            self.ticket_status.to_string(),
+            self.priority.to_string(),
+            submitter,
+            self.page_url.clone().unwrap_or_default(),
+            self.ai_confidence
+                .map(|c| c.to_string())
+                .unwrap_or_default(),
+            self.created_at.to_rfc3339(),
+            description,
+        ]
+    }
+}
+
+/// CSV-encode records; fields with commas, quotes or newlines are quoted
+fn encode_csv(records: &[[String; 9]]) -> anyhow::Result<Bytes> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for record in records {
+        writer.write_record(record)?;
+    }
+    Ok(Bytes::from(writer.into_inner()?))
+}
+
+/// Widget screenshots must be PNGs of at most `MAX_SCREENSHOT_BYTES`
+fn validate_screenshot(data: &[u8]) -> Result<()> {
+    if data.len() > MAX_SCREENSHOT_BYTES {
+        return Err(AppError::bad_request(format!(
+            "Screenshot too large ({:.1}MB). Max: {}MB",
+            data.len() as f64 / (1024.0 * 1024.0),
+            MAX_SCREENSHOT_BYTES / (1024 * 1024)
+        )));
+    }
+    if !data.starts_with(&PNG_SIGNATURE) {
+        return Err(AppError::bad_request("Screenshot must be a PNG image"));
+    }
+    Ok(())
+}
+
+/// Where a ticket's video is stored; direct uploads must use the same path
+fn video_storage_path(ticket: &FeedbackTicket) -> String {
+    let owner_id = ticket
+        .project_id
+        .unwrap_or(ticket.session_id.unwrap_or(Uuid::nil()));
+    format!("recordings/{}/{}.webm", owner_id, ticket.id)
+}
+
+/// Each ticket gets one uploaded video; reanalysis reuses it
+fn ensure_no_video_yet(ticket: &FeedbackTicket) -> Result<()> {
+    if ticket.analysis_job_id.is_some() {
+        return Err(AppError::conflict(
+            "A video was already uploaded for this ticket",
+        ));
+    }
+    Ok(())
+}
+
+/// Duration to store for an upload: the container's own duration when it records one,
+/// otherwise what the client reported. Large disagreements are logged.
+fn verified_duration(ticket_id: Uuid, probed_secs: Option<f64>, client_secs: i32) -> i32 {
+    let Some(probed_secs) = probed_secs else {
+        return client_secs;
+    };
+    let probed = probed_secs.round().min(i32::MAX as f64) as i32;
+    let tolerance = DURATION_MISMATCH_TOLERANCE_SECS.max(probed / 5);
+    if (probed - client_secs).abs() > tolerance {
+        tracing::warn!(
+            %ticket_id,
+            client_secs,
+            probed_secs = probed,
+            "Client-reported video duration disagrees with the video"
+        );
+    }
+    probed
+}
+
+/// MIME type of an uploaded video: the multipart Content-Type when it names a video
+/// (codec parameters dropped), otherwise derived from the file extension
+fn video_mime_type(content_type: Option<&str>, file_name: Option<&str>) -> Option<String> {
+    let from_header = content_type
+        .and_then(|ct| ct.split(';').next())
+        .map(|ct| ct.trim().to_ascii_lowercase())
+        .filter(|ct| ct.starts_with("video/"));
+    from_header.or_else(|| {
+        file_name
+            .and_then(|name| std::path::Path::new(name).extension())
+            .and_then(|ext| ext.to_str())
+            .and_then(GeminiService::mime_type_for_extension)
+            .map(String::from)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::models::{ClosedReason, UserRole};
+    use crate::test_support::{seed_project, seed_ticket, seed_user};
+
+    fn service(pool: &PgPool) -> TicketService {
+        let storage = Arc::new(StorageService::new(&Config::test_default()).unwrap());
+        let webhooks = Arc::new(WebhookService::new(pool.clone()));
+        let notifications = Arc::new(NotificationService::new(
+            &Config::test_default(),
+            pool.clone(),
+        ));
+        let emails = Arc::new(EmailService::new(&Config::test_default()).unwrap());
+        let chat = Arc::new(ChatService::new(pool.clone()));
+        TicketService::new(pool.clone(), storage, webhooks, notifications, emails, chat)
+    }
+
+    fn list_query() -> TicketListQuery {
+        TicketListQuery {
+            project_id: None,
+            feedback_type: None,
+            ticket_status: None,
+            priority: None,
+            search: None,
+            assignee_id: None,
+            created_after: None,
+            created_before: None,
+            label: None,
+            sentiment: None,
+            sort_by: TicketSortKey::default(),
+            sort_dir: SortDirection::default(),
+            cursor: None,
+            page: 1,
+            per_page: 20,
+        }
+    }
+
+    /// Owner, project and a ticket in the given state; returns (owner_id, ticket_id)
+    async fn seed_owned_ticket(
+        pool: &PgPool,
+        video_path: Option<&str>,
+        status: &str,
+    ) -> (Uuid, Uuid) {
+        let owner_id = seed_user(pool, "owner@example.com", "internal").await.id;
+        let project_id = seed_project(pool, owner_id).await;
+        let ticket_id = seed_ticket(pool, project_id, owner_id, status).await;
+        sqlx::query(
+            "UPDATE recordings SET video_storage_path = $2, video_size_bytes = 2048 WHERE id = $1",
+        )
+        .bind(ticket_id)
+        .bind(video_path)
+        .execute(pool)
+        .await
+        .unwrap();
+        (owner_id, ticket_id)
+    }
+
+    #[test]
+    fn verified_duration_prefers_the_probed_value() {
+        let id = Uuid::new_v4();
+        assert_eq!(verified_duration(id, Some(12.5), 3600), 13);
+        assert_eq!(verified_duration(id, Some(30.2), 30), 30);
+        assert_eq!(verified_duration(id, None, 42), 42);
+    }
+
+    #[test]
+    fn video_mime_type_prefers_content_type() {
+        assert_eq!(
+            video_mime_type(Some("video/webm;codecs=vp9"), Some("clip.mp4")).as_deref(),
+            Some("video/webm")
+        );
+    }
+
+    #[test]
+    fn video_mime_type_falls_back_to_extension() {
+        assert_eq!(
+            video_mime_type(Some("application/octet-stream"), Some("clip.MP4")).as_deref(),
+            Some("video/mp4")
+        );
+        assert_eq!(
+            video_mime_type(None, Some("clip.mov")).as_deref(),
+            Some("video/quicktime")
+        );
+        assert_eq!(video_mime_type(None, Some("blob")), None);
+        assert_eq!(video_mime_type(None, None), None);
+    }
+
+    fn png(len: usize) -> Vec<u8> {
+        let mut data = PNG_SIGNATURE.to_vec();
+        data.resize(len, 0);
+        data
+    }
+
+    #[test]
+    fn validate_screenshot_rejects_oversized_files() {
+        assert!(validate_screenshot(&png(MAX_SCREENSHOT_BYTES)).is_ok());
+        let err = validate_screenshot(&png(MAX_SCREENSHOT_BYTES + 1)).unwrap_err();
+        assert!(err.to_string().contains("too large"), "{err}");
+    }
+
+    #[test]
+    fn validate_screenshot_rejects_non_images() {
+        assert!(validate_screenshot(b"GIF89a not a png").is_err());
+        assert!(validate_screenshot(b"<svg xmlns='http://www.w3.org/2000/svg'/>").is_err());
+        assert!(validate_screenshot(&[]).is_err());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn upload_screenshot_stores_png_on_ticket(pool: PgPool) {
+        let (_, ticket_id) = seed_owned_ticket(&pool, None, "recording").await;
+        let project_id: Uuid =
+            sqlx::query_scalar("SELECT project_id FROM recordings WHERE id = $1")
+                .bind(ticket_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        let service = service(&pool);
+
+        // Only the ticket's own project may attach to it
+        assert!(matches!(
+            service
+                .upload_screenshot(ticket_id, Uuid::new_v4(), png(64))
+                .await,
+            Err(AppError::NotFound(_))
+        ));
+        assert!(matches!(
+            service
+                .upload_screenshot(ticket_id, project_id, b"not an image".to_vec())
+                .await,
+            Err(AppError::BadRequest(_))
+        ));
+
+        let ticket = service
+            .upload_screenshot(ticket_id, project_id, png(64))
+            .await
+            .unwrap();
+        let path = format!("screenshots/{}/{}.png", project_id, ticket_id);
+        assert_eq!(ticket.screenshot_url.as_deref(), Some(path.as_str()));
+        assert_eq!(service.storage.download(&path).await.unwrap(), png(64));
+        assert_eq!(
+            service.get_screenshot_url(&ticket).await.unwrap(),
+            Some(format!("/api/v1/tickets/{}/screenshot", ticket_id))
+        );
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn widget_status_follows_analysis(pool: PgPool) {
+        let (owner_id, ticket_id) = seed_owned_ticket(&pool, None, "recording").await;
+        let project_id: Uuid =
+            sqlx::query_scalar("SELECT project_id FROM recordings WHERE id = $1")
+                .bind(ticket_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        let service = service(&pool);
+
+        let ticket = service
+            .upload_video(ticket_id, owner_id, vec![0u8; 64], 5, None, None)
+            .await
+            .unwrap();
+        let status = service
+            .get_widget_status(ticket_id, project_id)
+            .await
+            .unwrap();
+        assert_eq!(status.status, ProcessingStatus::Processing);
+        assert_eq!(status.progress_stage, None);
+        assert!(!status.has_report);
+
+        // Worker picks the job up
+        sqlx::query(
+            "UPDATE analysis_jobs SET status = 'processing', progress_stage = 'analyzing' WHERE id = $1",
+        )
+        .bind(ticket.analysis_job_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let status = service
+            .get_widget_status(ticket_id, project_id)
+            .await
+            .unwrap();
+        assert_eq!(status.progress_stage, Some(JobProgressStage::Analyzing));
+
+        // ...and finishes
+        sqlx::query("UPDATE analysis_jobs SET status = 'completed' WHERE id = $1")
+            .bind(ticket.analysis_job_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO reports (recording_id, outcome) VALUES ($1, 'success')")
+            .bind(ticket_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        service.mark_analyzed(ticket_id).await.unwrap();
+        let status = service
+            .get_widget_status(ticket_id, project_id)
+            .await
+            .unwrap();
+        assert_eq!(status.status, ProcessingStatus::Analyzed);
+        assert_eq!(status.progress_stage, None);
+        assert!(status.has_report);
+
+        assert!(matches!(
+            service.get_widget_status(ticket_id, Uuid::new_v4()).await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn upload_video_stores_mime_type(pool: PgPool) {
+        let (owner_id, ticket_id) = seed_owned_ticket(&pool, None, "recording").await;
+
+        let ticket = service(&pool)
+            .upload_video(
+                ticket_id,
+                owner_id,
+                vec![0u8; 64],
+                5,
+                Some("video/mp4"),
+                Some("clip.mp4"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(ticket.video_mime_type.as_deref(), Some("video/mp4"));
+        assert_eq!(ticket.video_content_type(), "video/mp4");
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn concurrent_uploads_queue_one_analysis(pool: PgPool) {
+        let (owner_id, ticket_id) = seed_owned_ticket(&pool, None, "recording").await;
+        let service = service(&pool);
+        let upload = || service.upload_video(ticket_id, owner_id, vec![0u8; 64], 5, None, None);
+
+        // Racing uploads: only one may claim the ticket
+        let (first, second) = tokio::join!(upload(), upload());
+        let conflicts = [&first, &second]
+            .iter()
+            .filter(|result| matches!(result, Err(AppError::Conflict(_))))
+            .count();
+        assert!(first.is_ok() || second.is_ok());
+        assert_eq!(conflicts, 1);
+
+        // Uploading again later is refused up front
+        assert!(matches!(upload().await, Err(AppError::Conflict(_))));
+
+        let (jobs, quota_used): (i64, i32) = sqlx::query_as(
+            r#"
+            SELECT (SELECT COUNT(*) FROM analysis_jobs WHERE recording_id = $1),
+                   (SELECT quota_used FROM users WHERE id = $2)
+            "#,
+        )
+        .bind(ticket_id)
+        .bind(owner_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!((jobs, quota_used), (1, 1));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn upload_video_charges_the_project_owner_except_internal_ones(pool: PgPool) {
+        let (owner_id, ticket_id) = seed_owned_ticket(&pool, None, "recording").await;
+        // A non-internal owner with room for two analyses
+        sqlx::query("UPDATE users SET role = 'customer', quota_limit = 2 WHERE id = $1")
+            .bind(owner_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let mut submitters = Vec::new();
+        let mut tickets = Vec::new();
+        for email in ["a@example.com", "b@example.com", "c@example.com"] {
+            let customer_id = seed_user(&pool, email, "customer").await.id;
+            let id = sqlx::query_scalar::<_, Uuid>(
+                r#"
+                INSERT INTO recordings (project_id, customer_id, status)
+                SELECT project_id, $2, 'recording' FROM recordings WHERE id = $1
+                RETURNING id
+                "#,
+            )
+            .bind(ticket_id)
+            .bind(customer_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            submitters.push(customer_id);
+            tickets.push(id);
+        }
+        let service = service(&pool);
+        let quota_used = |user_id: Uuid| {
+            sqlx::query_scalar::<_, i32>("SELECT quota_used FROM users WHERE id = $1")
+                .bind(user_id)
+                .fetch_one(&pool)
+        };
+
+        for (id, customer_id) in tickets.iter().zip(&submitters).take(2) {
+            service
+                .upload_video(*id, *customer_id, vec![0u8; 64], 5, None, None)
+                .await
+                .unwrap();
+        }
+        let err = service
+            .upload_video(tickets[2], submitters[2], vec![0u8; 64], 5, None, None)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, AppError::QuotaExceeded { limit: 2 }),
+            "{err:?}"
+        );
+        assert_eq!(quota_used(owner_id).await.unwrap(), 2);
+        for customer_id in &submitters {
+            assert_eq!(quota_used(*customer_id).await.unwrap(), 0);
+        }
+        let status: String = sqlx::query_scalar("SELECT status FROM recordings WHERE id = $1")
+            .bind(tickets[2])
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(status, "recording");
+
+        // Internal owners are counted but never refused
+        sqlx::query("UPDATE users SET role = 'internal' WHERE id = $1")
+            .bind(owner_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        service
+            .upload_video(tickets[2], submitters[2], vec![0u8; 64], 5, None, None)
+            .await
+            .unwrap();
+        assert_eq!(quota_used(owner_id).await.unwrap(), 3);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn failed_enqueue_charges_no_quota(pool: PgPool) {
+        let (owner_id, ticket_id) = seed_owned_ticket(&pool, None, "recording").await;
+        // Make every job insert fail
+        sqlx::query("ALTER TABLE analysis_jobs ADD CONSTRAINT no_jobs CHECK (FALSE) NOT VALID")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let err = service(&pool)
+            .upload_video(ticket_id, owner_id, vec![0u8; 64], 5, None, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Internal(_)), "{err:?}");
+
+        let (quota_used, status, job_id): (i32, String, Option<Uuid>) = sqlx::query_as(
+            r#"
+            SELECT u.quota_used, r.status::text, r.analysis_job_id
+            FROM recordings r JOIN users u ON u.id = $2
+            WHERE r.id = $1
+            "#,
+        )
+        .bind(ticket_id)
+        .bind(owner_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            (quota_used, status.as_str(), job_id),
+            (0, "recording", None)
+        );
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn close_queues_status_changed_webhook(pool: PgPool) {
+        let (owner_id, ticket_id) = seed_owned_ticket(&pool, None, "analyzed").await;
+        sqlx::query(
+            r#"
+            INSERT INTO webhooks (project_id, url, secret, events)
+            SELECT project_id, 'https://hooks.example.com', 'secret', ARRAY['ticket.status_changed']
+            FROM recordings WHERE id = $1
+            "#,
+        )
+        .bind(ticket_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        service(&pool).close(ticket_id, owner_id).await.unwrap();
+
+        let payload: serde_json::Value =
+            sqlx::query_scalar("SELECT payload FROM webhook_deliveries")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(payload["event"], "ticket.status_changed");
+        assert_eq!(payload["data"]["ticket"]["ticket_status"], "resolved");
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn customer_list_only_includes_own_tickets(pool: PgPool) {
+        let (_, owner_ticket) = seed_owned_ticket(&pool, None, "analyzed").await;
+        let mut customers = Vec::new();
+        for email in ["alice@example.com", "bob@example.com"] {
+            let customer_id = seed_user(&pool, email, "customer").await.id;
+            sqlx::query(
+                r#"
+                INSERT INTO recordings (project_id, customer_id, status)
+                SELECT project_id, $1, 'analyzed' FROM recordings WHERE id = $2
+                "#,
+            )
+            .bind(customer_id)
+            .bind(owner_ticket)
+            .execute(&pool)
+            .await
+            .unwrap();
+            customers.push(customer_id);
+        }
+
+        let (tickets, total) = service(&pool)
+            .list_for_customer(customers[0], list_query())
+            .await
+            .unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(tickets.len(), 1);
+        assert_eq!(tickets[0].customer_id, customers[0]);
+    }
+
+    #[test]
+    fn unknown_sort_params_fall_back_to_newest_first() {
+        assert_eq!(
+            TicketSortKey::from_param(Some("priority")),
+            TicketSortKey::Priority
+        );
+        assert_eq!(
+            TicketSortKey::from_param(Some("r.id; DROP TABLE recordings")),
+            TicketSortKey::CreatedAt
+        );
+        assert_eq!(TicketSortKey::from_param(None), TicketSortKey::CreatedAt);
+        assert_eq!(SortDirection::from_param(Some("ASC")), SortDirection::Asc);
+        assert_eq!(SortDirection::from_param(Some("up")), SortDirection::Desc);
+        let mut order_by = QueryBuilder::<Postgres>::new("");
+        list_query().push_order_by(&mut order_by);
+        assert_eq!(
+            order_by.sql(),
+            " ORDER BY r.created_at DESC NULLS LAST, r.created_at DESC, r.id DESC"
+        );
+    }
+
+    /// Tickets under the seeded project; returns ids in insertion order
+    async fn seed_sortable_tickets(pool: &PgPool) -> (Uuid, Vec<Uuid>) {
+        let (owner_id, first) = seed_owned_ticket(pool, None, "analyzed").await;
+        sqlx::query("DELETE FROM recordings WHERE id = $1")
+            .bind(first)
+            .execute(pool)
+            .await
+            .unwrap();
+        let project_id: Uuid = sqlx::query_scalar("SELECT id FROM projects WHERE owner_id = $1")
+            .bind(owner_id)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+
+        // (priority, minutes since creation, minutes since update, confidence)
+        let rows = [
+            ("low", 30, 1, Some(90)),
+            ("urgent", 20, 3, None),
+            ("neutral", 10, 2, Some(40)),
+            ("high", 0, 4, Some(70)),
+        ];
+        let mut ids = Vec::new();
+        for (priority, created_ago, updated_ago, confidence) in rows {
+            let id = sqlx::query_scalar::<_, Uuid>(
+                r#"
+                INSERT INTO recordings (project_id, customer_id, status, priority, created_at, updated_at)
+                VALUES ($1, $2, 'analyzed', $3,
+                        NOW() - make_interval(mins => $4), NOW() - make_interval(mins => $5))
+                RETURNING id
+                "#,
+            )
+            .bind(project_id)
+            .bind(owner_id)
+            .bind(priority)
+            .bind(created_ago)
+            .bind(updated_ago)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+            if let Some(confidence) = confidence {
+                sqlx::query(
+                    "INSERT INTO reports (recording_id, outcome, confidence) VALUES ($1, 'success', $2)",
+                )
+                .bind(id)
+                .bind(confidence)
+                .execute(pool)
+                .await
+                .unwrap();
+            }
+            ids.push(id);
+        }
+        (owner_id, ids)
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn owner_list_sorts_by_each_key(pool: PgPool) {
+        let (owner_id, ids) = seed_sortable_tickets(&pool).await;
+        let service = service(&pool);
+        let order = |tickets: Vec<TicketWithDetails>| -> Vec<Uuid> {
+            tickets.into_iter().map(|t| t.id).collect()
+        };
+        let cases = [
+            (TicketSortKey::CreatedAt, SortDirection::Desc, [3, 2, 1, 0]),
+            (TicketSortKey::CreatedAt, SortDirection::Asc, [0, 1, 2, 3]),
+            (TicketSortKey::UpdatedAt, SortDirection::Desc, [0, 2, 1, 3]),
+            (TicketSortKey::Priority, SortDirection::Desc, [1, 3, 2, 0]),
+            (TicketSortKey::Priority, SortDirection::Asc, [0, 2, 3, 1]),
+            // Tickets without a report sort last either way
+            (TicketSortKey::Confidence, SortDirection::Desc, [0, 3, 2, 1]),
+            (TicketSortKey::Confidence, SortDirection::Asc, [2, 3, 0, 1]),
+        ];
+        for (sort_by, sort_dir, expected) in cases {
+            let query = TicketListQuery {
+                sort_by,
+                sort_dir,
+                ..list_query()
+            };
+            let (tickets, _) = service.list_for_owner(owner_id, query).await.unwrap();
+            let expected: Vec<Uuid> = expected.iter().map(|&i| ids[i]).collect();
+            assert_eq!(order(tickets), expected, "{:?} {:?}", sort_by, sort_dir);
+        }
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn search_matches_report_overviews_and_ranks_results(pool: PgPool) {
+        let (owner_id, ids) = seed_sortable_tickets(&pool).await;
+        let descriptions = [
+            "Checkout spinner never stops",
+            "Cannot change avatar",
+            "Settings page is slow",
+            "Nothing happens",
+        ];
+        for (id, description) in ids.iter().zip(descriptions) {
+            sqlx::query("UPDATE recordings SET task_description = $1 WHERE id = $2")
+                .bind(description)
+                .bind(id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        // The overview is written after the description, and a report is added later
+        sqlx::query("UPDATE reports SET overview = 'The spinner kept spinning on payment.' WHERE recording_id = $1")
+            .bind(ids[0])
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO reports (recording_id, overview) VALUES ($1, 'The Pay button shows a spinner forever.')")
+            .bind(ids[1])
+            .execute(&pool)
+            .await
+            .unwrap();
+        let service = service(&pool);
+        let search = |term: &str, sort_by| TicketListQuery {
+            search: Some(term.to_string()),
+            sort_by,
+            ..list_query()
+        };
+
+        // Only in the overview
+        let (tickets, total) = service
+            .list_for_owner(owner_id, search("payment", TicketSortKey::Relevance))
+            .await
+            .unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(tickets[0].id, ids[0]);
+
+        // Description and overview beat overview alone; stemming matches "spinning"
+        let (tickets, total) = service
+            .list_for_owner(owner_id, search("spinners", TicketSortKey::Relevance))
+            .await
+            .unwrap();
+        assert_eq!(total, 2);
+        let found: Vec<Uuid> = tickets.into_iter().map(|t| t.id).collect();
+        assert_eq!(found, vec![ids[0], ids[1]]);
+
+        // Removing the report drops its words from the index
+        sqlx::query("DELETE FROM reports WHERE recording_id = $1")
+            .bind(ids[1])
+            .execute(&pool)
+            .await
+            .unwrap();
+        let (_, total) = service
+            .list_for_owner(owner_id, search("pay button", TicketSortKey::CreatedAt))
+            .await
+            .unwrap();
+        assert_eq!(total, 0);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn owner_list_filters_by_assignee(pool: PgPool) {
+        let (owner_id, ids) = seed_sortable_tickets(&pool).await;
+        sqlx::query("UPDATE recordings SET assignee_id = $1 WHERE id = $2")
+            .bind(owner_id)
+            .bind(ids[1])
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let query = TicketListQuery {
+            assignee_id: Some(owner_id),
+            ..list_query()
+        };
+        let (tickets, total) = service(&pool)
+            .list_for_owner(owner_id, query)
+            .await
+            .unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(tickets.len(), 1);
+        assert_eq!(tickets[0].id, ids[1]);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn owner_list_filters_by_created_window(pool: PgPool) {
+        let (owner_id, ids) = seed_sortable_tickets(&pool).await;
+
+        // Tickets were created 30, 20, 10 and 0 minutes ago
+        let now = Utc::now();
+        let query = TicketListQuery {
+            created_after: Some(now - chrono::Duration::minutes(25)),
+            created_before: Some(now - chrono::Duration::minutes(5)),
+            ..list_query()
+        };
+        let (tickets, total) = service(&pool)
+            .list_for_owner(owner_id, query)
+            .await
+            .unwrap();
+        assert_eq!(total, 2);
+        let found: Vec<Uuid> = tickets.into_iter().map(|t| t.id).collect();
+        assert_eq!(found, vec![ids[2], ids[1]]);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn overview_stats_count_failed_and_processing_tickets(pool: PgPool) {
+        let (owner_id, failed) = seed_owned_ticket(&pool, None, "failed").await;
+        for status in ["processing", "analyzed", "analyzed"] {
+            sqlx::query(
+                "INSERT INTO recordings (project_id, customer_id, status) SELECT project_id, customer_id, $2 FROM recordings WHERE id = $1",
+            )
+            .bind(failed)
+            .bind(status)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let stats = service(&pool).get_overview_stats(owner_id).await.unwrap();
+        assert_eq!(stats.total_count, 4);
+        assert_eq!(stats.failed_count, 1);
+        assert_eq!(stats.failed_pct, 25);
+        assert_eq!(stats.processing_count, 1);
+        assert_eq!(stats.processing_pct, 25);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn top_issues_group_recurring_titles(pool: PgPool) {
+        let (_, first) = seed_owned_ticket(&pool, None, "analyzed").await;
+        let project_id: Uuid =
+            sqlx::query_scalar("SELECT project_id FROM recordings WHERE id = $1")
+                .bind(first)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        let mut tickets = vec![first];
+        for _ in 0..2 {
+            let id = sqlx::query_scalar::<_, Uuid>(
+                "INSERT INTO recordings (project_id, customer_id, status) SELECT project_id, customer_id, 'analyzed' FROM recordings WHERE id = $1 RETURNING id",
+            )
+            .bind(first)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            tickets.push(id);
+        }
+
+        let issues: [(usize, &str, &str, &str); 6] = [
+            (
+                0,
+                "Checkout button unresponsive",
+                "critical",
+                r#"["frontend"]"#,
+            ),
+            (1, "checkout button  unresponsive!", "high", r#"["ux"]"#),
+            (
+                2,
+                "Checkout Button Unresponsive",
+                "critical",
+                r#"["frontend"]"#,
+            ),
+            // Same problem twice on one ticket
+            (2, "Checkout button unresponsive.", "high", "[]"),
+            (0, "Slow search", "low", r#"["performance"]"#),
+            (1, "Slow search", "medium", "[]"),
+        ];
+        for (ticket, title, severity, tags) in issues {
+            let report_id = sqlx::query_scalar::<_, Uuid>(
+                "INSERT INTO reports (recording_id) VALUES ($1) RETURNING id",
+            )
+            .bind(tickets[ticket])
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            sqlx::query(
+                "INSERT INTO issues (report_id, title, severity, tags) VALUES ($1, $2, $3, $4::jsonb)",
+            )
+            .bind(report_id)
+            .bind(title)
+            .bind(severity)
+            .bind(tags)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        // Issues from other projects are not counted
+        let (_, other) = seed_owned_ticket(&pool, None, "analyzed").await;
+        let report_id = sqlx::query_scalar::<_, Uuid>(
+            "INSERT INTO reports (recording_id) VALUES ($1) RETURNING id",
+        )
+        .bind(other)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO issues (report_id, title) VALUES ($1, 'Slow search')")
+            .bind(report_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let top = service(&pool).top_issues(project_id, 10).await.unwrap();
+        assert_eq!(top.len(), 2);
+
+        let checkout = &top[0];
+        assert_eq!(checkout.normalized_title, "checkout button unresponsive");
+        assert_eq!(checkout.occurrences, 4);
+        assert_eq!(checkout.ticket_count, 3);
+        assert!((checkout.avg_severity - 3.5).abs() < 1e-9);
+        assert_eq!(checkout.tags, vec!["frontend", "ux"]);
+        let mut examples = checkout.example_ticket_ids.clone();
+        examples.sort();
+        let mut expected = tickets.clone();
+        expected.sort();
+        assert_eq!(examples, expected);
+
+        let search = &top[1];
+        assert_eq!(search.title, "Slow search");
+        assert_eq!(search.occurrences, 2);
+        assert_eq!(search.ticket_count, 2);
+        assert!((search.avg_severity - 1.5).abs() < 1e-9);
+
+        let top = service(&pool).top_issues(project_id, 1).await.unwrap();
+        assert_eq!(top.len(), 1);
+    }
+
+    /// Issue on a fresh report of the ticket
+    async fn seed_issue(pool: &PgPool, ticket_id: Uuid) -> Uuid {
+        let report_id = sqlx::query_scalar::<_, Uuid>(
+            "INSERT INTO reports (recording_id) VALUES ($1) RETURNING id",
+        )
+        .bind(ticket_id)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        sqlx::query_scalar::<_, Uuid>(
+            "INSERT INTO issues (report_id, title) VALUES ($1, 'Checkout fails') RETURNING id",
+        )
+        .bind(report_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn issue_status_moves_between_states(pool: PgPool) {
+        let (owner_id, ticket_id) = seed_owned_ticket(&pool, None, "analyzed").await;
+        let issue_id = seed_issue(&pool, ticket_id).await;
+        let service = service(&pool);
+
+        let issue = service.get_issue(ticket_id, issue_id).await.unwrap();
+        assert_eq!(issue.issue_status, IssueStatus::Open);
+
+        for status in [
+            IssueStatus::Resolved,
+            IssueStatus::Open,
+            IssueStatus::Dismissed,
+        ] {
+            let issue = service
+                .update_issue_status(ticket_id, issue_id, owner_id, status)
+                .await
+                .unwrap();
+            assert_eq!(issue.issue_status, status);
+        }
+        let ticket = service.get_for_owner(ticket_id, owner_id).await.unwrap();
+        assert_eq!(ticket.ticket_status, TicketStatus::Open);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn issue_status_requires_ticket_ownership(pool: PgPool) {
+        let (owner_id, ticket_id) = seed_owned_ticket(&pool, None, "analyzed").await;
+        let issue_id = seed_issue(&pool, ticket_id).await;
+        let (other_owner, other_ticket) = seed_owned_ticket(&pool, None, "analyzed").await;
+        let service = service(&pool);
+
+        // Another owner cannot touch it, even through their own ticket
+        for (ticket, owner) in [(ticket_id, other_owner), (other_ticket, other_owner)] {
+            assert!(matches!(
+                service
+                    .update_issue_status(ticket, issue_id, owner, IssueStatus::Resolved)
+                    .await,
+                Err(AppError::NotFound(_))
+            ));
+        }
+        // Nor can the owner through the wrong ticket
+        assert!(service
+            .update_issue_status(other_ticket, issue_id, owner_id, IssueStatus::Resolved)
+            .await
+            .is_err());
+
+        let issue = service.get_issue(ticket_id, issue_id).await.unwrap();
+        assert_eq!(issue.issue_status, IssueStatus::Open);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn status_changes_are_posted_to_chat(pool: PgPool) {
+        let (owner_id, ticket_id) = seed_owned_ticket(&pool, None, "analyzed").await;
+        sqlx::query("UPDATE users SET name = 'Alice' WHERE id = $1")
+            .bind(owner_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let service = service(&pool);
+
+        service
+            .update_status(ticket_id, owner_id, TicketStatus::InProgress)
+            .await
+            .unwrap();
+        service.close(ticket_id, owner_id).await.unwrap();
+
+        let messages = sqlx::query_as::<_, (Uuid, String)>(
+            "SELECT sender_id, message FROM chat_messages WHERE recording_id = $1 AND sender_role = 'system' ORDER BY created_at",
+        )
+        .bind(ticket_id)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    User::SYSTEM_ID,
+                    "Status changed to in_progress by Alice".to_string()
+                ),
+                (
+                    User::SYSTEM_ID,
+                    "Status changed to resolved by Alice".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn customer_can_reopen_only_their_own_resolved_ticket(pool: PgPool) {
+        let (owner_id, ticket_id) = seed_owned_ticket(&pool, None, "analyzed").await;
+        let customer_id = seed_user(&pool, "customer@example.com", "customer")
+            .await
+            .id;
+        let stranger_id = seed_user(&pool, "stranger@example.com", "customer")
+            .await
+            .id;
+        sqlx::query("UPDATE recordings SET customer_id = $1 WHERE id = $2")
+            .bind(customer_id)
+            .bind(ticket_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let service = service(&pool);
+
+        // Still open: nothing to reopen
+        let err = service
+            .reopen_for_customer(ticket_id, customer_id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Conflict(_)));
+
+        service.close(ticket_id, owner_id).await.unwrap();
+        let err = service
+            .reopen_for_customer(ticket_id, stranger_id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::NotFound(_)));
+
+        let ticket = service
+            .reopen_for_customer(ticket_id, customer_id)
+            .await
+            .unwrap();
+        assert_eq!(ticket.ticket_status, TicketStatus::Open);
+        assert!(ticket.closed_at.is_none());
+        let last_note = sqlx::query_scalar::<_, String>(
+            "SELECT message FROM chat_messages WHERE recording_id = $1 AND sender_role = 'system' ORDER BY created_at DESC LIMIT 1",
+        )
+        .bind(ticket_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(last_note, "Reopened by the customer");
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn merge_copies_chat_for_the_team_and_closes_the_source(pool: PgPool) {
+        let (owner_id, source_id) = seed_owned_ticket(&pool, None, "analyzed").await;
+        let customer_id = seed_user(&pool, "customer@example.com", "customer")
+            .await
+            .id;
+        let target_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO recordings (project_id, customer_id, status, ticket_status)
+            SELECT project_id, $2, 'analyzed', 'in_progress' FROM recordings WHERE id = $1
+            RETURNING id
+            "#,
+        )
+        .bind(source_id)
+        .bind(customer_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        for (ticket_id, message) in [
+            (source_id, "first"),
+            (source_id, "second"),
+            (target_id, "third"),
+        ] {
+            sqlx::query(
+                "INSERT INTO chat_messages (recording_id, sender_id, message) VALUES ($1, $2, $3)",
+            )
+            .bind(ticket_id)
+            .bind(owner_id)
+            .bind(message)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        let service = service(&pool);
+
+        // Merging into itself or into someone else's ticket is refused
+        assert!(matches!(
+            service.merge(source_id, source_id, owner_id).await,
+            Err(AppError::BadRequest(_))
+        ));
+        assert!(matches!(
+            service.merge(source_id, target_id, Uuid::new_v4()).await,
+            Err(AppError::NotFound(_))
+        ));
+
+        service.merge(source_id, target_id, owner_id).await.unwrap();
+
+        let chat = |ticket_id: Uuid, user_id: Uuid, role: UserRole| {
+            let chat = service.chat.clone();
+            async move {
+                let mut messages: Vec<String> = chat
+                    .get_messages(ticket_id, user_id, role)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .filter(|m| m.sender_role.is_none())
+                    .map(|m| m.message)
+                    .collect();
+                messages.sort();
+                messages
+            }
+        };
+        // The team sees the copies on the target; its customer does not, and the source
+        // keeps its own thread
+        assert_eq!(
+            chat(target_id, owner_id, UserRole::Internal).await,
+            vec!["first", "second", "third"]
+        );
+        assert_eq!(
+            chat(target_id, customer_id, UserRole::Customer).await,
+            vec!["third"]
+        );
+        assert_eq!(
+            chat(source_id, owner_id, UserRole::Customer).await,
+            vec!["first", "second"]
+        );
+
+        // A merged ticket cannot be merged again
+        assert!(matches!(
+            service.merge(source_id, target_id, owner_id).await,
+            Err(AppError::BadRequest(_))
+        ));
+
+        let source = service.get_for_owner(source_id, owner_id).await.unwrap();
+        assert_eq!(source.ticket_status, TicketStatus::Resolved);
+        assert_eq!(source.closed_reason, Some(ClosedReason::NotRelevant));
+        let target = service.get_for_owner(target_id, owner_id).await.unwrap();
+        assert_eq!(target.ticket_status, TicketStatus::InProgress);
+        assert!(target.closed_at.is_none());
+
+        let merged: (Uuid, Uuid, Option<Uuid>) = sqlx::query_as(
+            "SELECT source_ticket_id, target_ticket_id, merged_by FROM ticket_merges",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(merged, (source_id, target_id, Some(owner_id)));
+
+        for (ticket_id, note) in [
+            (source_id, format!("Merged into ticket {}", target_id)),
+            (target_id, format!("Ticket {} was merged", source_id)),
+        ] {
+            let notes: Vec<String> = sqlx::query_scalar(
+                "SELECT message FROM chat_messages WHERE recording_id = $1 AND sender_role = 'system'",
+            )
+            .bind(ticket_id)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+            assert_eq!(notes.len(), 1);
+            assert!(notes[0].starts_with(&note), "{}", notes[0]);
+        }
+    }
+
+    #[test]
+    fn cursor_round_trips_and_rejects_garbage() {
+        let cursor = TicketCursor {
+            created_at: DateTime::from_timestamp_micros(1_760_000_000_123_456).unwrap(),
+            id: Uuid::new_v4(),
+        };
+        assert_eq!(TicketCursor::decode(&cursor.encode()), Some(cursor));
+        assert_eq!(TicketCursor::decode("not a cursor"), None);
+        assert_eq!(TicketCursor::decode(""), None);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn cursor_pages_are_stable_while_tickets_arrive(pool: PgPool) {
+        let (owner_id, ids) = seed_sortable_tickets(&pool).await;
+        let service = service(&pool);
+        let page_query = |cursor: Option<&str>| TicketListQuery {
+            cursor: cursor.map(|c| TicketCursor::decode(c).unwrap()),
+            per_page: 2,
+            ..list_query()
+        };
+
+        let query = page_query(None);
+        let (first, total) = service
+            .list_for_owner(owner_id, query.clone())
+            .await
+            .unwrap();
+        assert_eq!(total, 4);
+        assert_eq!(
+            first.iter().map(|t| t.id).collect::<Vec<_>>(),
+            vec![ids[3], ids[2]]
+        );
+        let cursor = query.next_cursor(&first).unwrap();
+
+        // A new ticket lands before the next page is fetched
+        sqlx::query(
+            "INSERT INTO recordings (project_id, customer_id, status) SELECT project_id, customer_id, 'analyzed' FROM recordings WHERE id = $1",
+        )
+        .bind(ids[0])
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let query = page_query(Some(&cursor));
+        let (second, _) = service
+            .list_for_owner(owner_id, query.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            second.iter().map(|t| t.id).collect::<Vec<_>>(),
+            vec![ids[1], ids[0]]
+        );
+
+        let cursor = query.next_cursor(&second).unwrap();
+        let query = page_query(Some(&cursor));
+        let (last, _) = service
+            .list_for_owner(owner_id, query.clone())
+            .await
+            .unwrap();
+        assert!(last.is_empty());
+        assert_eq!(query.next_cursor(&last), None);
+    }
+
+    #[test]
+    fn csv_rows_quote_commas_and_newlines() {
+        let row = TicketCsvRow {
+            id: Uuid::nil(),
+            feedback_type: FeedbackType::Bug,
+            ticket_status: TicketStatus::Open,
+            priority: TicketPriority::High,
+            submitter_name: Some("Ada".to_string()),
+            submitter_email: Some("ada@example.com".to_string()),
+            page_url: Some("https://shop.example.com/cart".to_string()),
+            ai_confidence: Some(87),
+            created_at: DateTime::from_timestamp(1_760_000_000, 0).unwrap(),
+            task_description: Some("Pay fails, then\nthe \"retry\" button hangs".to_string()),
+        };
+        let csv = encode_csv(&[CSV_HEADER.map(String::from), row.record()]).unwrap();
+        assert_eq!(
+            std::str::from_utf8(&csv).unwrap(),
+            "id,feedback_type,ticket_status,priority,submitter,page_url,ai_confidence,created_at,description\n\
+             00000000-0000-0000-0000-000000000000,bug,open,high,Ada <ada@example.com>,\
+             https://shop.example.com/cart,87,2025-10-09T08:53:20+00:00,\
+             \"Pay fails, then\nthe \"\"retry\"\" button hangs\"\n"
+        );
+    }
+
+    #[test]
+    fn csv_description_is_truncated() {
+        let row = TicketCsvRow {
+            id: Uuid::nil(),
+            feedback_type: FeedbackType::Idea,
+            ticket_status: TicketStatus::Open,
+            priority: TicketPriority::Neutral,
+            submitter_name: None,
+            submitter_email: None,
+            page_url: None,
+            ai_confidence: None,
+            created_at: Utc::now(),
+            task_description: Some("x".repeat(CSV_DESCRIPTION_CHARS + 50)),
+        };
+        let record = row.record();
+        assert_eq!(record[4], "");
+        assert_eq!(record[6], "");
+        assert_eq!(record[8].chars().count(), CSV_DESCRIPTION_CHARS + 1);
+        assert!(record[8].ends_with('…'));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn export_csv_streams_project_tickets(pool: PgPool) {
+        let (_, ids) = seed_sortable_tickets(&pool).await;
+        let project_id: Uuid =
+            sqlx::query_scalar("SELECT project_id FROM recordings WHERE id = $1")
+                .bind(ids[0])
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+
+        let chunks: Vec<Bytes> = service(&pool)
+            .export_csv(project_id)
+            .map(|chunk| chunk.unwrap())
+            .collect()
+            .await;
+        let csv = String::from_utf8(chunks.concat()).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], CSV_HEADER.join(","));
+        assert_eq!(lines.len(), 5);
+        assert!(lines[1].starts_with(&format!("{},feedback,open,high,", ids[3])));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn labels_are_idempotent_and_owner_only(pool: PgPool) {
+        let (owner_id, ticket_id) = seed_owned_ticket(&pool, None, "analyzed").await;
+        let service = service(&pool);
+
+        service
+            .add_label(ticket_id, owner_id, "mobile")
+            .await
+            .unwrap();
+        let labels = service
+            .add_label(ticket_id, owner_id, "mobile")
+            .await
+            .unwrap();
+        assert_eq!(labels, vec!["mobile"]);
+        let labels = service
+            .add_label(ticket_id, owner_id, "billing")
+            .await
+            .unwrap();
+        assert_eq!(labels, vec!["billing", "mobile"]);
+
+        let labels = service
+            .remove_label(ticket_id, owner_id, "mobile")
+            .await
+            .unwrap();
+        assert_eq!(labels, vec!["billing"]);
+        let labels = service
+            .remove_label(ticket_id, owner_id, "mobile")
+            .await
+            .unwrap();
+        assert_eq!(labels, vec!["billing"]);
+
+        let stranger = Uuid::new_v4();
+        assert!(matches!(
+            service.add_label(ticket_id, stranger, "spam").await,
+            Err(AppError::NotFound(_))
+        ));
+        assert!(matches!(
+            service.remove_label(ticket_id, stranger, "billing").await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn owner_list_filters_by_label(pool: PgPool) {
+        let (owner_id, ids) = seed_sortable_tickets(&pool).await;
+        let service = service(&pool);
+        service
+            .add_label(ids[0], owner_id, "regression")
+            .await
+            .unwrap();
+        service
+            .add_label(ids[2], owner_id, "regression")
+            .await
+            .unwrap();
+        service.add_label(ids[2], owner_id, "mobile").await.unwrap();
+
+        let query = TicketListQuery {
+            label: Some("regression".to_string()),
+            ..list_query()
+        };
+        let (tickets, total) = service.list_for_owner(owner_id, query).await.unwrap();
+        assert_eq!(total, 2);
+        let found: Vec<Uuid> = tickets.into_iter().map(|t| t.id).collect();
+        assert_eq!(found, vec![ids[2], ids[0]]);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn owner_list_filters_by_sentiment(pool: PgPool) {
+        let (owner_id, ids) = seed_sortable_tickets(&pool).await;
+        // ids[1] has no report, so it never matches a sentiment
+        for (id, sentiment, score) in [(ids[0], "frustrated", 85), (ids[2], "satisfied", 5)] {
+            sqlx::query(
+                "UPDATE reports SET sentiment = $2, frustration_score = $3 WHERE recording_id = $1",
+            )
+            .bind(id)
+            .bind(sentiment)
+            .bind(score)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        let service = service(&pool);
+
+        let query = TicketListQuery {
+            sentiment: Some(ReportSentiment::Frustrated),
+            ..list_query()
+        };
+        let (tickets, total) = service.list_for_owner(owner_id, query).await.unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(tickets.len(), 1);
+        assert_eq!(tickets[0].id, ids[0]);
+        assert_eq!(tickets[0].sentiment, Some(ReportSentiment::Frustrated));
+        assert_eq!(tickets[0].frustration_score, Some(85));
+
+        let (tickets, total) = service
+            .list_for_owner(owner_id, list_query())
+            .await
+            .unwrap();
+        assert_eq!(total, 4);
+        let unscored = tickets.iter().find(|t| t.id == ids[3]).unwrap();
+        assert_eq!(unscored.sentiment, None);
+        assert_eq!(unscored.frustration_score, None);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn due_date_can_be_set_and_cleared_by_owner_only(pool: PgPool) {
+        let (owner_id, ticket_id) = seed_owned_ticket(&pool, None, "analyzed").await;
+        let service = service(&pool);
+        let due: DateTime<Utc> = "2026-11-01T12:00:00Z".parse().unwrap();
+
+        let ticket = service
+            .update_due_date(ticket_id, owner_id, Some(due))
+            .await
+            .unwrap();
+        assert_eq!(ticket.due_date, Some(due));
+
+        let ticket = service
+            .update_due_date(ticket_id, owner_id, None)
+            .await
+            .unwrap();
+        assert_eq!(ticket.due_date, None);
+
+        let result = service
+            .update_due_date(ticket_id, Uuid::new_v4(), Some(due))
+            .await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn reanalyze_replaces_report_with_pending_job(pool: PgPool) {
+        let (owner_id, ticket_id) =
+            seed_owned_ticket(&pool, Some("recordings/p/t.webm"), "analyzed").await;
+        let report_id = sqlx::query_scalar::<_, Uuid>(
+            "INSERT INTO reports (recording_id, outcome) VALUES ($1, 'failed') RETURNING id",
+        )
+        .bind(ticket_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO issues (report_id, title) VALUES ($1, 'Old issue')")
+            .bind(report_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let ticket = service(&pool).reanalyze(ticket_id, owner_id).await.unwrap();
+        assert_eq!(ticket.status, ProcessingStatus::Processing);
+
+        let reports: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM reports WHERE recording_id = $1")
+                .bind(ticket_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(reports, 0);
+
+        let (job_id, job_status, job_path): (Uuid, String, String) = sqlx::query_as(
+            "SELECT id, status, video_storage_path FROM analysis_jobs WHERE recording_id = $1",
+        )
+        .bind(ticket_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(job_status, "pending");
+        assert_eq!(job_path, "recordings/p/t.webm");
+        assert_eq!(ticket.analysis_job_id, Some(job_id));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn failed_reanalyze_enqueue_keeps_the_report(pool: PgPool) {
+        let (owner_id, ticket_id) =
+            seed_owned_ticket(&pool, Some("recordings/p/t.webm"), "analyzed").await;
+        sqlx::query("INSERT INTO reports (recording_id, outcome) VALUES ($1, 'failed')")
+            .bind(ticket_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        // Make every job insert fail
+        sqlx::query("ALTER TABLE analysis_jobs ADD CONSTRAINT no_jobs CHECK (FALSE) NOT VALID")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let err = service(&pool)
+            .reanalyze(ticket_id, owner_id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Internal(_)));
+
+        let (status, reports, versions): (String, i64, i64) = sqlx::query_as(
+            r#"
+            SELECT r.status::text,
+                (SELECT COUNT(*) FROM reports WHERE recording_id = r.id),
+                (SELECT COUNT(*) FROM report_versions WHERE recording_id = r.id)
+            FROM recordings r WHERE r.id = $1
+            "#,
+        )
+        .bind(ticket_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!((status.as_str(), reports, versions), ("analyzed", 1, 0));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn reanalyze_without_video_is_rejected(pool: PgPool) {
+        let (owner_id, ticket_id) = seed_owned_ticket(&pool, None, "recording").await;
+
+        let err = service(&pool)
+            .reanalyze(ticket_id, owner_id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::BadRequest(_)));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn reanalyze_without_video_uses_the_screenshot(pool: PgPool) {
+        let (owner_id, ticket_id) = seed_owned_ticket(&pool, None, "failed").await;
+        sqlx::query("UPDATE recordings SET screenshot_url = 'screenshots/p/t.png' WHERE id = $1")
+            .bind(ticket_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        service(&pool).reanalyze(ticket_id, owner_id).await.unwrap();
+
+        let (job_path, mime_type): (String, Option<String>) = sqlx::query_as(
+            "SELECT video_storage_path, mime_type FROM analysis_jobs WHERE recording_id = $1",
+        )
+        .bind(ticket_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(job_path, "screenshots/p/t.png");
+        assert_eq!(mime_type.as_deref(), Some("image/png"));
+    }
 }
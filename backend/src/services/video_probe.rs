@@ -0,0 +1,212 @@
+//! Video probing - recognises WebM/Matroska and MP4/QuickTime containers and reads a
+//! recording's duration from its header (`Info` or `mvhd`) without decoding any frames
+
+/// EBML magic at the start of every WebM/Matroska file
+const EBML_MAGIC: [u8; 4] = [0x1A, 0x45, 0xDF, 0xA3];
+const SEGMENT_ID: u32 = 0x1853_8067;
+const INFO_ID: u32 = 0x1549_A966;
+const TIMECODE_SCALE_ID: u32 = 0x2A_D7B1;
+const DURATION_ID: u32 = 0x4489;
+/// Matroska's default TimecodeScale: durations are in milliseconds
+const DEFAULT_TIMECODE_SCALE_NS: u64 = 1_000_000;
+/// Top-level atoms an MP4/MOV file may open with; older QuickTime files have no `ftyp`
+const MP4_LEADING_BOXES: [&[u8; 4]; 6] = [b"ftyp", b"moov", b"mdat", b"wide", b"free", b"skip"];
+
+/// Whether `data` starts like a WebM/MKV or MP4/MOV file. Only the magic bytes are checked,
+/// so this screens out non-video uploads rather than proving the file is playable.
+pub fn is_video_container(data: &[u8]) -> bool {
+    data.starts_with(&EBML_MAGIC)
+        || data
+            .get(4..8)
+            .is_some_and(|kind| MP4_LEADING_BOXES.iter().any(|b| kind == b.as_slice()))
+}
+
+/// Duration of a WebM or MP4 video in seconds, when its header records one. Browser
+/// MediaRecorder WebM files often omit it, so `None` is common and not an error.
+pub fn probe_duration_secs(data: &[u8]) -> Option<f64> {
+    let secs = if data.starts_with(&EBML_MAGIC) {
+        webm_duration_secs(data)
+    } else if data.get(4..8) == Some(b"ftyp") {
+        mp4_duration_secs(data)
+    } else {
+        None
+    }?;
+    (secs.is_finite() && secs > 0.0).then_some(secs)
+}
+
+// ============================================================================
+// WebM / Matroska
+// ============================================================================
+
+/// Element ID, markers kept as the spec writes them, and its byte length
+fn read_element_id(data: &[u8]) -> Option<(u32, usize)> {
+    let first = *data.first()?;
+    let len = first.leading_zeros() as usize + 1;
+    if len > 4 || data.len() < len {
+        return None;
+    }
+    let id = data[..len]
+        .iter()
+        .fold(0u32, |id, &b| (id << 8) | u32::from(b));
+    Some((id, len))
+}
+
+/// Element data size and its byte length; `None` size means "unknown" (live streams)
+fn read_element_size(data: &[u8]) -> Option<(Option<u64>, usize)> {
+    let first = *data.first()?;
+    let len = first.leading_zeros() as usize + 1;
+    if len > 8 || data.len() < len {
+        return None;
+    }
+    let mask = if len == 8 { 0 } else { 0xFFu8 >> len };
+    let value = data[1..len]
+        .iter()
+        .fold(u64::from(first & mask), |v, &b| (v << 8) | u64::from(b));
+    let unknown = value == (1u64 << (7 * len)) - 1;
+    Some(((!unknown).then_some(value), len))
+}
+
+/// Children of a master element as (id, payload) pairs. An unknown-size child runs to the
+/// end of `data` and ends the walk.
+fn ebml_children(data: &[u8]) -> impl Iterator<Item = (u32, &[u8])> {
+    let mut rest = data;
+    std::iter::from_fn(move || {
+        let (id, id_len) = read_element_id(rest)?;
+        let (size, size_len) = read_element_size(&rest[id_len..])?;
+        let body = &rest[id_len + size_len..];
+        let size = size.map_or(body.len(), |s| (s as usize).min(body.len()));
+        let payload = &body[..size];
+        rest = &body[size..];
+        Some((id, payload))
+    })
+}
+
+fn webm_duration_secs(data: &[u8]) -> Option<f64> {
+    let (_, segment) = ebml_children(data).find(|(id, _)| *id == SEGMENT_ID)?;
+    let (_, info) = ebml_children(segment).find(|(id, _)| *id == INFO_ID)?;
+
+    let mut timecode_scale = DEFAULT_TIMECODE_SCALE_NS;
+    let mut duration = None;
+    for (id, payload) in ebml_children(info) {
+        match id {
+            TIMECODE_SCALE_ID if (1..=8).contains(&payload.len()) => {
+                timecode_scale = payload.iter().fold(0u64, |v, &b| (v << 8) | u64::from(b));
+            }
+            DURATION_ID => {
+                duration = match payload.len() {
+                    4 => Some(f64::from(f32::from_be_bytes(payload.try_into().ok()?))),
+                    8 => Some(f64::from_be_bytes(payload.try_into().ok()?)),
+                    _ => None,
+                };
+            }
+            _ => {}
+        }
+    }
+    Some(duration? * timecode_scale as f64 / 1e9)
+}
+
+// ============================================================================
+// MP4 / QuickTime
+// ============================================================================
+
+/// Boxes at one level as (type, payload) pairs
+fn mp4_boxes(data: &[u8]) -> impl Iterator<Item = ([u8; 4], &[u8])> {
+    let mut rest = data;
+    std::iter::from_fn(move || {
+        let size = u32::from_be_bytes(rest.get(..4)?.try_into().ok()?) as u64;
+        let kind: [u8; 4] = rest.get(4..8)?.try_into().ok()?;
+        let (size, header) = match size {
+            0 => (rest.len() as u64, 8),
+            1 => (u64::from_be_bytes(rest.get(8..16)?.try_into().ok()?), 16),
+            size => (size, 8),
+        };
+        if size < header as u64 {
+            return None;
+        }
+        let end = (size as usize).min(rest.len());
+        let payload = rest.get(header..end)?;
+        rest = &rest[end..];
+        Some((kind, payload))
+    })
+}
+
+fn mp4_duration_secs(data: &[u8]) -> Option<f64> {
+    let (_, moov) = mp4_boxes(data).find(|(kind, _)| kind == b"moov")?;
+    let (_, mvhd) = mp4_boxes(moov).find(|(kind, _)| kind == b"mvhd")?;
+    let be_u32 = |at: usize| -> Option<u64> {
+        Some(u32::from_be_bytes(mvhd.get(at..at + 4)?.try_into().ok()?) as u64)
+    };
+    // Version 1 uses 64-bit creation/modification times and duration
+    let (timescale, duration) = match *mvhd.first()? {
+        0 => (be_u32(12)?, be_u32(16)?),
+        1 => (
+            be_u32(20)?,
+            u64::from_be_bytes(mvhd.get(24..32)?.try_into().ok()?),
+        ),
+        _ => return None,
+    };
+    (timescale > 0).then(|| duration as f64 / timescale as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Smallest useful WebM header: EBML header, then an unknown-size Segment whose Info
+    /// records a TimecodeScale of 1ms and a Duration of 12,500 ticks (12.5s) as a float64
+    const WEBM_FIXTURE: [u8; 44] = [
+        0x1A, 0x45, 0xDF, 0xA3, 0x84, 0x42, 0x82, 0x81, 0x77, // EBML { DocType "w" }
+        0x18, 0x53, 0x80, 0x67, 0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, // Segment
+        0x15, 0x49, 0xA9, 0x66, 0x92, // Info, 18 bytes
+        0x2A, 0xD7, 0xB1, 0x83, 0x0F, 0x42, 0x40, // TimecodeScale 1_000_000
+        0x44, 0x89, 0x88, 0x40, 0xC8, 0x6A, 0x00, 0x00, 0x00, 0x00, 0x00, // Duration 12500.0
+    ];
+    /// Offset of the Info size byte in the fixture
+    const INFO_SIZE_AT: usize = 25;
+
+    #[test]
+    fn webm_duration_is_read_from_segment_info() {
+        assert_eq!(probe_duration_secs(&WEBM_FIXTURE), Some(12.5));
+    }
+
+    #[test]
+    fn webm_without_duration_is_unknown() {
+        // MediaRecorder output: Info carries only the TimecodeScale
+        let mut data = WEBM_FIXTURE[..WEBM_FIXTURE.len() - 11].to_vec();
+        data[INFO_SIZE_AT] = 0x87;
+        assert_eq!(probe_duration_secs(&data), None);
+    }
+
+    #[test]
+    fn mp4_duration_is_read_from_mvhd() {
+        let mut mvhd = vec![0u8; 100];
+        mvhd[12..16].copy_from_slice(&1000u32.to_be_bytes());
+        mvhd[16..20].copy_from_slice(&42_000u32.to_be_bytes());
+        let mut moov = ((mvhd.len() + 8) as u32).to_be_bytes().to_vec();
+        moov.extend_from_slice(b"mvhd");
+        moov.extend_from_slice(&mvhd);
+        let mut data = vec![0, 0, 0, 16];
+        data.extend_from_slice(b"ftypisom\0\0\0\0");
+        data.extend_from_slice(&((moov.len() + 8) as u32).to_be_bytes());
+        data.extend_from_slice(b"moov");
+        data.extend_from_slice(&moov);
+        assert_eq!(probe_duration_secs(&data), Some(42.0));
+    }
+
+    #[test]
+    fn video_containers_are_recognised_by_magic_bytes() {
+        assert!(is_video_container(&WEBM_FIXTURE));
+        assert!(is_video_container(b"\0\0\0\x20ftypisom"));
+        assert!(is_video_container(b"\0\0\0\x08wide\0\0\0\0mdat"));
+        assert!(!is_video_container(b"just some notes about the bug"));
+        assert!(!is_video_container(b"\x89PNG\r\n\x1a\n"));
+        assert!(!is_video_container(b""));
+    }
+
+    #[test]
+    fn unknown_or_truncated_data_is_unknown() {
+        assert_eq!(probe_duration_secs(b""), None);
+        assert_eq!(probe_duration_secs(b"not a video at all"), None);
+        assert_eq!(probe_duration_secs(&WEBM_FIXTURE[..30]), None);
+    }
+}
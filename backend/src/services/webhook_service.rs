@@ -0,0 +1,855 @@
+//! Webhook service - project webhooks and signed, retried delivery of ticket events
+//!
+//! Events are written to `webhook_deliveries` first and sent by a background loop, so a
+//! delivery survives endpoint outages and restarts (at-least-once). Receivers should
+//! dedupe on the `X-Ortrace-Delivery` id.
+
+use chrono::Utc;
+use rand::RngCore;
+use sqlx::{FromRow, PgPool};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::timeout;
+use uuid::Uuid;
+
+use super::outbound_guard::{check_public_url, literal_ip, resolve_public};
+use crate::crypto::sign_payload;
+use crate::error::{AppError, Result};
+use crate::models::{FeedbackTicket, Webhook, WebhookEvent, WebhookTicket};
+
+/// Header carrying `sha256=<hex HMAC-SHA256 of the body>`
+pub const SIGNATURE_HEADER: &str = "X-Ortrace-Signature";
+const EVENT_HEADER: &str = "X-Ortrace-Event";
+const DELIVERY_HEADER: &str = "X-Ortrace-Delivery";
+/// Event name of test deliveries; never sent for ticket activity
+const PING_EVENT: &str = "ping";
+
+/// Attempts before a delivery is marked failed (~1 hour of retries)
+const MAX_ATTEMPTS: i32 = 8;
+/// Delay before the first retry; doubles per attempt
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(30);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(3600);
+/// A claimed delivery is retried after this if the sender dies mid-request
+const CLAIM_LEASE: Duration = Duration::from_secs(60);
+const BATCH_SIZE: i64 = 20;
+/// Fallback poll for due retries when no new event wakes the loop
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Webhook service for managing project webhooks and delivering events
+pub struct WebhookService {
+    db: PgPool,
+    /// Wakes the delivery loop when new deliveries are queued
+    wake: Notify,
+    /// Refuse targets that are not public internet hosts; off only for tests that
+    /// deliver to a local receiver
+    public_only: bool,
+}
+
+/// Outcome of a test `ping` sent to a webhook
+#[derive(Debug)]
+pub struct WebhookTestDelivery {
+    pub delivery_id: Uuid,
+    /// HTTP status the receiver answered with; `None` if it could not be reached
+    pub response_status: Option<u16>,
+    pub error: Option<String>,
+}
+
+/// Claimed delivery joined with its webhook's target
+#[derive(Debug, FromRow)]
+struct DueDelivery {
+    id: Uuid,
+    event: String,
+    payload: sqlx::types::Json<serde_json::Value>,
+    attempts: i32,
+    url: String,
+    secret: String,
+}
+
+impl WebhookService {
+    pub fn new(db: PgPool) -> Self {
+        Self::with_target_check(db, true)
+    }
+
+    /// Service that may deliver to loopback receivers
+    #[cfg(test)]
+    pub fn allowing_private_targets(db: PgPool) -> Self {
+        Self::with_target_check(db, false)
+    }
+
+    fn with_target_check(db: PgPool, public_only: bool) -> Self {
+        Self {
+            db,
+            wake: Notify::new(),
+            public_only,
+        }
+    }
+
+    /// Check a URL about to be saved resolves only to public addresses
+    async fn check_target(&self, url: &str) -> Result<()> {
+        if !self.public_only {
+            return Ok(());
+        }
+        check_public_url(url)
+            .await
+            .map_err(|e| AppError::validation(format!("Webhook {}", e)))
+    }
+
+    /// Client for one request to `url`. The host is checked again (its DNS may have
+    /// changed since the webhook was saved) and the client connects only to the addresses
+    /// checked here. Redirects are not followed: a public receiver could otherwise bounce
+    /// the request to an internal address.
+    async fn client_for(&self, url: &str) -> std::result::Result<reqwest::Client, String> {
+        let mut builder = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none());
+        if self.public_only {
+            let url =
+                reqwest::Url::parse(url).map_err(|_| "Webhook URL is not valid".to_string())?;
+            let addrs = resolve_public(&url)
+                .await
+                .map_err(|e| format!("Webhook {}", e))?;
+            if let (None, Some(host)) = (literal_ip(&url), url.host_str()) {
+                builder = builder.resolve_to_addrs(host, &addrs);
+            }
+        }
+        builder.build().map_err(|e| e.to_string())
+    }
+
+    /// Create a webhook with a freshly generated signing secret
+    pub async fn create(
+        &self,
+        project_id: Uuid,
+        url: &str,
+        events: &[WebhookEvent],
+        is_active: bool,
+    ) -> Result<Webhook> {
+        self.check_target(url).await?;
+        let webhook = sqlx::query_as::<_, Webhook>(
+            r#"
+            INSERT INTO webhooks (project_id, url, secret, events, is_active)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(project_id)
+        .bind(url.trim())
+        .bind(generate_secret())
+        .bind(event_names(events))
+        .bind(is_active)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(webhook)
+    }
+
+    /// List a project's webhooks
+    pub async fn list(&self, project_id: Uuid) -> Result<Vec<Webhook>> {
+        let webhooks = sqlx::query_as::<_, Webhook>(
+            "SELECT * FROM webhooks WHERE project_id = $1 ORDER BY created_at ASC",
+        )
+        .bind(project_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(webhooks)
+    }
+
+    /// Update a webhook; `None` fields are left unchanged
+    pub async fn update(
+        &self,
+        id: Uuid,
+        project_id: Uuid,
+        url: Option<&str>,
+        events: Option<&[WebhookEvent]>,
+        is_active: Option<bool>,
+    ) -> Result<Webhook> {
+        if let Some(url) = url {
+            self.check_target(url).await?;
+        }
+        let webhook = sqlx::query_as::<_, Webhook>(
+            r#"
+            UPDATE webhooks SET
+                url = COALESCE($1, url),
+                events = COALESCE($2, events),
+                is_active = COALESCE($3, is_active),
+                updated_at = NOW()
+            WHERE id = $4 AND project_id = $5
+            RETURNING *
+            "#,
+        )
+        .bind(url.map(str::trim))
+        .bind(events.map(event_names))
+        .bind(is_active)
+        .bind(id)
+        .bind(project_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::not_found("Webhook not found"))?;
+        Ok(webhook)
+    }
+
+    /// Delete a webhook (pending deliveries go with it)
+    pub async fn delete(&self, id: Uuid, project_id: Uuid) -> Result<()> {
+        let result = sqlx::query("DELETE FROM webhooks WHERE id = $1 AND project_id = $2")
+            .bind(id)
+            .bind(project_id)
+            .execute(&self.db)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Webhook not found"));
+        }
+        Ok(())
+    }
+
+    /// Send a signed `ping` event to a webhook right away, whether or not it is active, so
+    /// its owner can check their receiver. The ping is not recorded or retried.
+    ///
+    /// Non-public targets are refused before anything is sent, and transport failures are
+    /// reported without detail, so the endpoint cannot be used to probe hosts and ports.
+    pub async fn send_test(&self, id: Uuid, project_id: Uuid) -> Result<WebhookTestDelivery> {
+        let webhook = sqlx::query_as::<_, Webhook>(
+            "SELECT * FROM webhooks WHERE id = $1 AND project_id = $2",
+        )
+        .bind(id)
+        .bind(project_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::not_found("Webhook not found"))?;
+
+        let client = self
+            .client_for(&webhook.url)
+            .await
+            .map_err(AppError::validation)?;
+        let delivery_id = Uuid::new_v4();
+        let body = ping_payload(delivery_id, &webhook).to_string().into_bytes();
+        let response = client
+            .post(&webhook.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, sign_payload(&webhook.secret, &body))
+            .header(EVENT_HEADER, PING_EVENT)
+            .header(DELIVERY_HEADER, delivery_id.to_string())
+            .body(body)
+            .send()
+            .await;
+
+        let (response_status, error) = match response {
+            Err(e) if e.is_timeout() => (None, Some("Receiver did not answer in time".to_string())),
+            Err(_) => (None, Some("Could not connect to the receiver".to_string())),
+            response => outcome(response),
+        };
+        Ok(WebhookTestDelivery {
+            delivery_id,
+            response_status,
+            error,
+        })
+    }
+
+    /// Queue `event` for every active webhook of the ticket's project that subscribes to it.
+    /// Returns the number of deliveries queued.
+    pub async fn dispatch(&self, event: WebhookEvent, ticket: &FeedbackTicket) -> Result<usize> {
+        let Some(project_id) = ticket.project_id else {
+            return Ok(0);
+        };
+
+        let webhooks: Vec<Webhook> = sqlx::query_as::<_, Webhook>(
+            "SELECT * FROM webhooks WHERE project_id = $1 AND is_active = TRUE",
+        )
+        .bind(project_id)
+        .fetch_all(&self.db)
+        .await?
+        .into_iter()
+        .filter(|webhook| webhook.subscribes_to(event))
+        .collect();
+
+        let data = WebhookTicket::from(ticket);
+        for webhook in &webhooks {
+            let delivery_id = Uuid::new_v4();
+            sqlx::query(
+                r#"
+                INSERT INTO webhook_deliveries (id, webhook_id, event, payload)
+                VALUES ($1, $2, $3, $4)
+                "#,
+            )
+            .bind(delivery_id)
+            .bind(webhook.id)
+            .bind(event.as_str())
+            .bind(sqlx::types::Json(event_payload(delivery_id, event, &data)))
+            .execute(&self.db)
+            .await?;
+        }
+
+        if !webhooks.is_empty() {
+            self.wake.notify_one();
+        }
+        Ok(webhooks.len())
+    }
+
+    /// `dispatch` for callers whose own work must not fail because of webhooks
+    pub async fn fire(&self, event: WebhookEvent, ticket: &FeedbackTicket) {
+        if let Err(e) = self.dispatch(event, ticket).await {
+            tracing::warn!(ticket_id = %ticket.id, %event, "Failed to queue webhook: {}", e);
+        }
+    }
+
+    /// Deliver queued events until the process exits.
+    /// Safe to run in several processes; claims use `FOR UPDATE SKIP LOCKED`.
+    pub fn spawn_delivery_loop(service: Arc<Self>) {
+        tokio::spawn(async move {
+            loop {
+                match service.deliver_due().await {
+                    // Keep draining; failed attempts are rescheduled into the future
+                    Ok(sent) if sent > 0 => continue,
+                    Ok(_) => {}
+                    Err(e) => tracing::error!("Webhook delivery pass failed: {}", e),
+                }
+                let _ = timeout(POLL_INTERVAL, service.wake.notified()).await;
+            }
+        });
+    }
+
+    /// Claim and send a batch of due deliveries; returns how many were attempted
+    pub async fn deliver_due(&self) -> Result<usize> {
+        let due = sqlx::query_as::<_, DueDelivery>(
+            r#"
+            WITH due AS (
+                SELECT id FROM webhook_deliveries
+                WHERE status = 'pending' AND next_attempt_at <= NOW()
+                ORDER BY next_attempt_at ASC
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            UPDATE webhook_deliveries d SET
+                attempts = d.attempts + 1,
+                next_attempt_at = NOW() + make_interval(secs => $2)
+            FROM due, webhooks w
+            WHERE d.id = due.id AND w.id = d.webhook_id
+            RETURNING d.id, d.event, d.payload, d.attempts, w.url, w.secret
+            "#,
+        )
+        .bind(BATCH_SIZE)
+        .bind(CLAIM_LEASE.as_secs_f64())
+        .fetch_all(&self.db)
+        .await?;
+
+        let count = due.len();
+        futures::future::join_all(due.into_iter().map(|delivery| self.attempt(delivery))).await;
+        Ok(count)
+    }
+
+    /// POST one delivery and record the outcome
+    async fn attempt(&self, delivery: DueDelivery) {
+        let (response_status, error) = match self.client_for(&delivery.url).await {
+            Err(error) => (None, Some(error)),
+            Ok(client) => {
+                let body = delivery.payload.0.to_string().into_bytes();
+                let response = client
+                    .post(&delivery.url)
+                    .header(reqwest::header::CONTENT_TYPE, "application/json")
+                    .header(SIGNATURE_HEADER, sign_payload(&delivery.secret, &body))
+                    .header(EVENT_HEADER, &delivery.event)
+                    .header(DELIVERY_HEADER, delivery.id.to_string())
+                    .body(body)
+                    .send()
+                    .await;
+                outcome(response)
+            }
+        };
+        let response_status = response_status.map(i32::from);
+
+        let recorded = match error {
+            None => sqlx::query(
+                r#"
+                UPDATE webhook_deliveries SET
+                    status = 'delivered', delivered_at = $2, response_status = $3, last_error = NULL
+                WHERE id = $1
+                "#,
+            )
+            .bind(delivery.id)
+            .bind(Utc::now())
+            .bind(response_status)
+            .execute(&self.db)
+            .await
+            .map(|_| ()),
+            Some(error) => {
+                let give_up = delivery.attempts >= MAX_ATTEMPTS;
+                if give_up {
+                    tracing::warn!(delivery_id = %delivery.id, url = %delivery.url, "Webhook delivery failed permanently: {}", error);
+                } else {
+                    tracing::info!(delivery_id = %delivery.id, attempts = delivery.attempts, "Webhook delivery failed, will retry: {}", error);
+                }
+                sqlx::query(
+                    r#"
+                    UPDATE webhook_deliveries SET
+                        status = CASE WHEN $2 THEN 'failed' ELSE 'pending' END,
+                        next_attempt_at = NOW() + make_interval(secs => $3),
+                        response_status = $4,
+                        last_error = $5
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(delivery.id)
+                .bind(give_up)
+                .bind(retry_delay(delivery.attempts).as_secs_f64())
+                .bind(response_status)
+                .bind(error)
+                .execute(&self.db)
+                .await
+                .map(|_| ())
+            }
+        };
+        if let Err(e) = recorded {
+            // The claim lease expires and the delivery is retried
+            tracing::error!(delivery_id = %delivery.id, "Failed to record webhook delivery: {}", e);
+        }
+    }
+}
+
+/// Status the receiver answered with, and an error unless it was a 2xx
+fn outcome(response: reqwest::Result<reqwest::Response>) -> (Option<u16>, Option<String>) {
+    match response {
+        Ok(resp) if resp.status().is_success() => (Some(resp.status().as_u16()), None),
+        Ok(resp) => (
+            Some(resp.status().as_u16()),
+            Some(format!("Endpoint returned {}", resp.status())),
+        ),
+        Err(e) => (None, Some(e.to_string())),
+    }
+}
+
+/// Wait before retrying after `attempts` failed attempts
+fn retry_delay(attempts: i32) -> Duration {
+    let doublings = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    (RETRY_BASE_DELAY * 2u32.pow(doublings)).min(RETRY_MAX_DELAY)
+}
+
+fn event_payload(
+    delivery_id: Uuid,
+    event: WebhookEvent,
+    ticket: &WebhookTicket,
+) -> serde_json::Value {
+    serde_json::json!({
+        "id": delivery_id,
+        "event": event,
+        "created_at": Utc::now(),
+        "data": { "ticket": ticket },
+    })
+}
+
+fn ping_payload(delivery_id: Uuid, webhook: &Webhook) -> serde_json::Value {
+    serde_json::json!({
+        "id": delivery_id,
+        "event": PING_EVENT,
+        "created_at": Utc::now(),
+        "data": {
+            "webhook_id": webhook.id,
+            "project_id": webhook.project_id,
+            "events": webhook.events,
+        },
+    })
+}
+
+fn event_names(events: &[WebhookEvent]) -> Vec<String> {
+    let mut names: Vec<String> = events.iter().map(|e| e.as_str().to_string()).collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    format!("whsec_{}", hex::encode(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{seed_project, seed_ticket, seed_user};
+    use axum::{extract::State, http::HeaderMap, routing::post, Router};
+    use tokio::sync::mpsc;
+
+    #[test]
+    fn retry_delay_doubles_up_to_cap() {
+        assert_eq!(retry_delay(1), Duration::from_secs(30));
+        assert_eq!(retry_delay(2), Duration::from_secs(60));
+        assert_eq!(retry_delay(4), Duration::from_secs(240));
+        assert_eq!(retry_delay(MAX_ATTEMPTS + 10), RETRY_MAX_DELAY);
+    }
+
+    #[test]
+    fn event_names_are_deduplicated() {
+        let names = event_names(&[
+            WebhookEvent::TicketStatusChanged,
+            WebhookEvent::TicketCreated,
+            WebhookEvent::TicketCreated,
+        ]);
+        assert_eq!(names, vec!["ticket.created", "ticket.status_changed"]);
+    }
+
+    #[test]
+    fn ping_payload_describes_the_webhook() {
+        let webhook = Webhook {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            url: "https://hooks.example.com".to_string(),
+            secret: "whsec_test".to_string(),
+            events: vec!["ticket.created".to_string()],
+            is_active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let delivery_id = Uuid::new_v4();
+        let payload = ping_payload(delivery_id, &webhook);
+        assert_eq!(payload["event"], "ping");
+        assert_eq!(payload["id"], delivery_id.to_string());
+        assert_eq!(payload["data"]["webhook_id"], webhook.id.to_string());
+        assert_eq!(
+            payload["data"]["project_id"],
+            webhook.project_id.to_string()
+        );
+        assert_eq!(
+            payload["data"]["events"],
+            serde_json::json!(["ticket.created"])
+        );
+    }
+
+    #[test]
+    fn generated_secrets_are_unique() {
+        let a = generate_secret();
+        assert!(a.starts_with("whsec_"));
+        assert_eq!(a.len(), "whsec_".len() + 64);
+        assert_ne!(a, generate_secret());
+    }
+
+    /// Owner, project and ticket; returns (project_id, ticket)
+    async fn seed_owned_ticket(pool: &PgPool) -> (Uuid, FeedbackTicket) {
+        let owner_id = seed_user(pool, "owner@example.com", "internal").await.id;
+        let project_id = seed_project(pool, owner_id).await;
+        let ticket_id = seed_ticket(pool, project_id, owner_id, "recording").await;
+        let ticket = sqlx::query_as::<_, FeedbackTicket>("SELECT * FROM recordings WHERE id = $1")
+            .bind(ticket_id)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        (project_id, ticket)
+    }
+
+    /// Local endpoint that forwards each request's headers and body, answering with `status`
+    async fn spawn_receiver(
+        status: axum::http::StatusCode,
+    ) -> (String, mpsc::UnboundedReceiver<(HeaderMap, String)>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let app = Router::new()
+            .route(
+                "/hook",
+                post(
+                    move |State(tx): State<mpsc::UnboundedSender<(HeaderMap, String)>>,
+                          headers: HeaderMap,
+                          body: String| async move {
+                        let _ = tx.send((headers, body));
+                        status
+                    },
+                ),
+            )
+            .with_state(tx);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (url, rx)
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn dispatch_queues_only_subscribed_active_webhooks(pool: PgPool) {
+        let service = WebhookService::allowing_private_targets(pool.clone());
+        let (project_id, ticket) = seed_owned_ticket(&pool).await;
+        let created = service
+            .create(
+                project_id,
+                "https://a.example.com",
+                &[WebhookEvent::TicketCreated],
+                true,
+            )
+            .await
+            .unwrap();
+        service
+            .create(
+                project_id,
+                "https://b.example.com",
+                &[WebhookEvent::TicketAnalyzed],
+                true,
+            )
+            .await
+            .unwrap();
+        service
+            .create(
+                project_id,
+                "https://c.example.com",
+                &[WebhookEvent::TicketCreated],
+                false,
+            )
+            .await
+            .unwrap();
+
+        let queued = service
+            .dispatch(WebhookEvent::TicketCreated, &ticket)
+            .await
+            .unwrap();
+        assert_eq!(queued, 1);
+
+        let (webhook_id, payload): (Uuid, serde_json::Value) =
+            sqlx::query_as("SELECT webhook_id, payload FROM webhook_deliveries")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(webhook_id, created.id);
+        assert_eq!(payload["event"], "ticket.created");
+        assert_eq!(payload["data"]["ticket"]["id"], ticket.id.to_string());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn deliver_due_posts_signed_payload(pool: PgPool) {
+        let service = WebhookService::allowing_private_targets(pool.clone());
+        let (project_id, ticket) = seed_owned_ticket(&pool).await;
+        let (url, mut received) = spawn_receiver(axum::http::StatusCode::OK).await;
+        let webhook = service
+            .create(project_id, &url, &[WebhookEvent::TicketAnalyzed], true)
+            .await
+            .unwrap();
+
+        service
+            .dispatch(WebhookEvent::TicketAnalyzed, &ticket)
+            .await
+            .unwrap();
+        assert_eq!(service.deliver_due().await.unwrap(), 1);
+
+        let (headers, body) = received.recv().await.unwrap();
+        assert_eq!(
+            headers[SIGNATURE_HEADER].to_str().unwrap(),
+            sign_payload(&webhook.secret, body.as_bytes())
+        );
+        assert_eq!(headers[EVENT_HEADER], "ticket.analyzed");
+        let payload: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(headers[DELIVERY_HEADER].to_str().unwrap(), payload["id"]);
+
+        let (status, attempts): (String, i32) =
+            sqlx::query_as("SELECT status, attempts FROM webhook_deliveries")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(status, "delivered");
+        assert_eq!(attempts, 1);
+        // Nothing left to send
+        assert_eq!(service.deliver_due().await.unwrap(), 0);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn send_test_pings_and_reports_the_receiver_status(pool: PgPool) {
+        let service = WebhookService::allowing_private_targets(pool.clone());
+        let (project_id, _) = seed_owned_ticket(&pool).await;
+        let (url, mut received) = spawn_receiver(axum::http::StatusCode::IM_A_TEAPOT).await;
+        let webhook = service
+            .create(project_id, &url, &[WebhookEvent::TicketCreated], false)
+            .await
+            .unwrap();
+
+        let delivery = service.send_test(webhook.id, project_id).await.unwrap();
+        assert_eq!(delivery.response_status, Some(418));
+        assert!(delivery.error.is_some());
+
+        let (headers, body) = received.recv().await.unwrap();
+        assert!(crate::crypto::verify_signature(
+            &webhook.secret,
+            body.as_bytes(),
+            headers[SIGNATURE_HEADER].to_str().unwrap()
+        ));
+        assert_eq!(headers[EVENT_HEADER], "ping");
+        assert_eq!(
+            headers[DELIVERY_HEADER].to_str().unwrap(),
+            delivery.delivery_id.to_string()
+        );
+        let payload: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(payload["event"], "ping");
+
+        // Test pings are not queued for retry
+        let queued: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM webhook_deliveries")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(queued, 0);
+        assert!(matches!(
+            service.send_test(webhook.id, Uuid::new_v4()).await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn send_test_refuses_internal_targets(pool: PgPool) {
+        let service = WebhookService::new(pool.clone());
+        let (project_id, _) = seed_owned_ticket(&pool).await;
+        let (url, mut received) = spawn_receiver(axum::http::StatusCode::OK).await;
+        // Closed local port: would show as "connection refused" if it were probed
+        let closed = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}/", listener.local_addr().unwrap())
+        };
+        for url in [url, closed] {
+            let webhook_id = sqlx::query_scalar::<_, Uuid>(
+                "INSERT INTO webhooks (project_id, url, secret, events) VALUES ($1, $2, 'whsec_test', ARRAY['ticket.created']) RETURNING id",
+            )
+            .bind(project_id)
+            .bind(&url)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            assert!(matches!(
+                service.send_test(webhook_id, project_id).await,
+                Err(AppError::Validation(_))
+            ));
+        }
+        assert!(received.try_recv().is_err());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn send_test_hides_transport_errors(pool: PgPool) {
+        let service = WebhookService::allowing_private_targets(pool.clone());
+        let (project_id, _) = seed_owned_ticket(&pool).await;
+        let closed = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}/", listener.local_addr().unwrap())
+        };
+        let webhook = service
+            .create(project_id, &closed, &[WebhookEvent::TicketCreated], true)
+            .await
+            .unwrap();
+
+        let delivery = service.send_test(webhook.id, project_id).await.unwrap();
+        assert_eq!(delivery.response_status, None);
+        assert_eq!(
+            delivery.error.as_deref(),
+            Some("Could not connect to the receiver")
+        );
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn internal_targets_are_neither_saved_nor_sent_to(pool: PgPool) {
+        let service = WebhookService::new(pool.clone());
+        let (project_id, ticket) = seed_owned_ticket(&pool).await;
+        for url in [
+            "http://169.254.169.254/computeMetadata/v1/",
+            "http://metadata.google.internal/computeMetadata/v1/",
+            "http://localhost:5432/",
+            "http://10.0.0.7/hook",
+        ] {
+            assert!(
+                matches!(
+                    service
+                        .create(project_id, url, &[WebhookEvent::TicketCreated], true)
+                        .await,
+                    Err(AppError::Validation(_))
+                ),
+                "{url}"
+            );
+        }
+
+        // A target stored before the check (or re-pointed by DNS) is refused at send time
+        let (url, mut received) = spawn_receiver(axum::http::StatusCode::OK).await;
+        sqlx::query(
+            "INSERT INTO webhooks (project_id, url, secret, events, is_active) VALUES ($1, $2, 'whsec_test', ARRAY['ticket.created'], TRUE)",
+        )
+        .bind(project_id)
+        .bind(&url)
+        .execute(&pool)
+        .await
+        .unwrap();
+        service
+            .dispatch(WebhookEvent::TicketCreated, &ticket)
+            .await
+            .unwrap();
+        assert_eq!(service.deliver_due().await.unwrap(), 1);
+        assert!(received.try_recv().is_err());
+        let (status, last_error): (String, Option<String>) =
+            sqlx::query_as("SELECT status, last_error FROM webhook_deliveries")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(status, "pending");
+        assert!(last_error.unwrap().contains("public internet host"));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn redirects_are_not_followed(pool: PgPool) {
+        let service = WebhookService::allowing_private_targets(pool.clone());
+        let (project_id, ticket) = seed_owned_ticket(&pool).await;
+        let (inner_url, mut inner) = spawn_receiver(axum::http::StatusCode::OK).await;
+        let app = Router::new().route(
+            "/hook",
+            post(move || async move {
+                (
+                    axum::http::StatusCode::TEMPORARY_REDIRECT,
+                    [(axum::http::header::LOCATION, inner_url)],
+                )
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        service
+            .create(project_id, &url, &[WebhookEvent::TicketCreated], true)
+            .await
+            .unwrap();
+
+        service
+            .dispatch(WebhookEvent::TicketCreated, &ticket)
+            .await
+            .unwrap();
+        assert_eq!(service.deliver_due().await.unwrap(), 1);
+
+        let response_status: Option<i32> =
+            sqlx::query_scalar("SELECT response_status FROM webhook_deliveries")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(response_status, Some(307));
+        assert!(inner.try_recv().is_err());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn failed_delivery_is_rescheduled(pool: PgPool) {
+        let service = WebhookService::allowing_private_targets(pool.clone());
+        let (project_id, ticket) = seed_owned_ticket(&pool).await;
+        let (url, mut received) = spawn_receiver(axum::http::StatusCode::BAD_GATEWAY).await;
+        service
+            .create(project_id, &url, &[WebhookEvent::TicketCreated], true)
+            .await
+            .unwrap();
+
+        service
+            .dispatch(WebhookEvent::TicketCreated, &ticket)
+            .await
+            .unwrap();
+        assert_eq!(service.deliver_due().await.unwrap(), 1);
+        received.recv().await.unwrap();
+
+        let (status, response_status, retry_in_future): (String, Option<i32>, bool) =
+            sqlx::query_as(
+                "SELECT status, response_status, next_attempt_at > NOW() FROM webhook_deliveries",
+            )
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(status, "pending");
+        assert_eq!(response_status, Some(502));
+        assert!(retry_in_future);
+        // Not due again until the backoff elapses
+        assert_eq!(service.deliver_due().await.unwrap(), 0);
+    }
+}
@@ -1,62 +1,242 @@
 //! Background worker for processing analysis jobs
 
 use anyhow::{Context, Result};
+use futures::StreamExt;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::time::sleep;
+use tempfile::NamedTempFile;
+use tokio::io::AsyncWriteExt;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, sleep, timeout};
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
 
+use crate::config::JOB_HEARTBEAT_INTERVAL_SECS;
+use crate::models::{
+    AnalysisJob, ClientDiagnostics, JobProgressStage, JobStatus, ParsedAnalysis, Project,
+    ReportOutcome, WebhookEvent, DEFAULT_REPORT_LANGUAGE,
+};
+use crate::services::{ByteStream, PriorTicket, TicketService};
 use crate::state::AppState;
 
+/// How often a running job refreshes its heartbeat
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(JOB_HEARTBEAT_INTERVAL_SECS);
+/// How often the sweeper looks for jobs whose worker died
+const STALE_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+/// Most `{...}` candidates tried when looking for the analysis JSON in free text
+const MAX_JSON_CANDIDATES: usize = 32;
+/// Prompt budget for browser console/network diagnostics
+const MAX_DIAGNOSTICS_PROMPT_CHARS: usize = 6000;
+/// Earlier resolved tickets of the same submitter included in the prompt
+const MAX_PRIOR_SESSIONS: i64 = 5;
+/// Longest description quoted per prior ticket, in characters
+const MAX_PRIOR_DESCRIPTION_CHARS: usize = 200;
+/// Issue titles listed per prior ticket
+const MAX_PRIOR_ISSUE_TITLES: usize = 3;
+
+/// Aborts the heartbeat task when the job finishes, on any return path
+struct HeartbeatGuard(JoinHandle<()>);
+
+impl Drop for HeartbeatGuard {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
 pub struct Worker {
+    id: usize,
     state: Arc<AppState>,
+    /// Backoff after errors, and the poll interval when LISTEN is unavailable
     poll_interval: Duration,
+    /// Fallback poll while listening, in case a notification is missed
+    safety_poll_interval: Duration,
+    /// Cancelled on shutdown; the loop exits once the current job is done
+    shutdown: CancellationToken,
+    /// How long the current job may keep running after shutdown before it is released
+    shutdown_grace: Duration,
+    /// Where downloaded videos are staged for analysis
+    temp_dir: PathBuf,
 }
 
 impl Worker {
-    pub fn new(state: Arc<AppState>) -> Self {
+    pub fn new(id: usize, state: Arc<AppState>, shutdown: CancellationToken) -> Self {
+        let poll_interval = Duration::from_millis(state.config.worker_poll_interval_ms);
+        let shutdown_grace = Duration::from_secs(state.config.shutdown_grace_secs);
         Self {
+            id,
             state,
-            poll_interval: Duration::from_secs(5),
+            poll_interval,
+            safety_poll_interval: Duration::from_secs(60),
+            shutdown,
+            shutdown_grace,
+            temp_dir: std::env::temp_dir(),
         }
     }
 
-    /// Start the worker loop
-    pub async fn start(&self) -> Result<()> {
-        tracing::info!("Worker started, polling for jobs...");
+    /// Stage downloads in `dir` instead of the system temp directory
+    #[cfg(test)]
+    fn with_temp_dir(mut self, dir: &Path) -> Self {
+        self.temp_dir = dir.to_path_buf();
+        self
+    }
+
+    /// Spawn `concurrency` independent workers sharing the app state.
+    /// Each has its own poll loop; `FOR UPDATE SKIP LOCKED` in dequeue keeps them
+    /// from picking up the same job. A single sweeper reclaims jobs from dead workers.
+    /// Returns the worker and sweeper tasks, which finish once `shutdown` is cancelled and
+    /// they drain.
+    pub fn spawn_pool(
+        state: Arc<AppState>,
+        concurrency: usize,
+        shutdown: CancellationToken,
+    ) -> Vec<JoinHandle<()>> {
+        let sweeper = tokio::spawn(Self::run_stale_job_sweep(state.clone(), shutdown.clone()));
+
+        tracing::info!("Starting {} worker(s)", concurrency);
+        (0..concurrency)
+            .map(|id| {
+                let worker = Worker::new(id, state.clone(), shutdown.clone());
+                tokio::spawn(async move {
+                    if let Err(e) = worker.start().await {
+                        tracing::error!(worker_id = id, "Worker error: {}", e);
+                    }
+                })
+            })
+            .chain(std::iter::once(sweeper))
+            .collect()
+    }
 
+    /// Reclaim stuck jobs at startup and then periodically, until `shutdown` is cancelled
+    async fn run_stale_job_sweep(state: Arc<AppState>, shutdown: CancellationToken) {
+        let stale_after = Duration::from_secs(state.config.job_stale_after_secs);
+        let mut ticker = interval(STALE_SWEEP_INTERVAL);
         loop {
-            match self.process_next_job().await {
-                Ok(processed) => {
-                    if !processed {
-                        sleep(self.poll_interval).await;
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = shutdown.cancelled() => return,
+            }
+            match state.queue.recover_stale_jobs(stale_after).await {
+                Ok(jobs) => {
+                    for job in jobs {
+                        tracing::warn!(job_id = %job.id, status = %job.status, "Reclaimed stale job");
+                        if job.status == JobStatus::DeadLetter {
+                            if let Some(recording_id) = job.recording_id {
+                                if let Err(e) = state.tickets.mark_failed(recording_id).await {
+                                    tracing::error!(%recording_id, "Failed to mark ticket failed: {}", e);
+                                }
+                            }
+                        }
                     }
                 }
+                Err(e) => tracing::error!("Stale job sweep failed: {}", e),
+            }
+        }
+    }
+
+    /// Start the worker loop; returns once shutdown is signaled and the current job is done
+    pub async fn start(&self) -> Result<()> {
+        let mut listener = match self.state.queue.listen().await {
+            Ok(listener) => Some(listener),
+            Err(e) => {
+                tracing::warn!(
+                    worker_id = self.id,
+                    "Job notifications unavailable, falling back to polling: {}",
+                    e
+                );
+                None
+            }
+        };
+        tracing::info!(worker_id = self.id, "Worker started, waiting for jobs...");
+
+        while !self.shutdown.is_cancelled() {
+            match self.process_next_job().await {
+                Ok(true) => {}
+                Ok(false) => match listener.as_mut() {
+                    // Idle: sleep until a job is enqueued, the safety poll fires or shutdown
+                    Some(listener) => {
+                        let received = tokio::select! {
+                            _ = self.shutdown.cancelled() => continue,
+                            received = timeout(self.safety_poll_interval, listener.recv()) => received,
+                        };
+                        if let Ok(Err(e)) = received {
+                            tracing::warn!(worker_id = self.id, "Job listener error: {}", e);
+                            self.idle(self.poll_interval).await;
+                        }
+                    }
+                    None => self.idle(self.poll_interval).await,
+                },
                 Err(e) => {
-                    tracing::error!("Error processing job: {}", e);
-                    sleep(self.poll_interval).await;
+                    tracing::error!(worker_id = self.id, "Error processing job: {}", e);
+                    self.idle(self.poll_interval).await;
                 }
             }
         }
+
+        tracing::info!(worker_id = self.id, "Worker stopped");
+        Ok(())
     }
 
-    /// Process the next available job
+    /// Sleep, waking early on shutdown
+    async fn idle(&self, duration: Duration) {
+        tokio::select! {
+            _ = self.shutdown.cancelled() => {}
+            _ = sleep(duration) => {}
+        }
+    }
+
+    /// Resolves once shutdown was signaled and the grace period has elapsed
+    async fn shutdown_grace_elapsed(&self) {
+        self.shutdown.cancelled().await;
+        sleep(self.shutdown_grace).await;
+    }
+
+    /// Process the next available job. If shutdown outlasts the grace period, the job
+    /// is abandoned and put back to pending for another instance to pick up.
     async fn process_next_job(&self) -> Result<bool> {
         let job = match self.state.queue.dequeue().await? {
             Some(job) => job,
             None => return Ok(false),
         };
 
-        tracing::info!("Processing job {}: {}", job.id, job.video_storage_path);
+        let job_id = job.id;
+        let span = tracing::info_span!("job", %job_id, worker_id = self.id);
+        tokio::select! {
+            biased;
+            result = self.process_job(job).instrument(span) => result,
+            _ = self.shutdown_grace_elapsed() => {
+                tracing::warn!(worker_id = self.id, %job_id, "Shutdown grace period elapsed, returning job to pending");
+                self.state.queue.release_job(job_id).await?;
+                Ok(true)
+            }
+        }
+    }
+
+    /// Run a dequeued job through download, analysis and saving the results
+    async fn process_job(&self, job: AnalysisJob) -> Result<bool> {
+        tracing::info!(
+            worker_id = self.id,
+            "Processing job {}: {}",
+            job.id,
+            job.video_storage_path
+        );
+        let _heartbeat = self.spawn_heartbeat(job.id);
 
-        // Download video from storage
-        let video_data = match self.state.storage.download(&job.video_storage_path).await {
-            Ok(data) => data,
+        // Download video from storage straight into a temp file for analysis
+        self.advance(job.id, JobProgressStage::Downloading).await;
+        // Deleted when `temp_file` drops, on every return path
+        let temp_file = match self.download_to_temp_file(&job.video_storage_path).await {
+            Ok(file) => file,
             Err(e) => {
                 tracing::error!("Failed to download video: {}", e);
-                self.state
+                let status = self
+                    .state
                     .queue
                     .fail_job(job.id, format!("Download failed: {}", e))
                     .await?;
+                if status == JobStatus::DeadLetter {
+                    tracing::warn!(job_id = %job.id, "Job exceeded max retries, moved to dead letter");
+                }
                 if let Some(recording_id) = job.recording_id {
                     self.state.tickets.mark_failed(recording_id).await?;
                 }
@@ -64,12 +244,21 @@ impl Worker {
             }
         };
 
-        // Save to temp file for analysis
-        let temp_path = self.save_temp_file(&video_data).await?;
-
         // Build prompt based on ticket/project configuration
+        let project = match job.recording_id {
+            Some(recording_id) => self.ticket_project(recording_id).await,
+            None => None,
+        };
+        let language = project.as_ref().map_or_else(
+            || DEFAULT_REPORT_LANGUAGE.to_string(),
+            Project::report_language,
+        );
+        let params = project
+            .as_ref()
+            .map(Project::gemini_params)
+            .unwrap_or_default();
         let prompt = if let Some(recording_id) = job.recording_id {
-            self.build_prompt_for_ticket(recording_id)
+            self.build_prompt_for_ticket(recording_id, job.is_image(), &language)
                 .await
                 .unwrap_or_else(|_| self.default_prompt())
         } else {
@@ -77,18 +266,41 @@ impl Worker {
         };
 
         // Analyze with Gemini
-        let analysis_result = match self.state.gemini.analyze(&temp_path, &prompt).await {
-            Ok(result) => {
-                let _ = tokio::fs::remove_file(&temp_path).await;
-                result
+        self.advance(job.id, JobProgressStage::UploadingToGemini)
+            .await;
+        let analysis = async {
+            if job.is_image() {
+                let image = tokio::fs::read(temp_file.path())
+                    .await
+                    .context("Failed to read screenshot")?;
+                let mime = job.mime_type.as_deref().unwrap_or_default();
+                self.advance(job.id, JobProgressStage::Analyzing).await;
+                self.state
+                    .gemini
+                    .analyze_image(&image, mime, &prompt, params)
+                    .await
+            } else {
+                let video = self.state.gemini.prepare_video(temp_file.path())?;
+                self.advance(job.id, JobProgressStage::Analyzing).await;
+                self.state
+                    .gemini
+                    .analyze_video(&video, &prompt, params)
+                    .await
             }
+        }
+        .await;
+        let analysis_result = match analysis {
+            Ok(result) => result,
             Err(e) => {
-                let _ = tokio::fs::remove_file(&temp_path).await;
                 tracing::error!("Analysis failed: {}", e);
-                self.state
+                let status = self
+                    .state
                     .queue
                     .fail_job(job.id, format!("Analysis failed: {}", e))
                     .await?;
+                if status == JobStatus::DeadLetter {
+                    tracing::warn!(job_id = %job.id, "Job exceeded max retries, moved to dead letter");
+                }
                 if let Some(recording_id) = job.recording_id {
                     self.state.tickets.mark_failed(recording_id).await?;
                 }
@@ -96,21 +308,49 @@ impl Worker {
             }
         };
 
+        tracing::info!(
+            job_id = %job.id,
+            prompt_tokens = analysis_result.usage.prompt_tokens,
+            candidates_tokens = analysis_result.usage.candidates_tokens,
+            total_tokens = analysis_result.usage.total_tokens,
+            "Gemini token usage"
+        );
+        let analysis_text = analysis_result.text;
+
         // Save result
+        self.advance(job.id, JobProgressStage::Saving).await;
         self.state
             .queue
-            .complete_job(job.id, analysis_result.clone())
+            .complete_job(job.id, analysis_text.clone(), analysis_result.usage)
             .await?;
 
         // Update ticket status and create report
         if let Some(recording_id) = job.recording_id {
             self.state.tickets.mark_analyzed(recording_id).await?;
             // Parse analysis and create report/issues
-            if let Err(e) = self
-                .create_report_from_analysis(recording_id, &analysis_result)
+            match self
+                .create_report_from_analysis(recording_id, &analysis_text, &language)
                 .await
             {
-                tracing::warn!("Failed to parse analysis into report: {}", e);
+                Ok(Some(outcome)) => {
+                    if let Some(project) = &project {
+                        if let Err(e) = self
+                            .apply_outcome_mapping(recording_id, outcome, project)
+                            .await
+                        {
+                            tracing::warn!(%recording_id, "Failed to apply outcome mapping: {}", e);
+                        }
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!(%recording_id, "Failed to save analysis report: {}", e),
+            }
+            // Sent last so the payload carries any outcome-mapped priority/status
+            if let Some(ticket) = self.state.tickets.get_by_id(recording_id).await? {
+                self.state
+                    .webhooks
+                    .fire(WebhookEvent::TicketAnalyzed, &ticket)
+                    .await;
             }
         }
 
@@ -118,7 +358,55 @@ impl Worker {
         Ok(true)
     }
 
-    async fn build_prompt_for_ticket(&self, ticket_id: uuid::Uuid) -> Result<String> {
+    /// Record the job's current stage for ticket status display (best-effort)
+    async fn advance(&self, job_id: uuid::Uuid, stage: JobProgressStage) {
+        if let Err(e) = self.state.queue.set_progress(job_id, stage).await {
+            tracing::warn!(%job_id, ?stage, "Failed to record job progress: {}", e);
+        }
+    }
+
+    /// Keep `heartbeat_at` fresh while the job runs; stops when the guard is dropped
+    fn spawn_heartbeat(&self, job_id: uuid::Uuid) -> HeartbeatGuard {
+        let state = self.state.clone();
+        HeartbeatGuard(tokio::spawn(async move {
+            let mut ticker = interval(HEARTBEAT_INTERVAL);
+            ticker.tick().await; // first tick is immediate; dequeue already set heartbeat_at
+            loop {
+                ticker.tick().await;
+                if let Err(e) = state.queue.heartbeat(job_id).await {
+                    tracing::warn!(%job_id, "Failed to record heartbeat: {}", e);
+                }
+            }
+        }))
+    }
+
+    /// Project the ticket belongs to, for its report language and Gemini settings
+    /// (best-effort; `None` if the ticket has no project or the lookup fails)
+    async fn ticket_project(&self, ticket_id: uuid::Uuid) -> Option<Project> {
+        let project = async {
+            let ticket = self.state.tickets.get_by_id(ticket_id).await?;
+            match ticket.and_then(|t| t.project_id) {
+                Some(project_id) => self.state.projects.get_by_id(project_id).await,
+                None => Ok(None),
+            }
+        }
+        .await;
+        match project {
+            Ok(project) => project,
+            Err(e) => {
+                tracing::warn!(%ticket_id, "Failed to load ticket project: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Prompt for a ticket's recording, or for its screenshot when `image` is set
+    async fn build_prompt_for_ticket(
+        &self,
+        ticket_id: uuid::Uuid,
+        image: bool,
+        language: &str,
+    ) -> Result<String> {
         let ticket = self
             .state
             .tickets
@@ -132,53 +420,70 @@ impl Worker {
             crate::models::FeedbackType::Idea => "Idea",
         };
 
+        let (media, subject) = if image {
+            (
+                "screenshot. It is a single still image, so leave time-based metrics (hesitation time, retries) null",
+                "screenshot",
+            )
+        } else {
+            ("screen recording", "recording")
+        };
+
         // Context for the model based on submission type
         let feedback_context = match ticket.feedback_type {
-            crate::models::FeedbackType::Bug => {
-                "Focus on identifying bugs, errors, and unexpected behavior in the recording."
-            }
+            crate::models::FeedbackType::Bug => format!(
+                "Focus on identifying bugs, errors, and unexpected behavior in the {}.",
+                subject
+            ),
             crate::models::FeedbackType::Feedback => {
                 "Analyze the user experience, usability issues, and areas for improvement."
+                    .to_string()
             }
-            crate::models::FeedbackType::Idea => {
-                "Analyze the feature request or suggestion shown in the recording."
-            }
+            crate::models::FeedbackType::Idea => format!(
+                "Analyze the feature request or suggestion shown in the {}.",
+                subject
+            ),
+        };
+
+        let project = match ticket.project_id {
+            Some(project_id) => self.state.projects.get_by_id(project_id).await?,
+            None => None,
+        };
+        // Pull project-specific questions for this feedback type and include in prompt
+        let question_block = project
+            .as_ref()
+            .map(|project| {
+                question_block(
+                    &project
+                        .analysis_questions()
+                        .enabled_for_type(ticket.feedback_type),
+                )
+            })
+            .unwrap_or_default();
+        let prior_block = match &project {
+            Some(project) if project.include_prior_sessions() => prior_sessions_block(
+                &self
+                    .state
+                    .tickets
+                    .prior_resolved_tickets(&ticket, MAX_PRIOR_SESSIONS)
+                    .await?,
+            ),
+            _ => String::new(),
         };
 
         let description = ticket
             .task_description
             .unwrap_or_else(|| "No description provided".to_string());
+        let experience = experience_line(ticket.prior_experience.as_deref());
 
-        // Pull project-specific questions for this feedback type and include in prompt
-        let question_block = if let Some(project_id) = ticket.project_id {
-            if let Some(project) = self.state.projects.get_by_id(project_id).await? {
-                let questions = project
-                    .analysis_questions()
-                    .enabled_for_type(ticket.feedback_type);
-                if !questions.is_empty() {
-                    format!(
-                        "\n\nAnswer these questions in your analysis (include each in question_analysis):\n{}",
-                        questions
-                            .into_iter()
-                            .map(|q| format!("- {}", q))
-                            .collect::<Vec<_>>()
-                            .join("\n")
-                    )
-                } else {
-                    String::new()
-                }
-            } else {
-                String::new()
-            }
-        } else {
-            String::new()
-        };
+        let diagnostics_block =
+            diagnostics_block(&ticket.client_diagnostics, MAX_DIAGNOSTICS_PROMPT_CHARS);
 
         Ok(format!(
-            "Analyze this screen recording. This submission type is: {}.\n\n\
+            "Analyze this {}. This submission type is: {}.\n\n\
              {}\n\n\
              User's description: {}\n\
-             {}\n\n\
+             {}{}{}{}\n\n\
              Provide your analysis as a single JSON object with this exact structure (so it can be shown as text summary + top issues):\n\
              - outcome: \"success\" | \"partial\" | \"failed\"\n\
              - confidence: number 0-100 (overall confidence in the analysis)\n\
@@ -187,11 +492,16 @@ impl Worker {
              - issues: array of top issues, each with: title (short, for display as a pill), severity (\"critical\"|\"high\"|\"medium\"|\"low\"), tags, observed_behavior, expected_behavior, evidence, impact, reproduction_steps, confidence\n\
              - question_analysis: array of {{ question, answer, observations, confidence, timestamp }} for each question listed above\n\
              - suggested_actions: array of strings (recommended next steps)\n\
-             - possible_solutions: array of strings (concrete solutions to address the issues found; e.g. \"Add a loading spinner on submit\", \"Group related settings under a section\")",
+             - possible_solutions: array of strings (concrete solutions to address the issues found; e.g. \"Add a loading spinner on submit\", \"Group related settings under a section\"){}",
+            media,
             type_label,
             feedback_context,
             description,
-            question_block
+            experience,
+            question_block,
+            diagnostics_block,
+            prior_block,
+            language_line(language)
         ))
     }
 
@@ -202,239 +512,1284 @@ impl Worker {
             .to_string()
     }
 
-    async fn save_temp_file(&self, data: &[u8]) -> Result<std::path::PathBuf> {
-        let temp_file = tempfile::NamedTempFile::new()?;
-        let path = temp_file.path().to_path_buf();
-
-        tokio::fs::write(&path, data).await?;
-        std::mem::forget(temp_file);
-
-        Ok(path)
+    async fn download_to_temp_file(&self, storage_path: &str) -> Result<NamedTempFile> {
+        let stream = self.state.storage.download_stream(storage_path).await?;
+        write_temp_file(stream, &self.temp_dir).await
     }
 
-    /// Try to extract a JSON object from Gemini output (raw JSON, ```json block, or first {...}).
+    /// Try to extract a JSON object from Gemini output: raw JSON (after any "Here is the
+    /// JSON:" preamble), a fenced code block, or the first brace-matched `{...}` that parses.
+    /// Single-quoted keys and strings are accepted.
     fn extract_analysis_json(analysis: &str) -> Option<serde_json::Value> {
-        let trimmed = analysis.trim();
+        let trimmed = strip_preamble(analysis.trim());
         // 1) Raw JSON
-        if let Ok(v) = serde_json::from_str::<serde_json::Value>(trimmed) {
+        if let Some(v) = parse_json_object(trimmed) {
             return Some(v);
         }
-        // 2) Markdown code block ```json ... ``` (allow ``` or ```\n at end)
-        for start_marker in ["```json", "```JSON"] {
-            if let Some(start) = trimmed.find(start_marker) {
-                let after_start = trimmed[start + start_marker.len()..].trim_start();
-                let end = after_start
-                    .find("\n```")
-                    .or_else(|| after_start.find("```"));
-                let json_str = if let Some(e) = end {
-                    after_start[..e].trim()
-                } else {
-                    after_start.trim()
-                };
-                if let Ok(v) = serde_json::from_str::<serde_json::Value>(json_str) {
-                    return Some(v);
-                }
+        // 2) Fenced code blocks (```json, ```JSON, ``` json, plain ```, ...)
+        for block in fenced_blocks(trimmed) {
+            if let Some(v) = parse_json_object(block) {
+                return Some(v);
             }
         }
-        // 3) First outermost { ... } (brace-matched)
-        let open = trimmed.find('{')?;
-        let rest = &trimmed[open..];
-        let mut depth = 0i32;
-        let mut in_string = false;
-        let mut escape = false;
-        let mut quote = '\0';
-        let mut end_byte = 0usize;
-        for (i, c) in rest.char_indices() {
-            if escape {
-                escape = false;
-                continue;
-            }
-            if in_string {
-                if c == quote {
-                    in_string = false;
-                } else if c == '\\' {
-                    escape = true;
-                }
-                continue;
-            }
-            match c {
-                '"' | '\'' => {
-                    in_string = true;
-                    quote = c;
-                }
-                '{' => depth += 1,
-                '}' => {
-                    depth -= 1;
-                    if depth == 0 {
-                        end_byte = i + c.len_utf8();
-                        break;
-                    }
-                }
-                _ => {}
-            }
-        }
-        if depth == 0 && end_byte > 0 {
-            let json_str = rest.get(..end_byte)?;
-            serde_json::from_str::<serde_json::Value>(json_str).ok()
-        } else {
-            None
+        // 3) Brace-matched { ... } candidates, skipping ones that don't parse
+        brace_candidates(trimmed)
+            .take(MAX_JSON_CANDIDATES)
+            .find_map(parse_json_object)
+    }
+
+    /// Adjust ticket priority/status based on the project's outcome mapping
+    async fn apply_outcome_mapping(
+        &self,
+        recording_id: uuid::Uuid,
+        outcome: ReportOutcome,
+        project: &Project,
+    ) -> Result<()> {
+        let mapping = project.outcome_mapping();
+        let Some(rule) = mapping.rule_for(outcome) else {
+            return Ok(());
+        };
+        if let Err(e) = mapping.validate() {
+            tracing::warn!(project_id = %project.id, "Skipping invalid outcome mapping: {}", e);
+            return Ok(());
         }
+
+        let updated = self
+            .state
+            .tickets
+            .apply_outcome_rule(recording_id, rule)
+            .await?;
+        tracing::info!(
+            %recording_id,
+            ?outcome,
+            priority = %updated.priority,
+            ticket_status = %updated.ticket_status,
+            "Applied outcome mapping"
+        );
+        Ok(())
     }
 
+    /// Save the analysis as the ticket's next report version. Returns the outcome Gemini
+    /// reported, or `None` when the analysis did not parse and a fallback report was saved.
     async fn create_report_from_analysis(
         &self,
         recording_id: uuid::Uuid,
         analysis: &str,
-    ) -> Result<()> {
+        language: &str,
+    ) -> Result<Option<ReportOutcome>> {
         // Try to parse the analysis as JSON (raw, or from markdown code block, or extract first {...})
-        let parsed: serde_json::Value = Self::extract_analysis_json(analysis).ok_or_else(|| {
-            let snippet = analysis.chars().take(400).collect::<String>();
-            tracing::warn!(
-                "Gemini response was not valid JSON. First 400 chars: {}",
-                snippet
-            );
-            anyhow::anyhow!("Could not parse analysis as JSON")
-        })?;
+        let (report, outcome) = match Self::extract_analysis_json(analysis) {
+            Some(parsed) => {
+                let missing = ParsedAnalysis::missing_sections(&parsed);
+                if !missing.is_empty() {
+                    tracing::warn!(
+                        %recording_id,
+                        ?missing,
+                        "Gemini analysis is missing sections; saving what parsed"
+                    );
+                }
+                let report = ParsedAnalysis::from_value(&parsed);
+                let outcome = report.outcome;
+                (report, outcome)
+            }
+            None => {
+                let snippet = analysis.chars().take(400).collect::<String>();
+                tracing::warn!(
+                    %recording_id,
+                    "Gemini response was not valid JSON; saving a partial report. First 400 chars: {}",
+                    snippet
+                );
+                (ParsedAnalysis::fallback(analysis), None)
+            }
+        };
 
-        // Create report in database
+        // Create the report as the ticket's next version, keeping any earlier one
+        let mut tx = self.state.db.begin().await?;
+        TicketService::archive_report(&mut tx, recording_id).await?;
+        let report_version: i32 = sqlx::query_scalar(
+            "SELECT COALESCE(MAX(report_version), 0) + 1 FROM report_versions WHERE recording_id = $1",
+        )
+        .bind(recording_id)
+        .fetch_one(&mut *tx)
+        .await?;
         let report_id = sqlx::query_scalar::<_, uuid::Uuid>(
             r#"
             INSERT INTO reports (
                 recording_id, outcome, confidence, overview,
                 task_completion_rate, total_hesitation_time, retries_count, abandonment_point,
-                question_analysis, suggested_actions, possible_solutions, raw_analysis
+                question_analysis, suggested_actions, possible_solutions, raw_analysis, language,
+                sentiment, frustration_score, report_version
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
             RETURNING id
             "#,
         )
         .bind(recording_id)
-        .bind(parsed.get("outcome").and_then(|v| v.as_str()))
-        .bind(
-            parsed
-                .get("confidence")
-                .and_then(|v| v.as_i64())
-                .map(|v| v as i32),
-        )
-        .bind(parsed.get("overview").and_then(|v| v.as_str()))
-        .bind(
-            parsed
-                .get("metrics")
-                .and_then(|m| m.get("task_completion_rate"))
-                .and_then(|v| v.as_i64())
-                .map(|v| v as i32),
-        )
-        .bind(
-            parsed
-                .get("metrics")
-                .and_then(|m| m.get("total_hesitation_time"))
-                .and_then(|v| v.as_i64())
-                .map(|v| v as i32),
-        )
-        .bind(
-            parsed
-                .get("metrics")
-                .and_then(|m| m.get("retries_count"))
-                .and_then(|v| v.as_i64())
-                .map(|v| v as i32),
-        )
-        .bind(
-            parsed
-                .get("metrics")
-                .and_then(|m| m.get("abandonment_point"))
-                .and_then(|v| v.as_str()),
-        )
-        .bind(sqlx::types::Json(
-            parsed
-                .get("question_analysis")
-                .cloned()
-                .unwrap_or(serde_json::Value::Array(vec![])),
-        ))
-        .bind(sqlx::types::Json(
-            parsed
-                .get("suggested_actions")
-                .cloned()
-                .unwrap_or(serde_json::Value::Array(vec![])),
-        ))
-        .bind(sqlx::types::Json(
-            parsed
-                .get("possible_solutions")
-                .cloned()
-                .unwrap_or(serde_json::Value::Array(vec![])),
-        ))
+        .bind(report.outcome)
+        .bind(report.confidence)
+        .bind(&report.overview)
+        .bind(report.task_completion_rate)
+        .bind(report.total_hesitation_time)
+        .bind(report.retries_count)
+        .bind(&report.abandonment_point)
+        .bind(sqlx::types::Json(&report.question_analysis))
+        .bind(sqlx::types::Json(&report.suggested_actions))
+        .bind(sqlx::types::Json(&report.possible_solutions))
         .bind(analysis)
-        .fetch_one(&self.state.db)
+        .bind(language)
+        .bind(report.sentiment)
+        .bind(report.frustration_score)
+        .bind(report_version)
+        .fetch_one(&mut *tx)
         .await?;
 
         // Create issues
-        if let Some(issues) = parsed.get("issues").and_then(|v| v.as_array()) {
-            for issue in issues {
-                sqlx::query(
-                    r#"
-                    INSERT INTO issues (
-                        report_id, title, severity, tags,
-                        observed_behavior, expected_behavior,
-                        evidence, screenshots, impact, reproduction_steps, confidence
-                    )
-                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
-                    "#,
-                )
-                .bind(report_id)
-                .bind(
-                    issue
-                        .get("title")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("Unknown Issue"),
-                )
-                .bind(
-                    issue
-                        .get("severity")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("medium"),
-                )
-                .bind(sqlx::types::Json(
-                    issue
-                        .get("tags")
-                        .cloned()
-                        .unwrap_or(serde_json::Value::Array(vec![])),
-                ))
-                .bind(issue.get("observed_behavior").and_then(|v| v.as_str()))
-                .bind(issue.get("expected_behavior").and_then(|v| v.as_str()))
-                .bind(sqlx::types::Json(
-                    issue
-                        .get("evidence")
-                        .cloned()
-                        .unwrap_or(serde_json::Value::Array(vec![])),
-                ))
-                .bind(sqlx::types::Json(
-                    issue
-                        .get("screenshots")
-                        .cloned()
-                        .unwrap_or(serde_json::Value::Array(vec![])),
-                ))
-                .bind(sqlx::types::Json(
-                    issue
-                        .get("impact")
-                        .cloned()
-                        .unwrap_or(serde_json::Value::Array(vec![])),
-                ))
-                .bind(sqlx::types::Json(
-                    issue
-                        .get("reproduction_steps")
-                        .cloned()
-                        .unwrap_or(serde_json::Value::Array(vec![])),
-                ))
-                .bind(
-                    issue
-                        .get("confidence")
-                        .and_then(|v| v.as_i64())
-                        .map(|v| v as i32),
+        for issue in &report.issues {
+            sqlx::query(
+                r#"
+                INSERT INTO issues (
+                    report_id, title, severity, tags,
+                    observed_behavior, expected_behavior,
+                    evidence, screenshots, impact, reproduction_steps, confidence
                 )
-                .execute(&self.state.db)
-                .await?;
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                "#,
+            )
+            .bind(report_id)
+            .bind(&issue.title)
+            .bind(issue.severity)
+            .bind(sqlx::types::Json(&issue.tags))
+            .bind(&issue.observed_behavior)
+            .bind(&issue.expected_behavior)
+            .bind(sqlx::types::Json(&issue.evidence))
+            .bind(sqlx::types::Json(&issue.screenshots))
+            .bind(sqlx::types::Json(&issue.impact))
+            .bind(sqlx::types::Json(&issue.reproduction_steps))
+            .bind(issue.confidence)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(outcome)
+    }
+}
+
+/// Drop a leading "Here is the JSON:"-style line Gemini sometimes puts before its answer
+fn strip_preamble(text: &str) -> &str {
+    match text.split_once('\n') {
+        Some((first, rest)) if first.trim_end().ends_with(':') && !first.contains('{') => {
+            rest.trim_start()
+        }
+        _ => text,
+    }
+}
+
+/// Parse `text` as a JSON object, retrying with single quotes turned into double quotes
+fn parse_json_object(text: &str) -> Option<serde_json::Value> {
+    let text = text.trim();
+    let parsed = serde_json::from_str::<serde_json::Value>(text)
+        .ok()
+        .or_else(|| serde_json::from_str(&double_quoted(text)?).ok())?;
+    parsed.is_object().then_some(parsed)
+}
+
+/// Rewrite single-quoted strings as double-quoted JSON strings; `None` if there are none
+fn double_quoted(text: &str) -> Option<String> {
+    if !text.contains('\'') {
+        return None;
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut quote: Option<char> = None;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                out.push('"');
             }
+            (Some(q), _) if c == q => {
+                quote = None;
+                out.push('"');
+            }
+            (Some(_), '\\') => match chars.next() {
+                // JSON has no \' escape
+                Some('\'') => out.push('\''),
+                Some(next) => {
+                    out.push(c);
+                    out.push(next);
+                }
+                None => out.push(c),
+            },
+            (Some('\''), '"') => out.push_str("\\\""),
+            _ => out.push(c),
         }
+    }
+    Some(out)
+}
 
-        Ok(())
+/// Contents of each ``` fenced block, with any language tag on the opening line dropped
+fn fenced_blocks(text: &str) -> impl Iterator<Item = &str> {
+    text.split("```")
+        .skip(1)
+        .step_by(2)
+        .map(|block| match block.split_once('\n') {
+            Some((tag, body)) if !tag.contains('{') => body,
+            _ => block,
+        })
+}
+
+/// Brace-matched `{...}` spans, one per `{` that starts a balanced object, in order.
+/// Braces inside double- or single-quoted strings are ignored.
+fn brace_candidates(text: &str) -> impl Iterator<Item = &str> {
+    text.char_indices()
+        .filter(|&(_, c)| c == '{')
+        .filter_map(move |(open, _)| {
+            let rest = &text[open..];
+            let mut depth = 0i32;
+            let mut quote: Option<char> = None;
+            let mut escape = false;
+            for (i, c) in rest.char_indices() {
+                if escape {
+                    escape = false;
+                    continue;
+                }
+                if let Some(q) = quote {
+                    if c == q {
+                        quote = None;
+                    } else if c == '\\' {
+                        escape = true;
+                    }
+                    continue;
+                }
+                match c {
+                    '"' | '\'' => quote = Some(c),
+                    '{' => depth += 1,
+                    '}' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Some(&rest[..i + c.len_utf8()]);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            None
+        })
+}
+
+/// Write a byte stream to a new temp file that is removed when the handle drops,
+/// including when writing fails partway through
+async fn write_temp_file(mut stream: ByteStream, dir: &Path) -> Result<NamedTempFile> {
+    let temp_file = NamedTempFile::new_in(dir)?;
+    let mut file = tokio::fs::File::from_std(temp_file.reopen()?);
+    while let Some(chunk) = stream.next().await {
+        file.write_all(&chunk?).await?;
+    }
+    file.flush().await?;
+    Ok(temp_file)
+}
+
+/// Prompt line with the submitter's familiarity with the product, so the model can tell
+/// a first-timer's confusion from an expert's; empty when they didn't say
+fn experience_line(prior_experience: Option<&str>) -> String {
+    match prior_experience.map(str::trim).filter(|p| !p.is_empty()) {
+        Some(experience) => format!("User's prior experience with the product: {}\n", experience),
+        None => String::new(),
+    }
+}
+
+/// Closing prompt instruction asking for the report in the project's language; empty for
+/// English. Keys and enum values stay English so the JSON still parses.
+fn language_line(language: &str) -> String {
+    if language.eq_ignore_ascii_case(DEFAULT_REPORT_LANGUAGE) {
+        return String::new();
+    }
+    format!(
+        "\n\nWrite the overview, issues, and suggested actions in {}. Keep the JSON keys and the outcome and severity values in English.",
+        language
+    )
+}
+
+/// Prompt section listing the project's questions, in the order given
+fn question_block(questions: &[String]) -> String {
+    if questions.is_empty() {
+        return String::new();
+    }
+    format!(
+        "\n\nAnswer these questions in your analysis (include each in question_analysis):\n{}",
+        questions
+            .iter()
+            .map(|q| format!("- {}", q))
+            .collect::<Vec<_>>()
+            .join("\n")
+    )
+}
+
+/// Prompt section summarizing the submitter's earlier resolved tickets, so the model can
+/// point out recurring problems; empty when there are none
+fn prior_sessions_block(prior: &[PriorTicket]) -> String {
+    if prior.is_empty() {
+        return String::new();
+    }
+    let lines = prior
+        .iter()
+        .map(|ticket| {
+            let description = match ticket.task_description.as_deref().map(str::trim) {
+                Some(d) if d.chars().count() > MAX_PRIOR_DESCRIPTION_CHARS => format!(
+                    "{}…",
+                    d.chars()
+                        .take(MAX_PRIOR_DESCRIPTION_CHARS)
+                        .collect::<String>()
+                ),
+                Some(d) if !d.is_empty() => d.to_string(),
+                _ => "No description".to_string(),
+            };
+            let mut line = format!(
+                "- {}: {}",
+                ticket.created_at.format("%Y-%m-%d"),
+                description
+            );
+            if let Some(outcome) = ticket.outcome {
+                line.push_str(&format!(" (outcome: {})", outcome));
+            }
+            if !ticket.issue_titles.is_empty() {
+                let titles = ticket
+                    .issue_titles
+                    .iter()
+                    .take(MAX_PRIOR_ISSUE_TITLES)
+                    .map(String::as_str)
+                    .collect::<Vec<_>>();
+                line.push_str(&format!("; issues: {}", titles.join(", ")));
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "\n\nPrior sessions from this user (resolved, newest first). If this submission repeats one of these problems, say so in the overview:\n{}",
+        lines
+    )
+}
+
+/// Prompt section listing the submitter's browser console output and failed requests, so
+/// the model can correlate errors with the recording. Keeps the most recent lines within
+/// `max_chars`; empty when the widget sent nothing.
+fn diagnostics_block(diagnostics: &ClientDiagnostics, max_chars: usize) -> String {
+    if diagnostics.is_empty() {
+        return String::new();
+    }
+
+    let mut lines: Vec<String> = Vec::new();
+    for entry in &diagnostics.console_logs {
+        let at = entry
+            .timestamp
+            .map(|t| format!(" {}", t.format("%H:%M:%S")))
+            .unwrap_or_default();
+        lines.push(format!("[console.{}{}] {}", entry.level, at, entry.message));
+    }
+    for error in &diagnostics.network_errors {
+        let status = error
+            .status
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| "no response".to_string());
+        let message = error
+            .message
+            .as_deref()
+            .map(|m| format!(" ({})", m))
+            .unwrap_or_default();
+        lines.push(format!(
+            "[network] {} {} -> {}{}",
+            error.method.as_deref().unwrap_or("GET"),
+            error.url,
+            status,
+            message
+        ));
+    }
+
+    // Keep the last lines that fit (network errors, then the newest console output)
+    let mut used = 0;
+    let mut kept: Vec<&str> = Vec::new();
+    for line in lines.iter().rev() {
+        let cost = line.chars().count() + 1;
+        if used + cost > max_chars {
+            break;
+        }
+        used += cost;
+        kept.push(line);
+    }
+    kept.reverse();
+
+    let mut block = String::from(
+        "\n\nBrowser diagnostics captured with this submission (correlate errors with what happens on screen):\n",
+    );
+    let omitted = lines.len() - kept.len();
+    if omitted > 0 {
+        block.push_str(&format!("[{} earlier lines omitted]\n", omitted));
+    }
+    block.push_str(&kept.join("\n"));
+    block
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ConsoleEntry, NetworkError};
+    use crate::test_support::{seed_project, seed_project_with, seed_ticket, seed_user};
+    use bytes::Bytes;
+
+    fn stream_of(chunks: Vec<Result<Bytes>>) -> ByteStream {
+        futures::stream::iter(chunks).boxed()
+    }
+
+    #[tokio::test]
+    async fn temp_file_holds_streamed_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let temp = write_temp_file(
+            stream_of(vec![
+                Ok(Bytes::from_static(b"hello ")),
+                Ok(Bytes::from_static(b"world")),
+            ]),
+            dir.path(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(std::fs::read(temp.path()).unwrap(), b"hello world");
+    }
+
+    #[tokio::test]
+    async fn failed_download_leaves_no_temp_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = write_temp_file(
+            stream_of(vec![
+                Ok(Bytes::from_static(b"partial")),
+                Err(anyhow::anyhow!("connection reset")),
+            ]),
+            dir.path(),
+        )
+        .await;
+        assert!(result.is_err());
+
+        let leaked: Vec<_> = std::fs::read_dir(dir.path()).unwrap().collect();
+        assert!(leaked.is_empty(), "leaked temp files: {:?}", leaked);
+    }
+
+    fn console(level: &str, message: &str) -> ConsoleEntry {
+        ConsoleEntry {
+            level: level.to_string(),
+            message: message.to_string(),
+            timestamp: None,
+        }
+    }
+
+    #[test]
+    fn diagnostics_block_lists_console_and_network_errors() {
+        let diagnostics = ClientDiagnostics {
+            console_logs: vec![console("error", "Uncaught TypeError: cart is null")],
+            network_errors: vec![NetworkError {
+                method: Some("POST".to_string()),
+                url: "https://shop.example.com/api/pay".to_string(),
+                status: Some(500),
+                message: None,
+                timestamp: None,
+            }],
+        };
+        let block = diagnostics_block(&diagnostics, MAX_DIAGNOSTICS_PROMPT_CHARS);
+        assert!(block.contains("Browser diagnostics"));
+        assert!(block.contains("[console.error] Uncaught TypeError: cart is null"));
+        assert!(block.contains("[network] POST https://shop.example.com/api/pay -> 500"));
+        assert!(!block.contains("omitted"));
+
+        assert_eq!(
+            diagnostics_block(&ClientDiagnostics::default(), MAX_DIAGNOSTICS_PROMPT_CHARS),
+            ""
+        );
+    }
+
+    #[test]
+    fn extract_analysis_json_skips_decoy_braces_in_prose() {
+        let text = "I looked at {the recording} closely.\n{\"outcome\": \"failed\", \"note\": \"saw } and { in logs\"}";
+        let parsed = Worker::extract_analysis_json(text).unwrap();
+        assert_eq!(parsed["outcome"], "failed");
+        assert_eq!(parsed["note"], "saw } and { in logs");
+    }
+
+    #[test]
+    fn extract_analysis_json_uses_the_first_object_that_parses() {
+        let text =
+            "{outcome: broken} then {\"outcome\": \"success\"} and {\"outcome\": \"partial\"}";
+        let parsed = Worker::extract_analysis_json(text).unwrap();
+        assert_eq!(parsed["outcome"], "success");
+    }
+
+    #[test]
+    fn extract_analysis_json_accepts_single_quotes() {
+        let parsed =
+            Worker::extract_analysis_json("{'outcome': 'partial', 'overview': 'It\\'s \"ok\"'}")
+                .unwrap();
+        assert_eq!(parsed["outcome"], "partial");
+        assert_eq!(parsed["overview"], "It's \"ok\"");
+    }
+
+    #[test]
+    fn extract_analysis_json_strips_preamble_and_fences() {
+        let expected = serde_json::json!({ "outcome": "success" });
+        for text in [
+            "Here is the JSON:\n{\"outcome\": \"success\"}",
+            "```json\n{\"outcome\": \"success\"}\n```",
+            "```JSON\n{\"outcome\": \"success\"}\n```",
+            "Sure:\n``` json\n{\"outcome\": \"success\"}\n```\nLet me know!",
+            "```\n{\"outcome\": \"success\"}\n```",
+            "```jsonc\n{\"outcome\": \"success\"}```",
+            "```json {\"outcome\": \"success\"} ```",
+        ] {
+            assert_eq!(
+                Worker::extract_analysis_json(text).as_ref(),
+                Some(&expected),
+                "{text}"
+            );
+        }
+        assert!(Worker::extract_analysis_json("no json here").is_none());
+        assert!(Worker::extract_analysis_json("[1, 2]").is_none());
+    }
+
+    #[test]
+    fn experience_line_only_when_given() {
+        assert_eq!(
+            experience_line(Some(" first visit ")),
+            "User's prior experience with the product: first visit\n"
+        );
+        assert!(experience_line(Some("  ")).is_empty());
+        assert!(experience_line(None).is_empty());
+    }
+
+    #[test]
+    fn language_line_only_for_other_languages() {
+        assert!(language_line("English").is_empty());
+        assert!(language_line("english").is_empty());
+        assert_eq!(
+            language_line("Spanish"),
+            "\n\nWrite the overview, issues, and suggested actions in Spanish. Keep the JSON keys and the outcome and severity values in English."
+        );
+    }
+
+    #[test]
+    fn question_block_follows_configured_order() {
+        let mut questions = crate::models::AnalysisQuestions::default();
+        questions
+            .reorder(&["bug-user-error".to_string(), "bug-blocked".to_string()])
+            .unwrap();
+        let block = question_block(&questions.enabled_for_type(crate::models::FeedbackType::Bug));
+        let lines: Vec<&str> = block.lines().filter(|l| l.starts_with("- ")).collect();
+        assert_eq!(
+            lines,
+            [
+                "- Is this likely a user error or a product bug?",
+                "- Is the user completely blocked from completing the task?",
+                "- Did the user try alternative paths or workarounds?",
+            ]
+        );
+        assert!(question_block(&[]).is_empty());
+    }
+
+    #[test]
+    fn prior_sessions_block_lists_tickets_within_caps() {
+        use chrono::TimeZone;
+
+        assert_eq!(prior_sessions_block(&[]), "");
+
+        let ticket = PriorTicket {
+            created_at: chrono::Utc.with_ymd_and_hms(2026, 10, 1, 9, 0, 0).unwrap(),
+            task_description: Some("x".repeat(MAX_PRIOR_DESCRIPTION_CHARS + 10)),
+            outcome: Some(crate::models::ReportOutcome::Failed),
+            issue_titles: vec!["A".into(), "B".into(), "C".into(), "D".into()],
+        };
+        let block = prior_sessions_block(&[ticket]);
+        assert!(block.contains("Prior sessions from this user"));
+        assert!(block.ends_with(&format!(
+            "- 2026-10-01: {}… (outcome: failed); issues: A, B, C",
+            "x".repeat(MAX_PRIOR_DESCRIPTION_CHARS)
+        )));
+    }
+
+    #[test]
+    fn diagnostics_block_truncates_to_newest_lines() {
+        let diagnostics = ClientDiagnostics {
+            console_logs: (0..100)
+                .map(|i| console("log", &format!("line {:03} {}", i, "x".repeat(80))))
+                .collect(),
+            network_errors: Vec::new(),
+        };
+        let block = diagnostics_block(&diagnostics, 1000);
+        let body: usize = block
+            .lines()
+            .filter(|l| l.starts_with("[console"))
+            .map(|l| l.chars().count() + 1)
+            .sum();
+        assert!(body <= 1000);
+        assert!(block.contains("line 099"));
+        assert!(!block.contains("line 000"));
+        assert!(block.contains("earlier lines omitted"));
+    }
+
+    async fn test_worker(pool: sqlx::PgPool, shutdown: CancellationToken) -> Worker {
+        let state = AppState::new(crate::config::Config::test_default(), pool)
+            .await
+            .unwrap();
+        Worker::new(0, Arc::new(state), shutdown)
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn worker_uses_configured_poll_interval(pool: sqlx::PgPool) {
+        let config = crate::config::Config {
+            worker_poll_interval_ms: 250,
+            ..crate::config::Config::test_default()
+        };
+        let state = AppState::new(config, pool).await.unwrap();
+        let worker = Worker::new(0, Arc::new(state), CancellationToken::new());
+        assert_eq!(worker.poll_interval, Duration::from_millis(250));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn idle_worker_stops_on_shutdown(pool: sqlx::PgPool) {
+        let shutdown = CancellationToken::new();
+        let worker = test_worker(pool, shutdown.clone()).await;
+        let handle = tokio::spawn(async move { worker.start().await });
+
+        sleep(Duration::from_millis(100)).await;
+        shutdown.cancel();
+        timeout(Duration::from_secs(5), handle)
+            .await
+            .expect("worker did not stop")
+            .unwrap()
+            .unwrap();
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn pool_and_sweeper_stop_on_shutdown(pool: sqlx::PgPool) {
+        let shutdown = CancellationToken::new();
+        let state = AppState::new(crate::config::Config::test_default(), pool)
+            .await
+            .unwrap();
+        let handles = Worker::spawn_pool(Arc::new(state), 2, shutdown.clone());
+        assert_eq!(handles.len(), 3);
+
+        sleep(Duration::from_millis(100)).await;
+        shutdown.cancel();
+        timeout(Duration::from_secs(5), futures::future::join_all(handles))
+            .await
+            .expect("pool did not stop")
+            .into_iter()
+            .for_each(|result| result.unwrap());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn shutdown_lets_the_current_job_finish(pool: sqlx::PgPool) {
+        let shutdown = CancellationToken::new();
+        let worker = test_worker(pool, shutdown.clone()).await;
+        let state = worker.state.clone();
+        let job_id = state
+            .queue
+            .enqueue(crate::models::CreateJobRequest {
+                video_storage_path: "missing/video.webm".to_string(),
+                video_size_bytes: 1024,
+                mime_type: None,
+                prompt: None,
+                user_id: None,
+                recording_id: None,
+            })
+            .await
+            .unwrap();
+        let handle = tokio::spawn(async move { worker.start().await });
+
+        // Signal shutdown once the worker has picked the job up
+        while state.queue.get_job(job_id).await.unwrap().unwrap().status == JobStatus::Pending {
+            sleep(Duration::from_millis(10)).await;
+        }
+        shutdown.cancel();
+        timeout(Duration::from_secs(5), handle)
+            .await
+            .expect("worker did not stop")
+            .unwrap()
+            .unwrap();
+
+        // The download failure was recorded rather than the job being abandoned
+        let job = state.queue.get_job(job_id).await.unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Failed);
+        assert_eq!(job.retry_count, 1);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn prompt_lists_prior_sessions_when_enabled(pool: sqlx::PgPool) {
+        let owner_id = seed_user(&pool, "owner@example.com", "internal").await.id;
+        let customer_id = seed_user(&pool, "customer@example.com", "customer")
+            .await
+            .id;
+        let project_id = seed_project_with(
+            &pool,
+            owner_id,
+            serde_json::json!({"include_prior_sessions": true}),
+        )
+        .await;
+        let insert_ticket = |description: &'static str, ticket_status: &'static str| {
+            sqlx::query_scalar::<_, uuid::Uuid>(
+                "INSERT INTO recordings (project_id, customer_id, status, task_description, ticket_status) VALUES ($1, $2, 'processing', $3, $4) RETURNING id",
+            )
+            .bind(project_id)
+            .bind(customer_id)
+            .bind(description)
+            .bind(ticket_status)
+            .fetch_one(&pool)
+        };
+        let current_id = insert_ticket("Checkout fails again", "open").await.unwrap();
+        let worker = test_worker(pool.clone(), CancellationToken::new()).await;
+
+        // No history yet
+        let prompt = worker
+            .build_prompt_for_ticket(current_id, false, "English")
+            .await
+            .unwrap();
+        assert!(!prompt.contains("Prior sessions"));
+
+        let earlier_id = insert_ticket("Checkout fails", "resolved").await.unwrap();
+        let report_id = sqlx::query_scalar::<_, uuid::Uuid>(
+            "INSERT INTO reports (recording_id, outcome) VALUES ($1, 'failed') RETURNING id",
+        )
+        .bind(earlier_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO issues (report_id, title, severity) VALUES ($1, 'Pay button disabled', 'high')")
+            .bind(report_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        // Still open, so not part of the history
+        insert_ticket("Unrelated open ticket", "open")
+            .await
+            .unwrap();
+
+        let prompt = worker
+            .build_prompt_for_ticket(current_id, false, "English")
+            .await
+            .unwrap();
+        assert!(prompt.contains("Prior sessions from this user"));
+        assert!(prompt.contains("Checkout fails (outcome: failed); issues: Pay button disabled"));
+        assert!(!prompt.contains("Unrelated open ticket"));
+
+        sqlx::query(
+            "UPDATE projects SET settings = '{\"include_prior_sessions\": false}' WHERE id = $1",
+        )
+        .bind(project_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let prompt = worker
+            .build_prompt_for_ticket(current_id, false, "English")
+            .await
+            .unwrap();
+        assert!(!prompt.contains("Prior sessions"));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn prompt_and_report_use_the_project_language(pool: sqlx::PgPool) {
+        let owner_id = seed_user(&pool, "owner@example.com", "internal").await.id;
+        let project_id = seed_project_with(
+            &pool,
+            owner_id,
+            serde_json::json!({"report_language": "Spanish"}),
+        )
+        .await;
+        let recording_id = seed_ticket(&pool, project_id, owner_id, "processing").await;
+        let worker = test_worker(pool.clone(), CancellationToken::new()).await;
+
+        let language = worker
+            .ticket_project(recording_id)
+            .await
+            .unwrap()
+            .report_language();
+        assert_eq!(language, "Spanish");
+        let prompt = worker
+            .build_prompt_for_ticket(recording_id, false, &language)
+            .await
+            .unwrap();
+        assert!(prompt.contains("suggested actions in Spanish."));
+        assert!(worker.ticket_project(uuid::Uuid::new_v4()).await.is_none());
+
+        worker
+            .create_report_from_analysis(recording_id, r#"{"overview": "Hola"}"#, &language)
+            .await
+            .unwrap();
+        let saved: String =
+            sqlx::query_scalar("SELECT language FROM reports WHERE recording_id = $1")
+                .bind(recording_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(saved, "Spanish");
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn malformed_analysis_is_saved_with_sane_values(pool: sqlx::PgPool) {
+        let owner_id = seed_user(&pool, "owner@example.com", "internal").await.id;
+        let recording_id = sqlx::query_scalar::<_, uuid::Uuid>(
+            "INSERT INTO recordings (customer_id, status) VALUES ($1, 'analyzed') RETURNING id",
+        )
+        .bind(owner_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let worker = test_worker(pool.clone(), CancellationToken::new()).await;
+
+        let analysis = r#"{
+            "outcome": "meh",
+            "confidence": 500,
+            "metrics": { "task_completion_rate": 250 },
+            "suggested_actions": "Retry",
+            "issues": [
+                { "title": "Broken link", "severity": "urgent", "confidence": -5 },
+                { "severity": "low" }
+            ]
+        }"#;
+        worker
+            .create_report_from_analysis(recording_id, analysis, "English")
+            .await
+            .unwrap();
+
+        let report = sqlx::query_as::<_, crate::models::Report>(
+            "SELECT * FROM reports WHERE recording_id = $1",
+        )
+        .bind(recording_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(report.outcome, None);
+        assert_eq!(report.confidence, Some(100));
+        assert_eq!(report.task_completion_rate, Some(100));
+        assert_eq!(report.suggested_actions.0, ["Retry"]);
+
+        let issues =
+            sqlx::query_as::<_, crate::models::Issue>("SELECT * FROM issues WHERE report_id = $1")
+                .bind(report.id)
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].title, "Broken link");
+        assert_eq!(issues[0].severity, crate::models::IssueSeverity::Medium);
+        assert_eq!(issues[0].confidence, Some(0));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn reanalysis_saves_the_next_report_version(pool: sqlx::PgPool) {
+        let owner_id = seed_user(&pool, "owner@example.com", "internal").await.id;
+        let project_id = seed_project(&pool, owner_id).await;
+        let recording_id = sqlx::query_scalar::<_, uuid::Uuid>(
+            "INSERT INTO recordings (project_id, customer_id, status, video_storage_path) VALUES ($1, $2, 'analyzed', 'videos/t.webm') RETURNING id",
+        )
+        .bind(project_id)
+        .bind(owner_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let worker = test_worker(pool.clone(), CancellationToken::new()).await;
+
+        worker
+            .create_report_from_analysis(
+                recording_id,
+                r#"{"overview": "First look", "issues": [{"title": "Slow page", "severity": "low"}]}"#,
+                "English",
+            )
+            .await
+            .unwrap();
+        worker
+            .state
+            .tickets
+            .reanalyze(recording_id, owner_id)
+            .await
+            .unwrap();
+        worker
+            .create_report_from_analysis(recording_id, r#"{"overview": "Second look"}"#, "English")
+            .await
+            .unwrap();
+
+        let report = sqlx::query_as::<_, crate::models::Report>(
+            "SELECT * FROM reports WHERE recording_id = $1",
+        )
+        .bind(recording_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(report.report_version, 2);
+        assert_eq!(report.overview.as_deref(), Some("Second look"));
+
+        let versions = worker
+            .state
+            .tickets
+            .report_versions(recording_id)
+            .await
+            .unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].report_version, 1);
+        assert_eq!(versions[0].report.overview.as_deref(), Some("First look"));
+        assert_eq!(versions[0].issues.len(), 1);
+        assert_eq!(versions[0].issues[0].title, "Slow page");
+
+        // A report saved without a reanalysis (e.g. a recovered job) is versioned too
+        worker
+            .create_report_from_analysis(recording_id, r#"{"overview": "Third look"}"#, "English")
+            .await
+            .unwrap();
+        let versions: Vec<i32> = sqlx::query_scalar(
+            "SELECT report_version FROM report_versions WHERE recording_id = $1 ORDER BY report_version",
+        )
+        .bind(recording_id)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(versions, vec![1, 2]);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn unparseable_or_incomplete_analysis_still_saves_a_report(pool: sqlx::PgPool) {
+        let owner_id = seed_user(&pool, "owner@example.com", "internal").await.id;
+        let mut recording_ids = Vec::new();
+        for _ in 0..2 {
+            let recording_id = sqlx::query_scalar::<_, uuid::Uuid>(
+                "INSERT INTO recordings (customer_id, status) VALUES ($1, 'analyzed') RETURNING id",
+            )
+            .bind(owner_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            recording_ids.push(recording_id);
+        }
+        let worker = test_worker(pool.clone(), CancellationToken::new()).await;
+        let report_of = |recording_id: uuid::Uuid| {
+            sqlx::query_as::<_, crate::models::Report>(
+                "SELECT * FROM reports WHERE recording_id = $1",
+            )
+            .bind(recording_id)
+            .fetch_one(&pool)
+        };
+
+        let prose = "Sorry, I could not produce JSON. The user gave up at checkout.";
+        let outcome = worker
+            .create_report_from_analysis(recording_ids[0], prose, "English")
+            .await
+            .unwrap();
+        assert_eq!(outcome, None);
+        let report = report_of(recording_ids[0]).await.unwrap();
+        assert_eq!(report.outcome, Some(crate::models::ReportOutcome::Partial));
+        assert_eq!(report.overview.as_deref(), Some(prose));
+        assert_eq!(report.raw_analysis.as_deref(), Some(prose));
+
+        let no_issues_or_metrics = r#"{"outcome": "failed", "overview": "Checkout failed."}"#;
+        let outcome = worker
+            .create_report_from_analysis(recording_ids[1], no_issues_or_metrics, "English")
+            .await
+            .unwrap();
+        assert_eq!(outcome, Some(crate::models::ReportOutcome::Failed));
+        let report = report_of(recording_ids[1]).await.unwrap();
+        assert_eq!(report.outcome, Some(crate::models::ReportOutcome::Failed));
+        assert_eq!(report.overview.as_deref(), Some("Checkout failed."));
+        assert_eq!(report.task_completion_rate, None);
+        let issues: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM issues WHERE report_id = $1")
+            .bind(report.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(issues, 0);
+    }
+
+    /// Stand-in for Gemini that records each request and answers with a fixed analysis
+    async fn spawn_gemini_stub() -> (
+        String,
+        tokio::sync::mpsc::UnboundedReceiver<serde_json::Value>,
+    ) {
+        use axum::{routing::post, Json, Router};
+
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<serde_json::Value>();
+        let app = Router::new().fallback(post(move |Json(body): Json<serde_json::Value>| {
+            let tx = tx.clone();
+            async move {
+                let _ = tx.send(body);
+                let text = r#"{"outcome": "failed", "confidence": 70, "overview": "The pay button is greyed out.", "issues": [{"title": "Disabled pay button", "severity": "high"}]}"#;
+                Json(serde_json::json!({
+                    "candidates": [{ "content": { "parts": [{ "text": text }] } }],
+                    "usageMetadata": { "promptTokenCount": 10, "candidatesTokenCount": 5, "totalTokenCount": 15 }
+                }))
+            }
+        }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let api_base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (api_base, rx)
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn image_only_job_produces_a_report(pool: sqlx::PgPool) {
+        let (api_base, mut rx) = spawn_gemini_stub().await;
+
+        let owner_id = seed_user(&pool, "owner@example.com", "internal").await.id;
+        let screenshot_path = format!("screenshots/test/{}.png", uuid::Uuid::new_v4());
+        let recording_id = sqlx::query_scalar::<_, uuid::Uuid>(
+            "INSERT INTO recordings (customer_id, status, screenshot_url) VALUES ($1, 'processing', $2) RETURNING id",
+        )
+        .bind(owner_id)
+        .bind(&screenshot_path)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        let mut state = AppState::new(crate::config::Config::test_default(), pool.clone())
+            .await
+            .unwrap();
+        state.gemini = Arc::new(crate::services::GeminiService::with_api_base(
+            "test-key", &api_base,
+        ));
+        let png = b"\x89PNG\r\n\x1a\nscreenshot";
+        state.storage.upload(&screenshot_path, png).await.unwrap();
+        let job_id = state
+            .queue
+            .enqueue(crate::models::CreateJobRequest {
+                video_storage_path: screenshot_path,
+                video_size_bytes: png.len() as i64,
+                mime_type: Some("image/png".to_string()),
+                prompt: None,
+                user_id: Some(owner_id),
+                recording_id: Some(recording_id),
+            })
+            .await
+            .unwrap();
+        let worker = Worker::new(0, Arc::new(state), CancellationToken::new());
+
+        assert!(worker.process_next_job().await.unwrap());
+
+        let request = rx.recv().await.unwrap();
+        let parts = &request["contents"][0]["parts"];
+        assert!(parts[0]["text"]
+            .as_str()
+            .unwrap()
+            .starts_with("Analyze this screenshot."));
+        assert_eq!(parts[1]["inlineData"]["mime_type"], "image/png");
+
+        let job = worker.state.queue.get_job(job_id).await.unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Completed);
+        assert_eq!(job.total_tokens, Some(15));
+        let report = sqlx::query_as::<_, crate::models::Report>(
+            "SELECT * FROM reports WHERE recording_id = $1",
+        )
+        .bind(recording_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            report.overview.as_deref(),
+            Some("The pay button is greyed out.")
+        );
+        let issues: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM issues WHERE report_id = $1")
+            .bind(report.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(issues, 1);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn project_gemini_params_reach_the_request(pool: sqlx::PgPool) {
+        let (api_base, mut rx) = spawn_gemini_stub().await;
+
+        let owner_id = seed_user(&pool, "owner@example.com", "internal").await.id;
+        let project_id = seed_project_with(
+            &pool,
+            owner_id,
+            serde_json::json!({"gemini_params": {"temperature": 7.5, "max_output_tokens": 1024}}),
+        )
+        .await;
+        let screenshot_path = format!("screenshots/test/{}.png", uuid::Uuid::new_v4());
+        let recording_id = sqlx::query_scalar::<_, uuid::Uuid>(
+            "INSERT INTO recordings (project_id, customer_id, status, screenshot_url) VALUES ($1, $2, 'processing', $3) RETURNING id",
+        )
+        .bind(project_id)
+        .bind(owner_id)
+        .bind(&screenshot_path)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        let mut state = AppState::new(crate::config::Config::test_default(), pool.clone())
+            .await
+            .unwrap();
+        state.gemini = Arc::new(crate::services::GeminiService::with_api_base(
+            "test-key", &api_base,
+        ));
+        let png = b"\x89PNG\r\n\x1a\nscreenshot";
+        state.storage.upload(&screenshot_path, png).await.unwrap();
+        state
+            .queue
+            .enqueue(crate::models::CreateJobRequest {
+                video_storage_path: screenshot_path,
+                video_size_bytes: png.len() as i64,
+                mime_type: Some("image/png".to_string()),
+                prompt: None,
+                user_id: Some(owner_id),
+                recording_id: Some(recording_id),
+            })
+            .await
+            .unwrap();
+        let worker = Worker::new(0, Arc::new(state), CancellationToken::new());
+
+        assert!(worker.process_next_job().await.unwrap());
+
+        let request = rx.recv().await.unwrap();
+        let config = &request["generation_config"];
+        // Temperature is clamped to Gemini's range; the token limit passes through
+        assert_eq!(config["temperature"], 2.0);
+        assert_eq!(config["max_output_tokens"], 1024);
+        assert_eq!(config["top_k"], 40);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn video_job_reports_each_stage_in_order(pool: sqlx::PgPool) {
+        let (api_base, mut rx) = spawn_gemini_stub().await;
+        // Log every stage the worker records, however briefly it lasts
+        sqlx::query("CREATE TABLE observed_stages (seq SERIAL PRIMARY KEY, stage VARCHAR(32))")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            r#"
+            CREATE FUNCTION observe_stage() RETURNS trigger AS $$
+            BEGIN
+                INSERT INTO observed_stages (stage) VALUES (NEW.progress_stage);
+                RETURN NEW;
+            END
+            $$ LANGUAGE plpgsql
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"
+            CREATE TRIGGER observe_stage AFTER UPDATE OF progress_stage ON analysis_jobs
+            FOR EACH ROW WHEN (NEW.progress_stage IS NOT NULL)
+            EXECUTE FUNCTION observe_stage()
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let owner_id = seed_user(&pool, "owner@example.com", "internal").await.id;
+        let project_id = seed_project(&pool, owner_id).await;
+        let recording_id = seed_ticket(&pool, project_id, owner_id, "processing").await;
+        let mut state = AppState::new(crate::config::Config::test_default(), pool.clone())
+            .await
+            .unwrap();
+        state.gemini = Arc::new(crate::services::GeminiService::with_api_base(
+            "test-key", &api_base,
+        ));
+        let video_path = format!("recordings/{}/{}.webm", project_id, recording_id);
+        let webm = [0x1A, 0x45, 0xDF, 0xA3, 0x84, 0x42, 0x82, 0x81, 0x77];
+        state.storage.upload(&video_path, &webm).await.unwrap();
+        let job_id = state
+            .queue
+            .enqueue(crate::models::CreateJobRequest {
+                video_storage_path: video_path,
+                video_size_bytes: webm.len() as i64,
+                mime_type: Some("video/webm".to_string()),
+                prompt: None,
+                user_id: Some(owner_id),
+                recording_id: Some(recording_id),
+            })
+            .await
+            .unwrap();
+        let worker = Worker::new(0, Arc::new(state), CancellationToken::new());
+
+        assert!(worker.process_next_job().await.unwrap());
+        assert!(rx.recv().await.is_some());
+
+        let stages: Vec<JobProgressStage> =
+            sqlx::query_scalar("SELECT stage FROM observed_stages ORDER BY seq")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(
+            stages,
+            vec![
+                JobProgressStage::Downloading,
+                JobProgressStage::UploadingToGemini,
+                JobProgressStage::Analyzing,
+                JobProgressStage::Saving,
+            ]
+        );
+        let job = worker.state.queue.get_job(job_id).await.unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Completed);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "requires DATABASE_URL"]
+    async fn temp_file_removed_after_failed_analysis(pool: sqlx::PgPool) {
+        use axum::{http::StatusCode, routing::post, Router};
+
+        let app = Router::new().fallback(post(|| async {
+            (StatusCode::INTERNAL_SERVER_ERROR, "Gemini unavailable")
+        }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let api_base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let owner_id = seed_user(&pool, "owner@example.com", "internal").await.id;
+        let project_id = seed_project(&pool, owner_id).await;
+        let recording_id = seed_ticket(&pool, project_id, owner_id, "processing").await;
+        let mut state = AppState::new(crate::config::Config::test_default(), pool.clone())
+            .await
+            .unwrap();
+        state.gemini = Arc::new(crate::services::GeminiService::with_api_base(
+            "test-key", &api_base,
+        ));
+        let video_path = format!("recordings/{}/{}.webm", project_id, recording_id);
+        let webm = [0x1A, 0x45, 0xDF, 0xA3, 0x84, 0x42, 0x82, 0x81, 0x77];
+        state.storage.upload(&video_path, &webm).await.unwrap();
+        let job_id = state
+            .queue
+            .enqueue(crate::models::CreateJobRequest {
+                video_storage_path: video_path,
+                video_size_bytes: webm.len() as i64,
+                mime_type: Some("video/webm".to_string()),
+                prompt: None,
+                user_id: Some(owner_id),
+                recording_id: Some(recording_id),
+            })
+            .await
+            .unwrap();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let worker = Worker::new(0, Arc::new(state), CancellationToken::new())
+            .with_temp_dir(temp_dir.path());
+
+        assert!(worker.process_next_job().await.unwrap());
+
+        let job = worker.state.queue.get_job(job_id).await.unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Failed);
+        assert!(job
+            .error_message
+            .as_deref()
+            .is_some_and(|e| e.starts_with("Analysis failed")));
+        let left: Vec<_> = std::fs::read_dir(temp_dir.path()).unwrap().collect();
+        assert!(left.is_empty(), "temp files left behind: {:?}", left);
     }
 }
@@ -5,9 +5,11 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use crate::config::Config;
+use crate::middleware::WidgetRateLimiter;
 use crate::services::{
-    AuthService, ChatService, GeminiService, ProjectService, QueueService, StorageService,
-    TicketService,
+    ApiKeyService, AuthService, ChatService, EmailService, GeminiService, JiraService,
+    NotificationService, ProjectService, QueueService, SessionService, StorageService,
+    TicketService, WebhookService,
 };
 
 /// Shared application state
@@ -17,11 +19,16 @@ pub struct AppState {
     pub config: Arc<Config>,
     pub auth: Arc<AuthService>,
     pub projects: Arc<ProjectService>,
+    pub sessions: Arc<SessionService>,
     pub tickets: Arc<TicketService>,
     pub chat: Arc<ChatService>,
     pub gemini: Arc<GeminiService>,
     pub storage: Arc<StorageService>,
     pub queue: Arc<QueueService>,
+    pub webhooks: Arc<WebhookService>,
+    pub jira: Arc<JiraService>,
+    pub api_keys: Arc<ApiKeyService>,
+    pub widget_rate_limiter: Arc<WidgetRateLimiter>,
 }
 
 impl AppState {
@@ -30,27 +37,45 @@ impl AppState {
 
         // Initialize services
         let storage = Arc::new(StorageService::new(&config)?);
-        let queue = Arc::new(QueueService::new(db.clone()));
+        let queue = Arc::new(QueueService::new(db.clone(), config.job_max_retries));
         let gemini = Arc::new(GeminiService::new(&config).await?);
         let auth = Arc::new(AuthService::new(config.clone(), db.clone()));
-        let projects = Arc::new(ProjectService::new(db.clone()));
+        let projects = Arc::new(ProjectService::new(
+            db.clone(),
+            config.max_analysis_questions,
+        ));
+        let sessions = Arc::new(SessionService::new(db.clone()));
+        let webhooks = Arc::new(WebhookService::new(db.clone()));
+        let notifications = Arc::new(NotificationService::new(&config, db.clone()));
+        let emails = Arc::new(EmailService::new(&config)?);
+        let chat = Arc::new(ChatService::new(db.clone()));
         let tickets = Arc::new(TicketService::new(
             db.clone(),
             storage.clone(),
-            queue.clone(),
+            webhooks.clone(),
+            notifications,
+            emails,
+            chat.clone(),
         ));
-        let chat = Arc::new(ChatService::new(db.clone()));
+        let jira = Arc::new(JiraService::new(&config));
+        let api_keys = Arc::new(ApiKeyService::new(db.clone()));
+        let widget_rate_limiter = Arc::new(WidgetRateLimiter::new(config.widget_rate_limit));
 
         Ok(Self {
             db,
             config,
             auth,
             projects,
+            sessions,
             tickets,
             chat,
             gemini,
             storage,
             queue,
+            webhooks,
+            jira,
+            api_keys,
+            widget_rate_limiter,
         })
     }
 }
@@ -58,25 +83,40 @@ impl AppState {
 /// Wrapper that holds app state once startup (DB connect + migrations) has completed.
 /// Used so the server can bind and listen immediately; handlers return 503 until ready.
 #[derive(Clone)]
-pub struct ReadyAppState(pub Arc<RwLock<Option<Arc<AppState>>>>);
+pub struct ReadyAppState {
+    state: Arc<RwLock<Option<Arc<AppState>>>>,
+    /// `Retry-After` seconds sent with the 503s returned until ready
+    retry_after_secs: u64,
+}
 
 impl ReadyAppState {
     pub fn new() -> Self {
-        Self(Arc::new(RwLock::new(None)))
+        Self {
+            state: Arc::new(RwLock::new(None)),
+            retry_after_secs: 5,
+        }
+    }
+
+    /// Override how long clients are told to wait while starting up
+    pub fn with_retry_after(mut self, retry_after_secs: u64) -> Self {
+        self.retry_after_secs = retry_after_secs;
+        self
     }
 
     pub async fn get(&self) -> Option<Arc<AppState>> {
-        self.0.read().await.clone()
+        self.state.read().await.clone()
     }
 
     /// Get app state or return ServiceUnavailable for use in handlers.
     pub async fn get_or_unavailable(&self) -> Result<Arc<AppState>, crate::error::AppError> {
         self.get()
             .await
-            .ok_or(crate::error::AppError::ServiceUnavailable)
+            .ok_or(crate::error::AppError::service_unavailable(
+                self.retry_after_secs,
+            ))
     }
 
     pub async fn set(&self, state: Arc<AppState>) {
-        *self.0.write().await = Some(state);
+        *self.state.write().await = Some(state);
     }
 }
@@ -0,0 +1,71 @@
+//! Fixtures shared by the database-backed tests
+
+use std::sync::Arc;
+
+use axum::Router;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::models::User;
+use crate::router::create_router;
+use crate::state::{AppState, ReadyAppState};
+
+/// Onboarded user with quota to spare
+pub async fn seed_user(pool: &PgPool, email: &str, role: &str) -> User {
+    sqlx::query_as::<_, User>(
+        r#"
+        INSERT INTO users (email, role, onboarding_completed, quota_limit, quota_used)
+        VALUES ($1, $2, TRUE, 100, 0)
+        RETURNING *
+        "#,
+    )
+    .bind(email)
+    .bind(role)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+/// Project with default settings
+pub async fn seed_project(pool: &PgPool, owner_id: Uuid) -> Uuid {
+    seed_project_with(pool, owner_id, serde_json::json!({})).await
+}
+
+pub async fn seed_project_with(pool: &PgPool, owner_id: Uuid, settings: serde_json::Value) -> Uuid {
+    sqlx::query_scalar::<_, Uuid>(
+        "INSERT INTO projects (owner_id, name, domain, settings) VALUES ($1, 'App', 'example.com', $2) RETURNING id",
+    )
+    .bind(owner_id)
+    .bind(settings)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+/// Ticket in the given processing `status` ('pending', 'analyzed', ...)
+pub async fn seed_ticket(pool: &PgPool, project_id: Uuid, customer_id: Uuid, status: &str) -> Uuid {
+    sqlx::query_scalar::<_, Uuid>(
+        "INSERT INTO recordings (project_id, customer_id, status) VALUES ($1, $2, $3) RETURNING id",
+    )
+    .bind(project_id)
+    .bind(customer_id)
+    .bind(status)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+/// Full router over a ready `AppState`, plus that state for minting tokens or seeding storage
+pub async fn test_app(pool: PgPool, config: &Config) -> (Router, Arc<AppState>) {
+    let state = AppState::new(config.clone(), pool).await.unwrap();
+    serve_state(state).await
+}
+
+/// Like `test_app` for a state the test has already customised
+pub async fn serve_state(state: AppState) -> (Router, Arc<AppState>) {
+    let state = Arc::new(state);
+    let ready = ReadyAppState::new();
+    ready.set(state.clone()).await;
+    (create_router(ready, &state.config), state)
+}
@@ -69,4 +69,44 @@ impl TestContext {
         .await
         .expect("Failed to create test session")
     }
+
+    /// Create a test recording attached to a session and return its UUID
+    pub async fn create_test_recording(
+        &self,
+        session_id: uuid::Uuid,
+        customer_id: uuid::Uuid,
+    ) -> uuid::Uuid {
+        sqlx::query_scalar::<_, uuid::Uuid>(
+            r#"
+            INSERT INTO recordings (session_id, customer_id, status, video_storage_path)
+            VALUES ($1, $2, 'uploaded', 'test/video.webm')
+            RETURNING id
+            "#,
+        )
+        .bind(session_id)
+        .bind(customer_id)
+        .fetch_one(&self.pool)
+        .await
+        .expect("Failed to create test recording")
+    }
+
+    /// Enqueue a pending analysis job for the given recording
+    pub async fn create_test_job(
+        &self,
+        user_id: uuid::Uuid,
+        recording_id: uuid::Uuid,
+    ) -> uuid::Uuid {
+        sqlx::query_scalar::<_, uuid::Uuid>(
+            r#"
+            INSERT INTO analysis_jobs (user_id, recording_id, status, video_storage_path, video_size_bytes, prompt)
+            VALUES ($1, $2, 'pending', 'test/video.webm', 1024, 'test prompt')
+            RETURNING id
+            "#,
+        )
+        .bind(user_id)
+        .bind(recording_id)
+        .fetch_one(&self.pool)
+        .await
+        .expect("Failed to create test job")
+    }
 }
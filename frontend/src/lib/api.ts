@@ -178,6 +178,10 @@ export interface OverviewStats {
   backlog_pct: number;
   resolved_count: number;
   resolved_pct: number;
+  failed_count: number;
+  failed_pct: number;
+  processing_count: number;
+  processing_pct: number;
   total_count: number;
 }
 
@@ -431,7 +435,8 @@ export const projectsApi = {
   },
 
   async list(): Promise<Project[]> {
-    return apiRequest<Project[]>('/api/v1/projects');
+    const page = await apiRequest<PaginatedResponse<Project>>('/api/v1/projects?per_page=100');
+    return page.items;
   },
 
   async get(id: string): Promise<Project> {
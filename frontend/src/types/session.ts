@@ -158,6 +158,10 @@ export interface OverviewStats {
   backlog_pct: number;
   resolved_count: number;
   resolved_pct: number;
+  failed_count: number;
+  failed_pct: number;
+  processing_count: number;
+  processing_pct: number;
   total_count: number;
 }
 